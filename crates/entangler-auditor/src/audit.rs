@@ -0,0 +1,621 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::str::FromStr;
+
+use anchor_lang::{prelude::Pubkey, solana_program::program_option::COption, AccountDeserialize};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use anyhow::Result;
+use entangler_client::{
+    accounts::{EntangledCollection, EntangledPair},
+    pda::{find_entangled_mint, find_entangled_pair, find_entangler_authority, find_metadata},
+};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+use solana_sdk::account::Account;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+use crate::chain::Chain;
+use crate::report::{BurnedPair, CollectionReport, Mismatch, MismatchReason, Report, StrayToken};
+
+fn is_token_program(key: &Pubkey) -> bool {
+    *key == anchor_spl::token::ID || *key == anchor_spl::token_2022::ID
+}
+
+fn mint(account: Option<&Account>) -> Option<Mint> {
+    let account = account.filter(|account| is_token_program(&account.owner))?;
+    Mint::try_deserialize(&mut &account.data[..]).ok()
+}
+
+fn token_account(account: Option<&Account>) -> Option<TokenAccount> {
+    let account = account.filter(|account| is_token_program(&account.owner))?;
+    TokenAccount::try_deserialize(&mut &account.data[..]).ok()
+}
+
+/// Whether a token account holds the token for the authority, either as its owner or as the
+/// delegate it is frozen for, as `verify_pair` checks on chain
+fn holds(account: Option<&Account>, mint: &Pubkey, authority: &Pubkey) -> bool {
+    token_account(account).is_some_and(|token| {
+        token.mint == *mint
+            && token.amount == 1
+            && (token.owner == *authority
+                || (token.is_frozen() && token.delegate == COption::Some(*authority)))
+    })
+}
+
+/// The authorities a collection's tokens may be held by. Migrated collections keep tokens
+/// with the global authority until each of their pairs is migrated
+fn authorities(collection: &EntangledCollection) -> Vec<Pubkey> {
+    let mut authorities = vec![collection.authority().key];
+    if collection.scoped_authority {
+        authorities.push(find_entangler_authority().0);
+    }
+    authorities
+}
+
+/// The escrow of an authority for a mint, with the token program of the mint if it was fetched
+fn escrow(mints: &HashMap<Pubkey, Account>, authority: &Pubkey, mint: &Pubkey) -> Pubkey {
+    let program = mints
+        .get(mint)
+        .map_or(anchor_spl::token::ID, |mint| mint.owner);
+    get_associated_token_address_with_program_id(authority, mint, &program)
+}
+
+/// The entangled collection mint a token was issued into: its verified Metaplex collection,
+/// or the `collection` field Token-2022 entangled mints hold in their own metadata, as long
+/// as the authority can update it
+fn issued_collection(
+    mint: &Account,
+    metadata: Option<&Account>,
+    authority: &Pubkey,
+) -> Option<Pubkey> {
+    if mint.owner == anchor_spl::token_2022::ID {
+        let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint.data).ok()?;
+        if let Ok(token_metadata) = state.get_variable_len_extension::<TokenMetadata>() {
+            if Option::<Pubkey>::from(token_metadata.update_authority) != Some(*authority) {
+                return None;
+            }
+            let (_, collection) = token_metadata
+                .additional_metadata
+                .iter()
+                .find(|(key, _)| key == "collection")?;
+            return Pubkey::from_str(collection).ok();
+        }
+    }
+
+    let metadata = Metadata::safe_deserialize(&metadata?.data).ok()?;
+    metadata
+        .collection
+        .filter(|collection| collection.verified)
+        .map(|collection| collection.key)
+}
+
+/// Audits every collection, or only the one entangled under `id`
+pub fn audit(chain: &impl Chain, id: Option<Pubkey>, audited_at: u64) -> Result<Report> {
+    let slot = chain.slot()?;
+    let all_collections = chain.collections()?;
+    let pairs = chain.pairs()?;
+    let originals = chain.original_records()?;
+
+    // Pairs belong to the collection their entangled mint derives from
+    let mut collection_pairs: HashMap<Pubkey, Vec<(Pubkey, EntangledPair)>> = HashMap::new();
+    let mut unattributed_pairs = Vec::new();
+    for (address, pair) in &pairs {
+        let collection = all_collections.iter().find(|collection| {
+            find_entangled_mint(&collection.id, &pair.original_mint).0 == pair.entangled_mint
+        });
+        match collection {
+            Some(collection) => collection_pairs
+                .entry(collection.id)
+                .or_default()
+                .push((*address, pair.clone())),
+            None => unattributed_pairs.push(address.to_string()),
+        }
+    }
+
+    let collections: Vec<&EntangledCollection> = all_collections
+        .iter()
+        .filter(|collection| id.is_none_or(|id| collection.id == id))
+        .collect();
+    // Initialized originals keep their record once their pair is closed, or before it exists
+    let paired: HashSet<Pubkey> = pairs.iter().map(|(_, pair)| pair.entangled_mint).collect();
+    let mut reports = Vec::new();
+    for collection in &collections {
+        let pairs = collection_pairs.remove(&collection.id).unwrap_or_default();
+        let mut report = audit_pairs(chain, collection, &pairs)?;
+        audit_unpaired(chain, collection, &originals, &paired, &mut report)?;
+        reports.push(report);
+    }
+
+    // The tokens of every pair, recorded original and collection, so that escrows holding
+    // anything else stand out
+    let known: HashSet<Pubkey> = pairs
+        .iter()
+        .flat_map(|(_, pair)| [pair.original_mint, pair.entangled_mint])
+        .chain(originals.iter().copied())
+        .chain(all_collections.iter().flat_map(|collection| {
+            originals
+                .iter()
+                .map(|original| find_entangled_mint(&collection.id, original).0)
+        }))
+        .chain(
+            all_collections
+                .iter()
+                .map(|collection| collection.entangled_collection_mint),
+        )
+        .collect();
+    let scanned: BTreeSet<Pubkey> = collections
+        .iter()
+        .flat_map(|collection| authorities(collection))
+        .collect();
+    let mut stray_tokens = Vec::new();
+    for authority in scanned {
+        let escrows = chain.accounts(&chain.token_accounts(&authority)?)?;
+        let unknown: Vec<(Pubkey, TokenAccount)> = escrows
+            .iter()
+            .filter_map(|(address, account)| Some((*address, token_account(Some(account))?)))
+            .filter(|(_, escrow)| !known.contains(&escrow.mint))
+            .collect();
+        let mint_keys: Vec<Pubkey> = unknown
+            .iter()
+            .flat_map(|(_, escrow)| [escrow.mint, find_metadata(&escrow.mint).0])
+            .collect();
+        let mints = chain.accounts(&mint_keys)?;
+
+        for (address, escrow) in unknown {
+            let issued = mints.get(&escrow.mint).and_then(|mint| {
+                let metadata = mints.get(&find_metadata(&escrow.mint).0);
+                issued_collection(mint, metadata, &authority)
+            });
+            let report = issued.and_then(|issued| {
+                collections
+                    .iter()
+                    .zip(reports.iter_mut())
+                    .find(|(collection, _)| {
+                        collection.entangled_collection_mint == issued
+                            && authorities(collection).contains(&authority)
+                    })
+                    .map(|(_, report)| report)
+            });
+            match report {
+                // Burning an original without a record closes its pair, and its entangled
+                // escrow stays empty
+                Some(report) if escrow.amount == 0 => report.burned.push(BurnedPair {
+                    entangled_mint: escrow.mint.to_string(),
+                    escrow: address.to_string(),
+                    supply: mint(mints.get(&escrow.mint)).map_or(0, |mint| mint.supply),
+                }),
+                _ if escrow.amount > 0 => stray_tokens.push(StrayToken {
+                    authority: authority.to_string(),
+                    account: address.to_string(),
+                    mint: escrow.mint.to_string(),
+                    amount: escrow.amount,
+                }),
+                _ => {}
+            }
+        }
+    }
+
+    let backed =
+        unattributed_pairs.is_empty() && reports.iter().all(|report| report.mismatches.is_empty());
+    Ok(Report {
+        program: entangler_client::ID.to_string(),
+        slot,
+        audited_at,
+        backed,
+        collections: reports,
+        unattributed_pairs: if id.is_some() {
+            Vec::new()
+        } else {
+            unattributed_pairs
+        },
+        stray_tokens,
+    })
+}
+
+/// Checks that the authorities hold exactly one token of each pair of the collection
+fn audit_pairs(
+    chain: &impl Chain,
+    collection: &EntangledCollection,
+    pairs: &[(Pubkey, EntangledPair)],
+) -> Result<CollectionReport> {
+    let authorities = authorities(collection);
+    let mut report = CollectionReport {
+        id: collection.id.to_string(),
+        authority: authorities[0].to_string(),
+        entangled: 0,
+        disentangled: 0,
+        initialized: 0,
+        unverifiable: 0,
+        mismatches: Vec::new(),
+        burned: Vec::new(),
+    };
+    // Compressed entangled tokens are leaves of the collection's tree
+    if collection.is_compressed() {
+        report.unverifiable = pairs.len() as u64;
+        return Ok(report);
+    }
+
+    let mint_keys: Vec<Pubkey> = pairs
+        .iter()
+        .flat_map(|(_, pair)| [pair.original_mint, pair.entangled_mint])
+        .collect();
+    let mints = chain.accounts(&mint_keys)?;
+    let escrow = |authority: &Pubkey, mint: &Pubkey| escrow(&mints, authority, mint);
+
+    let mut custody_keys = Vec::new();
+    for (_, pair) in pairs {
+        custody_keys.extend(pair.frozen_account);
+        for authority in &authorities {
+            custody_keys.push(escrow(authority, &pair.original_mint));
+            custody_keys.push(escrow(authority, &pair.entangled_mint));
+        }
+    }
+    let custody = chain.accounts(&custody_keys)?;
+
+    for (address, pair) in pairs {
+        // Compressed originals are asset ids rather than mints
+        let original = mints.get(&pair.original_mint);
+        if !original.is_some_and(|account| is_token_program(&account.owner)) {
+            report.unverifiable += 1;
+            continue;
+        }
+
+        let supplies = (
+            mint(original).map(|mint| mint.supply),
+            mint(mints.get(&pair.entangled_mint)).map(|mint| mint.supply),
+        );
+        let reason = if supplies != (Some(1), Some(1)) {
+            Some(MismatchReason::InvalidSupply)
+        } else {
+            let original_held = authorities.iter().any(|authority| {
+                let account = pair
+                    .frozen_account
+                    .unwrap_or_else(|| escrow(authority, &pair.original_mint));
+                holds(custody.get(&account), &pair.original_mint, authority)
+            });
+            let entangled_held = authorities.iter().any(|authority| {
+                let account = escrow(authority, &pair.entangled_mint);
+                holds(custody.get(&account), &pair.entangled_mint, authority)
+            });
+            match (original_held, entangled_held) {
+                (true, false) => {
+                    report.entangled += 1;
+                    None
+                }
+                (false, true) => {
+                    report.disentangled += 1;
+                    None
+                }
+                (false, false) => Some(MismatchReason::Unbacked),
+                (true, true) => Some(MismatchReason::DoubleHeld),
+            }
+        };
+
+        if let Some(reason) = reason {
+            report.mismatches.push(Mismatch {
+                pair: address.to_string(),
+                original_mint: pair.original_mint.to_string(),
+                entangled_mint: pair.entangled_mint.to_string(),
+                reason,
+            });
+        }
+    }
+
+    Ok(report)
+}
+
+/// Checks the entangled mints the collection derives from recorded originals and that have no
+/// pair: initialized ones keep their token in escrow, those of burned originals circulate for
+/// good, and any other is orphaned
+fn audit_unpaired(
+    chain: &impl Chain,
+    collection: &EntangledCollection,
+    originals: &[Pubkey],
+    paired: &HashSet<Pubkey>,
+    report: &mut CollectionReport,
+) -> Result<()> {
+    if collection.is_compressed() {
+        return Ok(());
+    }
+    let authorities = authorities(collection);
+
+    let unpaired: Vec<(Pubkey, Pubkey)> = originals
+        .iter()
+        .map(|original| (*original, find_entangled_mint(&collection.id, original).0))
+        .filter(|(_, entangled_mint)| !paired.contains(entangled_mint))
+        .collect();
+    let mint_keys: Vec<Pubkey> = unpaired
+        .iter()
+        .flat_map(|(original_mint, entangled_mint)| [*original_mint, *entangled_mint])
+        .collect();
+    let mints = chain.accounts(&mint_keys)?;
+    // Only the originals initialized in this collection have an entangled mint
+    let unpaired: Vec<(Pubkey, Pubkey)> = unpaired
+        .into_iter()
+        .filter(|(_, entangled_mint)| mints.contains_key(entangled_mint))
+        .collect();
+
+    let mut custody_keys = Vec::new();
+    for (original_mint, entangled_mint) in &unpaired {
+        for authority in &authorities {
+            custody_keys.push(escrow(&mints, authority, original_mint));
+            custody_keys.push(escrow(&mints, authority, entangled_mint));
+        }
+    }
+    let custody = chain.accounts(&custody_keys)?;
+    let held = |mint: &Pubkey| {
+        authorities.iter().any(|authority| {
+            let account = escrow(&mints, authority, mint);
+            holds(custody.get(&account), mint, authority)
+        })
+    };
+
+    for (original_mint, entangled_mint) in unpaired {
+        let supplies = (
+            mint(mints.get(&original_mint)).map(|mint| mint.supply),
+            mint(mints.get(&entangled_mint)).map(|mint| mint.supply),
+        );
+        let reason = match supplies {
+            // Compressed originals are asset ids rather than mints
+            (None, _) => {
+                report.unverifiable += 1;
+                None
+            }
+            (Some(0), Some(supply)) => {
+                let emptied = authorities
+                    .iter()
+                    .map(|authority| escrow(&mints, authority, &entangled_mint))
+                    .find(|account| custody.contains_key(account))
+                    .unwrap_or_else(|| escrow(&mints, &authorities[0], &entangled_mint));
+                report.burned.push(BurnedPair {
+                    entangled_mint: entangled_mint.to_string(),
+                    escrow: emptied.to_string(),
+                    supply,
+                });
+                None
+            }
+            (Some(1), Some(1)) if held(&entangled_mint) && !held(&original_mint) => {
+                report.initialized += 1;
+                None
+            }
+            (Some(1), Some(1)) => Some(MismatchReason::Orphaned),
+            _ => Some(MismatchReason::InvalidSupply),
+        };
+
+        if let Some(reason) = reason {
+            report.mismatches.push(Mismatch {
+                pair: find_entangled_pair(&entangled_mint).0.to_string(),
+                original_mint: original_mint.to_string(),
+                entangled_mint: entangled_mint.to_string(),
+                reason,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use entangler_client::pda::find_collection_authority;
+
+    use super::*;
+    use crate::chain::tests::MemoryChain;
+
+    fn reasons(report: &CollectionReport) -> Vec<MismatchReason> {
+        report
+            .mismatches
+            .iter()
+            .map(|mismatch| mismatch.reason)
+            .collect()
+    }
+
+    #[test]
+    fn counts_entangled_and_disentangled_pairs() {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        chain.collection(&id, true, false);
+        let authority = find_collection_authority(&id).0;
+        let (_, entangled) = chain.pair(&id, None);
+        chain.escrow(&authority, &entangled.original_mint, 1);
+        let (_, disentangled) = chain.pair(&id, None);
+        chain.escrow(&authority, &disentangled.entangled_mint, 1);
+
+        let report = audit(&chain, None, 0).unwrap();
+        assert!(report.backed);
+        assert_eq!(report.collections.len(), 1);
+        let collection = &report.collections[0];
+        assert_eq!(collection.authority, authority.to_string());
+        assert_eq!((collection.entangled, collection.disentangled), (1, 1));
+        assert!(collection.mismatches.is_empty());
+    }
+
+    #[test]
+    fn flags_unbacked_and_double_held_pairs() {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        chain.collection(&id, true, false);
+        let authority = find_collection_authority(&id).0;
+        let (unbacked_address, _) = chain.pair(&id, None);
+        let (_, double_held) = chain.pair(&id, None);
+        chain.escrow(&authority, &double_held.original_mint, 1);
+        chain.escrow(&authority, &double_held.entangled_mint, 1);
+
+        let report = audit(&chain, None, 0).unwrap();
+        assert!(!report.backed);
+        let collection = &report.collections[0];
+        assert_eq!((collection.entangled, collection.disentangled), (0, 0));
+        let unbacked = collection
+            .mismatches
+            .iter()
+            .find(|mismatch| mismatch.pair == unbacked_address.to_string())
+            .unwrap();
+        assert_eq!(unbacked.reason, MismatchReason::Unbacked);
+        let mut found = reasons(collection);
+        found.sort_by_key(|reason| *reason as u8);
+        assert_eq!(
+            found,
+            [MismatchReason::Unbacked, MismatchReason::DoubleHeld]
+        );
+    }
+
+    #[test]
+    fn flags_mints_without_a_supply_of_one() {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        chain.collection(&id, true, false);
+        let authority = find_collection_authority(&id).0;
+        let (_, pair) = chain.pair(&id, None);
+        chain.escrow(&authority, &pair.original_mint, 1);
+        chain.mint_at(pair.entangled_mint, 2);
+
+        let report = audit(&chain, None, 0).unwrap();
+        assert!(!report.backed);
+        assert_eq!(
+            reasons(&report.collections[0]),
+            [MismatchReason::InvalidSupply]
+        );
+    }
+
+    #[test]
+    fn accepts_originals_frozen_for_the_authority() {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        chain.collection(&id, true, true);
+        let authority = find_collection_authority(&id).0;
+        let holder = Pubkey::new_unique();
+
+        let frozen = Pubkey::new_unique();
+        let (_, pair) = chain.pair(&id, Some(frozen));
+        chain.token(frozen, &holder, &pair.original_mint, 1, Some(authority));
+        let report = audit(&chain, None, 0).unwrap();
+        assert!(report.backed);
+        assert_eq!(report.collections[0].entangled, 1);
+
+        // Thawed, or frozen for anyone else, the original no longer backs the entangled token
+        chain.token(frozen, &holder, &pair.original_mint, 1, None);
+        let report = audit(&chain, None, 0).unwrap();
+        assert_eq!(reasons(&report.collections[0]), [MismatchReason::Unbacked]);
+        chain.token(frozen, &holder, &pair.original_mint, 1, Some(holder));
+        let report = audit(&chain, None, 0).unwrap();
+        assert_eq!(reasons(&report.collections[0]), [MismatchReason::Unbacked]);
+    }
+
+    #[test]
+    fn accepts_pairs_held_by_either_authority_after_migration() {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        chain.collection(&id, true, false);
+        let global = find_entangler_authority().0;
+        let scoped = find_collection_authority(&id).0;
+        let (_, unmigrated) = chain.pair(&id, None);
+        chain.escrow(&global, &unmigrated.original_mint, 1);
+        let (_, migrated) = chain.pair(&id, None);
+        chain.escrow(&scoped, &migrated.original_mint, 1);
+        let (_, disentangled) = chain.pair(&id, None);
+        chain.escrow(&global, &disentangled.entangled_mint, 1);
+
+        let report = audit(&chain, None, 0).unwrap();
+        assert!(report.backed);
+        let collection = &report.collections[0];
+        assert_eq!((collection.entangled, collection.disentangled), (2, 1));
+
+        // Collections still under the global authority have no tokens with their own
+        let global_id = Pubkey::new_unique();
+        chain.collection(&global_id, false, false);
+        let (_, pair) = chain.pair(&global_id, None);
+        chain.escrow(
+            &find_collection_authority(&global_id).0,
+            &pair.original_mint,
+            1,
+        );
+        let report = audit(&chain, Some(global_id), 0).unwrap();
+        assert_eq!(report.collections[0].authority, global.to_string());
+        assert_eq!(reasons(&report.collections[0]), [MismatchReason::Unbacked]);
+    }
+
+    #[test]
+    fn attributes_escrowed_tokens_outside_of_pairs() {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        let collection = chain.collection(&id, true, false);
+        let authority = find_collection_authority(&id).0;
+
+        // The entangled mint of a pair closed by burning its original
+        let burned_mint = chain.mint(1);
+        chain.metadata(&burned_mint, &collection.entangled_collection_mint);
+        let burned_escrow = chain.escrow(&authority, &burned_mint, 0);
+        // A token sent to the authority
+        let stray_mint = chain.mint(1);
+        let stray_escrow = chain.escrow(&authority, &stray_mint, 1);
+        // An emptied escrow of a token of another collection
+        let other_mint = chain.mint(1);
+        chain.metadata(&other_mint, &Pubkey::new_unique());
+        chain.escrow(&authority, &other_mint, 0);
+
+        let report = audit(&chain, None, 0).unwrap();
+        assert!(report.backed);
+        let burned = &report.collections[0].burned;
+        assert_eq!(burned.len(), 1);
+        assert_eq!(burned[0].entangled_mint, burned_mint.to_string());
+        assert_eq!(burned[0].escrow, burned_escrow.to_string());
+        assert_eq!(burned[0].supply, 1);
+        assert_eq!(report.stray_tokens.len(), 1);
+        let stray = &report.stray_tokens[0];
+        assert_eq!(stray.authority, authority.to_string());
+        assert_eq!(stray.account, stray_escrow.to_string());
+        assert_eq!(stray.mint, stray_mint.to_string());
+        assert_eq!(stray.amount, 1);
+    }
+
+    #[test]
+    fn checks_entangled_mints_without_a_pair() {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        chain.collection(&id, true, false);
+        let authority = find_collection_authority(&id).0;
+        let (_, pair) = chain.pair(&id, None);
+        chain.escrow(&authority, &pair.original_mint, 1);
+
+        // Initialized but never entangled, the entangled token waits in its escrow
+        let (_, initialized) = chain.unpaired(&id);
+        chain.escrow(&authority, &initialized, 1);
+        // The pair was closed by burning the original
+        let (burned_original, burned) = chain.unpaired(&id);
+        chain.mint_at(burned_original, 0);
+        let burned_escrow = chain.escrow(&authority, &burned, 0);
+        // The entangled token circulates with no pair backing it
+        let (_, orphaned) = chain.unpaired(&id);
+
+        let report = audit(&chain, None, 0).unwrap();
+        assert!(!report.backed);
+        assert!(report.stray_tokens.is_empty());
+        let collection = &report.collections[0];
+        assert_eq!((collection.entangled, collection.initialized), (1, 1));
+        assert_eq!(collection.burned.len(), 1);
+        assert_eq!(collection.burned[0].entangled_mint, burned.to_string());
+        assert_eq!(collection.burned[0].escrow, burned_escrow.to_string());
+        assert_eq!(reasons(collection), [MismatchReason::Orphaned]);
+        assert_eq!(collection.mismatches[0].entangled_mint, orphaned.to_string());
+
+        // Back in its escrow, the orphaned token waits for an entanglement like the other
+        chain.escrow(&authority, &orphaned, 1);
+        let report = audit(&chain, None, 0).unwrap();
+        assert!(report.backed);
+        assert_eq!(report.collections[0].initialized, 2);
+    }
+
+    #[test]
+    fn reports_pairs_of_no_collection() {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        chain.collection(&id, true, false);
+        let (address, _) = chain.pair(&Pubkey::new_unique(), None);
+
+        let report = audit(&chain, None, 0).unwrap();
+        assert!(!report.backed);
+        assert_eq!(report.unattributed_pairs, [address.to_string()]);
+        let report = audit(&chain, Some(id), 0).unwrap();
+        assert!(report.unattributed_pairs.is_empty());
+    }
+}
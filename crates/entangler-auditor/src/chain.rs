@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anchor_lang::{prelude::Pubkey, Discriminator};
+use anyhow::Result;
+use entangler_client::accounts::{
+    EntangledCollection, EntangledPair, EntanglerAccount, OriginalRecord,
+};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+    rpc_request::TokenAccountsFilter,
+};
+use solana_sdk::account::Account;
+
+/// Reads the program and token accounts the audit checks. `RpcChain` serves them from an RPC
+/// node
+pub trait Chain {
+    /// Every account of the program starting with a discriminator
+    fn program_accounts(&self, discriminator: [u8; 8]) -> Result<Vec<(Pubkey, Account)>>;
+
+    /// Fetches many accounts at once, leaving out those that don't exist
+    fn accounts(&self, keys: &[Pubkey]) -> Result<HashMap<Pubkey, Account>>;
+
+    /// Every token account an authority owns, with either token program
+    fn token_accounts(&self, authority: &Pubkey) -> Result<Vec<Pubkey>>;
+
+    fn slot(&self) -> Result<u64>;
+
+    /// Every account of the program starting with a discriminator, decoded
+    fn entangler_accounts(
+        &self,
+        discriminator: [u8; 8],
+    ) -> Result<Vec<(Pubkey, EntanglerAccount)>> {
+        Ok(self
+            .program_accounts(discriminator)?
+            .into_iter()
+            .filter_map(|(address, account)| {
+                Some((address, EntanglerAccount::try_from_bytes(&account.data)?))
+            })
+            .collect())
+    }
+
+    fn collections(&self) -> Result<Vec<EntangledCollection>> {
+        Ok(self
+            .entangler_accounts(EntangledCollection::DISCRIMINATOR)?
+            .into_iter()
+            .filter_map(|(_, account)| match account {
+                EntanglerAccount::Collection(collection) => Some(collection),
+                _ => None,
+            })
+            .collect())
+    }
+
+    fn pairs(&self) -> Result<Vec<(Pubkey, EntangledPair)>> {
+        Ok(self
+            .entangler_accounts(EntangledPair::DISCRIMINATOR)?
+            .into_iter()
+            .filter_map(|(address, account)| match account {
+                EntanglerAccount::Pair(pair) => Some((address, pair)),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// The originals of every initialized pair, which outlive pairs closed by burns
+    fn original_records(&self) -> Result<Vec<Pubkey>> {
+        Ok(self
+            .entangler_accounts(OriginalRecord::DISCRIMINATOR)?
+            .into_iter()
+            .filter_map(|(_, account)| match account {
+                EntanglerAccount::OriginalRecord(record) => Some(record.original_mint),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+pub struct RpcChain {
+    pub rpc: RpcClient,
+}
+
+impl Chain for RpcChain {
+    fn program_accounts(&self, discriminator: [u8; 8]) -> Result<Vec<(Pubkey, Account)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                0,
+                discriminator.to_vec(),
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        Ok(self
+            .rpc
+            .get_program_accounts_with_config(&entangler_client::ID, config)?)
+    }
+
+    fn accounts(&self, keys: &[Pubkey]) -> Result<HashMap<Pubkey, Account>> {
+        let mut fetched = HashMap::new();
+        for chunk in keys.chunks(100) {
+            let accounts = self.rpc.get_multiple_accounts(chunk)?;
+            for (key, account) in chunk.iter().zip(accounts) {
+                if let Some(account) = account {
+                    fetched.insert(*key, account);
+                }
+            }
+        }
+        Ok(fetched)
+    }
+
+    fn token_accounts(&self, authority: &Pubkey) -> Result<Vec<Pubkey>> {
+        let mut addresses = Vec::new();
+        for program in [anchor_spl::token::ID, anchor_spl::token_2022::ID] {
+            let accounts = self
+                .rpc
+                .get_token_accounts_by_owner(authority, TokenAccountsFilter::ProgramId(program))?;
+            for account in accounts {
+                addresses.push(Pubkey::from_str(&account.pubkey)?);
+            }
+        }
+        Ok(addresses)
+    }
+
+    fn slot(&self) -> Result<u64> {
+        Ok(self.rpc.get_slot()?)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use anchor_lang::{solana_program::program_pack::Pack, AccountSerialize};
+    use anchor_spl::{associated_token::get_associated_token_address, token::spl_token};
+    use entangler_client::{
+        accounts::EntangledStandard,
+        pda::{
+            find_entangled_collection, find_entangled_mint, find_entangled_pair, find_metadata,
+            find_original_record,
+        },
+    };
+    use mpl_token_metadata::state::{Collection, Data, Key, Metadata, MAX_METADATA_LEN};
+    use solana_sdk::program_option::COption;
+
+    use super::*;
+
+    /// A chain held in memory, with every token minted by the classic token program
+    #[derive(Default)]
+    pub struct MemoryChain {
+        pub accounts: HashMap<Pubkey, Account>,
+    }
+
+    impl MemoryChain {
+        pub fn set(&mut self, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+            let account = Account {
+                lamports: 1,
+                data,
+                owner,
+                executable: false,
+                rent_epoch: 0,
+            };
+            self.accounts.insert(address, account);
+        }
+
+        pub fn mint(&mut self, supply: u64) -> Pubkey {
+            let mint = Pubkey::new_unique();
+            self.mint_at(mint, supply);
+            mint
+        }
+
+        pub fn mint_at(&mut self, mint: Pubkey, supply: u64) {
+            let mut data = vec![0; spl_token::state::Mint::LEN];
+            let state = spl_token::state::Mint {
+                supply,
+                is_initialized: true,
+                ..Default::default()
+            };
+            spl_token::state::Mint::pack(state, &mut data).unwrap();
+            self.set(mint, spl_token::ID, data);
+        }
+
+        /// Writes a token account of `owner`, frozen for `delegate` if there is one
+        pub fn token(
+            &mut self,
+            address: Pubkey,
+            owner: &Pubkey,
+            mint: &Pubkey,
+            amount: u64,
+            delegate: Option<Pubkey>,
+        ) {
+            let mut data = vec![0; spl_token::state::Account::LEN];
+            let token = spl_token::state::Account {
+                mint: *mint,
+                owner: *owner,
+                amount,
+                delegate: delegate.map_or(COption::None, COption::Some),
+                delegated_amount: delegate.map_or(0, |_| amount),
+                state: if delegate.is_some() {
+                    spl_token::state::AccountState::Frozen
+                } else {
+                    spl_token::state::AccountState::Initialized
+                },
+                ..Default::default()
+            };
+            spl_token::state::Account::pack(token, &mut data).unwrap();
+            self.set(address, spl_token::ID, data);
+        }
+
+        /// Writes the associated token account of `owner` for `mint`
+        pub fn escrow(&mut self, owner: &Pubkey, mint: &Pubkey, amount: u64) -> Pubkey {
+            let address = get_associated_token_address(owner, mint);
+            self.token(address, owner, mint, amount, None);
+            address
+        }
+
+        /// Writes the collection entangled under `id`
+        pub fn collection(
+            &mut self,
+            id: &Pubkey,
+            scoped_authority: bool,
+            non_custodial: bool,
+        ) -> EntangledCollection {
+            let collection = EntangledCollection {
+                id: *id,
+                original_collection_mint: Pubkey::new_unique(),
+                entangled_collection_mint: Pubkey::new_unique(),
+                royalties: 500,
+                one_way: false,
+                allow_unverified: false,
+                original_creator: None,
+                entangled_standard: EntangledStandard::NonFungible,
+                rule_set: None,
+                transfer_hook_program: None,
+                entangled_tree: None,
+                non_custodial,
+                scoped_authority,
+            };
+            let mut data = Vec::new();
+            collection.try_serialize(&mut data).unwrap();
+            self.set(find_entangled_collection(id).0, entangler_client::ID, data);
+            collection
+        }
+
+        /// Writes a pair of the collection entangled under `id`, the record of its original and
+        /// both its mints, with one token each in circulation
+        pub fn pair(
+            &mut self,
+            id: &Pubkey,
+            frozen_account: Option<Pubkey>,
+        ) -> (Pubkey, EntangledPair) {
+            let original_mint = self.mint(1);
+            let entangled_mint = find_entangled_mint(id, &original_mint).0;
+            self.mint_at(entangled_mint, 1);
+            self.record(original_mint);
+            let pair = EntangledPair {
+                original_mint,
+                entangled_mint,
+                frozen_account,
+                migrated: true,
+            };
+            let address = find_entangled_pair(&entangled_mint).0;
+            let mut data = Vec::new();
+            pair.try_serialize(&mut data).unwrap();
+            self.set(address, entangler_client::ID, data);
+            (address, pair)
+        }
+
+        /// Writes the record initializing a pair leaves for its original
+        pub fn record(&mut self, original_mint: Pubkey) {
+            let mut data = Vec::new();
+            OriginalRecord { original_mint }
+                .try_serialize(&mut data)
+                .unwrap();
+            self.set(
+                find_original_record(&original_mint).0,
+                entangler_client::ID,
+                data,
+            );
+        }
+
+        /// Writes an original initialized under `id` with its record, and both its mints, with
+        /// one token each in circulation, but no pair
+        pub fn unpaired(&mut self, id: &Pubkey) -> (Pubkey, Pubkey) {
+            let original_mint = self.mint(1);
+            let entangled_mint = find_entangled_mint(id, &original_mint).0;
+            self.mint_at(entangled_mint, 1);
+            self.record(original_mint);
+            (original_mint, entangled_mint)
+        }
+
+        /// Writes the Metaplex metadata of `mint`, verified in `collection_mint`
+        pub fn metadata(&mut self, mint: &Pubkey, collection_mint: &Pubkey) {
+            let metadata = Metadata {
+                key: Key::MetadataV1,
+                update_authority: Pubkey::new_unique(),
+                mint: *mint,
+                data: Data {
+                    name: String::new(),
+                    symbol: String::new(),
+                    uri: String::new(),
+                    seller_fee_basis_points: 500,
+                    creators: None,
+                },
+                primary_sale_happened: false,
+                is_mutable: true,
+                edition_nonce: None,
+                token_standard: None,
+                collection: Some(Collection {
+                    verified: true,
+                    key: *collection_mint,
+                }),
+                uses: None,
+                collection_details: None,
+                programmable_config: None,
+            };
+            let mut data = vec![0; MAX_METADATA_LEN];
+            metadata.save(&mut data).unwrap();
+            self.set(find_metadata(mint).0, mpl_token_metadata::ID, data);
+        }
+    }
+
+    impl Chain for MemoryChain {
+        fn program_accounts(&self, discriminator: [u8; 8]) -> Result<Vec<(Pubkey, Account)>> {
+            Ok(self
+                .accounts
+                .iter()
+                .filter(|(_, account)| {
+                    account.owner == entangler_client::ID
+                        && account.data.starts_with(&discriminator)
+                })
+                .map(|(address, account)| (*address, account.clone()))
+                .collect())
+        }
+
+        fn accounts(&self, keys: &[Pubkey]) -> Result<HashMap<Pubkey, Account>> {
+            Ok(keys
+                .iter()
+                .filter_map(|key| Some((*key, self.accounts.get(key)?.clone())))
+                .collect())
+        }
+
+        fn token_accounts(&self, authority: &Pubkey) -> Result<Vec<Pubkey>> {
+            Ok(self
+                .accounts
+                .iter()
+                .filter(|(_, account)| account.owner == spl_token::ID)
+                .filter(|(_, account)| {
+                    spl_token::state::Account::unpack(&account.data)
+                        .is_ok_and(|token| token.owner == *authority)
+                })
+                .map(|(address, _)| *address)
+                .collect())
+        }
+
+        fn slot(&self) -> Result<u64> {
+            Ok(1)
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Proof-of-reserves auditor for the entangler: checks that every circulating entangled token is
+//! backed by an original held by the entangler, and emits a signed JSON report
+
+mod audit;
+mod chain;
+mod report;
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Result};
+use chain::RpcChain;
+use clap::{Parser, Subcommand};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, signature::read_keypair_file};
+
+#[derive(Parser)]
+#[command(name = "entangler-auditor")]
+struct Cli {
+    /// The RPC URL, such as a local validator's
+    #[arg(
+        short,
+        long,
+        env = "ANCHOR_PROVIDER_URL",
+        default_value = "http://127.0.0.1:8899"
+    )]
+    url: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Audits every collection, or only one, and writes the report signed by the auditor
+    Audit {
+        /// The keypair signing the report
+        #[arg(
+            short,
+            long,
+            env = "ANCHOR_WALLET",
+            default_value = "~/.config/solana/id.json"
+        )]
+        keypair: String,
+        #[arg(long)]
+        id: Option<Pubkey>,
+        /// Where to write the report, the standard output by default
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Checks the signature of a report and prints the auditor who signed it
+    Verify { report: PathBuf },
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{home}/{rest}"),
+        _ => path.to_string(),
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Audit {
+            keypair,
+            id,
+            output,
+        } => {
+            let auditor = read_keypair_file(expand_home(&keypair))
+                .map_err(|err| anyhow!("Could not read {keypair}: {err}"))?;
+            let chain = RpcChain {
+                rpc: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+            };
+            let audited_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+            let report = audit::audit(&chain, id, audited_at)?;
+            let signed = report.sign(&auditor)?;
+            match output {
+                Some(output) => std::fs::write(output, signed)?,
+                None => println!("{signed}"),
+            }
+
+            let mismatches: usize = report
+                .collections
+                .iter()
+                .map(|collection| collection.mismatches.len())
+                .sum();
+            eprintln!(
+                "{} collections, {mismatches} mismatches, {} unattributed pairs, {} stray tokens",
+                report.collections.len(),
+                report.unattributed_pairs.len(),
+                report.stray_tokens.len(),
+            );
+            if !report.backed {
+                std::process::exit(1);
+            }
+        }
+        Command::Verify { report } => {
+            let auditor = report::verify(&std::fs::read_to_string(report)?)?;
+            println!("{auditor}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use solana_sdk::{
+    signature::{Keypair, Signature},
+    signer::Signer,
+};
+
+/// The custody of every audited collection at a slot
+#[derive(Serialize, Deserialize)]
+pub struct Report {
+    pub program: String,
+    pub slot: u64,
+    /// Unix timestamp of the audit
+    pub audited_at: u64,
+    /// Whether every circulating entangled token is backed, burned pairs aside
+    pub backed: bool,
+    pub collections: Vec<CollectionReport>,
+    /// Pairs whose entangled mint doesn't derive from any collection and their original
+    pub unattributed_pairs: Vec<String>,
+    /// Tokens held by an entangler authority outside of any pair
+    pub stray_tokens: Vec<StrayToken>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CollectionReport {
+    pub id: String,
+    pub authority: String,
+    /// Pairs whose original is held, their entangled token circulating
+    pub entangled: u64,
+    /// Pairs whose entangled token is held, their original circulating
+    pub disentangled: u64,
+    /// Originals initialized but never entangled, their entangled token held
+    pub initialized: u64,
+    /// Pairs with a compressed token, whose custody only a merkle proof can show
+    pub unverifiable: u64,
+    pub mismatches: Vec<Mismatch>,
+    pub burned: Vec<BurnedPair>,
+}
+
+/// A pair breaking the custody invariant
+#[derive(Serialize, Deserialize)]
+pub struct Mismatch {
+    /// The pair, or the address it would have for orphaned entangled mints
+    pub pair: String,
+    pub original_mint: String,
+    pub entangled_mint: String,
+    pub reason: MismatchReason,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MismatchReason {
+    /// Neither token is held, so the circulating entangled token isn't backed
+    Unbacked,
+    /// Both tokens are held
+    DoubleHeld,
+    /// A mint of the pair doesn't have a supply of one
+    InvalidSupply,
+    /// An entangled mint has no pair, yet its token circulates or its original is held
+    Orphaned,
+}
+
+/// An entangled token whose original was burned, circulating without backing for good
+#[derive(Serialize, Deserialize)]
+pub struct BurnedPair {
+    pub entangled_mint: String,
+    /// The emptied escrow the entangled token was minted into
+    pub escrow: String,
+    pub supply: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct StrayToken {
+    pub authority: String,
+    pub account: String,
+    pub mint: String,
+    pub amount: u64,
+}
+
+/// A report with the auditor's signature over its exact JSON bytes
+#[derive(Serialize, Deserialize)]
+struct SignedReport {
+    report: Box<RawValue>,
+    auditor: String,
+    signature: String,
+}
+
+impl Report {
+    /// Serializes the report signed by the auditor's keypair
+    pub fn sign(&self, auditor: &Keypair) -> Result<String> {
+        let report = serde_json::to_string(self)?;
+        let signature = auditor.sign_message(report.as_bytes());
+        Ok(serde_json::to_string(&SignedReport {
+            report: RawValue::from_string(report)?,
+            auditor: auditor.pubkey().to_string(),
+            signature: signature.to_string(),
+        })?)
+    }
+}
+
+/// Checks the signature of a signed report, returning the auditor who signed it
+pub fn verify(signed: &str) -> Result<Pubkey> {
+    let signed: SignedReport = serde_json::from_str(signed)?;
+    let auditor = Pubkey::from_str(&signed.auditor)?;
+    let signature = Signature::from_str(&signed.signature)?;
+    if !signature.verify(auditor.as_ref(), signed.report.get().as_bytes()) {
+        bail!("The signature of {auditor} doesn't match the report");
+    }
+    Ok(auditor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        Report {
+            program: entangler_client::ID.to_string(),
+            slot: 1,
+            audited_at: 2,
+            backed: true,
+            collections: Vec::new(),
+            unattributed_pairs: Vec::new(),
+            stray_tokens: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn verifies_signed_reports() {
+        let auditor = Keypair::new();
+        let signed = report().sign(&auditor).unwrap();
+        assert_eq!(verify(&signed).unwrap(), auditor.pubkey());
+
+        let parsed: SignedReport = serde_json::from_str(&signed).unwrap();
+        let report: Report = serde_json::from_str(parsed.report.get()).unwrap();
+        assert!(report.backed);
+        assert_eq!(report.slot, 1);
+    }
+
+    #[test]
+    fn rejects_tampered_reports() {
+        let signed = report().sign(&Keypair::new()).unwrap();
+        let tampered = signed.replace("\"backed\":true", "\"backed\":false");
+        assert_ne!(tampered, signed);
+        assert!(verify(&tampered).is_err());
+    }
+
+    #[test]
+    fn rejects_reports_claiming_another_auditor() {
+        let auditor = Keypair::new();
+        let signed = report().sign(&auditor).unwrap();
+        let other = Keypair::new().pubkey().to_string();
+        let forged = signed.replace(&auditor.pubkey().to_string(), &other);
+        assert!(verify(&forged).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use anyhow::{anyhow, bail, Context as _, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use entangler_client::{
+    accounts::{deserialize, EntangledCollection, EntanglerState},
+    pda::{find_entangled_collection, find_entangler_state, find_metadata},
+    EntanglerClient,
+};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+
+/// The RPC connection and the keypair paying for and signing every transaction
+pub struct Context {
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+}
+
+impl Context {
+    pub fn new(url: String, payer: Keypair) -> Self {
+        Self {
+            rpc: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+            payer,
+        }
+    }
+
+    /// Sends the instructions in a single transaction and waits for its confirmation
+    pub fn send(&self, instructions: &[Instruction]) -> Result<Signature> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            blockhash,
+        );
+        let signature = self
+            .rpc
+            .send_and_confirm_transaction_with_spinner(&transaction)?;
+        Ok(signature)
+    }
+
+    /// Simulates the instruction and returns the data it returned
+    pub fn simulate(&self, instruction: Instruction) -> Result<Vec<u8>> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            blockhash,
+        );
+        let simulation = self.rpc.simulate_transaction(&transaction)?.value;
+        if let Some(err) = simulation.err {
+            let logs = simulation.logs.unwrap_or_default().join("\n");
+            bail!("The simulation failed: {err}\n{logs}");
+        }
+        let Some(return_data) = simulation.return_data else {
+            bail!("The instruction returned no data");
+        };
+        Ok(STANDARD.decode(return_data.data.0)?)
+    }
+
+    /// Fetches and deserializes an entangler account
+    pub fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        let account = self
+            .rpc
+            .get_account(address)
+            .with_context(|| format!("Could not fetch {address}"))?;
+        deserialize(&account.data).map_err(|err| anyhow!("Could not decode {address}: {err}"))
+    }
+
+    pub fn state(&self) -> Result<EntanglerState> {
+        self.fetch(&find_entangler_state().0)
+    }
+
+    pub fn collection(&self, id: &Pubkey) -> Result<EntangledCollection> {
+        self.fetch(&find_entangled_collection(id).0)
+    }
+
+    /// The token program owning a mint
+    pub fn token_program(&self, mint: &Pubkey) -> Result<Pubkey> {
+        Ok(self.rpc.get_account(mint)?.owner)
+    }
+
+    /// A client for an existing collection, reading its creator from the entangled collection metadata
+    pub fn client(&self, id: &Pubkey) -> Result<EntanglerClient> {
+        let collection = self.collection(id)?;
+        let metadata = self
+            .rpc
+            .get_account_data(&find_metadata(&collection.entangled_collection_mint).0)?;
+        let metadata = Metadata::safe_deserialize(&metadata)?;
+        let creator = metadata
+            .data
+            .creators
+            .as_ref()
+            .and_then(|creators| creators.get(1))
+            .ok_or_else(|| anyhow!("The entangled collection {id} has no creator"))?
+            .address;
+
+        let client = EntanglerClient::new(
+            collection.original_collection_mint,
+            self.payer.pubkey(),
+            *id,
+            creator,
+            collection.royalties,
+        )
+        .with_entangled_token_program(collection.entangled_token_program());
+        let client = if collection.non_custodial {
+            client.with_non_custodial()
+        } else {
+            client
+        };
+        Ok(if collection.scoped_authority {
+            client
+        } else {
+            client.with_global_authority()
+        })
+    }
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::Pubkey;
+use entangler_client::accounts::{EntanglerAccount, PairCustody};
+
+fn optional(key: &Option<Pubkey>) -> String {
+    key.map_or_else(|| "-".to_string(), |key| key.to_string())
+}
+
+/// Prints an entangler account, one field per line
+pub fn print_account(address: &Pubkey, account: &EntanglerAccount) {
+    match account {
+        EntanglerAccount::State(state) => {
+            println!("EntanglerState {address}");
+            println!("  admin:                     {}", state.admin);
+            println!("  earner:                    {}", state.earner);
+            println!("  fee mint:                  {}", state.fee_mint);
+            println!("  price:                     {}", state.price);
+            println!("  records backfilled:        {}", state.records_backfilled);
+        }
+        EntanglerAccount::Collection(collection) => {
+            println!("EntangledCollection {address}");
+            println!("  id:                        {}", collection.id);
+            println!(
+                "  original collection mint:  {}",
+                collection.original_collection_mint
+            );
+            println!(
+                "  entangled collection mint: {}",
+                collection.entangled_collection_mint
+            );
+            println!("  royalties:                 {}", collection.royalties);
+            println!("  one way:                   {}", collection.one_way);
+            println!(
+                "  allow unverified:          {}",
+                collection.allow_unverified
+            );
+            println!(
+                "  original creator:          {}",
+                optional(&collection.original_creator)
+            );
+            println!(
+                "  entangled standard:        {:?}",
+                collection.entangled_standard
+            );
+            println!(
+                "  rule set:                  {}",
+                optional(&collection.rule_set)
+            );
+            println!(
+                "  transfer hook program:     {}",
+                optional(&collection.transfer_hook_program)
+            );
+            println!(
+                "  entangled tree:            {}",
+                optional(&collection.entangled_tree)
+            );
+            println!("  non custodial:             {}", collection.non_custodial);
+            println!(
+                "  scoped authority:          {}",
+                collection.scoped_authority
+            );
+        }
+        EntanglerAccount::CollectionEntry(entry) => {
+            println!("CollectionEntry {address}");
+            println!("  id:                        {}", entry.id);
+            println!("  key:                       {}", entry.key);
+        }
+        EntanglerAccount::Pair(pair) => {
+            println!("EntangledPair {address}");
+            println!("  original mint:             {}", pair.original_mint);
+            println!("  entangled mint:            {}", pair.entangled_mint);
+            println!(
+                "  frozen account:            {}",
+                optional(&pair.frozen_account)
+            );
+            println!("  migrated:                  {}", pair.migrated);
+        }
+        EntanglerAccount::OriginalRecord(record) => {
+            println!("OriginalRecord {address}");
+            println!("  original mint:             {}", record.original_mint);
+        }
+    }
+}
+
+/// Prints the custody of a pair, as verified on chain
+pub fn print_custody(custody: &PairCustody) {
+    println!("PairCustody");
+    println!("  original mint:             {}", custody.original_mint);
+    println!("  entangled mint:            {}", custody.entangled_mint);
+    println!("  authority:                 {}", custody.authority);
+    println!("  entangled:                 {}", custody.entangled);
+    println!("  custody account:           {}", custody.custody_account);
+}
@@ -0,0 +1,466 @@
+//! Admin command line for the entangler program
+
+mod context;
+mod display;
+mod manifest;
+
+use std::path::PathBuf;
+
+use anchor_lang::{prelude::Pubkey, Discriminator};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anyhow::{anyhow, bail, Result};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use context::Context;
+use entangler_client::{
+    accounts::{
+        deserialize_custody, CollectionEntry, EntangledCollection, EntangledPair,
+        EntangledStandard, EntanglerAccount, EntanglerState, OriginalRecord,
+    },
+    instructions::{resize_account, set_entangler_state, set_records_backfilled},
+    pda::{find_entangled_mint, find_entangled_pair, find_entangler_authority},
+    EntanglerClient,
+};
+use manifest::{Manifest, Plan};
+use serde::Deserialize;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{signature::read_keypair_file, signer::Signer};
+
+#[derive(Parser)]
+#[command(name = "entangler", about = "Administrates the entangler program")]
+struct Cli {
+    /// The RPC URL, such as a local validator's
+    #[arg(
+        short,
+        long,
+        env = "ANCHOR_PROVIDER_URL",
+        default_value = "http://127.0.0.1:8899"
+    )]
+    url: String,
+
+    /// The keypair paying for and signing the transactions
+    #[arg(
+        short,
+        long,
+        env = "ANCHOR_WALLET",
+        default_value = "~/.config/solana/id.json"
+    )]
+    keypair: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Sets the admin, the fee earner and the entry price
+    SetState {
+        #[arg(long)]
+        admin: Pubkey,
+        #[arg(long)]
+        earner: Pubkey,
+        #[arg(long)]
+        fee_mint: Pubkey,
+        /// The price of an entry, in base units of the fee mint
+        #[arg(long)]
+        price: u64,
+    },
+    /// Creates an entangled collection
+    CreateCollection {
+        #[arg(long)]
+        id: Pubkey,
+        #[arg(long)]
+        original_collection_mint: Pubkey,
+        /// The creator receiving royalties
+        #[arg(long)]
+        creator: Pubkey,
+        /// The royalties in basis points
+        #[arg(long)]
+        royalties: u16,
+        #[arg(long)]
+        one_way: bool,
+        #[arg(long)]
+        allow_unverified: bool,
+        #[arg(long)]
+        original_creator: Option<Pubkey>,
+        #[arg(long, value_enum, default_value_t = Standard::NonFungible)]
+        standard: Standard,
+        #[arg(long)]
+        rule_set: Option<Pubkey>,
+        #[arg(long)]
+        transfer_hook_program: Option<Pubkey>,
+        #[arg(long)]
+        non_custodial: bool,
+    },
+    /// Creates the entry mapping a key to a collection, paying the state's price
+    CreateEntry {
+        #[arg(long)]
+        id: Pubkey,
+        #[arg(long)]
+        key: String,
+    },
+    /// Creates the entangled mint of an original
+    InitializePair {
+        #[arg(long)]
+        id: Pubkey,
+        #[arg(long)]
+        original_mint: Pubkey,
+    },
+    /// Swaps an original token for its entangled token
+    Entangle {
+        #[arg(long)]
+        id: Pubkey,
+        #[arg(long)]
+        original_mint: Pubkey,
+        #[arg(long)]
+        authorization_rules: Option<Pubkey>,
+        #[arg(long)]
+        entangled_authorization_rules: Option<Pubkey>,
+    },
+    /// Swaps an entangled token back for its original
+    Disentangle {
+        #[arg(long)]
+        id: Pubkey,
+        #[arg(long)]
+        original_mint: Pubkey,
+        #[arg(long)]
+        authorization_rules: Option<Pubkey>,
+        #[arg(long)]
+        entangled_authorization_rules: Option<Pubkey>,
+    },
+    /// Burns an escrowed original, preventing its disentanglement
+    BurnOriginal {
+        #[arg(long)]
+        id: Pubkey,
+        #[arg(long)]
+        original_mint: Pubkey,
+    },
+    /// Moves a collection from the global authority to its own
+    MigrateCollection {
+        #[arg(long)]
+        id: Pubkey,
+    },
+    /// Moves the escrowed token and the authorities of a pair to its collection's authority
+    MigratePair {
+        #[arg(long)]
+        id: Pubkey,
+        #[arg(long)]
+        original_mint: Pubkey,
+        #[arg(long)]
+        authorization_rules: Option<Pubkey>,
+        #[arg(long)]
+        entangled_authorization_rules: Option<Pubkey>,
+    },
+    /// Grows the state, a collection or a pair created with an older layout to the current one
+    ResizeAccount { address: Pubkey },
+    /// Checks that the entangler holds exactly one token of a pair, and prints its custody
+    VerifyPair {
+        #[arg(long)]
+        id: Pubkey,
+        #[arg(long)]
+        original_mint: Pubkey,
+    },
+    /// Records an original initialized before originals got a record
+    RecordOriginal {
+        #[arg(long)]
+        id: Pubkey,
+        #[arg(long)]
+        original_mint: Pubkey,
+    },
+    /// Declares whether every original initialized before records existed got one, which
+    /// rescues from the global authority require
+    SetRecordsBackfilled {
+        #[arg(action = ArgAction::Set)]
+        records_backfilled: bool,
+    },
+    /// Moves tokens backing no pair of the collection out of an account of its authority
+    RescueTokens {
+        #[arg(long)]
+        id: Pubkey,
+        #[arg(long)]
+        mint: Pubkey,
+        /// Rescues from the global authority, which still holds tokens of migrating collections
+        #[arg(long)]
+        global_authority: bool,
+        /// The account holding the tokens, the authority's associated token account by default
+        #[arg(long)]
+        source: Option<Pubkey>,
+        #[arg(long)]
+        destination: Pubkey,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Moves the lamports the collection's authority holds beyond its rent exemption to the earner
+    SweepAuthorityLamports {
+        #[arg(long)]
+        id: Pubkey,
+    },
+    /// Prints any entangler account
+    Show { address: Pubkey },
+    /// Prints every entangler account of a kind
+    List {
+        #[arg(value_enum)]
+        kind: Kind,
+    },
+    /// Prints the changes needed for the chain to match a TOML or JSON manifest
+    Plan { manifest: PathBuf },
+    /// Applies the changes needed for the chain to match a TOML or JSON manifest
+    Apply { manifest: PathBuf },
+}
+
+/// The standard of the entangled tokens, as the command line and manifests spell it
+#[derive(Clone, Copy, Default, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum Standard {
+    #[default]
+    NonFungible,
+    ProgrammableNonFungible,
+    Token2022,
+    Compressed,
+}
+
+impl From<Standard> for EntangledStandard {
+    fn from(standard: Standard) -> Self {
+        match standard {
+            Standard::NonFungible => EntangledStandard::NonFungible,
+            Standard::ProgrammableNonFungible => EntangledStandard::ProgrammableNonFungible,
+            Standard::Token2022 => EntangledStandard::Token2022,
+            Standard::Compressed => EntangledStandard::Compressed,
+        }
+    }
+}
+
+#[derive(Clone, ValueEnum)]
+enum Kind {
+    State,
+    Collections,
+    Entries,
+    Pairs,
+    Originals,
+}
+
+impl Kind {
+    fn discriminator(&self) -> [u8; 8] {
+        match self {
+            Kind::State => EntanglerState::DISCRIMINATOR,
+            Kind::Collections => EntangledCollection::DISCRIMINATOR,
+            Kind::Entries => CollectionEntry::DISCRIMINATOR,
+            Kind::Pairs => EntangledPair::DISCRIMINATOR,
+            Kind::Originals => OriginalRecord::DISCRIMINATOR,
+        }
+    }
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{home}/{rest}"),
+        _ => path.to_string(),
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let payer = read_keypair_file(expand_home(&cli.keypair))
+        .map_err(|err| anyhow!("Could not read {}: {err}", cli.keypair))?;
+    let ctx = Context::new(cli.url, payer);
+
+    let instruction = match cli.command {
+        Command::SetState {
+            admin,
+            earner,
+            fee_mint,
+            price,
+        } => set_entangler_state(ctx.payer.pubkey(), admin, earner, fee_mint, price),
+        Command::CreateCollection {
+            id,
+            original_collection_mint,
+            creator,
+            royalties,
+            one_way,
+            allow_unverified,
+            original_creator,
+            standard,
+            rule_set,
+            transfer_hook_program,
+            non_custodial,
+        } => EntanglerClient::new(
+            original_collection_mint,
+            ctx.payer.pubkey(),
+            id,
+            creator,
+            royalties,
+        )
+        .create_collection(
+            one_way,
+            allow_unverified,
+            original_creator,
+            standard.into(),
+            rule_set,
+            transfer_hook_program,
+            non_custodial,
+        ),
+        Command::CreateEntry { id, key } => {
+            let state = ctx.state()?;
+            ctx.client(&id)?
+                .create_collection_entry(key, state.fee_mint, state.earner)
+        }
+        Command::InitializePair { id, original_mint } => ctx
+            .client(&id)?
+            .initialize_pair(original_mint, ctx.token_program(&original_mint)?),
+        Command::Entangle {
+            id,
+            original_mint,
+            authorization_rules,
+            entangled_authorization_rules,
+        } => ctx.client(&id)?.entangle(
+            original_mint,
+            authorization_rules,
+            entangled_authorization_rules,
+            ctx.token_program(&original_mint)?,
+        ),
+        Command::Disentangle {
+            id,
+            original_mint,
+            authorization_rules,
+            entangled_authorization_rules,
+        } => {
+            let entangled_mint = find_entangled_mint(&id, &original_mint).0;
+            let pair: EntangledPair = ctx.fetch(&find_entangled_pair(&entangled_mint).0)?;
+            ctx.client(&id)?.disentangle(
+                original_mint,
+                authorization_rules,
+                entangled_authorization_rules,
+                ctx.token_program(&original_mint)?,
+                pair.frozen_account,
+            )
+        }
+        Command::BurnOriginal { id, original_mint } => {
+            ctx.client(&id)?
+                .burn_original(original_mint, ctx.token_program(&original_mint)?, None)
+        }
+        Command::MigrateCollection { id } => ctx.client(&id)?.migrate_collection(),
+        Command::MigratePair {
+            id,
+            original_mint,
+            authorization_rules,
+            entangled_authorization_rules,
+        } => {
+            let original_token_program = ctx.token_program(&original_mint)?;
+            let original_escrow = get_associated_token_address_with_program_id(
+                &find_entangler_authority().0,
+                &original_mint,
+                &original_token_program,
+            );
+            let original_escrowed = ctx
+                .rpc
+                .get_account_with_commitment(&original_escrow, ctx.rpc.commitment())?
+                .value
+                .is_some();
+            ctx.client(&id)?.migrate_pair(
+                original_mint,
+                original_escrowed,
+                authorization_rules,
+                entangled_authorization_rules,
+                original_token_program,
+            )
+        }
+        Command::ResizeAccount { address } => resize_account(ctx.payer.pubkey(), address),
+        Command::VerifyPair { id, original_mint } => {
+            let entangled_mint = find_entangled_mint(&id, &original_mint).0;
+            let pair: EntangledPair = ctx.fetch(&find_entangled_pair(&entangled_mint).0)?;
+            let instruction = ctx.client(&id)?.verify_pair(
+                original_mint,
+                pair.frozen_account,
+                ctx.token_program(&original_mint)?,
+            );
+            let custody = deserialize_custody(&ctx.simulate(instruction)?)?;
+            display::print_custody(&custody);
+            return Ok(());
+        }
+        Command::RecordOriginal { id, original_mint } => {
+            ctx.client(&id)?.record_original(original_mint)
+        }
+        Command::SetRecordsBackfilled { records_backfilled } => {
+            set_records_backfilled(ctx.payer.pubkey(), records_backfilled)
+        }
+        Command::RescueTokens {
+            id,
+            mint,
+            global_authority,
+            source,
+            destination,
+            amount,
+        } => {
+            let client = ctx.client(&id)?;
+            let client = if global_authority {
+                client.with_global_authority()
+            } else {
+                client
+            };
+            let token_program = ctx.token_program(&mint)?;
+            let source = source.unwrap_or_else(|| {
+                get_associated_token_address_with_program_id(
+                    &client.entangler_authority,
+                    &mint,
+                    &token_program,
+                )
+            });
+            client.rescue_tokens(mint, source, destination, amount, token_program)
+        }
+        Command::SweepAuthorityLamports { id } => {
+            let state = ctx.state()?;
+            ctx.client(&id)?.sweep_authority_lamports(state.earner)
+        }
+        Command::Show { address } => {
+            let account = ctx.rpc.get_account(&address)?;
+            if account.owner != entangler_client::ID {
+                bail!("{address} is not owned by the entangler");
+            }
+            let Some(decoded) = EntanglerAccount::try_from_bytes(&account.data) else {
+                bail!("{address} is not an entangler account");
+            };
+            display::print_account(&address, &decoded);
+            return Ok(());
+        }
+        Command::List { kind } => {
+            let config = RpcProgramAccountsConfig {
+                filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    0,
+                    kind.discriminator().to_vec(),
+                ))]),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    ..RpcAccountInfoConfig::default()
+                },
+                ..RpcProgramAccountsConfig::default()
+            };
+            let accounts = ctx
+                .rpc
+                .get_program_accounts_with_config(&entangler_client::ID, config)?;
+            for (address, account) in accounts {
+                if let Some(decoded) = EntanglerAccount::try_from_bytes(&account.data) {
+                    display::print_account(&address, &decoded);
+                }
+            }
+            return Ok(());
+        }
+        Command::Plan { manifest } => {
+            let manifest = Manifest::load(&manifest)?;
+            Plan::compute(&ctx, &manifest)?.print();
+            return Ok(());
+        }
+        Command::Apply { manifest } => {
+            let manifest = Manifest::load(&manifest)?;
+            let plan = Plan::compute(&ctx, &manifest)?;
+            plan.print();
+            return plan.apply(&ctx, &manifest);
+        }
+    };
+
+    let signature = ctx.send(&[instruction])?;
+    println!("{signature}");
+    Ok(())
+}
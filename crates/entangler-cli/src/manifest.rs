@@ -0,0 +1,579 @@
+//! Declarative description of entangled collections, diffed against the chain
+
+use std::{collections::HashMap, fmt, path::Path, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Context as _, Result};
+use entangler_client::{
+    accounts::{CollectionEntry, EntangledCollection, EntangledStandard},
+    pda::{find_collection_entry, find_entangled_collection},
+    EntanglerClient,
+};
+use serde::{Deserialize, Deserializer};
+use solana_sdk::{instruction::Instruction, signer::Signer};
+
+use crate::{context::Context, Standard};
+
+#[derive(Deserialize)]
+pub struct Manifest {
+    #[serde(rename = "collection", default)]
+    pub collections: Vec<CollectionManifest>,
+}
+
+#[derive(Deserialize)]
+pub struct CollectionManifest {
+    #[serde(deserialize_with = "pubkey")]
+    pub id: Pubkey,
+    #[serde(deserialize_with = "pubkey")]
+    pub original_collection_mint: Pubkey,
+    /// The creator receiving royalties
+    #[serde(deserialize_with = "pubkey")]
+    pub creator: Pubkey,
+    pub royalties: u16,
+    #[serde(default)]
+    pub one_way: bool,
+    #[serde(default)]
+    pub allow_unverified: bool,
+    #[serde(default, deserialize_with = "optional_pubkey")]
+    pub original_creator: Option<Pubkey>,
+    /// The standard of the entangled tokens, `non-fungible` by default
+    #[serde(default)]
+    pub standard: Standard,
+    /// The rule set of programmable entangled tokens
+    #[serde(default, deserialize_with = "optional_pubkey")]
+    pub rule_set: Option<Pubkey>,
+    /// The transfer hook program of Token-2022 entangled tokens
+    #[serde(default, deserialize_with = "optional_pubkey")]
+    pub transfer_hook_program: Option<Pubkey>,
+    #[serde(default)]
+    pub non_custodial: bool,
+    /// The keys of the collection's entries
+    #[serde(default)]
+    pub entries: Vec<String>,
+}
+
+fn pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+    let key = String::deserialize(deserializer)?;
+    Pubkey::from_str(&key).map_err(serde::de::Error::custom)
+}
+
+fn optional_pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Pubkey>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|key| Pubkey::from_str(&key).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+impl CollectionManifest {
+    fn client(&self, signer: Pubkey) -> EntanglerClient {
+        EntanglerClient::new(
+            self.original_collection_mint,
+            signer,
+            self.id,
+            self.creator,
+            self.royalties,
+        )
+    }
+
+    fn create_collection(&self, client: &EntanglerClient) -> Instruction {
+        client.create_collection(
+            self.one_way,
+            self.allow_unverified,
+            self.original_creator,
+            self.standard.into(),
+            self.rule_set,
+            self.transfer_hook_program,
+            self.non_custodial,
+        )
+    }
+}
+
+impl Manifest {
+    /// Reads a TOML manifest, or a JSON one if the file ends with `.json`
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+        let manifest = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => serde_json::from_str(&content)?,
+            _ => toml::from_str(&content)?,
+        };
+        Ok(manifest)
+    }
+}
+
+/// A change bringing the chain in line with the manifest
+pub enum Action {
+    CreateCollection {
+        id: Pubkey,
+    },
+    SetVerification {
+        id: Pubkey,
+        allow_unverified: bool,
+        original_creator: Option<Pubkey>,
+    },
+    CreateEntry {
+        id: Pubkey,
+        key: String,
+    },
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::CreateCollection { id } => write!(f, "create collection {id}"),
+            Action::SetVerification {
+                id,
+                allow_unverified,
+                original_creator,
+            } => write!(
+                f,
+                "set verification of {id}: allow unverified {allow_unverified}, original creator {}",
+                optional(original_creator)
+            ),
+            Action::CreateEntry { id, key } => write!(f, "create entry {key:?} for {id}"),
+        }
+    }
+}
+
+/// The actions to apply, and the differences no instruction can fix
+#[derive(Default)]
+pub struct Plan {
+    pub actions: Vec<Action>,
+    pub conflicts: Vec<String>,
+}
+
+/// An existing entangled collection, with the creator of its entangled tokens
+pub struct OnChainCollection {
+    pub collection: EntangledCollection,
+    pub creator: Pubkey,
+}
+
+/// The accounts a manifest is diffed against: its collections and entries that exist on chain
+#[derive(Default)]
+pub struct OnChainState {
+    pub collections: HashMap<Pubkey, OnChainCollection>,
+    /// The id of the collection each existing entry maps to, by key
+    pub entries: HashMap<String, Pubkey>,
+}
+
+impl OnChainState {
+    /// Reads the `EntangledCollection` and `CollectionEntry` accounts the manifest names
+    pub fn fetch(ctx: &Context, manifest: &Manifest) -> Result<Self> {
+        let mut state = Self::default();
+
+        for wanted in &manifest.collections {
+            let id = wanted.id;
+            let exists = ctx
+                .rpc
+                .get_account_with_commitment(
+                    &find_entangled_collection(&id).0,
+                    ctx.rpc.commitment(),
+                )?
+                .value
+                .is_some();
+            if exists {
+                let collection = OnChainCollection {
+                    collection: ctx.collection(&id)?,
+                    creator: ctx.client(&id)?.creator,
+                };
+                state.collections.insert(id, collection);
+            }
+
+            for key in &wanted.entries {
+                let address = find_collection_entry(key).0;
+                let entry = ctx
+                    .rpc
+                    .get_account_with_commitment(&address, ctx.rpc.commitment())?
+                    .value;
+                if entry.is_some() {
+                    let entry: CollectionEntry = ctx.fetch(&address)?;
+                    state.entries.insert(key.clone(), entry.id);
+                }
+            }
+        }
+
+        Ok(state)
+    }
+}
+
+fn optional(key: &Option<Pubkey>) -> String {
+    key.map_or_else(|| "-".to_string(), |key| key.to_string())
+}
+
+/// Diffs the manifest against the chain. Only the verification of a collection can change
+/// after its creation, any other difference is a conflict
+pub fn diff(manifest: &Manifest, chain: &OnChainState) -> Plan {
+    let mut plan = Plan::default();
+
+    for wanted in &manifest.collections {
+        let id = wanted.id;
+
+        match chain.collections.get(&id) {
+            None => plan.actions.push(Action::CreateCollection { id }),
+            Some(OnChainCollection {
+                collection,
+                creator,
+            }) => {
+                let mut conflict = |field: &str, wanted: String, current: String| {
+                    if wanted != current {
+                        plan.conflicts.push(format!(
+                            "{id}: {field} is {current} on chain but {wanted} in the manifest, \
+                             and can't be changed after creation"
+                        ))
+                    }
+                };
+                conflict(
+                    "original collection mint",
+                    wanted.original_collection_mint.to_string(),
+                    collection.original_collection_mint.to_string(),
+                );
+                conflict(
+                    "royalties",
+                    wanted.royalties.to_string(),
+                    collection.royalties.to_string(),
+                );
+                conflict(
+                    "one way",
+                    wanted.one_way.to_string(),
+                    collection.one_way.to_string(),
+                );
+                conflict("creator", wanted.creator.to_string(), creator.to_string());
+                conflict(
+                    "standard",
+                    format!("{:?}", EntangledStandard::from(wanted.standard)),
+                    format!("{:?}", collection.entangled_standard),
+                );
+                conflict(
+                    "rule set",
+                    optional(&wanted.rule_set),
+                    optional(&collection.rule_set),
+                );
+                conflict(
+                    "transfer hook program",
+                    optional(&wanted.transfer_hook_program),
+                    optional(&collection.transfer_hook_program),
+                );
+                conflict(
+                    "non custodial",
+                    wanted.non_custodial.to_string(),
+                    collection.non_custodial.to_string(),
+                );
+
+                if collection.allow_unverified != wanted.allow_unverified
+                    || collection.original_creator != wanted.original_creator
+                {
+                    plan.actions.push(Action::SetVerification {
+                        id,
+                        allow_unverified: wanted.allow_unverified,
+                        original_creator: wanted.original_creator,
+                    });
+                }
+            }
+        }
+
+        for key in &wanted.entries {
+            match chain.entries.get(key) {
+                None => plan.actions.push(Action::CreateEntry {
+                    id,
+                    key: key.clone(),
+                }),
+                Some(owner) if *owner != id => plan
+                    .conflicts
+                    .push(format!("{id}: entry {key:?} belongs to {owner}")),
+                Some(_) => {}
+            }
+        }
+    }
+
+    plan
+}
+
+impl Plan {
+    /// Diffs the manifest against the `EntangledCollection` and `CollectionEntry` accounts
+    pub fn compute(ctx: &Context, manifest: &Manifest) -> Result<Self> {
+        Ok(diff(manifest, &OnChainState::fetch(ctx, manifest)?))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty() && self.conflicts.is_empty()
+    }
+
+    pub fn print(&self) {
+        if self.is_empty() {
+            println!("The chain matches the manifest");
+        }
+        for action in &self.actions {
+            println!("+ {action}");
+        }
+        for conflict in &self.conflicts {
+            println!("! {conflict}");
+        }
+    }
+
+    /// Sends every action, one transaction each, refusing to start if any conflict remains
+    pub fn apply(&self, ctx: &Context, manifest: &Manifest) -> Result<()> {
+        if !self.conflicts.is_empty() {
+            bail!(
+                "{} conflicts need to be fixed by hand first",
+                self.conflicts.len()
+            );
+        }
+
+        for action in &self.actions {
+            let instruction = self.instruction(ctx, manifest, action)?;
+            let signature = ctx.send(&[instruction])?;
+            println!("{action}: {signature}");
+        }
+        Ok(())
+    }
+
+    fn instruction(
+        &self,
+        ctx: &Context,
+        manifest: &Manifest,
+        action: &Action,
+    ) -> Result<Instruction> {
+        let wanted = match action {
+            Action::CreateCollection { id }
+            | Action::SetVerification { id, .. }
+            | Action::CreateEntry { id, .. } => manifest
+                .collections
+                .iter()
+                .find(|collection| collection.id == *id)
+                .expect("Actions come from the manifest"),
+        };
+        let client = wanted.client(ctx.payer.pubkey());
+
+        Ok(match action {
+            Action::CreateCollection { .. } => wanted.create_collection(&client),
+            Action::SetVerification {
+                allow_unverified,
+                original_creator,
+                ..
+            } => client.set_collection_verification(*allow_unverified, *original_creator),
+            Action::CreateEntry { key, .. } => {
+                let state = ctx.state()?;
+                client.create_collection_entry(key.clone(), state.fee_mint, state.earner)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+        [[collection]]
+        id = "UuGEwN9aeh676ufphbavfssWVxH7BJCqacq1RYhco8e"
+        original_collection_mint = "318p2nhXSiKSPhsQhCtBL1fXNgjUUGPAXG5dbQqSCEpw"
+        creator = "3h2CFnu8w7NRemnX9ybVeXsXAP3agkMuC1Kz8TnERYUi"
+        royalties = 500
+        standard = "token2022"
+        transfer_hook_program = "HookEhJXHPBqNKQ7v8CSBmDWbvkzGsvkbRhvrgXaEfSA"
+        non_custodial = true
+        entries = ["dippies"]
+    "#;
+
+    fn manifest() -> Manifest {
+        toml::from_str(MANIFEST).unwrap()
+    }
+
+    /// The chain once the manifest was applied
+    fn applied(manifest: &Manifest) -> OnChainState {
+        let mut chain = OnChainState::default();
+        for wanted in &manifest.collections {
+            let collection = EntangledCollection {
+                id: wanted.id,
+                original_collection_mint: wanted.original_collection_mint,
+                entangled_collection_mint: Pubkey::new_unique(),
+                royalties: wanted.royalties,
+                one_way: wanted.one_way,
+                allow_unverified: wanted.allow_unverified,
+                original_creator: wanted.original_creator,
+                entangled_standard: wanted.standard.into(),
+                rule_set: wanted.rule_set,
+                transfer_hook_program: wanted.transfer_hook_program,
+                entangled_tree: None,
+                non_custodial: wanted.non_custodial,
+                scoped_authority: true,
+            };
+            let creator = wanted.creator;
+            chain.collections.insert(
+                wanted.id,
+                OnChainCollection {
+                    collection,
+                    creator,
+                },
+            );
+            for key in &wanted.entries {
+                chain.entries.insert(key.clone(), wanted.id);
+            }
+        }
+        chain
+    }
+
+    fn actions(plan: &Plan) -> Vec<String> {
+        plan.actions.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn parses_every_creation_field() {
+        let manifest = manifest();
+        let wanted = &manifest.collections[0];
+        assert_eq!(
+            EntangledStandard::from(wanted.standard),
+            EntangledStandard::Token2022
+        );
+        assert_eq!(wanted.rule_set, None);
+        assert!(wanted.transfer_hook_program.is_some());
+        assert!(wanted.non_custodial);
+
+        // Left out, the fields default to a custodial collection of plain NFTs
+        let manifest: Manifest = toml::from_str(
+            &MANIFEST
+                .lines()
+                .filter(|line| {
+                    !["standard", "transfer_hook_program", "non_custodial"]
+                        .iter()
+                        .any(|field| line.trim().starts_with(field))
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+        .unwrap();
+        let wanted = &manifest.collections[0];
+        assert_eq!(
+            EntangledStandard::from(wanted.standard),
+            EntangledStandard::NonFungible
+        );
+        assert_eq!(wanted.transfer_hook_program, None);
+        assert!(!wanted.non_custodial);
+    }
+
+    #[test]
+    fn creates_missing_collections_and_entries() {
+        let manifest = manifest();
+        let id = manifest.collections[0].id;
+
+        let plan = diff(&manifest, &OnChainState::default());
+        assert_eq!(
+            actions(&plan),
+            [
+                format!("create collection {id}"),
+                format!("create entry \"dippies\" for {id}"),
+            ]
+        );
+        assert!(plan.conflicts.is_empty());
+    }
+
+    #[test]
+    fn creates_collections_with_their_standard_and_options() {
+        let manifest = manifest();
+        let wanted = &manifest.collections[0];
+        let client = wanted.client(Pubkey::new_unique());
+
+        let instruction = wanted.create_collection(&client);
+        let expected = client.create_collection(
+            false,
+            false,
+            None,
+            EntangledStandard::Token2022,
+            None,
+            wanted.transfer_hook_program,
+            true,
+        );
+        assert_eq!(instruction.data, expected.data);
+    }
+
+    #[test]
+    fn applied_manifests_plan_nothing() {
+        let manifest = manifest();
+        assert!(diff(&manifest, &applied(&manifest)).is_empty());
+    }
+
+    #[test]
+    fn updates_the_verification() {
+        let manifest = manifest();
+        let id = manifest.collections[0].id;
+        let mut chain = applied(&manifest);
+        chain
+            .collections
+            .get_mut(&id)
+            .unwrap()
+            .collection
+            .allow_unverified = true;
+
+        let plan = diff(&manifest, &chain);
+        assert_eq!(
+            actions(&plan),
+            [format!(
+                "set verification of {id}: allow unverified false, original creator -"
+            )]
+        );
+        assert!(plan.conflicts.is_empty());
+    }
+
+    #[test]
+    fn reports_fields_fixed_at_creation() {
+        let manifest = manifest();
+        let id = manifest.collections[0].id;
+        let mut chain = applied(&manifest);
+        let current = chain.collections.get_mut(&id).unwrap();
+        current.collection.royalties = 100;
+        current.collection.entangled_standard = EntangledStandard::ProgrammableNonFungible;
+        current.collection.rule_set = Some(Pubkey::new_unique());
+        current.collection.transfer_hook_program = None;
+        current.collection.non_custodial = false;
+        current.creator = Pubkey::new_unique();
+
+        let plan = diff(&manifest, &chain);
+        assert!(plan.actions.is_empty());
+        let fields: Vec<_> = plan
+            .conflicts
+            .iter()
+            .map(|conflict| {
+                assert!(conflict.ends_with("can't be changed after creation"));
+                conflict
+                    .strip_prefix(&format!("{id}: "))
+                    .unwrap()
+                    .split(" is ")
+                    .next()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "royalties",
+                "creator",
+                "standard",
+                "rule set",
+                "transfer hook program",
+                "non custodial",
+            ]
+        );
+        assert_eq!(
+            plan.conflicts[2],
+            format!(
+                "{id}: standard is ProgrammableNonFungible on chain but Token2022 in the \
+                 manifest, and can't be changed after creation"
+            )
+        );
+    }
+
+    #[test]
+    fn reports_entries_of_other_collections() {
+        let manifest = manifest();
+        let id = manifest.collections[0].id;
+        let other = Pubkey::new_unique();
+        let mut chain = applied(&manifest);
+        chain.entries.insert("dippies".to_string(), other);
+
+        let plan = diff(&manifest, &chain);
+        assert!(plan.actions.is_empty());
+        assert_eq!(
+            plan.conflicts,
+            [format!("{id}: entry \"dippies\" belongs to {other}")]
+        );
+    }
+}
@@ -0,0 +1,47 @@
+//! Deserialisers for the entangler's accounts
+
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+
+pub use entangler::state::{
+    CollectionEntry, EntangledCollection, EntangledPair, EntangledStandard, EntanglerState,
+    OriginalRecord, PairCustody,
+};
+
+/// Deserializes an entangler account, checking its discriminator
+pub fn deserialize<T: AccountDeserialize>(mut data: &[u8]) -> anchor_lang::Result<T> {
+    T::try_deserialize(&mut data)
+}
+
+/// Deserializes the custody `verify_pair` returns, from its transaction's return data
+pub fn deserialize_custody(mut data: &[u8]) -> anchor_lang::Result<PairCustody> {
+    Ok(PairCustody::deserialize(&mut data)?)
+}
+
+/// Any account owned by the entangler
+pub enum EntanglerAccount {
+    State(EntanglerState),
+    Collection(EntangledCollection),
+    CollectionEntry(CollectionEntry),
+    Pair(EntangledPair),
+    OriginalRecord(OriginalRecord),
+}
+
+impl EntanglerAccount {
+    /// Deserializes an account from its discriminator, if it is one of the entangler's
+    pub fn try_from_bytes(data: &[u8]) -> Option<Self> {
+        let discriminator = data.get(..8)?;
+        if discriminator == EntanglerState::DISCRIMINATOR {
+            deserialize(data).ok().map(Self::State)
+        } else if discriminator == EntangledCollection::DISCRIMINATOR {
+            deserialize(data).ok().map(Self::Collection)
+        } else if discriminator == CollectionEntry::DISCRIMINATOR {
+            deserialize(data).ok().map(Self::CollectionEntry)
+        } else if discriminator == EntangledPair::DISCRIMINATOR {
+            deserialize(data).ok().map(Self::Pair)
+        } else if discriminator == OriginalRecord::DISCRIMINATOR {
+            deserialize(data).ok().map(Self::OriginalRecord)
+        } else {
+            None
+        }
+    }
+}
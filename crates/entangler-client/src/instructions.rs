@@ -0,0 +1,1499 @@
+//! Builders for every entangler instruction, deriving all the accounts they need
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_program,
+        sysvar::{instructions as sysvar_instructions, rent},
+    },
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use entangler::{accounts, instruction, state::EntangledStandard};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+
+use crate::pda::{
+    asset_id, find_bubblegum_signer, find_collection_entry, find_edition_marker,
+    find_entangled_collection, find_entangled_collection_mint, find_entangled_mint,
+    find_collection_authority, find_entangled_pair, find_entangler_authority,
+    find_entangler_state, find_master_edition, find_metadata, find_original_record,
+    find_token_record, find_tree_config,
+};
+
+/// A compressed NFT with the proof of its leaf
+#[derive(Clone, Debug)]
+pub struct CompressedAsset {
+    pub merkle_tree: Pubkey,
+    pub root: [u8; 32],
+    /// The Borsh-serialized Bubblegum metadata of the leaf
+    pub metadata: Vec<u8>,
+    pub nonce: u64,
+    pub index: u32,
+    pub leaf_owner: Pubkey,
+    pub leaf_delegate: Pubkey,
+    /// The merkle proof, minus the tree's canopy
+    pub proof: Vec<Pubkey>,
+}
+
+impl CompressedAsset {
+    fn proof_accounts(&self) -> impl Iterator<Item = AccountMeta> + '_ {
+        self.proof
+            .iter()
+            .map(|node| AccountMeta::new_readonly(*node, false))
+    }
+}
+
+/// The master edition a print edition original was printed from
+#[derive(Clone, Debug)]
+pub struct PrintEdition {
+    pub master_mint: Pubkey,
+    /// A token account holding the master edition
+    pub master_token: Pubkey,
+    pub edition: u64,
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: entangler::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Sets the entangler's admin and fees, which needs no collection
+pub fn set_entangler_state(
+    signer: Pubkey,
+    admin: Pubkey,
+    earner: Pubkey,
+    fee_mint: Pubkey,
+    price: u64,
+) -> Instruction {
+    build(
+        accounts::SetEntanglerState {
+            signer,
+            fee_mint,
+            state: find_entangler_state().0,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        },
+        instruction::SetEntanglerState {
+            admin,
+            earner,
+            price,
+        },
+    )
+}
+
+/// Declares whether every original initialized before original records existed got one, which
+/// needs no collection. The signer must be the entangler's admin
+pub fn set_records_backfilled(signer: Pubkey, records_backfilled: bool) -> Instruction {
+    build(
+        accounts::SetRecordsBackfilled {
+            signer,
+            state: find_entangler_state().0,
+            system_program: system_program::ID,
+        },
+        instruction::SetRecordsBackfilled { records_backfilled },
+    )
+}
+
+/// Grows an account created with an older layout to the current one, which needs no collection
+pub fn resize_account(signer: Pubkey, account: Pubkey) -> Instruction {
+    build(
+        accounts::ResizeAccount {
+            signer,
+            account,
+            system_program: system_program::ID,
+        },
+        instruction::ResizeAccount {},
+    )
+}
+
+/// Builds the instructions of one entangled collection, signed by `signer`
+#[derive(Clone, Debug)]
+pub struct EntanglerClient {
+    pub signer: Pubkey,
+    pub id: Pubkey,
+    pub creator: Pubkey,
+    pub royalties: u16,
+    pub entangler_authority: Pubkey,
+    pub entangled_collection: Pubkey,
+    pub entangled_collection_mint: Pubkey,
+    pub entangled_collection_master_edition: Pubkey,
+    pub entangled_collection_metadata: Pubkey,
+    pub entangled_collection_mint_account: Pubkey,
+    pub original_collection_mint: Pubkey,
+    pub original_collection_metadata: Pubkey,
+    pub entangled_token_program: Pubkey,
+    pub non_custodial: bool,
+}
+
+impl EntanglerClient {
+    pub fn new(
+        original_collection_mint: Pubkey,
+        signer: Pubkey,
+        id: Pubkey,
+        creator: Pubkey,
+        royalties: u16,
+    ) -> Self {
+        let entangler_authority = find_collection_authority(&id).0;
+        let entangled_collection_mint = find_entangled_collection_mint(&id).0;
+
+        Self {
+            signer,
+            id,
+            creator,
+            royalties,
+            entangler_authority,
+            entangled_collection: find_entangled_collection(&id).0,
+            entangled_collection_mint,
+            entangled_collection_master_edition: find_master_edition(&entangled_collection_mint).0,
+            entangled_collection_metadata: find_metadata(&entangled_collection_mint).0,
+            entangled_collection_mint_account: get_associated_token_address_with_program_id(
+                &entangler_authority,
+                &entangled_collection_mint,
+                &anchor_spl::token::ID,
+            ),
+            original_collection_mint,
+            original_collection_metadata: find_metadata(&original_collection_mint).0,
+            entangled_token_program: anchor_spl::token::ID,
+            non_custodial: false,
+        }
+    }
+
+    /// Uses the global authority, for collections that weren't migrated to their own
+    pub fn with_global_authority(mut self) -> Self {
+        self.entangler_authority = find_entangler_authority().0;
+        self.entangled_collection_mint_account = get_associated_token_address_with_program_id(
+            &self.entangler_authority,
+            &self.entangled_collection_mint,
+            &anchor_spl::token::ID,
+        );
+        self
+    }
+
+    /// Uses another token program, such as Token-2022, for the entangled tokens
+    pub fn with_entangled_token_program(mut self, entangled_token_program: Pubkey) -> Self {
+        self.entangled_token_program = entangled_token_program;
+        self
+    }
+
+    /// Leaves originals frozen in their owner's account, for non-custodial collections
+    pub fn with_non_custodial(mut self) -> Self {
+        self.non_custodial = true;
+        self
+    }
+
+    fn escrow(&self, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(&self.entangler_authority, mint, token_program)
+    }
+
+    fn account(&self, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(&self.signer, mint, token_program)
+    }
+
+    fn entangled_mint(&self, original: &Pubkey) -> Pubkey {
+        find_entangled_mint(&self.id, original).0
+    }
+
+    pub fn set_entangler_state(
+        &self,
+        admin: Pubkey,
+        earner: Pubkey,
+        fee_mint: Pubkey,
+        price: u64,
+    ) -> Instruction {
+        set_entangler_state(self.signer, admin, earner, fee_mint, price)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_collection(
+        &self,
+        one_way: bool,
+        allow_unverified: bool,
+        original_creator: Option<Pubkey>,
+        entangled_standard: EntangledStandard,
+        rule_set: Option<Pubkey>,
+        transfer_hook_program: Option<Pubkey>,
+        non_custodial: bool,
+    ) -> Instruction {
+        build(
+            accounts::CreateCollection {
+                signer: self.signer,
+                creator: self.creator,
+                entangler_authority: self.entangler_authority,
+                entangled_collection: self.entangled_collection,
+                master_edition: self.entangled_collection_master_edition,
+                original_collection_mint: self.original_collection_mint,
+                original_collection_metadata: self.original_collection_metadata,
+                entangled_collection_mint: self.entangled_collection_mint,
+                entangled_collection_metadata: self.entangled_collection_metadata,
+                entangled_collection_mint_account: self.entangled_collection_mint_account,
+                metadata_program: mpl_token_metadata::ID,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+                rent: rent::ID,
+            },
+            instruction::CreateCollection {
+                id: self.id,
+                royalties: self.royalties,
+                one_way,
+                allow_unverified,
+                original_creator,
+                entangled_standard,
+                rule_set,
+                transfer_hook_program,
+                non_custodial,
+            },
+        )
+    }
+
+    pub fn set_collection_verification(
+        &self,
+        allow_unverified: bool,
+        original_creator: Option<Pubkey>,
+    ) -> Instruction {
+        build(
+            accounts::SetCollectionVerification {
+                signer: self.signer,
+                state: find_entangler_state().0,
+                entangled_collection: self.entangled_collection,
+            },
+            instruction::SetCollectionVerification {
+                allow_unverified,
+                original_creator,
+            },
+        )
+    }
+
+    pub fn create_collection_entry(
+        &self,
+        key: String,
+        fee_mint: Pubkey,
+        earner: Pubkey,
+    ) -> Instruction {
+        build(
+            accounts::CreateCollectionEntry {
+                signer: self.signer,
+                state: find_entangler_state().0,
+                fee_mint,
+                earner,
+                signer_account: self.account(&fee_mint, &anchor_spl::token::ID),
+                earner_account: get_associated_token_address_with_program_id(
+                    &earner,
+                    &fee_mint,
+                    &anchor_spl::token::ID,
+                ),
+                entangled_collection: self.entangled_collection,
+                entangled_collection_entry: find_collection_entry(&key).0,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+                rent: rent::ID,
+            },
+            instruction::CreateCollectionEntry { key },
+        )
+    }
+
+    pub fn initialize_pair(
+        &self,
+        original_mint: Pubkey,
+        original_token_program: Pubkey,
+    ) -> Instruction {
+        let entangled_mint = self.entangled_mint(&original_mint);
+        let entangled_mint_escrow = self.escrow(&entangled_mint, &self.entangled_token_program);
+
+        build(
+            accounts::InitializePair {
+                signer: self.signer,
+                creator: self.creator,
+                entangler_authority: self.entangler_authority,
+                master_edition: self.entangled_collection_master_edition,
+                entangled_collection: self.entangled_collection,
+                entangled_collection_mint: self.entangled_collection_mint,
+                entangled_collection_metadata: self.entangled_collection_metadata,
+                original_mint,
+                original_metadata: find_metadata(&original_mint).0,
+                original_edition: Some(find_master_edition(&original_mint).0),
+                original_mint_escrow: (!self.non_custodial)
+                    .then(|| self.escrow(&original_mint, &original_token_program)),
+                original_record: find_original_record(&original_mint).0,
+                entangled_mint,
+                entangled_metadata: find_metadata(&entangled_mint).0,
+                entangled_master_edition: Some(find_master_edition(&entangled_mint).0),
+                entangled_mint_escrow,
+                entangled_mint_escrow_record: Some(
+                    find_token_record(&entangled_mint, &entangled_mint_escrow).0,
+                ),
+                metadata_program: mpl_token_metadata::ID,
+                sysvar_instructions: Some(sysvar_instructions::ID),
+                original_token_program,
+                token_program: self.entangled_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+                rent: rent::ID,
+            },
+            instruction::InitializePair {},
+        )
+    }
+
+    pub fn create_entangled_edition(&self, original_mint: Pubkey) -> Instruction {
+        let entangled_mint = self.entangled_mint(&original_mint);
+
+        build(
+            accounts::CreateEntangledEdition {
+                signer: self.signer,
+                entangler_authority: self.entangler_authority,
+                entangled_collection: self.entangled_collection,
+                original_mint,
+                entangled_mint,
+                entangled_metadata: find_metadata(&entangled_mint).0,
+                entangled_master_edition: find_master_edition(&entangled_mint).0,
+                metadata_program: mpl_token_metadata::ID,
+                token_program: anchor_spl::token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CreateEntangledEdition {},
+        )
+    }
+
+    pub fn entangle(
+        &self,
+        original_mint: Pubkey,
+        authorization_rules: Option<Pubkey>,
+        entangled_authorization_rules: Option<Pubkey>,
+        original_token_program: Pubkey,
+    ) -> Instruction {
+        let entangled_mint = self.entangled_mint(&original_mint);
+        let original_mint_account = self.account(&original_mint, &original_token_program);
+        let original_mint_escrow = self.escrow(&original_mint, &original_token_program);
+        let entangled_mint_account = self.account(&entangled_mint, &self.entangled_token_program);
+        let entangled_mint_escrow = self.escrow(&entangled_mint, &self.entangled_token_program);
+
+        build(
+            accounts::Entangle {
+                signer: self.signer,
+                entangler_authority: self.entangler_authority,
+                entangled_collection: self.entangled_collection,
+                entangled_pair: find_entangled_pair(&entangled_mint).0,
+                entangled_collection_mint: self.entangled_collection_mint,
+                entangled_collection_metadata: self.entangled_collection_metadata,
+                original_mint,
+                original_metadata: find_metadata(&original_mint).0,
+                original_master_edition: Some(find_master_edition(&original_mint).0),
+                authorization_rules,
+                original_mint_account,
+                original_mint_account_record: Some(
+                    find_token_record(&original_mint, &original_mint_account).0,
+                ),
+                original_mint_escrow: (!self.non_custodial).then_some(original_mint_escrow),
+                original_mint_escrow_record: Some(
+                    find_token_record(&original_mint, &original_mint_escrow).0,
+                ),
+                entangled_mint,
+                entangled_master_edition: Some(find_master_edition(&entangled_mint).0),
+                entangled_authorization_rules,
+                entangled_mint_account,
+                entangled_mint_account_record: Some(
+                    find_token_record(&entangled_mint, &entangled_mint_account).0,
+                ),
+                entangled_metadata: find_metadata(&entangled_mint).0,
+                entangled_mint_escrow,
+                entangled_mint_escrow_record: Some(
+                    find_token_record(&entangled_mint, &entangled_mint_escrow).0,
+                ),
+                metadata_program: mpl_token_metadata::ID,
+                authorization_rules_program: Some(mpl_token_auth_rules::ID),
+                sysvar_instructions: Some(sysvar_instructions::ID),
+                original_token_program,
+                token_program: self.entangled_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+                rent: rent::ID,
+            },
+            instruction::Entangle {},
+        )
+    }
+
+    /// Each original is followed by its own transfer hook accounts, empty past the end of
+    /// `hook_accounts`
+    pub fn entangle_batch(
+        &self,
+        original_mints: &[Pubkey],
+        original_token_program: Pubkey,
+        hook_accounts: &[Vec<AccountMeta>],
+    ) -> Instruction {
+        let group_hooks = |i: usize| hook_accounts.get(i).map_or(&[][..], Vec::as_slice);
+        let mut ix = build(
+            accounts::EntangleBatch {
+                signer: self.signer,
+            },
+            instruction::EntangleBatch {
+                hook_accounts: (0..original_mints.len())
+                    .map(|i| group_hooks(i).len() as u8)
+                    .collect(),
+            },
+        );
+        for (i, original_mint) in original_mints.iter().enumerate() {
+            ix.accounts.extend(
+                self.entangle(*original_mint, None, None, original_token_program)
+                    .accounts,
+            );
+            ix.accounts.extend_from_slice(group_hooks(i));
+        }
+        ix
+    }
+
+    pub fn initialize_and_entangle(
+        &self,
+        original_mint: Pubkey,
+        authorization_rules: Option<Pubkey>,
+        entangled_authorization_rules: Option<Pubkey>,
+        original_token_program: Pubkey,
+    ) -> Instruction {
+        let entangled_mint = self.entangled_mint(&original_mint);
+        let original_mint_account = self.account(&original_mint, &original_token_program);
+        let original_mint_escrow = self.escrow(&original_mint, &original_token_program);
+        let entangled_mint_account = self.account(&entangled_mint, &self.entangled_token_program);
+        let entangled_mint_escrow = self.escrow(&entangled_mint, &self.entangled_token_program);
+
+        build(
+            accounts::InitializeAndEntangle {
+                signer: self.signer,
+                creator: self.creator,
+                entangler_authority: self.entangler_authority,
+                master_edition: self.entangled_collection_master_edition,
+                entangled_collection: self.entangled_collection,
+                entangled_pair: find_entangled_pair(&entangled_mint).0,
+                entangled_collection_mint: self.entangled_collection_mint,
+                entangled_collection_metadata: self.entangled_collection_metadata,
+                original_mint,
+                original_metadata: find_metadata(&original_mint).0,
+                original_edition: Some(find_master_edition(&original_mint).0),
+                authorization_rules,
+                original_mint_account,
+                original_mint_account_record: Some(
+                    find_token_record(&original_mint, &original_mint_account).0,
+                ),
+                original_mint_escrow: (!self.non_custodial).then_some(original_mint_escrow),
+                original_mint_escrow_record: Some(
+                    find_token_record(&original_mint, &original_mint_escrow).0,
+                ),
+                original_record: find_original_record(&original_mint).0,
+                entangled_mint,
+                entangled_metadata: find_metadata(&entangled_mint).0,
+                entangled_master_edition: Some(find_master_edition(&entangled_mint).0),
+                entangled_authorization_rules,
+                entangled_mint_account,
+                entangled_mint_account_record: Some(
+                    find_token_record(&entangled_mint, &entangled_mint_account).0,
+                ),
+                entangled_mint_escrow,
+                entangled_mint_escrow_record: Some(
+                    find_token_record(&entangled_mint, &entangled_mint_escrow).0,
+                ),
+                metadata_program: mpl_token_metadata::ID,
+                authorization_rules_program: Some(mpl_token_auth_rules::ID),
+                sysvar_instructions: Some(sysvar_instructions::ID),
+                original_token_program,
+                token_program: self.entangled_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+                rent: rent::ID,
+            },
+            instruction::InitializeAndEntangle {},
+        )
+    }
+
+    /// `frozen_account` is the account holding the original of a non-custodial pair
+    pub fn disentangle(
+        &self,
+        original_mint: Pubkey,
+        authorization_rules: Option<Pubkey>,
+        entangled_authorization_rules: Option<Pubkey>,
+        original_token_program: Pubkey,
+        frozen_account: Option<Pubkey>,
+    ) -> Instruction {
+        let entangled_mint = self.entangled_mint(&original_mint);
+        let original_mint_account = self.account(&original_mint, &original_token_program);
+        let original_mint_escrow = self.escrow(&original_mint, &original_token_program);
+        let entangled_mint_account = self.account(&entangled_mint, &self.entangled_token_program);
+        let entangled_mint_escrow = self.escrow(&entangled_mint, &self.entangled_token_program);
+
+        build(
+            accounts::Disentangle {
+                signer: self.signer,
+                entangler_authority: self.entangler_authority,
+                entangled_collection: self.entangled_collection,
+                entangled_pair: find_entangled_pair(&entangled_mint).0,
+                entangled_collection_mint: self.entangled_collection_mint,
+                entangled_collection_metadata: self.entangled_collection_metadata,
+                original_mint,
+                original_metadata: find_metadata(&original_mint).0,
+                original_master_edition: Some(find_master_edition(&original_mint).0),
+                authorization_rules,
+                original_mint_account,
+                original_mint_account_record: Some(
+                    find_token_record(&original_mint, &original_mint_account).0,
+                ),
+                original_mint_escrow: frozen_account.is_none().then_some(original_mint_escrow),
+                original_mint_escrow_record: Some(
+                    find_token_record(&original_mint, &original_mint_escrow).0,
+                ),
+                frozen_account,
+                entangled_mint,
+                entangled_master_edition: Some(find_master_edition(&entangled_mint).0),
+                entangled_authorization_rules,
+                entangled_mint_account,
+                entangled_mint_account_record: Some(
+                    find_token_record(&entangled_mint, &entangled_mint_account).0,
+                ),
+                entangled_metadata: find_metadata(&entangled_mint).0,
+                entangled_mint_escrow,
+                entangled_mint_escrow_record: Some(
+                    find_token_record(&entangled_mint, &entangled_mint_escrow).0,
+                ),
+                metadata_program: mpl_token_metadata::ID,
+                authorization_rules_program: Some(mpl_token_auth_rules::ID),
+                sysvar_instructions: Some(sysvar_instructions::ID),
+                original_token_program,
+                token_program: self.entangled_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+                rent: rent::ID,
+            },
+            instruction::Disentangle {},
+        )
+    }
+
+    /// Each original is followed by its own transfer hook accounts, empty past the end of
+    /// `hook_accounts`
+    pub fn disentangle_batch(
+        &self,
+        original_mints: &[Pubkey],
+        original_token_program: Pubkey,
+        hook_accounts: &[Vec<AccountMeta>],
+    ) -> Instruction {
+        let group_hooks = |i: usize| hook_accounts.get(i).map_or(&[][..], Vec::as_slice);
+        let mut ix = build(
+            accounts::DisentangleBatch {
+                signer: self.signer,
+            },
+            instruction::DisentangleBatch {
+                hook_accounts: (0..original_mints.len())
+                    .map(|i| group_hooks(i).len() as u8)
+                    .collect(),
+            },
+        );
+        for (i, original_mint) in original_mints.iter().enumerate() {
+            ix.accounts.extend(
+                self.disentangle(*original_mint, None, None, original_token_program, None)
+                    .accounts,
+            );
+            ix.accounts.extend_from_slice(group_hooks(i));
+        }
+        ix
+    }
+
+    pub fn burn_original(
+        &self,
+        original_mint: Pubkey,
+        original_token_program: Pubkey,
+        print_edition: Option<&PrintEdition>,
+    ) -> Instruction {
+        let entangled_mint = self.entangled_mint(&original_mint);
+        let original_mint_escrow = self.escrow(&original_mint, &original_token_program);
+
+        build(
+            accounts::BurnOriginal {
+                signer: self.signer,
+                entangler_authority: self.entangler_authority,
+                entangled_collection: self.entangled_collection,
+                entangled_pair: find_entangled_pair(&entangled_mint).0,
+                original_collection_mint: self.original_collection_mint,
+                original_collection_metadata: self.original_collection_metadata,
+                original_mint,
+                original_metadata: find_metadata(&original_mint).0,
+                master_edition: find_master_edition(&original_mint).0,
+                parent_mint: print_edition.map(|print| print.master_mint),
+                parent_edition: print_edition
+                    .map(|print| find_master_edition(&print.master_mint).0),
+                parent_token: print_edition.map(|print| print.master_token),
+                edition_marker: print_edition
+                    .map(|print| find_edition_marker(&print.master_mint, print.edition).0),
+                original_mint_escrow,
+                original_mint_escrow_record: Some(
+                    find_token_record(&original_mint, &original_mint_escrow).0,
+                ),
+                entangled_mint,
+                entangled_collection_mint: self.entangled_collection_mint,
+                entangled_mint_account: self
+                    .account(&entangled_mint, &self.entangled_token_program),
+                metadata_program: mpl_token_metadata::ID,
+                sysvar_instructions: Some(sysvar_instructions::ID),
+                original_token_program,
+                token_program: self.entangled_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+                rent: rent::ID,
+            },
+            instruction::BurnOriginal {},
+        )
+    }
+
+    pub fn initialize_compressed_pair(&self, asset: &CompressedAsset) -> Instruction {
+        let entangled_mint = self.entangled_mint(&asset_id(&asset.merkle_tree, asset.nonce));
+        let entangled_mint_escrow = self.escrow(&entangled_mint, &self.entangled_token_program);
+
+        let mut ix = build(
+            accounts::InitializeCompressedPair {
+                signer: self.signer,
+                creator: self.creator,
+                entangler_authority: self.entangler_authority,
+                master_edition: self.entangled_collection_master_edition,
+                entangled_collection: self.entangled_collection,
+                entangled_collection_mint: self.entangled_collection_mint,
+                entangled_collection_metadata: self.entangled_collection_metadata,
+                merkle_tree: asset.merkle_tree,
+                leaf_owner: asset.leaf_owner,
+                leaf_delegate: asset.leaf_delegate,
+                entangled_mint,
+                entangled_metadata: find_metadata(&entangled_mint).0,
+                entangled_master_edition: Some(find_master_edition(&entangled_mint).0),
+                entangled_mint_escrow,
+                entangled_mint_escrow_record: Some(
+                    find_token_record(&entangled_mint, &entangled_mint_escrow).0,
+                ),
+                metadata_program: mpl_token_metadata::ID,
+                compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+                sysvar_instructions: Some(sysvar_instructions::ID),
+                token_program: self.entangled_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+                rent: rent::ID,
+            },
+            instruction::InitializeCompressedPair {
+                root: asset.root,
+                metadata: asset.metadata.clone(),
+                nonce: asset.nonce,
+                index: asset.index,
+            },
+        );
+        ix.accounts.extend(asset.proof_accounts());
+        ix
+    }
+
+    pub fn entangle_compressed(
+        &self,
+        asset: &CompressedAsset,
+        entangled_authorization_rules: Option<Pubkey>,
+    ) -> Instruction {
+        let entangled_mint = self.entangled_mint(&asset_id(&asset.merkle_tree, asset.nonce));
+        let entangled_mint_account = self.account(&entangled_mint, &self.entangled_token_program);
+        let entangled_mint_escrow = self.escrow(&entangled_mint, &self.entangled_token_program);
+
+        let mut ix = build(
+            accounts::EntangleCompressed {
+                signer: self.signer,
+                entangler_authority: self.entangler_authority,
+                entangled_collection: self.entangled_collection,
+                entangled_pair: find_entangled_pair(&entangled_mint).0,
+                entangled_collection_mint: self.entangled_collection_mint,
+                entangled_collection_metadata: self.entangled_collection_metadata,
+                tree_config: find_tree_config(&asset.merkle_tree).0,
+                merkle_tree: asset.merkle_tree,
+                leaf_delegate: asset.leaf_delegate,
+                entangled_mint,
+                entangled_master_edition: Some(find_master_edition(&entangled_mint).0),
+                entangled_authorization_rules,
+                entangled_mint_account,
+                entangled_mint_account_record: Some(
+                    find_token_record(&entangled_mint, &entangled_mint_account).0,
+                ),
+                entangled_metadata: find_metadata(&entangled_mint).0,
+                entangled_mint_escrow,
+                entangled_mint_escrow_record: Some(
+                    find_token_record(&entangled_mint, &entangled_mint_escrow).0,
+                ),
+                metadata_program: mpl_token_metadata::ID,
+                authorization_rules_program: Some(mpl_token_auth_rules::ID),
+                bubblegum_program: mpl_bubblegum::ID,
+                compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+                log_wrapper: SPL_NOOP_ID,
+                sysvar_instructions: Some(sysvar_instructions::ID),
+                token_program: self.entangled_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+                rent: rent::ID,
+            },
+            instruction::EntangleCompressed {
+                root: asset.root,
+                metadata: asset.metadata.clone(),
+                nonce: asset.nonce,
+                index: asset.index,
+                proof_len: asset.proof.len() as u8,
+            },
+        );
+        ix.accounts.extend(asset.proof_accounts());
+        ix
+    }
+
+    pub fn disentangle_compressed(
+        &self,
+        asset: &CompressedAsset,
+        entangled_authorization_rules: Option<Pubkey>,
+    ) -> Instruction {
+        let entangled_mint = self.entangled_mint(&asset_id(&asset.merkle_tree, asset.nonce));
+        let entangled_mint_account = self.account(&entangled_mint, &self.entangled_token_program);
+        let entangled_mint_escrow = self.escrow(&entangled_mint, &self.entangled_token_program);
+
+        let mut ix = build(
+            accounts::DisentangleCompressed {
+                signer: self.signer,
+                entangler_authority: self.entangler_authority,
+                entangled_collection: self.entangled_collection,
+                entangled_collection_mint: self.entangled_collection_mint,
+                entangled_collection_metadata: self.entangled_collection_metadata,
+                tree_config: find_tree_config(&asset.merkle_tree).0,
+                merkle_tree: asset.merkle_tree,
+                entangled_mint,
+                entangled_master_edition: Some(find_master_edition(&entangled_mint).0),
+                entangled_authorization_rules,
+                entangled_mint_account,
+                entangled_mint_account_record: Some(
+                    find_token_record(&entangled_mint, &entangled_mint_account).0,
+                ),
+                entangled_metadata: find_metadata(&entangled_mint).0,
+                entangled_mint_escrow,
+                entangled_mint_escrow_record: Some(
+                    find_token_record(&entangled_mint, &entangled_mint_escrow).0,
+                ),
+                metadata_program: mpl_token_metadata::ID,
+                authorization_rules_program: Some(mpl_token_auth_rules::ID),
+                bubblegum_program: mpl_bubblegum::ID,
+                compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+                log_wrapper: SPL_NOOP_ID,
+                sysvar_instructions: Some(sysvar_instructions::ID),
+                token_program: self.entangled_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+                rent: rent::ID,
+            },
+            instruction::DisentangleCompressed {
+                root: asset.root,
+                metadata: asset.metadata.clone(),
+                nonce: asset.nonce,
+                index: asset.index,
+                proof_len: asset.proof.len() as u8,
+            },
+        );
+        ix.accounts.extend(asset.proof_accounts());
+        ix
+    }
+
+    /// Creates the collection's tree in `merkle_tree`, allocated beforehand. The signer must be
+    /// the entangler's admin or the collection's creator
+    pub fn create_entangled_tree(
+        &self,
+        merkle_tree: Pubkey,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Instruction {
+        build(
+            accounts::CreateEntangledTree {
+                signer: self.signer,
+                state: find_entangler_state().0,
+                entangler_authority: self.entangler_authority,
+                entangled_collection: self.entangled_collection,
+                entangled_collection_metadata: self.entangled_collection_metadata,
+                original_collection_metadata: self.original_collection_metadata,
+                tree_config: find_tree_config(&merkle_tree).0,
+                merkle_tree,
+                bubblegum_program: mpl_bubblegum::ID,
+                compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+                log_wrapper: SPL_NOOP_ID,
+                system_program: system_program::ID,
+            },
+            instruction::CreateEntangledTree {
+                max_depth,
+                max_buffer_size,
+            },
+        )
+    }
+
+    pub fn initialize_tree_pair(
+        &self,
+        original_mint: Pubkey,
+        merkle_tree: Pubkey,
+        original_token_program: Pubkey,
+    ) -> Instruction {
+        let entangled_mint = self.entangled_mint(&original_mint);
+
+        build(
+            accounts::InitializeTreePair {
+                signer: self.signer,
+                creator: self.creator,
+                entangler_authority: self.entangler_authority,
+                master_edition: self.entangled_collection_master_edition,
+                entangled_collection: self.entangled_collection,
+                entangled_pair: find_entangled_pair(&entangled_mint).0,
+                entangled_collection_mint: self.entangled_collection_mint,
+                entangled_collection_metadata: self.entangled_collection_metadata,
+                original_mint,
+                original_metadata: find_metadata(&original_mint).0,
+                original_edition: Some(find_master_edition(&original_mint).0),
+                original_mint_escrow: self.escrow(&original_mint, &original_token_program),
+                original_record: find_original_record(&original_mint).0,
+                entangled_mint,
+                tree_config: find_tree_config(&merkle_tree).0,
+                merkle_tree,
+                bubblegum_signer: find_bubblegum_signer().0,
+                metadata_program: mpl_token_metadata::ID,
+                bubblegum_program: mpl_bubblegum::ID,
+                compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+                log_wrapper: SPL_NOOP_ID,
+                original_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::InitializeTreePair {},
+        )
+    }
+
+    pub fn entangle_tree_pair(
+        &self,
+        original_mint: Pubkey,
+        asset: &CompressedAsset,
+        authorization_rules: Option<Pubkey>,
+        original_token_program: Pubkey,
+    ) -> Instruction {
+        let entangled_mint = self.entangled_mint(&original_mint);
+        let original_mint_account = self.account(&original_mint, &original_token_program);
+        let original_mint_escrow = self.escrow(&original_mint, &original_token_program);
+
+        let mut ix = build(
+            accounts::EntangleTreePair {
+                signer: self.signer,
+                entangler_authority: self.entangler_authority,
+                entangled_collection: self.entangled_collection,
+                entangled_pair: find_entangled_pair(&entangled_mint).0,
+                original_mint,
+                original_metadata: find_metadata(&original_mint).0,
+                original_master_edition: Some(find_master_edition(&original_mint).0),
+                authorization_rules,
+                original_mint_account,
+                original_mint_account_record: Some(
+                    find_token_record(&original_mint, &original_mint_account).0,
+                ),
+                original_mint_escrow,
+                original_mint_escrow_record: Some(
+                    find_token_record(&original_mint, &original_mint_escrow).0,
+                ),
+                entangled_mint,
+                tree_config: find_tree_config(&asset.merkle_tree).0,
+                merkle_tree: asset.merkle_tree,
+                metadata_program: mpl_token_metadata::ID,
+                authorization_rules_program: Some(mpl_token_auth_rules::ID),
+                bubblegum_program: mpl_bubblegum::ID,
+                compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+                log_wrapper: SPL_NOOP_ID,
+                sysvar_instructions: Some(sysvar_instructions::ID),
+                original_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::EntangleTreePair {
+                root: asset.root,
+                metadata: asset.metadata.clone(),
+                nonce: asset.nonce,
+                index: asset.index,
+                proof_len: asset.proof.len() as u8,
+            },
+        );
+        ix.accounts.extend(asset.proof_accounts());
+        ix
+    }
+
+    pub fn disentangle_tree_pair(
+        &self,
+        original_mint: Pubkey,
+        asset: &CompressedAsset,
+        authorization_rules: Option<Pubkey>,
+        original_token_program: Pubkey,
+    ) -> Instruction {
+        let entangled_mint = self.entangled_mint(&original_mint);
+        let original_mint_account = self.account(&original_mint, &original_token_program);
+        let original_mint_escrow = self.escrow(&original_mint, &original_token_program);
+
+        let mut ix = build(
+            accounts::DisentangleTreePair {
+                signer: self.signer,
+                entangler_authority: self.entangler_authority,
+                entangled_collection: self.entangled_collection,
+                entangled_pair: find_entangled_pair(&entangled_mint).0,
+                original_mint,
+                original_metadata: find_metadata(&original_mint).0,
+                original_master_edition: Some(find_master_edition(&original_mint).0),
+                authorization_rules,
+                original_mint_account,
+                original_mint_account_record: Some(
+                    find_token_record(&original_mint, &original_mint_account).0,
+                ),
+                original_mint_escrow,
+                original_mint_escrow_record: Some(
+                    find_token_record(&original_mint, &original_mint_escrow).0,
+                ),
+                entangled_mint,
+                tree_config: find_tree_config(&asset.merkle_tree).0,
+                merkle_tree: asset.merkle_tree,
+                leaf_delegate: asset.leaf_delegate,
+                metadata_program: mpl_token_metadata::ID,
+                authorization_rules_program: Some(mpl_token_auth_rules::ID),
+                bubblegum_program: mpl_bubblegum::ID,
+                compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+                log_wrapper: SPL_NOOP_ID,
+                sysvar_instructions: Some(sysvar_instructions::ID),
+                original_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::DisentangleTreePair {
+                root: asset.root,
+                metadata: asset.metadata.clone(),
+                nonce: asset.nonce,
+                index: asset.index,
+                proof_len: asset.proof.len() as u8,
+            },
+        );
+        ix.accounts.extend(asset.proof_accounts());
+        ix
+    }
+
+    /// Moves the collection from the global authority to its own, signed by the admin
+    pub fn migrate_collection(&self) -> Instruction {
+        let global_authority = find_entangler_authority().0;
+        let entangler_authority = find_collection_authority(&self.id).0;
+
+        build(
+            accounts::MigrateCollection {
+                signer: self.signer,
+                state: find_entangler_state().0,
+                global_authority,
+                entangler_authority,
+                entangled_collection: self.entangled_collection,
+                entangled_collection_mint: self.entangled_collection_mint,
+                entangled_collection_metadata: self.entangled_collection_metadata,
+                master_edition: self.entangled_collection_master_edition,
+                legacy_collection_mint_account: get_associated_token_address_with_program_id(
+                    &global_authority,
+                    &self.entangled_collection_mint,
+                    &anchor_spl::token::ID,
+                ),
+                entangled_collection_mint_account: get_associated_token_address_with_program_id(
+                    &entangler_authority,
+                    &self.entangled_collection_mint,
+                    &anchor_spl::token::ID,
+                ),
+                metadata_program: mpl_token_metadata::ID,
+                sysvar_instructions: sysvar_instructions::ID,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::MigrateCollection {},
+        )
+    }
+
+    /// Moves a pair of a migrated collection to the collection's authority.
+    /// `original_escrowed` tells whether the global authority has an escrow for the original,
+    /// which compressed originals and never entangled ones lack
+    pub fn migrate_pair(
+        &self,
+        original_mint: Pubkey,
+        original_escrowed: bool,
+        authorization_rules: Option<Pubkey>,
+        entangled_authorization_rules: Option<Pubkey>,
+        original_token_program: Pubkey,
+    ) -> Instruction {
+        let global_authority = find_entangler_authority().0;
+        let entangler_authority = find_collection_authority(&self.id).0;
+        let entangled_mint = self.entangled_mint(&original_mint);
+        let original_mint_escrow = get_associated_token_address_with_program_id(
+            &global_authority,
+            &original_mint,
+            &original_token_program,
+        );
+        let original_mint_destination = get_associated_token_address_with_program_id(
+            &entangler_authority,
+            &original_mint,
+            &original_token_program,
+        );
+        let entangled_mint_escrow = get_associated_token_address_with_program_id(
+            &global_authority,
+            &entangled_mint,
+            &self.entangled_token_program,
+        );
+        let entangled_mint_destination = get_associated_token_address_with_program_id(
+            &entangler_authority,
+            &entangled_mint,
+            &self.entangled_token_program,
+        );
+
+        build(
+            accounts::MigratePair {
+                signer: self.signer,
+                global_authority,
+                entangler_authority,
+                entangled_collection: self.entangled_collection,
+                original_mint,
+                original_metadata: find_metadata(&original_mint).0,
+                original_master_edition: Some(find_master_edition(&original_mint).0),
+                authorization_rules,
+                original_mint_escrow: original_escrowed.then_some(original_mint_escrow),
+                original_mint_escrow_record: Some(
+                    find_token_record(&original_mint, &original_mint_escrow).0,
+                ),
+                original_mint_destination,
+                original_mint_destination_record: Some(
+                    find_token_record(&original_mint, &original_mint_destination).0,
+                ),
+                entangled_mint,
+                entangled_pair: find_entangled_pair(&entangled_mint).0,
+                entangled_metadata: find_metadata(&entangled_mint).0,
+                entangled_master_edition: Some(find_master_edition(&entangled_mint).0),
+                entangled_authorization_rules,
+                entangled_mint_escrow,
+                entangled_mint_escrow_record: Some(
+                    find_token_record(&entangled_mint, &entangled_mint_escrow).0,
+                ),
+                entangled_mint_destination,
+                entangled_mint_destination_record: Some(
+                    find_token_record(&entangled_mint, &entangled_mint_destination).0,
+                ),
+                metadata_program: mpl_token_metadata::ID,
+                authorization_rules_program: Some(mpl_token_auth_rules::ID),
+                sysvar_instructions: Some(sysvar_instructions::ID),
+                original_token_program,
+                token_program: self.entangled_token_program,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::MigratePair {},
+        )
+    }
+
+    /// Moves a compressed original escrowed by the global authority to the collection's one
+    pub fn migrate_compressed_original(&self, asset: &CompressedAsset) -> Instruction {
+        let global_authority = find_entangler_authority().0;
+        let entangler_authority = find_collection_authority(&self.id).0;
+        let entangled_mint = self.entangled_mint(&asset_id(&asset.merkle_tree, asset.nonce));
+
+        let mut ix = build(
+            accounts::MigrateCompressedOriginal {
+                signer: self.signer,
+                global_authority,
+                entangler_authority,
+                entangled_collection: self.entangled_collection,
+                tree_config: find_tree_config(&asset.merkle_tree).0,
+                merkle_tree: asset.merkle_tree,
+                entangled_mint,
+                entangled_pair: find_entangled_pair(&entangled_mint).0,
+                entangled_mint_escrow: get_associated_token_address_with_program_id(
+                    &global_authority,
+                    &entangled_mint,
+                    &self.entangled_token_program,
+                ),
+                entangled_mint_destination: get_associated_token_address_with_program_id(
+                    &entangler_authority,
+                    &entangled_mint,
+                    &self.entangled_token_program,
+                ),
+                bubblegum_program: mpl_bubblegum::ID,
+                compression_program: SPL_ACCOUNT_COMPRESSION_ID,
+                log_wrapper: SPL_NOOP_ID,
+                system_program: system_program::ID,
+            },
+            instruction::MigrateCompressedOriginal {
+                root: asset.root,
+                metadata: asset.metadata.clone(),
+                nonce: asset.nonce,
+                index: asset.index,
+            },
+        );
+        ix.accounts.extend(asset.proof_accounts());
+        ix
+    }
+
+    /// Checks the custody of a pair, returning it as return data.
+    /// `frozen_account` is the account holding the original of a non-custodial pair
+    pub fn verify_pair(
+        &self,
+        original_mint: Pubkey,
+        frozen_account: Option<Pubkey>,
+        original_token_program: Pubkey,
+    ) -> Instruction {
+        let entangled_mint = self.entangled_mint(&original_mint);
+
+        build(
+            accounts::VerifyPair {
+                entangler_authority: self.entangler_authority,
+                entangled_collection: self.entangled_collection,
+                entangled_pair: find_entangled_pair(&entangled_mint).0,
+                original_mint,
+                original_mint_escrow: self.escrow(&original_mint, &original_token_program),
+                frozen_account,
+                entangled_mint,
+                entangled_mint_escrow: self.escrow(&entangled_mint, &self.entangled_token_program),
+                original_token_program,
+                token_program: self.entangled_token_program,
+            },
+            instruction::VerifyPair {},
+        )
+    }
+
+    /// Records an original initialized before originals got a record
+    pub fn record_original(&self, original_mint: Pubkey) -> Instruction {
+        let entangled_mint = self.entangled_mint(&original_mint);
+
+        build(
+            accounts::RecordOriginal {
+                signer: self.signer,
+                entangled_collection: self.entangled_collection,
+                original_mint,
+                entangled_mint,
+                entangled_pair: find_entangled_pair(&entangled_mint).0,
+                original_record: find_original_record(&original_mint).0,
+                system_program: system_program::ID,
+            },
+            instruction::RecordOriginal {},
+        )
+    }
+
+    /// Moves tokens backing no pair out of `source`, an account of the client's authority. The
+    /// mint must be no original of the collection, and rescues from the global authority wait
+    /// for original records to be backfilled. The signer must be the entangler's admin
+    pub fn rescue_tokens(
+        &self,
+        mint: Pubkey,
+        source: Pubkey,
+        destination: Pubkey,
+        amount: u64,
+        token_program: Pubkey,
+    ) -> Instruction {
+        let entangled_mint = find_entangled_mint(&self.id, &mint).0;
+
+        build(
+            accounts::RescueTokens {
+                signer: self.signer,
+                state: find_entangler_state().0,
+                entangled_collection: self.entangled_collection,
+                entangler_authority: self.entangler_authority,
+                mint,
+                metadata: find_metadata(&mint).0,
+                original_record: find_original_record(&mint).0,
+                entangled_mint,
+                entangled_pair: find_entangled_pair(&entangled_mint).0,
+                source,
+                destination,
+                token_program,
+            },
+            instruction::RescueTokens { amount },
+        )
+    }
+
+    /// Moves the lamports the collection's authority holds beyond its rent exemption to `earner`,
+    /// the state's earner. The signer must be the entangler's admin
+    pub fn sweep_authority_lamports(&self, earner: Pubkey) -> Instruction {
+        let global = self.entangler_authority == find_entangler_authority().0;
+
+        build(
+            accounts::SweepAuthorityLamports {
+                signer: self.signer,
+                state: find_entangler_state().0,
+                entangled_collection: (!global).then_some(self.entangled_collection),
+                entangler_authority: self.entangler_authority,
+                earner,
+                system_program: system_program::ID,
+            },
+            instruction::SweepAuthorityLamports {},
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN_2022_ID: Pubkey = anchor_spl::token_2022::ID;
+
+    fn client() -> EntanglerClient {
+        EntanglerClient::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            500,
+        )
+    }
+
+    fn ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, mint, token_program)
+    }
+
+    /// Checks the accounts of `ix` against `expected`, the addresses and writability listed in
+    /// the order of the program's `Accounts` struct. Only the first account signs
+    fn assert_accounts(ix: &Instruction, expected: &[(Pubkey, bool)]) {
+        let accounts: Vec<_> = ix
+            .accounts
+            .iter()
+            .map(|meta| (meta.pubkey, meta.is_writable))
+            .collect();
+        assert_eq!(accounts, expected);
+        assert!(ix.accounts[0].is_signer);
+        assert!(ix.accounts[1..].iter().all(|meta| !meta.is_signer));
+    }
+
+    /// The accounts of `entangle` and `disentangle` with the original's escrow at index 12,
+    /// and the frozen account of `disentangle` after it
+    fn swap_accounts(
+        client: &EntanglerClient,
+        original: &Pubkey,
+        original_token_program: &Pubkey,
+        original_escrow: (Pubkey, bool),
+        frozen_account: Option<(Pubkey, bool)>,
+    ) -> Vec<(Pubkey, bool)> {
+        let authority = &client.entangler_authority;
+        let entangled_mint = find_entangled_mint(&client.id, original).0;
+        let original_account = ata(&client.signer, original, original_token_program);
+        let original_escrow_address = ata(authority, original, original_token_program);
+        let entangled_account = ata(&client.signer, &entangled_mint, &anchor_spl::token::ID);
+        let entangled_escrow = ata(authority, &entangled_mint, &anchor_spl::token::ID);
+
+        let mut accounts = vec![
+            (client.signer, true),
+            (*authority, false),
+            (find_entangled_collection(&client.id).0, false),
+            (find_entangled_pair(&entangled_mint).0, true),
+            (find_entangled_collection_mint(&client.id).0, false),
+            (client.entangled_collection_metadata, false),
+            (*original, true),
+            (find_metadata(original).0, true),
+            (find_master_edition(original).0, false),
+            (entangler::ID, false),
+            (original_account, true),
+            (find_token_record(original, &original_account).0, true),
+            original_escrow,
+            (
+                find_token_record(original, &original_escrow_address).0,
+                true,
+            ),
+        ];
+        accounts.extend(frozen_account);
+        accounts.extend([
+            (entangled_mint, false),
+            (find_master_edition(&entangled_mint).0, false),
+            (entangler::ID, false),
+            (entangled_account, true),
+            (
+                find_token_record(&entangled_mint, &entangled_account).0,
+                true,
+            ),
+            (find_metadata(&entangled_mint).0, true),
+            (entangled_escrow, true),
+            (
+                find_token_record(&entangled_mint, &entangled_escrow).0,
+                true,
+            ),
+            (mpl_token_metadata::ID, false),
+            (mpl_token_auth_rules::ID, false),
+            (sysvar_instructions::ID, false),
+            (*original_token_program, false),
+            (anchor_spl::token::ID, false),
+            (associated_token::ID, false),
+            (system_program::ID, false),
+            (rent::ID, false),
+        ]);
+        accounts
+    }
+
+    #[test]
+    fn initialize_pair_follows_the_program_accounts() {
+        let client = client();
+        let original = Pubkey::new_unique();
+        let authority = client.entangler_authority;
+        let entangled_mint = find_entangled_mint(&client.id, &original).0;
+        let entangled_escrow = ata(&authority, &entangled_mint, &anchor_spl::token::ID);
+
+        assert_accounts(
+            &client.initialize_pair(original, TOKEN_2022_ID),
+            &[
+                (client.signer, true),
+                (client.creator, false),
+                (find_collection_authority(&client.id).0, true),
+                (
+                    find_master_edition(&client.entangled_collection_mint).0,
+                    true,
+                ),
+                (client.entangled_collection, false),
+                (client.entangled_collection_mint, true),
+                (client.entangled_collection_metadata, false),
+                (original, true),
+                (find_metadata(&original).0, false),
+                (find_master_edition(&original).0, false),
+                (ata(&authority, &original, &TOKEN_2022_ID), true),
+                (find_original_record(&original).0, true),
+                (entangled_mint, true),
+                (find_metadata(&entangled_mint).0, true),
+                (find_master_edition(&entangled_mint).0, true),
+                (entangled_escrow, true),
+                (
+                    find_token_record(&entangled_mint, &entangled_escrow).0,
+                    true,
+                ),
+                (mpl_token_metadata::ID, false),
+                (sysvar_instructions::ID, false),
+                (TOKEN_2022_ID, false),
+                (anchor_spl::token::ID, false),
+                (associated_token::ID, false),
+                (system_program::ID, false),
+                (rent::ID, false),
+            ],
+        );
+    }
+
+    #[test]
+    fn entangle_follows_the_program_accounts() {
+        let client = client();
+        let original = Pubkey::new_unique();
+        let escrow = ata(&client.entangler_authority, &original, &TOKEN_2022_ID);
+
+        assert_accounts(
+            &client.entangle(original, None, None, TOKEN_2022_ID),
+            &swap_accounts(&client, &original, &TOKEN_2022_ID, (escrow, true), None),
+        );
+    }
+
+    #[test]
+    fn disentangle_follows_the_program_accounts() {
+        let client = client();
+        let original = Pubkey::new_unique();
+        let escrow = ata(&client.entangler_authority, &original, &TOKEN_2022_ID);
+        let frozen = ata(&client.signer, &original, &TOKEN_2022_ID);
+
+        assert_accounts(
+            &client.disentangle(original, None, None, TOKEN_2022_ID, None),
+            &swap_accounts(
+                &client,
+                &original,
+                &TOKEN_2022_ID,
+                (escrow, true),
+                Some((entangler::ID, false)),
+            ),
+        );
+        // Frozen originals don't leave their account, which replaces the escrow
+        assert_accounts(
+            &client.disentangle(original, None, None, TOKEN_2022_ID, Some(frozen)),
+            &swap_accounts(
+                &client,
+                &original,
+                &TOKEN_2022_ID,
+                (entangler::ID, false),
+                Some((frozen, true)),
+            ),
+        );
+    }
+
+    #[test]
+    fn non_custodial_clients_skip_the_original_escrow() {
+        let client = client().with_non_custodial();
+        let original = Pubkey::new_unique();
+
+        assert_accounts(
+            &client.entangle(original, None, None, TOKEN_2022_ID),
+            &swap_accounts(
+                &client,
+                &original,
+                &TOKEN_2022_ID,
+                (entangler::ID, false),
+                None,
+            ),
+        );
+        assert_eq!(
+            client.initialize_pair(original, TOKEN_2022_ID).accounts[10],
+            AccountMeta::new_readonly(entangler::ID, false)
+        );
+    }
+
+    #[test]
+    fn global_authority_clients_use_its_escrows() {
+        let client = client().with_global_authority();
+        let original = Pubkey::new_unique();
+        let authority = find_entangler_authority().0;
+
+        assert_eq!(client.entangler_authority, authority);
+        assert_accounts(
+            &client.entangle(original, None, None, TOKEN_2022_ID),
+            &swap_accounts(
+                &client,
+                &original,
+                &TOKEN_2022_ID,
+                (ata(&authority, &original, &TOKEN_2022_ID), true),
+                None,
+            ),
+        );
+    }
+
+    #[test]
+    fn batches_follow_each_group_with_its_hook_accounts() {
+        let client = client();
+        let originals = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let hooks = vec![
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        ];
+
+        let ix = client.entangle_batch(&originals, TOKEN_2022_ID, std::slice::from_ref(&hooks));
+        assert_eq!(
+            ix.data,
+            instruction::EntangleBatch {
+                hook_accounts: vec![2, 0],
+            }
+            .data()
+        );
+        let mut expected = vec![AccountMeta::new_readonly(client.signer, true)];
+        expected.extend(
+            client
+                .entangle(originals[0], None, None, TOKEN_2022_ID)
+                .accounts,
+        );
+        expected.extend(hooks.clone());
+        expected.extend(
+            client
+                .entangle(originals[1], None, None, TOKEN_2022_ID)
+                .accounts,
+        );
+        assert_eq!(ix.accounts, expected);
+
+        let ix = client.disentangle_batch(&originals, TOKEN_2022_ID, &[vec![], hooks.clone()]);
+        assert_eq!(
+            ix.data,
+            instruction::DisentangleBatch {
+                hook_accounts: vec![0, 2],
+            }
+            .data()
+        );
+        let mut expected = vec![AccountMeta::new_readonly(client.signer, true)];
+        for original in originals {
+            expected.extend(
+                client
+                    .disentangle(original, None, None, TOKEN_2022_ID, None)
+                    .accounts,
+            );
+        }
+        expected.extend(hooks);
+        assert_eq!(ix.accounts, expected);
+    }
+}
@@ -0,0 +1,12 @@
+//! Rust client for the entangler program: PDA helpers, instruction builders
+//! and account deserialisers, plus builders for its royalty hook
+
+#![allow(clippy::result_large_err)]
+
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+pub mod royalty_hook;
+
+pub use entangler::{errors::EntanglerError, seeds, ID};
+pub use instructions::{CompressedAsset, EntanglerClient, PrintEdition};
@@ -0,0 +1,262 @@
+//! Addresses derived by the entangler, Token Metadata and Bubblegum
+
+use anchor_lang::prelude::Pubkey;
+use entangler::seeds::{
+    AUTHORITY_SEED, COLLECTION_AUTHORITY_SEED, COLLECTION_ENTRY_SEED, COLLECTION_MINT_SEED,
+    COLLECTION_SEED, ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED, ORIGINAL_RECORD_SEED, STATE_SEED,
+};
+use mpl_token_metadata::state::EDITION_MARKER_BIT_SIZE;
+
+/// The PDA holding the entangler's admin and fees
+pub fn find_entangler_state() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STATE_SEED.as_bytes()], &entangler::ID)
+}
+
+/// The PDA owning the escrows and entangled mints of collections that still use the
+/// global authority
+pub fn find_entangler_authority() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUTHORITY_SEED.as_bytes()], &entangler::ID)
+}
+
+/// The PDA owning the escrows and entangled mints of the collection entangled under `id`
+pub fn find_collection_authority(id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[COLLECTION_AUTHORITY_SEED.as_bytes(), id.as_ref()],
+        &entangler::ID,
+    )
+}
+
+/// The entry mapping a collection key to its entanglement
+pub fn find_collection_entry(key: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[COLLECTION_ENTRY_SEED.as_bytes(), key.as_bytes()],
+        &entangler::ID,
+    )
+}
+
+/// The account storing the data of the collection entangled under `id`
+pub fn find_entangled_collection(id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[COLLECTION_SEED.as_bytes(), id.as_ref()], &entangler::ID)
+}
+
+/// The collection mint of the entangled tokens
+pub fn find_entangled_collection_mint(id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[COLLECTION_MINT_SEED.as_bytes(), id.as_ref()],
+        &entangler::ID,
+    )
+}
+
+/// The entangled mint of an original mint, or of the asset id of a compressed original
+pub fn find_entangled_mint(id: &Pubkey, original: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ENTANGLED_MINT_SEED.as_bytes(),
+            id.as_ref(),
+            original.as_ref(),
+        ],
+        &entangler::ID,
+    )
+}
+
+/// The pair binding an entangled mint to its original
+pub fn find_entangled_pair(entangled_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ENTANGLED_PAIR_SEED.as_bytes(), entangled_mint.as_ref()],
+        &entangler::ID,
+    )
+}
+
+/// The record marking a mint as the original of a pair
+pub fn find_original_record(original: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ORIGINAL_RECORD_SEED.as_bytes(), original.as_ref()],
+        &entangler::ID,
+    )
+}
+
+/// The Token Metadata account of a mint
+pub fn find_metadata(mint: &Pubkey) -> (Pubkey, u8) {
+    mpl_token_metadata::pda::find_metadata_account(mint)
+}
+
+/// The master or print edition of a mint
+pub fn find_master_edition(mint: &Pubkey) -> (Pubkey, u8) {
+    mpl_token_metadata::pda::find_master_edition_account(mint)
+}
+
+/// The marker recording which editions of a master edition were printed
+pub fn find_edition_marker(master_mint: &Pubkey, edition: u64) -> (Pubkey, u8) {
+    mpl_token_metadata::pda::find_edition_account(
+        master_mint,
+        (edition / EDITION_MARKER_BIT_SIZE).to_string(),
+    )
+}
+
+/// The token record of a programmable token account
+pub fn find_token_record(mint: &Pubkey, token: &Pubkey) -> (Pubkey, u8) {
+    mpl_token_metadata::pda::find_token_record_account(mint, token)
+}
+
+/// The Bubblegum config of a merkle tree
+pub fn find_tree_config(merkle_tree: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[merkle_tree.as_ref()], &mpl_bubblegum::ID)
+}
+
+/// The PDA Bubblegum signs collection verifications with
+pub fn find_bubblegum_signer() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"collection_cpi"], &mpl_bubblegum::ID)
+}
+
+/// The asset id of the leaf minted with `nonce` in a merkle tree
+pub fn asset_id(merkle_tree: &Pubkey, nonce: u64) -> Pubkey {
+    mpl_bubblegum::utils::get_asset_id(merkle_tree, nonce)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that `found` is the address and bump of `seeds`, written out as the programs spell
+    /// them rather than through the shared constants
+    fn assert_pda(found: (Pubkey, u8), seeds: &[&[u8]], program_id: &Pubkey) {
+        let bump = [found.1];
+        let mut seeds = seeds.to_vec();
+        seeds.push(&bump);
+        assert_eq!(
+            Pubkey::create_program_address(&seeds, program_id).unwrap(),
+            found.0
+        );
+    }
+
+    #[test]
+    fn entangler_pdas_use_the_program_seeds() {
+        let id = Pubkey::new_unique();
+        let original = Pubkey::new_unique();
+        let program = &entangler::ID;
+
+        assert_pda(find_entangler_state(), &[b"state"], program);
+        assert_pda(find_entangler_authority(), &[b"authority"], program);
+        assert_pda(
+            find_collection_authority(&id),
+            &[b"collection-authority", id.as_ref()],
+            program,
+        );
+        assert_pda(
+            find_collection_entry("collection key"),
+            &[b"collection-entry", b"collection key"],
+            program,
+        );
+        assert_pda(
+            find_entangled_collection(&id),
+            &[b"collection", id.as_ref()],
+            program,
+        );
+        assert_pda(
+            find_entangled_collection_mint(&id),
+            &[b"collection-mint", id.as_ref()],
+            program,
+        );
+        let entangled_mint = find_entangled_mint(&id, &original);
+        assert_pda(
+            entangled_mint,
+            &[b"entanglement-mint", id.as_ref(), original.as_ref()],
+            program,
+        );
+        assert_pda(
+            find_entangled_pair(&entangled_mint.0),
+            &[b"entanglement-pair", entangled_mint.0.as_ref()],
+            program,
+        );
+        assert_pda(
+            find_original_record(&original),
+            &[b"original-record", original.as_ref()],
+            program,
+        );
+    }
+
+    #[test]
+    fn entangled_mints_depend_on_the_collection() {
+        let original = Pubkey::new_unique();
+        assert_ne!(
+            find_entangled_mint(&Pubkey::new_unique(), &original),
+            find_entangled_mint(&Pubkey::new_unique(), &original)
+        );
+    }
+
+    #[test]
+    fn token_metadata_pdas_use_its_seeds() {
+        let mint = Pubkey::new_unique();
+        let token = Pubkey::new_unique();
+        let program = &mpl_token_metadata::ID;
+
+        assert_pda(
+            find_metadata(&mint),
+            &[b"metadata", program.as_ref(), mint.as_ref()],
+            program,
+        );
+        assert_pda(
+            find_master_edition(&mint),
+            &[b"metadata", program.as_ref(), mint.as_ref(), b"edition"],
+            program,
+        );
+        assert_pda(
+            find_token_record(&mint, &token),
+            &[
+                b"metadata",
+                program.as_ref(),
+                mint.as_ref(),
+                b"token_record",
+                token.as_ref(),
+            ],
+            program,
+        );
+    }
+
+    #[test]
+    fn edition_markers_hold_248_editions() {
+        let master_mint = Pubkey::new_unique();
+        let program = &mpl_token_metadata::ID;
+
+        assert_eq!(
+            find_edition_marker(&master_mint, 1),
+            find_edition_marker(&master_mint, 247)
+        );
+        assert_ne!(
+            find_edition_marker(&master_mint, 247),
+            find_edition_marker(&master_mint, 248)
+        );
+        assert_pda(
+            find_edition_marker(&master_mint, 500),
+            &[
+                b"metadata",
+                program.as_ref(),
+                master_mint.as_ref(),
+                b"edition",
+                b"2",
+            ],
+            program,
+        );
+    }
+
+    #[test]
+    fn bubblegum_pdas_use_its_seeds() {
+        let merkle_tree = Pubkey::new_unique();
+        let program = &mpl_bubblegum::ID;
+
+        assert_pda(
+            find_tree_config(&merkle_tree),
+            &[merkle_tree.as_ref()],
+            program,
+        );
+        assert_pda(find_bubblegum_signer(), &[b"collection_cpi"], program);
+        assert_eq!(
+            asset_id(&merkle_tree, 7),
+            Pubkey::find_program_address(
+                &[b"asset", merkle_tree.as_ref(), &7u64.to_le_bytes()],
+                program
+            )
+            .0
+        );
+    }
+}
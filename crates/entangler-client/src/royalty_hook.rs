@@ -0,0 +1,146 @@
+//! Builders for the royalty hook, the transfer hook of Token-2022 entangled tokens
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_program,
+    },
+    InstructionData, ToAccountMetas,
+};
+use royalty_hook::{
+    accounts, instruction,
+    seeds::{CONFIG_SEED, EXTRA_ACCOUNT_METAS_SEED, RECEIPT_SEED},
+};
+
+pub use royalty_hook::{
+    errors::RoyaltyHookError,
+    state::{RoyaltyConfig, RoyaltyReceipt},
+    ID,
+};
+
+/// The validation account listing the accounts Token-2022 forwards to the hook
+pub fn find_extra_account_meta_list(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[EXTRA_ACCOUNT_METAS_SEED.as_bytes(), mint.as_ref()],
+        &royalty_hook::ID,
+    )
+}
+
+/// The receipt of the royalties paid for the next transfer of a mint
+pub fn find_royalty_receipt(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECEIPT_SEED.as_bytes(), mint.as_ref()], &royalty_hook::ID)
+}
+
+/// The minimum price royalties are paid on for an entangled collection
+pub fn find_royalty_config(collection_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CONFIG_SEED.as_bytes(), collection_mint.as_ref()],
+        &royalty_hook::ID,
+    )
+}
+
+/// The accounts every transfer of a mint hooked by the royalty hook needs, to append to the
+/// transfer or to the entangler instructions moving the entangled token
+pub fn transfer_hook_accounts(mint: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(find_extra_account_meta_list(mint).0, false),
+        AccountMeta::new(find_royalty_receipt(mint).0, false),
+        AccountMeta::new_readonly(royalty_hook::ID, false),
+    ]
+}
+
+/// Creates the validation account of an entangled mint, once after its pair got initialized
+pub fn initialize_extra_account_meta_list(payer: Pubkey, mint: Pubkey) -> Instruction {
+    Instruction {
+        program_id: royalty_hook::ID,
+        accounts: accounts::InitializeExtraAccountMetaList {
+            payer,
+            mint,
+            extra_account_meta_list: find_extra_account_meta_list(&mint).0,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitializeExtraAccountMetaList {}.data(),
+    }
+}
+
+/// Sets the minimum price of the collection of `mint`, signed by the creator in its metadata
+pub fn set_min_price(
+    creator: Pubkey,
+    mint: Pubkey,
+    collection_mint: Pubkey,
+    min_price: u64,
+) -> Instruction {
+    Instruction {
+        program_id: royalty_hook::ID,
+        accounts: accounts::SetMinPrice {
+            creator,
+            mint,
+            collection_mint,
+            config: find_royalty_config(&collection_mint).0,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::SetMinPrice { min_price }.data(),
+    }
+}
+
+/// Pays the royalties of a sale at `price` lamports to the mint's creator, which allows its next
+/// transfer. The price can't be below the minimum of the collection
+pub fn pay_royalties(
+    payer: Pubkey,
+    mint: Pubkey,
+    creator: Pubkey,
+    collection_mint: Pubkey,
+    price: u64,
+) -> Instruction {
+    Instruction {
+        program_id: royalty_hook::ID,
+        accounts: accounts::PayRoyalties {
+            payer,
+            mint,
+            creator,
+            config: find_royalty_config(&collection_mint).0,
+            receipt: find_royalty_receipt(&mint).0,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::PayRoyalties { price }.data(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn royalty_hook_pdas_use_the_program_seeds() {
+        let mint = Pubkey::new_unique();
+
+        // Token-2022 looks the validation account up at the transfer hook interface's address
+        assert_eq!(
+            find_extra_account_meta_list(&mint).0,
+            spl_transfer_hook_interface::get_extra_account_metas_address(&mint, &royalty_hook::ID)
+        );
+        let (receipt, bump) = find_royalty_receipt(&mint);
+        assert_eq!(
+            Pubkey::create_program_address(
+                &[b"royalty-receipt", mint.as_ref(), &[bump]],
+                &royalty_hook::ID
+            )
+            .unwrap(),
+            receipt
+        );
+        let collection_mint = Pubkey::new_unique();
+        let (config, bump) = find_royalty_config(&collection_mint);
+        assert_eq!(
+            Pubkey::create_program_address(
+                &[b"royalty-config", collection_mint.as_ref(), &[bump]],
+                &royalty_hook::ID
+            )
+            .unwrap(),
+            config
+        );
+    }
+}
@@ -0,0 +1,6 @@
+#![no_main]
+
+use entangler_fuzz::Input;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: Input| entangler_fuzz::run(input));
@@ -0,0 +1,63 @@
+use arbitrary::Arbitrary;
+
+/// A sequence of operations on the fuzzed bank
+#[derive(Arbitrary, Debug)]
+pub struct Input {
+    pub kind: Kind,
+    pub operations: Vec<Operation>,
+}
+
+/// The collection the operations run against
+#[derive(Arbitrary, Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    /// Legacy originals held in escrow while entangled
+    Custodial,
+    /// Legacy originals frozen in their holder's account while entangled
+    NonCustodial,
+    /// Programmable originals entangled with programmable tokens
+    Programmable,
+    /// Token-2022 originals entangled with Token-2022 tokens
+    Token2022,
+}
+
+/// Originals and actors are picked by index, modulo their count
+#[derive(Arbitrary, Debug)]
+pub enum Operation {
+    /// An entangler instruction sent by an actor, with some of its accounts substituted
+    Instruction {
+        actor: u8,
+        action: Action,
+        substitutions: Vec<Substitution>,
+    },
+    /// A plain transfer of an original, or of its entangled token, between actors
+    Transfer {
+        original: u8,
+        entangled: bool,
+        from: u8,
+        to: u8,
+    },
+}
+
+/// The entangler instructions moving tokens
+#[derive(Arbitrary, Debug)]
+pub enum Action {
+    InitializePair { original: u8 },
+    CreateEntangledEdition { original: u8 },
+    Entangle { original: u8 },
+    Disentangle { original: u8 },
+    InitializeAndEntangle { original: u8 },
+    EntangleBatch { originals: Vec<u8> },
+    DisentangleBatch { originals: Vec<u8> },
+    BurnOriginal { original: u8 },
+}
+
+/// Replaces one account of an instruction with an address of the world's pool
+#[derive(Arbitrary, Debug)]
+pub struct Substitution {
+    /// The account replaced, modulo the instruction's account count
+    pub index: u8,
+    /// The replacement, modulo the pool's size
+    pub address: u8,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
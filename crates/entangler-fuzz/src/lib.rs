@@ -0,0 +1,29 @@
+//! Fuzzing of the entangler's account validation: arbitrary sequences of instructions, with
+//! arbitrary accounts substituted into them, run against the BPF builds of the programs.
+//!
+//! After every operation the holdings of each original and entangled token are checked against
+//! its pair, so a substitution that slips past an unchecked account shows up as a broken
+//! invariant. Each input picks the kind of collection it runs against.
+
+mod input;
+mod world;
+
+pub use input::{Action, Input, Kind, Operation, Substitution};
+pub use world::World;
+
+/// The operations run on one bank, the rest of the input is ignored
+pub const MAX_OPERATIONS: usize = 16;
+
+/// Runs the operations on a fresh bank, panicking when an invariant breaks
+pub fn run(input: Input) {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let mut world = World::new(input.kind).await;
+            for operation in input.operations.into_iter().take(MAX_OPERATIONS) {
+                world.step(operation).await;
+            }
+        });
+}
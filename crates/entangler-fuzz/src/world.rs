@@ -0,0 +1,568 @@
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{system_program, sysvar},
+};
+use anchor_spl::{
+    token::spl_token,
+    token_2022::{self, spl_token_2022},
+};
+use entangler_client::{
+    accounts::{EntangledPair, EntangledStandard},
+    pda::{
+        find_entangled_mint, find_entangled_pair, find_entangler_state, find_master_edition,
+        find_metadata, find_original_record, find_token_record,
+    },
+    EntanglerClient,
+};
+use entangler_tests::{
+    create_keypair, fetch, mint_nft, mint_programmable_nft, mint_token, mint_token_2022_nft,
+    solana_program_test::ProgramTestContext, start, verify_collection,
+};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction, instruction::Instruction, signature::Keypair,
+    signer::Signer, transaction::Transaction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account as TokenAccount, Mint},
+};
+
+use crate::input::{Action, Kind, Operation, Substitution};
+
+/// The accounts sending instructions and holding tokens
+const ACTORS: usize = 3;
+
+/// The originals in the verified collection, followed by an unverified one and an outsider
+const VERIFIED: usize = 3;
+const ORIGINALS: usize = VERIFIED + 2;
+
+/// The accounts an instruction gets substituted, and the originals a batch takes
+const MAX_SUBSTITUTIONS: usize = 4;
+const MAX_BATCH: usize = 4;
+
+/// Programmable tokens need more than the default compute units
+const COMPUTE_UNITS: u32 = 600_000;
+
+/// Where a token's supply is held
+#[derive(Clone, Debug, PartialEq)]
+struct Holdings {
+    supply: u64,
+    escrow: u64,
+    actors: [u64; ACTORS],
+    /// Whether each actor's account is frozen
+    frozen: [bool; ACTORS],
+}
+
+impl Holdings {
+    fn circulating(&self) -> u64 {
+        self.actors.iter().sum()
+    }
+
+    fn holder(&self) -> Option<usize> {
+        self.actors.iter().position(|amount| *amount > 0)
+    }
+
+    /// The amount in the entangler's custody: escrowed, or frozen in its holder's account by a
+    /// non-custodial pair
+    fn custody(&self, non_custodial: bool) -> u64 {
+        let frozen: u64 = self
+            .actors
+            .iter()
+            .zip(&self.frozen)
+            .filter(|(_, frozen)| non_custodial && **frozen)
+            .map(|(amount, _)| amount)
+            .sum();
+        self.escrow + frozen
+    }
+}
+
+/// The state of an original and its entangled token
+#[derive(Clone, Debug, PartialEq)]
+struct Token {
+    original: Holdings,
+    entangled: Holdings,
+    paired: bool,
+}
+
+/// A bank with an entangled collection of some kind and originals spread among the actors
+pub struct World {
+    ctx: ProgramTestContext,
+    kind: Kind,
+    actors: Vec<Keypair>,
+    client: EntanglerClient,
+    originals: Vec<Pubkey>,
+    /// The token program of the verified originals, the others are legacy tokens
+    token_program: Pubkey,
+    /// Every address the instructions use, to substitute accounts with
+    pool: Vec<Pubkey>,
+}
+
+impl World {
+    pub async fn new(kind: Kind) -> Self {
+        let mut ctx = start().await;
+        let admin = ctx.payer.pubkey();
+        let creator = create_keypair(&mut ctx).await;
+        let mut actors = Vec::new();
+        for _ in 0..ACTORS {
+            actors.push(create_keypair(&mut ctx).await);
+        }
+
+        let fee_mint = mint_token(&mut ctx, &creator, &admin, 10_000).await;
+        let collection_mint =
+            mint_nft(&mut ctx, "FUZZ", &creator, &creator.pubkey(), None, 0).await;
+        let mut originals = Vec::new();
+        for index in 0..ORIGINALS {
+            let owner = actors[index % ACTORS].pubkey();
+            let mint = match kind {
+                Kind::Programmable if index < VERIFIED => {
+                    mint_programmable_nft(&mut ctx, &creator, &owner, &collection_mint).await
+                }
+                Kind::Token2022 if index < VERIFIED => {
+                    mint_token_2022_nft(&mut ctx, &creator, &owner, &collection_mint).await
+                }
+                _ => {
+                    let collection = (index <= VERIFIED).then_some(collection_mint);
+                    let mint = mint_nft(&mut ctx, "FUZZ", &creator, &owner, collection, 0).await;
+                    if index < VERIFIED {
+                        verify_collection(&mut ctx, &mint, &collection_mint, &creator).await;
+                    }
+                    mint
+                }
+            };
+            originals.push(mint);
+        }
+
+        let mut client = EntanglerClient::new(
+            collection_mint,
+            admin,
+            Pubkey::new_unique(),
+            creator.pubkey(),
+            500,
+        );
+        let mut token_program = spl_token::ID;
+        let entangled_standard = match kind {
+            Kind::Custodial => EntangledStandard::NonFungible,
+            Kind::NonCustodial => {
+                client = client.with_non_custodial();
+                EntangledStandard::NonFungible
+            }
+            Kind::Programmable => EntangledStandard::ProgrammableNonFungible,
+            Kind::Token2022 => {
+                client = client.with_entangled_token_program(token_2022::ID);
+                token_program = token_2022::ID;
+                EntangledStandard::Token2022
+            }
+        };
+        let instructions = [
+            client.set_entangler_state(admin, creator.pubkey(), fee_mint, 0),
+            client.create_collection(
+                false,
+                false,
+                None,
+                entangled_standard,
+                None,
+                None,
+                kind == Kind::NonCustodial,
+            ),
+        ];
+        entangler_tests::process(&mut ctx, &instructions, &[])
+            .await
+            .unwrap();
+
+        let mut pool = vec![
+            admin,
+            creator.pubkey(),
+            fee_mint,
+            find_entangler_state().0,
+            client.entangler_authority,
+            client.entangled_collection,
+            client.entangled_collection_mint,
+            client.entangled_collection_metadata,
+            client.entangled_collection_master_edition,
+            client.entangled_collection_mint_account,
+            collection_mint,
+            find_metadata(&collection_mint).0,
+            find_master_edition(&collection_mint).0,
+            spl_token::ID,
+            token_2022::ID,
+            mpl_token_metadata::ID,
+            system_program::ID,
+            sysvar::instructions::ID,
+            Pubkey::new_unique(),
+        ];
+        pool.extend(actors.iter().map(|actor| actor.pubkey()));
+        for (index, original) in originals.iter().enumerate() {
+            let entangled = find_entangled_mint(&client.id, original).0;
+            let original_token_program = if index < VERIFIED {
+                token_program
+            } else {
+                spl_token::ID
+            };
+            let mints = [
+                (*original, original_token_program),
+                (entangled, client.entangled_token_program),
+            ];
+            for (mint, token_program) in mints {
+                let owners = std::iter::once(client.entangler_authority)
+                    .chain(actors.iter().map(|actor| actor.pubkey()));
+                let accounts: Vec<Pubkey> = owners
+                    .map(|owner| {
+                        get_associated_token_address_with_program_id(&owner, &mint, &token_program)
+                    })
+                    .collect();
+                pool.extend([mint, find_metadata(&mint).0, find_master_edition(&mint).0]);
+                if kind == Kind::Programmable {
+                    pool.extend(
+                        accounts
+                            .iter()
+                            .map(|account| find_token_record(&mint, account).0),
+                    );
+                }
+                pool.extend(accounts);
+            }
+            pool.extend([
+                find_entangled_pair(&entangled).0,
+                find_original_record(original).0,
+            ]);
+        }
+
+        Self {
+            ctx,
+            kind,
+            actors,
+            client,
+            originals,
+            token_program,
+            pool,
+        }
+    }
+
+    fn original(&self, index: u8) -> Pubkey {
+        self.originals[index as usize % ORIGINALS]
+    }
+
+    /// The token program of the original at `index`
+    fn token_program(&self, index: u8) -> Pubkey {
+        if (index as usize % ORIGINALS) < VERIFIED {
+            self.token_program
+        } else {
+            spl_token::ID
+        }
+    }
+
+    fn batch(&self, indices: &[u8]) -> Vec<Pubkey> {
+        indices
+            .iter()
+            .take(MAX_BATCH)
+            .map(|index| self.original(*index))
+            .collect()
+    }
+
+    /// The account a non-custodial pair froze its original in
+    async fn frozen_account(&mut self, original: &Pubkey) -> Option<Pubkey> {
+        let entangled = find_entangled_mint(&self.client.id, original).0;
+        let pair: Option<EntangledPair> =
+            fetch(&mut self.ctx, &find_entangled_pair(&entangled).0).await;
+        pair.and_then(|pair| pair.frozen_account)
+    }
+
+    async fn instruction(&mut self, client: &EntanglerClient, action: &Action) -> Instruction {
+        // Batches take a single token program, the verified originals'
+        let token_program = self.token_program;
+        match action {
+            Action::InitializePair { original } => {
+                client.initialize_pair(self.original(*original), self.token_program(*original))
+            }
+            Action::CreateEntangledEdition { original } => {
+                client.create_entangled_edition(self.original(*original))
+            }
+            Action::Entangle { original } => client.entangle(
+                self.original(*original),
+                None,
+                None,
+                self.token_program(*original),
+            ),
+            Action::Disentangle { original } => {
+                let mint = self.original(*original);
+                let frozen_account = self.frozen_account(&mint).await;
+                client.disentangle(
+                    mint,
+                    None,
+                    None,
+                    self.token_program(*original),
+                    frozen_account,
+                )
+            }
+            Action::InitializeAndEntangle { original } => client.initialize_and_entangle(
+                self.original(*original),
+                None,
+                None,
+                self.token_program(*original),
+            ),
+            Action::EntangleBatch { originals } => {
+                client.entangle_batch(&self.batch(originals), token_program, &[])
+            }
+            Action::DisentangleBatch { originals } => {
+                client.disentangle_batch(&self.batch(originals), token_program, &[])
+            }
+            Action::BurnOriginal { original } => client.burn_original(
+                self.original(*original),
+                self.token_program(*original),
+                None,
+            ),
+        }
+    }
+
+    fn substitute(&self, instruction: &mut Instruction, substitutions: &[Substitution]) {
+        if instruction.accounts.is_empty() {
+            return;
+        }
+        for substitution in substitutions.iter().take(MAX_SUBSTITUTIONS) {
+            let index = substitution.index as usize % instruction.accounts.len();
+            let account = &mut instruction.accounts[index];
+            account.pubkey = self.pool[substitution.address as usize % self.pool.len()];
+            account.is_signer = substitution.is_signer;
+            account.is_writable = substitution.is_writable;
+        }
+
+        // Only the payer and the actors can sign, the other signer accounts go unsigned
+        let payer = self.ctx.payer.pubkey();
+        for account in &mut instruction.accounts {
+            let signs = account.pubkey == payer
+                || self
+                    .actors
+                    .iter()
+                    .any(|actor| actor.pubkey() == account.pubkey);
+            account.is_signer &= signs;
+        }
+    }
+
+    /// Sends the instruction signed by the actors it needs, `false` if it failed
+    async fn send(&mut self, instruction: Instruction) -> bool {
+        let signers: Vec<&Keypair> = std::iter::once(&self.ctx.payer)
+            .chain(self.actors.iter().filter(|actor| {
+                instruction
+                    .accounts
+                    .iter()
+                    .any(|account| account.is_signer && account.pubkey == actor.pubkey())
+            }))
+            .collect();
+        let instructions = [
+            ComputeBudgetInstruction::set_compute_unit_limit(COMPUTE_UNITS),
+            instruction,
+        ];
+        // Identical operations reuse the blockhash and fail as duplicates, which must not
+        // move tokens either, rather than waiting for a new blockhash every time
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&self.ctx.payer.pubkey()),
+            &signers,
+            self.ctx.last_blockhash,
+        );
+        self.ctx
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .is_ok()
+    }
+
+    async fn supply(&mut self, mint: &Pubkey) -> u64 {
+        let account = self.ctx.banks_client.get_account(*mint).await.unwrap();
+        account
+            .and_then(|account| StateWithExtensions::<Mint>::unpack(&account.data).ok())
+            .map_or(0, |mint| mint.base.supply)
+    }
+
+    /// A token account of either token program, `None` if it doesn't exist
+    async fn token_account(&mut self, address: &Pubkey) -> Option<TokenAccount> {
+        let account = self.ctx.banks_client.get_account(*address).await.unwrap()?;
+        StateWithExtensions::<TokenAccount>::unpack(&account.data)
+            .ok()
+            .map(|state| state.base)
+    }
+
+    async fn holdings(&mut self, mint: &Pubkey, token_program: &Pubkey) -> Holdings {
+        let escrow = get_associated_token_address_with_program_id(
+            &self.client.entangler_authority,
+            mint,
+            token_program,
+        );
+        let owners: Vec<Pubkey> = self.actors.iter().map(|actor| actor.pubkey()).collect();
+        let mut actors = [0; ACTORS];
+        let mut frozen = [false; ACTORS];
+        for (index, owner) in owners.iter().enumerate() {
+            let address = get_associated_token_address_with_program_id(owner, mint, token_program);
+            if let Some(account) = self.token_account(&address).await {
+                actors[index] = account.amount;
+                frozen[index] = account.is_frozen();
+            }
+        }
+        Holdings {
+            supply: self.supply(mint).await,
+            escrow: self
+                .token_account(&escrow)
+                .await
+                .map_or(0, |account| account.amount),
+            actors,
+            frozen,
+        }
+    }
+
+    async fn snapshot(&mut self) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        for index in 0..ORIGINALS {
+            let original = self.originals[index];
+            let token_program = self.token_program(index as u8);
+            let entangled = find_entangled_mint(&self.client.id, &original).0;
+            let entangled_token_program = self.client.entangled_token_program;
+            let pair = find_entangled_pair(&entangled).0;
+            tokens.push(Token {
+                original: self.holdings(&original, &token_program).await,
+                entangled: self.holdings(&entangled, &entangled_token_program).await,
+                paired: self
+                    .ctx
+                    .banks_client
+                    .get_account(pair)
+                    .await
+                    .unwrap()
+                    .is_some(),
+            });
+        }
+        tokens
+    }
+
+    /// Runs the operation and checks the invariants it must keep
+    pub async fn step(&mut self, operation: Operation) {
+        let before = self.snapshot().await;
+        let succeeded = match operation {
+            Operation::Instruction {
+                actor,
+                action,
+                substitutions,
+            } => {
+                let mut client = self.client.clone();
+                client.signer = self.actors[actor as usize % ACTORS].pubkey();
+                let mut instruction = self.instruction(&client, &action).await;
+                self.substitute(&mut instruction, &substitutions);
+                self.send(instruction).await
+            }
+            Operation::Transfer {
+                original,
+                entangled,
+                from,
+                to,
+            } => {
+                let (mint, token_program) = if entangled {
+                    let mint = find_entangled_mint(&self.client.id, &self.original(original)).0;
+                    (mint, self.client.entangled_token_program)
+                } else {
+                    (self.original(original), self.token_program(original))
+                };
+                let from = self.actors[from as usize % ACTORS].pubkey();
+                let to = self.actors[to as usize % ACTORS].pubkey();
+                // Both instructions are sent on their own, as the transfer fails more often
+                let create = create_associated_token_account_idempotent(
+                    &self.ctx.payer.pubkey(),
+                    &to,
+                    &mint,
+                    &token_program,
+                );
+                let transfer = spl_token_2022::instruction::transfer_checked(
+                    &token_program,
+                    &get_associated_token_address_with_program_id(&from, &mint, &token_program),
+                    &mint,
+                    &get_associated_token_address_with_program_id(&to, &mint, &token_program),
+                    &from,
+                    &[],
+                    1,
+                    0,
+                )
+                .unwrap();
+                self.send(create).await && self.send(transfer).await
+            }
+        };
+        let after = self.snapshot().await;
+
+        let non_custodial = self.kind == Kind::NonCustodial;
+        for (index, (before, after)) in before.iter().zip(&after).enumerate() {
+            check(index, before, after, succeeded, non_custodial);
+        }
+    }
+}
+
+fn check(index: usize, before: &Token, after: &Token, succeeded: bool, non_custodial: bool) {
+    if !succeeded {
+        assert_eq!(before, after, "A failed operation moved token {index}");
+        return;
+    }
+
+    for holdings in [&after.original, &after.entangled] {
+        assert!(holdings.supply <= 1, "Token {index} was minted twice");
+        assert_eq!(
+            holdings.escrow + holdings.circulating(),
+            holdings.supply,
+            "Token {index} left the escrows and the actors' accounts"
+        );
+    }
+
+    // Initializing a pair mints its entangled token into escrow, and only entangling creates the
+    // pair, after which exactly one of its tokens is in custody until the original is burned
+    let original_custody = after.original.custody(non_custodial);
+    if after.paired {
+        assert_eq!(after.original.supply, 1, "Pair {index} outlived its original");
+        assert_eq!(
+            after.entangled.supply, 1,
+            "Pair {index} has no entangled token"
+        );
+        assert_eq!(
+            original_custody + after.entangled.escrow,
+            1,
+            "Pair {index} has both or neither of its tokens in custody"
+        );
+    } else if after.original.supply == 1 {
+        assert_eq!(
+            original_custody, 0,
+            "Original {index} is in custody without a pair"
+        );
+        assert_eq!(
+            after.entangled.circulating(),
+            0,
+            "Entangled token {index} circulates without a pair"
+        );
+    }
+
+    // Tokens only leave custody for the holder of their counterpart
+    if before.original.custody(non_custodial) == 1
+        && original_custody == 0
+        && after.original.supply == 1
+    {
+        assert_eq!(
+            after.original.holder(),
+            before.entangled.holder(),
+            "Original {index} was released to someone else than the entangled token's holder"
+        );
+    }
+    if before.entangled.circulating() == 0 && after.entangled.circulating() == 1 {
+        assert_eq!(
+            after.entangled.holder(),
+            before.original.holder(),
+            "Entangled token {index} was released to someone else than the original's holder"
+        );
+    }
+
+    if before.original.supply == 1 && after.original.supply == 0 {
+        assert!(before.paired, "Original {index} was burned without a pair");
+        assert_eq!(
+            before.original.escrow, 1,
+            "Original {index} was burned while it circulated"
+        );
+        assert!(
+            !after.paired,
+            "Original {index} was burned but its pair remains"
+        );
+    }
+}
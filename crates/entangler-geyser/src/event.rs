@@ -0,0 +1,203 @@
+use anchor_lang::prelude::Pubkey;
+use entangler_client::{accounts::EntanglerAccount, pda::find_collection_authority};
+use serde_json::{json, Value};
+
+/// An SPL token account is at least this long, Token-2022 ones add extensions after it
+const TOKEN_ACCOUNT_LEN: usize = 165;
+
+fn optional(key: &Option<Pubkey>) -> Value {
+    key.map_or(Value::Null, |key| Value::String(key.to_string()))
+}
+
+/// Decodes an entangler account update, or `None` for the accounts nobody indexes
+pub fn program_account(address: &Pubkey, data: &[u8], lamports: u64) -> Option<Value> {
+    if lamports == 0 {
+        return Some(json!({ "kind": "closed", "address": address.to_string() }));
+    }
+
+    Some(match EntanglerAccount::try_from_bytes(data)? {
+        EntanglerAccount::Pair(pair) => json!({
+            "kind": "pair",
+            "address": address.to_string(),
+            "original_mint": pair.original_mint.to_string(),
+            "entangled_mint": pair.entangled_mint.to_string(),
+            "frozen_account": optional(&pair.frozen_account),
+            "migrated": pair.migrated,
+        }),
+        EntanglerAccount::Collection(collection) => json!({
+            "kind": "collection",
+            "address": address.to_string(),
+            "id": collection.id.to_string(),
+            "original_collection_mint": collection.original_collection_mint.to_string(),
+            "entangled_collection_mint": collection.entangled_collection_mint.to_string(),
+            "royalties": collection.royalties,
+            "one_way": collection.one_way,
+            "allow_unverified": collection.allow_unverified,
+            "original_creator": optional(&collection.original_creator),
+            "entangled_standard": format!("{:?}", collection.entangled_standard),
+            "rule_set": optional(&collection.rule_set),
+            "transfer_hook_program": optional(&collection.transfer_hook_program),
+            "entangled_tree": optional(&collection.entangled_tree),
+            "non_custodial": collection.non_custodial,
+            "scoped_authority": collection.scoped_authority,
+        }),
+        EntanglerAccount::CollectionEntry(_)
+        | EntanglerAccount::State(_)
+        | EntanglerAccount::OriginalRecord(_) => return None,
+    })
+}
+
+/// The authority owning the escrows of a collection, if the account is one
+pub fn collection_authority(data: &[u8]) -> Option<Pubkey> {
+    match EntanglerAccount::try_from_bytes(data)? {
+        EntanglerAccount::Collection(collection) => {
+            Some(find_collection_authority(&collection.id).0)
+        }
+        _ => None,
+    }
+}
+
+/// Decodes a token account update as the update of an escrow, along with its owner, which
+/// makes it one if it is an entangler authority
+pub fn escrow_account(address: &Pubkey, data: &[u8]) -> Option<(Pubkey, Value)> {
+    if data.len() < TOKEN_ACCOUNT_LEN {
+        return None;
+    }
+    let owner = Pubkey::try_from(&data[32..64]).ok()?;
+    let mint = Pubkey::try_from(&data[..32]).ok()?;
+    let amount = u64::from_le_bytes(data[64..72].try_into().ok()?);
+
+    Some((
+        owner,
+        json!({
+            "kind": "escrow",
+            "address": address.to_string(),
+            "mint": mint.to_string(),
+            "authority": owner.to_string(),
+            "amount": amount,
+        }),
+    ))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use anchor_lang::{solana_program::program_pack::Pack, AccountSerialize};
+    use anchor_spl::token::spl_token;
+    use entangler_client::accounts::{EntangledCollection, EntangledPair, EntangledStandard};
+
+    use super::*;
+
+    pub fn collection(id: &Pubkey) -> Vec<u8> {
+        let collection = EntangledCollection {
+            id: *id,
+            original_collection_mint: Pubkey::new_unique(),
+            entangled_collection_mint: Pubkey::new_unique(),
+            royalties: 500,
+            one_way: false,
+            allow_unverified: false,
+            original_creator: None,
+            entangled_standard: EntangledStandard::NonFungible,
+            rule_set: None,
+            transfer_hook_program: None,
+            entangled_tree: None,
+            non_custodial: false,
+            scoped_authority: true,
+        };
+        let mut data = Vec::new();
+        collection.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    pub fn pair(original_mint: &Pubkey, entangled_mint: &Pubkey) -> Vec<u8> {
+        let pair = EntangledPair {
+            original_mint: *original_mint,
+            entangled_mint: *entangled_mint,
+            frozen_account: None,
+            migrated: true,
+        };
+        let mut data = Vec::new();
+        pair.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    pub fn token(owner: &Pubkey, mint: &Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        let token = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        spl_token::state::Account::pack(token, &mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn decodes_pairs_and_collections() {
+        let address = Pubkey::new_unique();
+        let (original_mint, entangled_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let event = program_account(&address, &pair(&original_mint, &entangled_mint), 1).unwrap();
+        assert_eq!(event["kind"], "pair");
+        assert_eq!(event["address"], address.to_string());
+        assert_eq!(event["original_mint"], original_mint.to_string());
+        assert_eq!(event["entangled_mint"], entangled_mint.to_string());
+        assert_eq!(event["frozen_account"], Value::Null);
+        assert_eq!(event["migrated"], true);
+
+        let id = Pubkey::new_unique();
+        let event = program_account(&address, &collection(&id), 1).unwrap();
+        assert_eq!(event["kind"], "collection");
+        assert_eq!(event["id"], id.to_string());
+        assert_eq!(event["entangled_standard"], "NonFungible");
+        assert_eq!(event["scoped_authority"], true);
+    }
+
+    #[test]
+    fn reports_closed_accounts_and_skips_unknown_ones() {
+        let address = Pubkey::new_unique();
+        let event = program_account(&address, &[], 0).unwrap();
+        assert_eq!(
+            event,
+            json!({ "kind": "closed", "address": address.to_string() })
+        );
+        assert!(program_account(&address, &[0; 64], 1).is_none());
+    }
+
+    #[test]
+    fn finds_the_authority_of_collections() {
+        let id = Pubkey::new_unique();
+        assert_eq!(
+            collection_authority(&collection(&id)),
+            Some(find_collection_authority(&id).0)
+        );
+        let mint = Pubkey::new_unique();
+        assert_eq!(collection_authority(&pair(&mint, &mint)), None);
+    }
+
+    #[test]
+    fn decodes_token_accounts_as_escrows() {
+        let (authority, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let address = Pubkey::new_unique();
+        let (owner, event) = escrow_account(&address, &token(&authority, &mint, 1)).unwrap();
+        assert_eq!(owner, authority);
+        assert_eq!(
+            event,
+            json!({
+                "kind": "escrow",
+                "address": address.to_string(),
+                "mint": mint.to_string(),
+                "authority": authority.to_string(),
+                "amount": 1,
+            })
+        );
+        assert!(escrow_account(&address, &[0; 64]).is_none());
+
+        // Token-2022 accounts carry their extensions after the base account
+        let mut extended = token(&authority, &mint, 0);
+        extended.extend([2, 0, 0, 0]);
+        let (owner, event) = escrow_account(&address, &extended).unwrap();
+        assert_eq!(owner, authority);
+        assert_eq!(event["amount"], 0);
+    }
+}
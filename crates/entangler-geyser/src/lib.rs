@@ -0,0 +1,342 @@
+//! Geyser plugin streaming entangler pairs, collections and escrows as JSON lines,
+//! one update per line with the slot it happened in
+
+mod event;
+
+use std::{
+    collections::HashSet,
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    sync::{Mutex, RwLock},
+};
+
+use anchor_lang::prelude::Pubkey;
+use entangler_client::pda::find_entangler_authority;
+use serde::Deserialize;
+use serde_json::Value;
+use solana_geyser_plugin_interface::geyser_plugin_interface::{
+    GeyserPlugin, GeyserPluginError, ReplicaAccountInfoVersions, Result, SlotStatus,
+};
+
+#[derive(Deserialize)]
+struct Config {
+    /// The file the updates are appended to
+    output: String,
+}
+
+#[derive(Default)]
+pub struct EntanglerPlugin {
+    /// The global authority, and the authorities of the collections seen so far
+    authorities: RwLock<HashSet<Pubkey>>,
+    /// Escrow updates streamed at startup whose owner wasn't a known authority yet, with their
+    /// slot. Their collection may come later in the snapshot, so they are replayed once it ends
+    startup_escrows: Mutex<Vec<(Pubkey, Value, u64)>>,
+    sink: Option<Mutex<BufWriter<File>>>,
+}
+
+impl std::fmt::Debug for EntanglerPlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EntanglerPlugin").finish()
+    }
+}
+
+impl EntanglerPlugin {
+    /// Appends an update to the output as a JSON line
+    fn write(&self, mut event: Value, slot: u64) -> Result<()> {
+        let Some(sink) = &self.sink else {
+            return Ok(());
+        };
+        event["slot"] = slot.into();
+        let mut sink = sink.lock().unwrap();
+        writeln!(sink, "{event}").map_err(|err| GeyserPluginError::AccountsUpdateError {
+            msg: err.to_string(),
+        })
+    }
+}
+
+impl GeyserPlugin for EntanglerPlugin {
+    fn name(&self) -> &'static str {
+        "entangler-geyser"
+    }
+
+    fn on_load(&mut self, config_file: &str) -> Result<()> {
+        let config = std::fs::read_to_string(config_file)?;
+        let config: Config = serde_json::from_str(&config).map_err(|err| {
+            GeyserPluginError::ConfigFileReadError {
+                msg: err.to_string(),
+            }
+        })?;
+        let output = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(config.output)?;
+
+        self.authorities = RwLock::new(HashSet::from([find_entangler_authority().0]));
+        self.sink = Some(Mutex::new(BufWriter::new(output)));
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        if let Some(sink) = self.sink.take() {
+            let _ = sink.into_inner().map(|mut sink| sink.flush());
+        }
+    }
+
+    fn update_account(
+        &self,
+        account: ReplicaAccountInfoVersions,
+        slot: u64,
+        is_startup: bool,
+    ) -> Result<()> {
+        let (pubkey, owner, lamports, data) = match account {
+            ReplicaAccountInfoVersions::V0_0_1(account) => (
+                account.pubkey,
+                account.owner,
+                account.lamports,
+                account.data,
+            ),
+            ReplicaAccountInfoVersions::V0_0_2(account) => (
+                account.pubkey,
+                account.owner,
+                account.lamports,
+                account.data,
+            ),
+            ReplicaAccountInfoVersions::V0_0_3(account) => (
+                account.pubkey,
+                account.owner,
+                account.lamports,
+                account.data,
+            ),
+        };
+        let (Ok(address), Ok(owner)) = (Pubkey::try_from(pubkey), Pubkey::try_from(owner)) else {
+            return Ok(());
+        };
+
+        let event = if owner == entangler_client::ID {
+            if let Some(authority) = event::collection_authority(data) {
+                self.authorities.write().unwrap().insert(authority);
+            }
+            event::program_account(&address, data, lamports)
+        } else if owner == anchor_spl::token::ID || owner == anchor_spl::token_2022::ID {
+            let Some((authority, event)) = event::escrow_account(&address, data) else {
+                return Ok(());
+            };
+            if self.authorities.read().unwrap().contains(&authority) {
+                Some(event)
+            } else {
+                // Authorities are PDAs, so accounts of wallets can't turn out to be escrows
+                if is_startup && !authority.is_on_curve() {
+                    let mut startup_escrows = self.startup_escrows.lock().unwrap();
+                    startup_escrows.push((authority, event, slot));
+                }
+                None
+            }
+        } else {
+            None
+        };
+        match event {
+            Some(event) => self.write(event, slot),
+            None => Ok(()),
+        }
+    }
+
+    fn notify_end_of_startup(&self) -> Result<()> {
+        let startup_escrows = std::mem::take(&mut *self.startup_escrows.lock().unwrap());
+        let authorities = self.authorities.read().unwrap();
+        for (authority, event, slot) in startup_escrows {
+            if authorities.contains(&authority) {
+                self.write(event, slot)?;
+            }
+        }
+        if let Some(sink) = &self.sink {
+            sink.lock().unwrap().flush()?;
+        }
+        Ok(())
+    }
+
+    fn update_slot_status(
+        &self,
+        _slot: u64,
+        _parent: Option<u64>,
+        _status: SlotStatus,
+    ) -> Result<()> {
+        // Flushed once per slot so readers tailing the file see updates promptly
+        if let Some(sink) = &self.sink {
+            sink.lock().unwrap().flush()?;
+        }
+        Ok(())
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        true
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        false
+    }
+}
+
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+/// # Safety
+///
+/// Called by the validator, which takes ownership of the returned plugin
+pub unsafe extern "C" fn _create_plugin() -> *mut dyn GeyserPlugin {
+    Box::into_raw(Box::<EntanglerPlugin>::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use entangler_client::pda::{find_collection_authority, find_entangled_collection};
+    use serde_json::json;
+    use solana_geyser_plugin_interface::geyser_plugin_interface::ReplicaAccountInfo;
+
+    use super::*;
+    use crate::event::tests::{collection, pair, token};
+
+    /// A plugin loaded with an empty output file of its own
+    fn load(name: &str) -> (EntanglerPlugin, PathBuf) {
+        let directory = std::env::temp_dir();
+        let output = directory.join(format!("entangler-geyser-{name}-{}", std::process::id()));
+        let config = output.with_extension("json");
+        std::fs::write(&output, "").unwrap();
+        std::fs::write(&config, json!({ "output": output }).to_string()).unwrap();
+
+        let mut plugin = EntanglerPlugin::default();
+        plugin.on_load(config.to_str().unwrap()).unwrap();
+        (plugin, output)
+    }
+
+    fn update(
+        plugin: &EntanglerPlugin,
+        address: &Pubkey,
+        owner: &Pubkey,
+        data: &[u8],
+        slot: u64,
+        is_startup: bool,
+    ) {
+        let account = ReplicaAccountInfo {
+            pubkey: address.as_ref(),
+            lamports: 1,
+            owner: owner.as_ref(),
+            executable: false,
+            rent_epoch: 0,
+            data,
+            write_version: 0,
+        };
+        plugin
+            .update_account(
+                ReplicaAccountInfoVersions::V0_0_1(&account),
+                slot,
+                is_startup,
+            )
+            .unwrap();
+    }
+
+    fn written(output: &PathBuf) -> Vec<Value> {
+        std::fs::read_to_string(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn replays_startup_escrows_once_their_collection_is_seen() {
+        let (plugin, output) = load("startup");
+        let id = Pubkey::new_unique();
+        let authority = find_collection_authority(&id).0;
+        let mint = Pubkey::new_unique();
+        let escrow = Pubkey::new_unique();
+        update(
+            &plugin,
+            &escrow,
+            &anchor_spl::token::ID,
+            &token(&authority, &mint, 1),
+            1,
+            true,
+        );
+        // Escrow of a collection the snapshot doesn't hold
+        let unknown = find_collection_authority(&Pubkey::new_unique()).0;
+        update(
+            &plugin,
+            &Pubkey::new_unique(),
+            &anchor_spl::token::ID,
+            &token(&unknown, &mint, 1),
+            1,
+            true,
+        );
+        let address = find_entangled_collection(&id).0;
+        update(
+            &plugin,
+            &address,
+            &entangler_client::ID,
+            &collection(&id),
+            2,
+            true,
+        );
+        plugin.notify_end_of_startup().unwrap();
+
+        let events = written(&output);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["kind"], "collection");
+        assert_eq!(events[0]["slot"], 2);
+        assert_eq!(events[1]["kind"], "escrow");
+        assert_eq!(events[1]["address"], escrow.to_string());
+        assert_eq!(events[1]["authority"], authority.to_string());
+        assert_eq!(events[1]["slot"], 1);
+
+        // Replayed once only
+        plugin.notify_end_of_startup().unwrap();
+        assert_eq!(written(&output).len(), 2);
+    }
+
+    #[test]
+    fn streams_updates_of_known_authorities() {
+        let (plugin, output) = load("stream");
+        let mint = Pubkey::new_unique();
+        let global = find_entangler_authority().0;
+        update(
+            &plugin,
+            &Pubkey::new_unique(),
+            &anchor_spl::token_2022::ID,
+            &token(&global, &mint, 1),
+            3,
+            false,
+        );
+        // Escrows of collections not seen yet are left out after startup
+        let id = Pubkey::new_unique();
+        let authority = find_collection_authority(&id).0;
+        update(
+            &plugin,
+            &Pubkey::new_unique(),
+            &anchor_spl::token::ID,
+            &token(&authority, &mint, 1),
+            3,
+            false,
+        );
+        let pair_address = Pubkey::new_unique();
+        update(
+            &plugin,
+            &pair_address,
+            &entangler_client::ID,
+            &pair(&mint, &Pubkey::new_unique()),
+            4,
+            false,
+        );
+        plugin
+            .update_slot_status(4, None, SlotStatus::Processed)
+            .unwrap();
+
+        let events = written(&output);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["kind"], "escrow");
+        assert_eq!(events[0]["authority"], global.to_string());
+        assert_eq!(events[0]["slot"], 3);
+        assert_eq!(events[1]["kind"], "pair");
+        assert_eq!(events[1]["address"], pair_address.to_string());
+        assert_eq!(events[1]["slot"], 4);
+    }
+}
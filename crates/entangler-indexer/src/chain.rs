@@ -0,0 +1,632 @@
+use std::str::FromStr;
+
+use anchor_lang::{prelude::Pubkey, Discriminator};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_2022::spl_token_2022::{
+        extension::StateWithExtensions,
+        state::{Account as TokenAccount, Mint},
+    },
+};
+use anyhow::Result;
+use entangler_client::{
+    accounts::{EntangledPair, EntanglerAccount},
+    pda::{find_collection_authority, find_entangled_mint, find_entangler_authority},
+};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+    rpc_response::RpcConfirmedTransactionStatusWithSignature,
+};
+use solana_sdk::{account::Account, signature::Signature};
+use solana_transaction_status::{option_serializer::OptionSerializer, UiTransactionEncoding};
+
+/// Where the original of a pair currently is
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PairStatus {
+    /// The original is escrowed or frozen, the entangled token circulates
+    Entangled,
+    /// The entangled token is escrowed, the original circulates
+    Disentangled,
+    /// The original was burned and the pair closed, the entangled token circulates for good
+    Burned,
+}
+
+impl PairStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PairStatus::Entangled => "entangled",
+            PairStatus::Disentangled => "disentangled",
+            PairStatus::Burned => "burned",
+        }
+    }
+}
+
+/// A pair with the collection it belongs to, its status and the owner of its circulating token
+pub struct PairState {
+    pub address: Pubkey,
+    pub id: Option<Pubkey>,
+    pub pair: EntangledPair,
+    pub status: PairStatus,
+    /// The owner of the circulating token, unknown when that token is compressed
+    pub holder: Option<Pubkey>,
+}
+
+/// The reads the index is built from. `RpcChain` serves them from an RPC node, and every
+/// classification is derived from them
+pub trait Chain {
+    /// Every account of the program starting with a discriminator
+    fn program_accounts(&self, discriminator: [u8; 8]) -> Result<Vec<(Pubkey, Account)>>;
+
+    /// The accounts at `keys`, in order, `None` where there is none
+    fn accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<Account>>>;
+
+    /// The token accounts of a mint, largest first
+    fn largest_token_accounts(&self, mint: &Pubkey) -> Result<Vec<Pubkey>>;
+
+    fn slot(&self) -> Result<u64>;
+
+    /// The program's latest transaction
+    fn newest_signature(&self) -> Result<Option<RpcConfirmedTransactionStatusWithSignature>>;
+
+    /// The program's transactions newer than `until`, oldest first
+    fn signatures_since(
+        &self,
+        until: Option<&str>,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>>;
+
+    /// Every account a transaction loaded, including those of its address lookup tables
+    fn transaction_keys(&self, signature: &str) -> Result<Vec<Pubkey>>;
+
+    fn pairs(&self) -> Result<Vec<(Pubkey, EntangledPair)>> {
+        Ok(self
+            .program_accounts(EntangledPair::DISCRIMINATOR)?
+            .into_iter()
+            .filter_map(|(address, account)| {
+                match EntanglerAccount::try_from_bytes(&account.data)? {
+                    EntanglerAccount::Pair(pair) => Some((address, pair)),
+                    _ => None,
+                }
+            })
+            .collect())
+    }
+
+    /// Decodes the entangler accounts among `keys`, skipping the others
+    fn entangler_accounts(&self, keys: &[Pubkey]) -> Result<Vec<(Pubkey, EntanglerAccount)>> {
+        let mut decoded = Vec::new();
+        for (key, account) in keys.iter().zip(self.accounts(keys)?) {
+            let decoded_account = account
+                .filter(|account| account.owner == entangler_client::ID)
+                .and_then(|account| EntanglerAccount::try_from_bytes(&account.data));
+            if let Some(account) = decoded_account {
+                decoded.push((*key, account));
+            }
+        }
+        Ok(decoded)
+    }
+
+    fn account(&self, key: &Pubkey) -> Result<Option<Account>> {
+        Ok(self.accounts(&[*key])?.pop().flatten())
+    }
+
+    /// The amount an SPL mint has in circulation, or `None` if it is not one (a compressed asset
+    /// id, which has no account)
+    fn supply(&self, mint: &Pubkey) -> Result<Option<u64>> {
+        let Some(account) = self.account(mint)? else {
+            return Ok(None);
+        };
+        if account.owner != anchor_spl::token::ID && account.owner != anchor_spl::token_2022::ID {
+            return Ok(None);
+        }
+        Ok(Some(
+            StateWithExtensions::<Mint>::unpack(&account.data)?
+                .base
+                .supply,
+        ))
+    }
+
+    /// Whether an escrow of a mint holds its token, either the global authority's or the one of
+    /// the collection entangled under `id`, as pairs move from the first to the second
+    fn escrowed(&self, mint: &Pubkey, id: Option<&Pubkey>) -> Result<bool> {
+        let Some(account) = self.account(mint)? else {
+            return Ok(false);
+        };
+        let escrows: Vec<Pubkey> = std::iter::once(find_entangler_authority().0)
+            .chain(id.map(|id| find_collection_authority(id).0))
+            .map(|authority| {
+                get_associated_token_address_with_program_id(&authority, mint, &account.owner)
+            })
+            .collect();
+        Ok(self
+            .accounts(&escrows)?
+            .iter()
+            .flatten()
+            .any(|escrow| token_account(escrow).is_some_and(|escrow| escrow.amount != 0)))
+    }
+
+    /// The owner of the token account holding a non-fungible mint, unknown for compressed tokens
+    fn holder(&self, mint: &Pubkey) -> Result<Option<Pubkey>> {
+        if self.supply(mint)?.is_none() {
+            return Ok(None);
+        }
+        let largest = self.largest_token_accounts(mint)?;
+        Ok(self
+            .accounts(&largest)?
+            .iter()
+            .flatten()
+            .filter_map(token_account)
+            .find(|account| account.amount != 0)
+            .map(|account| account.owner))
+    }
+
+    /// Finds out where both tokens of a pair are
+    fn pair_state(
+        &self,
+        address: Pubkey,
+        pair: EntangledPair,
+        ids: &[Pubkey],
+    ) -> Result<PairState> {
+        let id = ids
+            .iter()
+            .find(|id| find_entangled_mint(id, &pair.original_mint).0 == pair.entangled_mint)
+            .copied();
+
+        let status = match self.supply(&pair.original_mint)? {
+            Some(0) => PairStatus::Burned,
+            Some(_)
+                if pair.frozen_account.is_some()
+                    || self.escrowed(&pair.original_mint, id.as_ref())? =>
+            {
+                PairStatus::Entangled
+            }
+            Some(_) => PairStatus::Disentangled,
+            // Compressed originals are told apart by the entangled escrow
+            None if self.escrowed(&pair.entangled_mint, id.as_ref())? => PairStatus::Disentangled,
+            None => PairStatus::Entangled,
+        };
+        let holder = match status {
+            PairStatus::Entangled | PairStatus::Burned => self.holder(&pair.entangled_mint)?,
+            PairStatus::Disentangled => self.holder(&pair.original_mint)?,
+        };
+
+        Ok(PairState {
+            address,
+            id,
+            pair,
+            status,
+            holder,
+        })
+    }
+}
+
+/// Decodes a token account of either token program
+fn token_account(account: &Account) -> Option<TokenAccount> {
+    StateWithExtensions::<TokenAccount>::unpack(&account.data)
+        .ok()
+        .map(|account| account.base)
+}
+
+/// Reads the program and token accounts the index is built from
+pub struct RpcChain {
+    pub rpc: RpcClient,
+}
+
+impl Chain for RpcChain {
+    fn program_accounts(&self, discriminator: [u8; 8]) -> Result<Vec<(Pubkey, Account)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                0,
+                discriminator.to_vec(),
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        Ok(self
+            .rpc
+            .get_program_accounts_with_config(&entangler_client::ID, config)?)
+    }
+
+    fn accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        let mut accounts = Vec::new();
+        for chunk in keys.chunks(100) {
+            accounts.extend(self.rpc.get_multiple_accounts(chunk)?);
+        }
+        Ok(accounts)
+    }
+
+    fn largest_token_accounts(&self, mint: &Pubkey) -> Result<Vec<Pubkey>> {
+        self.rpc
+            .get_token_largest_accounts(mint)?
+            .into_iter()
+            .map(|largest| Ok(Pubkey::from_str(&largest.address)?))
+            .collect()
+    }
+
+    fn slot(&self) -> Result<u64> {
+        Ok(self.rpc.get_slot()?)
+    }
+
+    fn newest_signature(&self) -> Result<Option<RpcConfirmedTransactionStatusWithSignature>> {
+        let page = self.rpc.get_signatures_for_address_with_config(
+            &entangler_client::ID,
+            GetConfirmedSignaturesForAddress2Config {
+                before: None,
+                until: None,
+                limit: Some(1),
+                commitment: Some(self.rpc.commitment()),
+            },
+        )?;
+        Ok(page.into_iter().next())
+    }
+
+    fn signatures_since(
+        &self,
+        until: Option<&str>,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let until = until.map(Signature::from_str).transpose()?;
+        let mut signatures = Vec::new();
+        let mut before = None;
+        loop {
+            let page = self.rpc.get_signatures_for_address_with_config(
+                &entangler_client::ID,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: None,
+                    commitment: Some(self.rpc.commitment()),
+                },
+            )?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            before = Some(Signature::from_str(&last.signature)?);
+            signatures.extend(page);
+        }
+        signatures.reverse();
+        Ok(signatures)
+    }
+
+    fn transaction_keys(&self, signature: &str) -> Result<Vec<Pubkey>> {
+        let transaction = self.rpc.get_transaction_with_config(
+            &Signature::from_str(signature)?,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(self.rpc.commitment()),
+                max_supported_transaction_version: Some(0),
+            },
+        )?;
+        let mut keys = transaction
+            .transaction
+            .transaction
+            .decode()
+            .map(|transaction| transaction.message.static_account_keys().to_vec())
+            .unwrap_or_default();
+        if let Some(meta) = transaction.transaction.meta {
+            if let OptionSerializer::Some(loaded) = meta.loaded_addresses {
+                for key in loaded.writable.iter().chain(&loaded.readonly) {
+                    keys.push(Pubkey::from_str(key)?);
+                }
+            }
+        }
+        Ok(keys)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::collections::HashMap;
+
+    use anchor_lang::{solana_program::program_pack::Pack, AccountSerialize};
+    use anchor_spl::token::spl_token;
+    use entangler_client::{
+        accounts::{EntangledCollection, EntangledStandard},
+        pda::{find_entangled_collection, find_entangled_pair},
+    };
+    use solana_sdk::program_option::COption;
+
+    use super::*;
+
+    /// A chain held in memory, with the program's transactions and the keys they loaded
+    #[derive(Default)]
+    pub struct MemoryChain {
+        pub accounts: HashMap<Pubkey, Account>,
+        pub transactions: Vec<(String, u64, Vec<Pubkey>)>,
+    }
+
+    impl MemoryChain {
+        pub fn set(&mut self, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+            let account = Account {
+                lamports: 1,
+                data,
+                owner,
+                executable: false,
+                rent_epoch: 0,
+            };
+            self.accounts.insert(address, account);
+        }
+
+        pub fn mint(&mut self, supply: u64) -> Pubkey {
+            let mint = Pubkey::new_unique();
+            let mut data = vec![0; spl_token::state::Mint::LEN];
+            let state = spl_token::state::Mint {
+                mint_authority: COption::None,
+                supply,
+                decimals: 0,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            };
+            spl_token::state::Mint::pack(state, &mut data).unwrap();
+            self.set(mint, spl_token::ID, data);
+            mint
+        }
+
+        /// Gives the token of `mint` to the associated account of `owner`, emptying the others
+        pub fn hold(&mut self, owner: &Pubkey, mint: &Pubkey) {
+            for account in self.accounts.values_mut() {
+                if let Ok(mut token) = spl_token::state::Account::unpack(&account.data) {
+                    if token.mint == *mint {
+                        token.amount = 0;
+                        spl_token::state::Account::pack(token, &mut account.data).unwrap();
+                    }
+                }
+            }
+            let address = get_associated_token_address_with_program_id(owner, mint, &spl_token::ID);
+            let mut data = vec![0; spl_token::state::Account::LEN];
+            let token = spl_token::state::Account {
+                mint: *mint,
+                owner: *owner,
+                amount: 1,
+                state: spl_token::state::AccountState::Initialized,
+                ..Default::default()
+            };
+            spl_token::state::Account::pack(token, &mut data).unwrap();
+            self.set(address, spl_token::ID, data);
+        }
+
+        /// Writes the collection entangled under `id`
+        pub fn collection(&mut self, id: &Pubkey) {
+            let collection = EntangledCollection {
+                id: *id,
+                original_collection_mint: Pubkey::new_unique(),
+                entangled_collection_mint: Pubkey::new_unique(),
+                royalties: 500,
+                one_way: false,
+                allow_unverified: false,
+                original_creator: None,
+                entangled_standard: EntangledStandard::NonFungible,
+                rule_set: None,
+                transfer_hook_program: None,
+                entangled_tree: None,
+                non_custodial: false,
+                scoped_authority: true,
+            };
+            let mut data = Vec::new();
+            collection.try_serialize(&mut data).unwrap();
+            self.set(find_entangled_collection(id).0, entangler_client::ID, data);
+        }
+
+        /// Writes a pair of the collection entangled under `id`
+        pub fn pair(&mut self, id: &Pubkey, original_mint: Pubkey) -> (Pubkey, EntangledPair) {
+            let entangled_mint = find_entangled_mint(id, &original_mint).0;
+            let pair = EntangledPair {
+                original_mint,
+                entangled_mint,
+                frozen_account: None,
+                migrated: true,
+            };
+            let address = find_entangled_pair(&entangled_mint).0;
+            let mut data = Vec::new();
+            pair.try_serialize(&mut data).unwrap();
+            self.set(address, entangler_client::ID, data);
+            (address, pair)
+        }
+
+        /// Writes the entangled mint of a pair, with one token in circulation
+        pub fn entangled_mint(&mut self, pair: &EntangledPair) {
+            let mut data = vec![0; spl_token::state::Mint::LEN];
+            let state = spl_token::state::Mint {
+                supply: 1,
+                is_initialized: true,
+                ..Default::default()
+            };
+            spl_token::state::Mint::pack(state, &mut data).unwrap();
+            self.set(pair.entangled_mint, spl_token::ID, data);
+        }
+
+        fn signature(
+            &self,
+            (signature, slot, _): &(String, u64, Vec<Pubkey>),
+        ) -> RpcConfirmedTransactionStatusWithSignature {
+            RpcConfirmedTransactionStatusWithSignature {
+                signature: signature.clone(),
+                slot: *slot,
+                err: None,
+                memo: None,
+                block_time: None,
+                confirmation_status: None,
+            }
+        }
+    }
+
+    impl Chain for MemoryChain {
+        fn program_accounts(&self, discriminator: [u8; 8]) -> Result<Vec<(Pubkey, Account)>> {
+            Ok(self
+                .accounts
+                .iter()
+                .filter(|(_, account)| {
+                    account.owner == entangler_client::ID
+                        && account.data.starts_with(&discriminator)
+                })
+                .map(|(address, account)| (*address, account.clone()))
+                .collect())
+        }
+
+        fn accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+            Ok(keys
+                .iter()
+                .map(|key| self.accounts.get(key).cloned())
+                .collect())
+        }
+
+        fn largest_token_accounts(&self, mint: &Pubkey) -> Result<Vec<Pubkey>> {
+            let mut accounts: Vec<(u64, Pubkey)> = self
+                .accounts
+                .iter()
+                .filter_map(|(address, account)| {
+                    let token = token_account(account)?;
+                    (token.mint == *mint).then_some((token.amount, *address))
+                })
+                .collect();
+            accounts.sort_by_key(|(amount, _)| std::cmp::Reverse(*amount));
+            Ok(accounts.into_iter().map(|(_, address)| address).collect())
+        }
+
+        fn slot(&self) -> Result<u64> {
+            Ok(self.transactions.last().map_or(0, |(_, slot, _)| *slot))
+        }
+
+        fn newest_signature(&self) -> Result<Option<RpcConfirmedTransactionStatusWithSignature>> {
+            Ok(self
+                .transactions
+                .last()
+                .map(|transaction| self.signature(transaction)))
+        }
+
+        fn signatures_since(
+            &self,
+            until: Option<&str>,
+        ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+            let start = self
+                .transactions
+                .iter()
+                .position(|(signature, _, _)| Some(signature.as_str()) == until)
+                .map_or(0, |position| position + 1);
+            Ok(self.transactions[start..]
+                .iter()
+                .map(|transaction| self.signature(transaction))
+                .collect())
+        }
+
+        fn transaction_keys(&self, signature: &str) -> Result<Vec<Pubkey>> {
+            Ok(self
+                .transactions
+                .iter()
+                .find(|(known, _, _)| known == signature)
+                .map(|(_, _, keys)| keys.clone())
+                .unwrap_or_default())
+        }
+    }
+
+    fn classify(
+        chain: &MemoryChain,
+        id: &Pubkey,
+        (address, pair): (Pubkey, EntangledPair),
+    ) -> PairState {
+        chain.pair_state(address, pair, &[*id]).unwrap()
+    }
+
+    #[test]
+    fn escrowed_originals_are_entangled() {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        let holder = Pubkey::new_unique();
+        let original = chain.mint(1);
+        let pair = chain.pair(&id, original);
+        chain.entangled_mint(&pair.1);
+        chain.hold(&find_collection_authority(&id).0, &original);
+        chain.hold(&holder, &pair.1.entangled_mint);
+
+        let state = classify(&chain, &id, pair);
+        assert_eq!(state.id, Some(id));
+        assert_eq!(state.status, PairStatus::Entangled);
+        assert_eq!(state.holder, Some(holder));
+    }
+
+    #[test]
+    fn originals_escrowed_by_the_global_authority_are_entangled() {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        let original = chain.mint(1);
+        let pair = chain.pair(&id, original);
+        chain.entangled_mint(&pair.1);
+        chain.hold(&find_entangler_authority().0, &original);
+
+        assert_eq!(classify(&chain, &id, pair).status, PairStatus::Entangled);
+    }
+
+    #[test]
+    fn frozen_originals_are_entangled() {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let original = chain.mint(1);
+        let (address, mut pair) = chain.pair(&id, original);
+        chain.entangled_mint(&pair);
+        chain.hold(&owner, &original);
+        pair.frozen_account = Some(get_associated_token_address_with_program_id(
+            &owner,
+            &original,
+            &anchor_spl::token::ID,
+        ));
+
+        let state = classify(&chain, &id, (address, pair));
+        assert_eq!(state.status, PairStatus::Entangled);
+        assert_eq!(state.holder, None);
+    }
+
+    #[test]
+    fn circulating_originals_are_disentangled() {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        let holder = Pubkey::new_unique();
+        let original = chain.mint(1);
+        let pair = chain.pair(&id, original);
+        chain.entangled_mint(&pair.1);
+        chain.hold(&holder, &original);
+        chain.hold(&find_collection_authority(&id).0, &pair.1.entangled_mint);
+
+        let state = classify(&chain, &id, pair);
+        assert_eq!(state.status, PairStatus::Disentangled);
+        assert_eq!(state.holder, Some(holder));
+    }
+
+    #[test]
+    fn originals_without_supply_are_burned() {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        let holder = Pubkey::new_unique();
+        let original = chain.mint(0);
+        let pair = chain.pair(&id, original);
+        chain.entangled_mint(&pair.1);
+        chain.hold(&holder, &pair.1.entangled_mint);
+
+        let state = classify(&chain, &id, pair);
+        assert_eq!(state.status, PairStatus::Burned);
+        assert_eq!(state.holder, Some(holder));
+    }
+
+    #[test]
+    fn compressed_originals_follow_the_entangled_escrow() {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        let holder = Pubkey::new_unique();
+        // The asset id of a compressed original has no account
+        let pair = chain.pair(&id, Pubkey::new_unique());
+        chain.entangled_mint(&pair.1);
+        chain.hold(&holder, &pair.1.entangled_mint);
+
+        let state = classify(&chain, &id, pair.clone());
+        assert_eq!(state.status, PairStatus::Entangled);
+        assert_eq!(state.holder, Some(holder));
+
+        chain.hold(&find_collection_authority(&id).0, &pair.1.entangled_mint);
+        let state = classify(&chain, &id, pair);
+        assert_eq!(state.status, PairStatus::Disentangled);
+        assert_eq!(state.holder, None);
+    }
+}
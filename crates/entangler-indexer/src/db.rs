@@ -0,0 +1,243 @@
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use entangler_client::accounts::{CollectionEntry, EntangledCollection};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::chain::{PairState, PairStatus};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS collections (
+    address TEXT PRIMARY KEY,
+    id TEXT NOT NULL UNIQUE,
+    original_collection_mint TEXT NOT NULL,
+    entangled_collection_mint TEXT NOT NULL,
+    royalties INTEGER NOT NULL,
+    one_way INTEGER NOT NULL,
+    allow_unverified INTEGER NOT NULL,
+    original_creator TEXT,
+    entangled_standard TEXT NOT NULL,
+    rule_set TEXT,
+    transfer_hook_program TEXT,
+    entangled_tree TEXT,
+    non_custodial INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS entries (
+    address TEXT PRIMARY KEY,
+    id TEXT NOT NULL,
+    key TEXT NOT NULL UNIQUE
+);
+CREATE TABLE IF NOT EXISTS pairs (
+    address TEXT PRIMARY KEY,
+    id TEXT,
+    original_mint TEXT NOT NULL,
+    entangled_mint TEXT NOT NULL UNIQUE,
+    frozen_account TEXT,
+    status TEXT NOT NULL,
+    holder TEXT,
+    slot INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS pairs_by_holder ON pairs (holder);
+CREATE INDEX IF NOT EXISTS pairs_by_original ON pairs (original_mint);
+CREATE TABLE IF NOT EXISTS cursor (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL
+);
+";
+
+fn optional(key: &Option<Pubkey>) -> Option<String> {
+    key.map(|key| key.to_string())
+}
+
+/// The SQLite store of the indexer
+pub struct Database {
+    connection: Connection,
+}
+
+impl Database {
+    pub fn open(path: &str) -> Result<Self> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// Runs `f` in a transaction, so readers never see a half-applied refresh
+    pub fn transaction<T>(&self, f: impl FnOnce(&Self) -> Result<T>) -> Result<T> {
+        self.connection.execute_batch("BEGIN")?;
+        match f(self) {
+            Ok(value) => {
+                self.connection.execute_batch("COMMIT")?;
+                Ok(value)
+            }
+            Err(err) => {
+                self.connection.execute_batch("ROLLBACK")?;
+                Err(err)
+            }
+        }
+    }
+
+    pub fn upsert_collection(
+        &self,
+        address: &Pubkey,
+        collection: &EntangledCollection,
+    ) -> Result<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO collections VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                address.to_string(),
+                collection.id.to_string(),
+                collection.original_collection_mint.to_string(),
+                collection.entangled_collection_mint.to_string(),
+                collection.royalties,
+                collection.one_way,
+                collection.allow_unverified,
+                optional(&collection.original_creator),
+                format!("{:?}", collection.entangled_standard),
+                optional(&collection.rule_set),
+                optional(&collection.transfer_hook_program),
+                optional(&collection.entangled_tree),
+                collection.non_custodial,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn upsert_entry(&self, address: &Pubkey, entry: &CollectionEntry) -> Result<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO entries VALUES (?1, ?2, ?3)",
+            params![address.to_string(), entry.id.to_string(), entry.key],
+        )?;
+        Ok(())
+    }
+
+    pub fn upsert_pair(&self, pair: &PairState, slot: u64) -> Result<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO pairs VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                pair.address.to_string(),
+                optional(&pair.id),
+                pair.pair.original_mint.to_string(),
+                pair.pair.entangled_mint.to_string(),
+                optional(&pair.pair.frozen_account),
+                pair.status.as_str(),
+                optional(&pair.holder),
+                slot,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Marks a pair whose account `burn_original` closed
+    pub fn set_burned(&self, address: &Pubkey, holder: Option<Pubkey>, slot: u64) -> Result<()> {
+        self.connection.execute(
+            "UPDATE pairs SET status = ?2, holder = ?3, slot = ?4 WHERE address = ?1",
+            params![
+                address.to_string(),
+                PairStatus::Burned.as_str(),
+                optional(&holder),
+                slot,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The indexed pairs with their entangled mint
+    pub fn pairs(&self) -> Result<Vec<(Pubkey, Pubkey)>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT address, entangled_mint FROM pairs")?;
+        let pairs = statement
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .map(|pair| {
+                let (address, entangled_mint) = pair?;
+                Ok((address.parse()?, entangled_mint.parse()?))
+            })
+            .collect::<Result<_>>()?;
+        Ok(pairs)
+    }
+
+    /// The indexed pairs with the mint of their circulating token and its holder
+    pub fn circulating(&self) -> Result<Vec<(Pubkey, Pubkey, Option<Pubkey>)>> {
+        let mut statement = self.connection.prepare(
+            "SELECT address, CASE status WHEN ?1 THEN original_mint ELSE entangled_mint END, holder FROM pairs",
+        )?;
+        let pairs = statement
+            .query_map([PairStatus::Disentangled.as_str()], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ))
+            })?
+            .map(|pair| {
+                let (address, mint, holder) = pair?;
+                let holder = holder.map(|holder| holder.parse()).transpose()?;
+                Ok((address.parse()?, mint.parse()?, holder))
+            })
+            .collect::<Result<_>>()?;
+        Ok(pairs)
+    }
+
+    pub fn set_holder(&self, address: &Pubkey, holder: Option<Pubkey>, slot: u64) -> Result<()> {
+        self.connection.execute(
+            "UPDATE pairs SET holder = ?2, slot = ?3 WHERE address = ?1",
+            params![address.to_string(), optional(&holder), slot],
+        )?;
+        Ok(())
+    }
+
+    /// The ids of every indexed collection
+    pub fn collection_ids(&self) -> Result<Vec<Pubkey>> {
+        let mut statement = self.connection.prepare("SELECT id FROM collections")?;
+        let ids = statement
+            .query_map([], |row| row.get::<_, String>(0))?
+            .map(|id| Ok(id?.parse()?))
+            .collect::<Result<_>>()?;
+        Ok(ids)
+    }
+
+    /// The addresses of the pairs whose account or mints are among `keys`
+    pub fn pairs_touching(&self, keys: &[Pubkey]) -> Result<Vec<Pubkey>> {
+        let mut statement = self.connection.prepare(
+            "SELECT address FROM pairs WHERE address = ?1 OR original_mint = ?1 OR entangled_mint = ?1",
+        )?;
+        let mut pairs = Vec::new();
+        for key in keys {
+            let rows = statement.query_map([key.to_string()], |row| row.get::<_, String>(0))?;
+            for address in rows {
+                pairs.push(address?.parse()?);
+            }
+        }
+        pairs.sort();
+        pairs.dedup();
+        Ok(pairs)
+    }
+
+    pub fn count(&self, status: PairStatus) -> Result<u64> {
+        Ok(self.connection.query_row(
+            "SELECT COUNT(*) FROM pairs WHERE status = ?1",
+            [status.as_str()],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// The newest transaction already indexed
+    pub fn cursor(&self) -> Result<Option<String>> {
+        Ok(self
+            .connection
+            .query_row("SELECT signature FROM cursor WHERE id = 0", [], |row| {
+                row.get(0)
+            })
+            .optional()?)
+    }
+
+    pub fn set_cursor(&self, signature: &str, slot: u64) -> Result<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO cursor VALUES (0, ?1, ?2)",
+            params![signature, slot],
+        )?;
+        Ok(())
+    }
+}
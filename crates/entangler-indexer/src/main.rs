@@ -0,0 +1,254 @@
+//! Indexes entangled collections, entries and pairs, with the current holder of every pair, into SQLite
+
+mod chain;
+mod db;
+
+use anchor_lang::{prelude::Pubkey, Discriminator};
+use anyhow::Result;
+use chain::{Chain, PairStatus, RpcChain};
+use clap::{Parser, Subcommand};
+use db::Database;
+use entangler_client::accounts::{CollectionEntry, EntangledCollection, EntanglerAccount};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+#[derive(Parser)]
+#[command(name = "entangler-indexer")]
+struct Cli {
+    /// The RPC URL, such as a local validator's
+    #[arg(
+        short,
+        long,
+        env = "ANCHOR_PROVIDER_URL",
+        default_value = "http://127.0.0.1:8899"
+    )]
+    url: String,
+
+    /// The SQLite database the index is kept in
+    #[arg(short, long, default_value = "entangler.sqlite")]
+    database: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Walks every program account and rebuilds the whole index
+    Sync,
+    /// Only updates the accounts touched by the program's transactions since the last run, and
+    /// the holder of every pair
+    Refresh,
+}
+
+/// Indexes every collection, entry and pair, then moves the cursor to the newest transaction
+fn sync(chain: &impl Chain, db: &Database) -> Result<()> {
+    // Taken first so transactions landing during the walk are refreshed next time
+    let newest = chain.newest_signature()?;
+    let slot = chain.slot()?;
+
+    db.transaction(|db| {
+        for discriminator in [
+            EntangledCollection::DISCRIMINATOR,
+            CollectionEntry::DISCRIMINATOR,
+        ] {
+            for (address, account) in chain.program_accounts(discriminator)? {
+                match EntanglerAccount::try_from_bytes(&account.data) {
+                    Some(EntanglerAccount::Collection(collection)) => {
+                        db.upsert_collection(&address, &collection)?
+                    }
+                    Some(EntanglerAccount::CollectionEntry(entry)) => {
+                        db.upsert_entry(&address, &entry)?
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let ids = db.collection_ids()?;
+        let pairs = chain.pairs()?;
+        for (address, entangled_mint) in db.pairs()? {
+            if pairs.iter().all(|(known, _)| *known != address) {
+                db.set_burned(&address, chain.holder(&entangled_mint)?, slot)?;
+            }
+        }
+        for (address, pair) in pairs {
+            db.upsert_pair(&chain.pair_state(address, pair, &ids)?, slot)?;
+        }
+
+        if let Some(newest) = &newest {
+            db.set_cursor(&newest.signature, newest.slot)?;
+        }
+        Ok(())
+    })
+}
+
+/// Replays the program's transactions since the cursor, refreshing every account they touched
+fn refresh(chain: &impl Chain, db: &Database) -> Result<()> {
+    let Some(cursor) = db.cursor()? else {
+        return sync(chain, db);
+    };
+
+    for signature in chain.signatures_since(Some(&cursor))? {
+        let keys = chain.transaction_keys(&signature.signature)?;
+
+        db.transaction(|db| {
+            let mut pairs = Vec::new();
+            for (address, account) in chain.entangler_accounts(&keys)? {
+                match account {
+                    EntanglerAccount::Collection(collection) => {
+                        db.upsert_collection(&address, &collection)?
+                    }
+                    EntanglerAccount::CollectionEntry(entry) => {
+                        db.upsert_entry(&address, &entry)?
+                    }
+                    EntanglerAccount::Pair(pair) => pairs.push((address, pair)),
+                    EntanglerAccount::State(_) | EntanglerAccount::OriginalRecord(_) => {}
+                }
+            }
+
+            // Pairs only read by the transaction still changed state, and burned ones are closed
+            let touched: Vec<Pubkey> = db
+                .pairs_touching(&keys)?
+                .into_iter()
+                .filter(|address| pairs.iter().all(|(known, _)| known != address))
+                .collect();
+            for (address, account) in chain.entangler_accounts(&touched)? {
+                if let EntanglerAccount::Pair(pair) = account {
+                    pairs.push((address, pair));
+                }
+            }
+            for (address, entangled_mint) in db.pairs()? {
+                let closed =
+                    touched.contains(&address) && pairs.iter().all(|(known, _)| *known != address);
+                if closed {
+                    db.set_burned(&address, chain.holder(&entangled_mint)?, signature.slot)?;
+                }
+            }
+
+            let ids = db.collection_ids()?;
+            for (address, pair) in pairs {
+                db.upsert_pair(&chain.pair_state(address, pair, &ids)?, signature.slot)?;
+            }
+            db.set_cursor(&signature.signature, signature.slot)
+        })?;
+    }
+
+    refresh_holders(chain, db)
+}
+
+/// Resolves the holder of every pair's circulating token again, as transfers outside the program
+/// leave no transaction of its own to replay
+fn refresh_holders(chain: &impl Chain, db: &Database) -> Result<()> {
+    let slot = chain.slot()?;
+    db.transaction(|db| {
+        for (address, mint, holder) in db.circulating()? {
+            let current = chain.holder(&mint)?;
+            if current != holder {
+                db.set_holder(&address, current, slot)?;
+            }
+        }
+        Ok(())
+    })
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let chain = RpcChain {
+        rpc: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+    };
+    let db = Database::open(&cli.database)?;
+
+    match cli.command {
+        Command::Sync => sync(&chain, &db)?,
+        Command::Refresh => refresh(&chain, &db)?,
+    }
+
+    println!(
+        "{} entangled, {} disentangled, {} burned",
+        db.count(PairStatus::Entangled)?,
+        db.count(PairStatus::Disentangled)?,
+        db.count(PairStatus::Burned)?,
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use entangler_client::pda::find_collection_authority;
+
+    use super::*;
+    use crate::chain::tests::MemoryChain;
+
+    /// A chain holding one entangled pair, indexed by `sync`
+    fn synced() -> (MemoryChain, Database, Pubkey, Pubkey) {
+        let mut chain = MemoryChain::default();
+        let id = Pubkey::new_unique();
+        chain.collection(&id);
+        let original = chain.mint(1);
+        let (address, pair) = chain.pair(&id, original);
+        chain.entangled_mint(&pair);
+        chain.hold(&find_collection_authority(&id).0, &original);
+        chain.hold(&Pubkey::new_unique(), &pair.entangled_mint);
+        chain
+            .transactions
+            .push(("entangle".to_string(), 1, vec![address]));
+
+        let db = Database::open(":memory:").unwrap();
+        sync(&chain, &db).unwrap();
+        assert_eq!(db.count(PairStatus::Entangled).unwrap(), 1);
+        (chain, db, id, address)
+    }
+
+    #[test]
+    fn refresh_marks_pairs_closed_by_burn_original_as_burned() {
+        let (mut chain, db, _, address) = synced();
+        let original = chain.pairs().unwrap()[0].1.original_mint;
+
+        chain.accounts.remove(&address);
+        chain
+            .transactions
+            .push(("burn".to_string(), 2, vec![address, original]));
+        refresh(&chain, &db).unwrap();
+
+        assert_eq!(db.count(PairStatus::Entangled).unwrap(), 0);
+        assert_eq!(db.count(PairStatus::Burned).unwrap(), 1);
+        assert_eq!(db.cursor().unwrap().as_deref(), Some("burn"));
+    }
+
+    #[test]
+    fn refresh_follows_transfers_outside_the_program() {
+        let (mut chain, db, _, address) = synced();
+        let entangled_mint = chain.pairs().unwrap()[0].1.entangled_mint;
+
+        // A plain token transfer, which the program never sees
+        let holder = Pubkey::new_unique();
+        chain.hold(&holder, &entangled_mint);
+        refresh(&chain, &db).unwrap();
+
+        assert_eq!(
+            db.circulating().unwrap(),
+            vec![(address, entangled_mint, Some(holder))]
+        );
+        assert_eq!(db.cursor().unwrap().as_deref(), Some("entangle"));
+    }
+
+    #[test]
+    fn refresh_reclassifies_pairs_whose_tokens_moved() {
+        let (mut chain, db, id, _) = synced();
+        let pair = chain.pairs().unwrap()[0].1.clone();
+
+        // Only the mints show up in a disentangle of the pair
+        chain.hold(&Pubkey::new_unique(), &pair.original_mint);
+        chain.hold(&find_collection_authority(&id).0, &pair.entangled_mint);
+        chain.transactions.push((
+            "disentangle".to_string(),
+            2,
+            vec![pair.original_mint, pair.entangled_mint],
+        ));
+        refresh(&chain, &db).unwrap();
+
+        assert_eq!(db.count(PairStatus::Entangled).unwrap(), 0);
+        assert_eq!(db.count(PairStatus::Disentangled).unwrap(), 1);
+    }
+}
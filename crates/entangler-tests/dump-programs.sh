@@ -0,0 +1,12 @@
+#!/usr/bin/env bash
+# Dumps the mainnet programs the program tests load into the given directory, by default
+# tests/fixtures where solana-program-test looks for them. The binaries are not committed, so this
+# runs once before the first test run and again to pick up new program versions
+set -euo pipefail
+
+destination="$(realpath "${1:-$(dirname "$0")/tests/fixtures}")"
+mkdir -p "$destination"
+solana program dump -u m metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s "$destination/mpl_token_metadata.so"
+solana program dump -u m BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY "$destination/mpl_bubblegum.so"
+solana program dump -u m cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK "$destination/spl_account_compression.so"
+solana program dump -u m noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV "$destination/spl_noop.so"
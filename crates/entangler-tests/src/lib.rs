@@ -0,0 +1,833 @@
+//! Fixtures for the entangler's program tests, the Rust side of `tests/utils.ts`.
+//!
+//! The tests run the BPF builds of the programs: `anchor build` the entangler and its royalty
+//! hook, run `dump-programs.sh` to dump the Metaplex and compression programs from mainnet into
+//! `tests/fixtures`, then `SBF_OUT_DIR=../../target/deploy cargo test` from this crate. The dumps
+//! are not committed, so the suite runs on the programs' current mainnet versions.
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        keccak, program_option::COption, program_pack::Pack, system_instruction, system_program,
+        sysvar,
+    },
+    AccountDeserialize, AccountSerialize, AnchorSerialize,
+};
+use anchor_spl::token::spl_token;
+use entangler_client::{
+    accounts::EntangledCollection,
+    instructions::CompressedAsset,
+    pda::{
+        asset_id, find_bubblegum_signer, find_entangler_authority, find_master_edition,
+        find_metadata, find_token_record, find_tree_config,
+    },
+    royalty_hook,
+};
+use mpl_bubblegum::{
+    hash::{hash_creators, hash_metadata},
+    instructions::{CreateTreeConfigBuilder, MintToCollectionV1Builder},
+    programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID},
+    types::{LeafSchema, MetadataArgs, TokenProgramVersion},
+};
+use mpl_token_metadata::{
+    instruction::{
+        builders::{CreateBuilder, MintBuilder, VerifyBuilder},
+        CreateArgs, InstructionBuilder, MintArgs, VerificationArgs,
+    },
+    state::{AssetData, Collection, Creator, PrintSupply, TokenStandard},
+};
+use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account,
+};
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_token_2022::extension::{
+    transfer_hook::TransferHook, ExtensionType, StateWithExtensionsMut,
+};
+use spl_transfer_hook_interface::{
+    get_extra_account_metas_address, instruction::ExecuteInstruction,
+};
+
+pub use solana_program_test;
+
+/// The depth of the trees `mint_compressed_nft` mints in
+pub const COMPRESSED_TREE_DEPTH: usize = 3;
+const COMPRESSED_TREE_BUFFER_SIZE: usize = 8;
+
+/// Starts a bank running the entangler and its royalty hook, Token Metadata and Bubblegum with its
+/// compression programs
+pub async fn start() -> ProgramTestContext {
+    let mut test = ProgramTest::default();
+    test.prefer_bpf(true);
+    test.add_program("entangler", entangler::ID, None);
+    test.add_program("royalty_hook", royalty_hook::ID, None);
+    test.add_program("mpl_token_metadata", mpl_token_metadata::ID, None);
+    test.add_program("mpl_bubblegum", mpl_bubblegum::ID, None);
+    test.add_program("spl_account_compression", SPL_ACCOUNT_COMPRESSION_ID, None);
+    test.add_program("spl_noop", SPL_NOOP_ID, None);
+    test.start_with_context().await
+}
+
+/// Sends the instructions in one transaction paid by the context's payer
+pub async fn process(
+    ctx: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    // A fresh blockhash keeps identical transactions from being deduplicated
+    let blockhash = ctx.get_new_latest_blockhash().await?;
+    let mut all_signers = vec![&ctx.payer];
+    all_signers.extend(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&ctx.payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    ctx.banks_client.process_transaction(transaction).await
+}
+
+/// Simulates the instruction in a transaction paid by the context's payer, and returns
+/// the data it returned
+pub async fn simulate(
+    ctx: &mut ProgramTestContext,
+    instruction: Instruction,
+) -> Result<Vec<u8>, BanksClientError> {
+    let blockhash = ctx.get_new_latest_blockhash().await?;
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&ctx.payer.pubkey()),
+        &[&ctx.payer],
+        blockhash,
+    );
+    let simulation = ctx.banks_client.simulate_transaction(transaction).await?;
+    if let Some(Err(err)) = simulation.result {
+        return Err(BanksClientError::TransactionError(err));
+    }
+    Ok(simulation
+        .simulation_details
+        .and_then(|details| details.return_data)
+        .map(|return_data| return_data.data)
+        .unwrap_or_default())
+}
+
+/// The custom error code a failed transaction returned
+pub fn custom_error(err: BanksClientError) -> Option<u32> {
+    match err.unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(code),
+        _ => None,
+    }
+}
+
+/// Asserts that a transaction failed with the given program error
+pub fn assert_error(result: Result<(), BanksClientError>, error: impl Into<u32>) {
+    let error = error.into();
+    let err = result.expect_err("The transaction should have failed");
+    assert_eq!(custom_error(err), Some(error), "Expected error {error}");
+}
+
+/// Creates a keypair holding 10 SOL
+pub async fn create_keypair(ctx: &mut ProgramTestContext) -> Keypair {
+    let keypair = Keypair::new();
+    let transfer =
+        system_instruction::transfer(&ctx.payer.pubkey(), &keypair.pubkey(), 10_000_000_000);
+    process(ctx, &[transfer], &[]).await.unwrap();
+    keypair
+}
+
+async fn create_mint(ctx: &mut ProgramTestContext, authority: &Keypair, decimals: u8) -> Pubkey {
+    let mint = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &ctx.payer.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint2(
+            &spl_token::ID,
+            &mint.pubkey(),
+            &authority.pubkey(),
+            Some(&authority.pubkey()),
+            decimals,
+        )
+        .unwrap(),
+    ];
+    process(ctx, &instructions, &[&mint]).await.unwrap();
+    mint.pubkey()
+}
+
+async fn mint_to(
+    ctx: &mut ProgramTestContext,
+    mint: &Pubkey,
+    authority: &Keypair,
+    destination: &Pubkey,
+    amount: u64,
+    decimals: u8,
+) -> Pubkey {
+    let account = get_associated_token_address(destination, mint);
+    let instructions = [
+        create_associated_token_account(&ctx.payer.pubkey(), destination, mint, &spl_token::ID),
+        spl_token::instruction::mint_to_checked(
+            &spl_token::ID,
+            mint,
+            &account,
+            &authority.pubkey(),
+            &[],
+            amount,
+            decimals,
+        )
+        .unwrap(),
+    ];
+    process(ctx, &instructions, &[authority]).await.unwrap();
+    account
+}
+
+/// Mints `amount` of a fungible token with 9 decimals to `destination`
+pub async fn mint_token(
+    ctx: &mut ProgramTestContext,
+    authority: &Keypair,
+    destination: &Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let mint = create_mint(ctx, authority, 9).await;
+    mint_to(ctx, &mint, authority, destination, amount, 9).await;
+    mint
+}
+
+/// Mints an NFT with a master edition to `destination`, in an unverified collection if given
+pub async fn mint_nft(
+    ctx: &mut ProgramTestContext,
+    symbol: &str,
+    creator: &Keypair,
+    destination: &Pubkey,
+    collection_mint: Option<Pubkey>,
+    max_supply: u64,
+) -> Pubkey {
+    let mint = create_mint(ctx, creator, 0).await;
+    mint_to(ctx, &mint, creator, destination, 1, 0).await;
+
+    let metadata = find_metadata(&mint).0;
+    let instructions = [
+        mpl_token_metadata::instruction::create_metadata_accounts_v3(
+            mpl_token_metadata::ID,
+            metadata,
+            mint,
+            creator.pubkey(),
+            ctx.payer.pubkey(),
+            creator.pubkey(),
+            "Pretty Cool NFT".to_string(),
+            symbol.to_string(),
+            "https://pretty-cool-nft.xyz/metadata".to_string(),
+            Some(vec![Creator {
+                address: creator.pubkey(),
+                verified: true,
+                share: 100,
+            }]),
+            100,
+            true,
+            false,
+            collection_mint.map(|key| Collection {
+                verified: false,
+                key,
+            }),
+            None,
+            None,
+        ),
+        mpl_token_metadata::instruction::create_master_edition_v3(
+            mpl_token_metadata::ID,
+            find_master_edition(&mint).0,
+            mint,
+            creator.pubkey(),
+            creator.pubkey(),
+            metadata,
+            ctx.payer.pubkey(),
+            Some(max_supply),
+        ),
+    ];
+    process(ctx, &instructions, &[creator]).await.unwrap();
+    mint
+}
+
+/// Writes a mint with no decimals at `address`, whatever its key, such as the PDA of an
+/// entangled mint
+async fn create_mint_at(ctx: &mut ProgramTestContext, authority: &Keypair, address: &Pubkey) {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    let mint = spl_token::state::Mint {
+        mint_authority: COption::Some(authority.pubkey()),
+        supply: 0,
+        decimals: 0,
+        is_initialized: true,
+        freeze_authority: COption::Some(authority.pubkey()),
+    };
+    spl_token::state::Mint::pack(mint, &mut data).unwrap();
+    set_account(ctx, address, data, spl_token::ID).await;
+}
+
+/// Writes a rent exempt account holding `data` at `address`
+async fn set_account(ctx: &mut ProgramTestContext, address: &Pubkey, data: Vec<u8>, owner: Pubkey) {
+    let account = Account {
+        lamports: ctx
+            .banks_client
+            .get_rent()
+            .await
+            .unwrap()
+            .minimum_balance(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    };
+    ctx.set_account(address, &account.into());
+}
+
+/// Mints an NFT without a master edition at `address` to `destination`, and hands its mint,
+/// freeze and update authorities to `authority`, like the entangled tokens minted before they got
+/// master editions
+pub async fn mint_editionless_nft(
+    ctx: &mut ProgramTestContext,
+    creator: &Keypair,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    address: &Pubkey,
+) {
+    let mint = *address;
+    create_mint_at(ctx, creator, &mint).await;
+    mint_to(ctx, &mint, creator, destination, 1, 0).await;
+
+    let metadata = find_metadata(&mint).0;
+    let instructions = [
+        mpl_token_metadata::instruction::create_metadata_accounts_v3(
+            mpl_token_metadata::ID,
+            metadata,
+            mint,
+            creator.pubkey(),
+            ctx.payer.pubkey(),
+            creator.pubkey(),
+            "Pretty Cool NFT".to_string(),
+            "TEST".to_string(),
+            "https://pretty-cool-nft.xyz/metadata".to_string(),
+            None,
+            100,
+            true,
+            false,
+            None,
+            None,
+            None,
+        ),
+        mpl_token_metadata::instruction::update_metadata_accounts_v2(
+            mpl_token_metadata::ID,
+            metadata,
+            creator.pubkey(),
+            Some(*authority),
+            None,
+            None,
+            None,
+        ),
+        spl_token::instruction::set_authority(
+            &spl_token::ID,
+            &mint,
+            Some(authority),
+            spl_token::instruction::AuthorityType::MintTokens,
+            &creator.pubkey(),
+            &[],
+        )
+        .unwrap(),
+        spl_token::instruction::set_authority(
+            &spl_token::ID,
+            &mint,
+            Some(authority),
+            spl_token::instruction::AuthorityType::FreezeAccount,
+            &creator.pubkey(),
+            &[],
+        )
+        .unwrap(),
+    ];
+    process(ctx, &instructions, &[creator]).await.unwrap();
+}
+
+/// Mints a programmable NFT to `destination`, in a collection verified by `creator`
+pub async fn mint_programmable_nft(
+    ctx: &mut ProgramTestContext,
+    creator: &Keypair,
+    destination: &Pubkey,
+    collection_mint: &Pubkey,
+) -> Pubkey {
+    mint_nft_v1(
+        ctx,
+        creator,
+        destination,
+        collection_mint,
+        TokenStandard::ProgrammableNonFungible,
+        spl_token::ID,
+    )
+    .await
+}
+
+/// Mints a Token-2022 NFT to `destination`, in a collection verified by `creator`
+pub async fn mint_token_2022_nft(
+    ctx: &mut ProgramTestContext,
+    creator: &Keypair,
+    destination: &Pubkey,
+    collection_mint: &Pubkey,
+) -> Pubkey {
+    mint_nft_v1(
+        ctx,
+        creator,
+        destination,
+        collection_mint,
+        TokenStandard::NonFungible,
+        spl_token_2022::ID,
+    )
+    .await
+}
+
+/// Mints a Token-2022 NFT whose transfers call the noop program as their transfer hook, which
+/// allows them all. The hook needs no extra account, so its transfers only need the accounts of
+/// `transfer_hook_accounts`
+pub async fn mint_hooked_token_2022_nft(
+    ctx: &mut ProgramTestContext,
+    creator: &Keypair,
+    destination: &Pubkey,
+    collection_mint: &Pubkey,
+) -> Pubkey {
+    let mint = Pubkey::new_unique();
+    let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+        ExtensionType::TransferHook,
+    ])
+    .unwrap();
+    let mut data = vec![0; space];
+    let mut state =
+        StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data)
+            .unwrap();
+    state
+        .init_extension::<TransferHook>(true)
+        .unwrap()
+        .program_id = Some(SPL_NOOP_ID).try_into().unwrap();
+    state.base = spl_token_2022::state::Mint {
+        mint_authority: COption::Some(creator.pubkey()),
+        supply: 0,
+        decimals: 0,
+        is_initialized: true,
+        freeze_authority: COption::Some(creator.pubkey()),
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    set_account(ctx, &mint, data, spl_token_2022::ID).await;
+
+    let mut validation = vec![0; ExtraAccountMetaList::size_of(0).unwrap()];
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut validation, &[]).unwrap();
+    let validation_address = get_extra_account_metas_address(&mint, &SPL_NOOP_ID);
+    set_account(ctx, &validation_address, validation, SPL_NOOP_ID).await;
+
+    mint_nft_v1_at(
+        ctx,
+        creator,
+        destination,
+        collection_mint,
+        TokenStandard::NonFungible,
+        spl_token_2022::ID,
+        None,
+        mint,
+    )
+    .await;
+    mint
+}
+
+/// The accounts that transfers of a `mint_hooked_token_2022_nft` token forward to its hook
+pub fn transfer_hook_accounts(mint: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(get_extra_account_metas_address(mint, &SPL_NOOP_ID), false),
+        AccountMeta::new_readonly(SPL_NOOP_ID, false),
+    ]
+}
+
+async fn mint_nft_v1(
+    ctx: &mut ProgramTestContext,
+    creator: &Keypair,
+    destination: &Pubkey,
+    collection_mint: &Pubkey,
+    token_standard: TokenStandard,
+    token_program: Pubkey,
+) -> Pubkey {
+    let mint = Keypair::new();
+    mint_nft_v1_at(
+        ctx,
+        creator,
+        destination,
+        collection_mint,
+        token_standard,
+        token_program,
+        Some(&mint),
+        mint.pubkey(),
+    )
+    .await;
+    mint.pubkey()
+}
+
+/// Creates the NFT at `mint`, initializing the mint when its keypair is given, or reusing the
+/// mint already there with `creator` as its authorities
+#[allow(clippy::too_many_arguments)]
+async fn mint_nft_v1_at(
+    ctx: &mut ProgramTestContext,
+    creator: &Keypair,
+    destination: &Pubkey,
+    collection_mint: &Pubkey,
+    token_standard: TokenStandard,
+    token_program: Pubkey,
+    mint_keypair: Option<&Keypair>,
+    mint: Pubkey,
+) {
+    let metadata = find_metadata(&mint).0;
+    let master_edition = find_master_edition(&mint).0;
+    let token = get_associated_token_address_with_program_id(destination, &mint, &token_program);
+    let token_record = (token_standard == TokenStandard::ProgrammableNonFungible)
+        .then(|| find_token_record(&mint, &token).0);
+
+    let mut asset_data = AssetData::new(
+        token_standard,
+        "Pretty Cool NFT".to_string(),
+        "TEST".to_string(),
+        "https://pretty-cool-nft.xyz/metadata".to_string(),
+    );
+    asset_data.seller_fee_basis_points = 100;
+    asset_data.creators = Some(vec![Creator {
+        address: creator.pubkey(),
+        verified: true,
+        share: 100,
+    }]);
+    asset_data.collection = Some(Collection {
+        key: *collection_mint,
+        verified: false,
+    });
+
+    let create = CreateBuilder::new()
+        .metadata(metadata)
+        .master_edition(master_edition)
+        .mint(mint)
+        .authority(creator.pubkey())
+        .payer(ctx.payer.pubkey())
+        .update_authority(creator.pubkey())
+        .system_program(system_program::ID)
+        .sysvar_instructions(sysvar::instructions::ID)
+        .spl_token_program(token_program)
+        .initialize_mint(mint_keypair.is_some())
+        .update_authority_as_signer(true)
+        .build(CreateArgs::V1 {
+            asset_data,
+            decimals: Some(0),
+            print_supply: Some(PrintSupply::Zero),
+        })
+        .unwrap()
+        .instruction();
+    let mut mint_builder = MintBuilder::new();
+    mint_builder
+        .token(token)
+        .token_owner(*destination)
+        .metadata(metadata)
+        .master_edition(master_edition)
+        .mint(mint)
+        .authority(creator.pubkey())
+        .payer(ctx.payer.pubkey())
+        .system_program(system_program::ID)
+        .sysvar_instructions(sysvar::instructions::ID)
+        .spl_token_program(token_program)
+        .spl_ata_program(spl_associated_token_account::ID);
+    if let Some(token_record) = token_record {
+        mint_builder.token_record(token_record);
+    }
+    let mint_to = mint_builder
+        .build(MintArgs::V1 {
+            amount: 1,
+            authorization_data: None,
+        })
+        .unwrap()
+        .instruction();
+    let verify = VerifyBuilder::new()
+        .authority(creator.pubkey())
+        .metadata(metadata)
+        .collection_mint(*collection_mint)
+        .collection_metadata(find_metadata(collection_mint).0)
+        .collection_master_edition(find_master_edition(collection_mint).0)
+        .system_program(system_program::ID)
+        .sysvar_instructions(sysvar::instructions::ID)
+        .build(VerificationArgs::CollectionV1)
+        .unwrap()
+        .instruction();
+
+    let instructions = [
+        ComputeBudgetInstruction::set_compute_unit_limit(400_000),
+        create,
+        mint_to,
+        verify,
+    ];
+    let mut signers = vec![creator];
+    signers.extend(mint_keypair);
+    process(ctx, &instructions, &signers).await.unwrap();
+}
+
+/// A compressed NFT minted by `mint_compressed_nft`, the first leaf of its tree
+pub struct CompressedNft {
+    pub merkle_tree: Pubkey,
+    pub metadata: MetadataArgs,
+}
+
+impl CompressedNft {
+    /// Proves the leaf as owned by `leaf_owner`. The other leaves of the tree are empty, so the
+    /// proof is the roots of empty subtrees
+    pub fn prove(&self, leaf_owner: &Pubkey) -> CompressedAsset {
+        let nonce = 0;
+        let leaf = LeafSchema::V1 {
+            id: asset_id(&self.merkle_tree, nonce),
+            owner: *leaf_owner,
+            delegate: *leaf_owner,
+            nonce,
+            data_hash: hash_metadata(&self.metadata).unwrap(),
+            creator_hash: hash_creators(&self.metadata.creators),
+        };
+
+        let mut root = leaf.hash();
+        let mut empty = [0; 32];
+        let mut proof = Vec::with_capacity(COMPRESSED_TREE_DEPTH);
+        for _ in 0..COMPRESSED_TREE_DEPTH {
+            proof.push(Pubkey::new_from_array(empty));
+            root = keccak::hashv(&[root.as_ref(), empty.as_ref()]).to_bytes();
+            empty = keccak::hashv(&[empty.as_ref(), empty.as_ref()]).to_bytes();
+        }
+
+        CompressedAsset {
+            merkle_tree: self.merkle_tree,
+            root,
+            metadata: self.metadata.try_to_vec().unwrap(),
+            nonce,
+            index: 0,
+            leaf_owner: *leaf_owner,
+            leaf_delegate: *leaf_owner,
+            proof,
+        }
+    }
+}
+
+/// Mints a compressed NFT to `leaf_owner` in a new tree, in a collection verified by `creator`
+pub async fn mint_compressed_nft(
+    ctx: &mut ProgramTestContext,
+    creator: &Keypair,
+    leaf_owner: &Pubkey,
+    collection_mint: &Pubkey,
+) -> CompressedNft {
+    let merkle_tree =
+        allocate_merkle_tree(ctx, COMPRESSED_TREE_DEPTH, COMPRESSED_TREE_BUFFER_SIZE).await;
+    let tree_config = find_tree_config(&merkle_tree).0;
+    let mut metadata = MetadataArgs {
+        name: "Pretty Cool NFT".to_string(),
+        symbol: "TEST".to_string(),
+        uri: "https://pretty-cool-nft.xyz/metadata".to_string(),
+        seller_fee_basis_points: 100,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: Some(mpl_bubblegum::types::TokenStandard::NonFungible),
+        collection: Some(mpl_bubblegum::types::Collection {
+            verified: false,
+            key: *collection_mint,
+        }),
+        uses: None,
+        token_program_version: TokenProgramVersion::Original,
+        creators: vec![mpl_bubblegum::types::Creator {
+            address: creator.pubkey(),
+            verified: false,
+            share: 100,
+        }],
+    };
+
+    let instructions = [
+        CreateTreeConfigBuilder::new()
+            .tree_config(tree_config)
+            .merkle_tree(merkle_tree)
+            .payer(ctx.payer.pubkey())
+            .tree_creator(creator.pubkey())
+            .max_depth(COMPRESSED_TREE_DEPTH as u32)
+            .max_buffer_size(COMPRESSED_TREE_BUFFER_SIZE as u32)
+            .public(false)
+            .instruction(),
+        MintToCollectionV1Builder::new()
+            .tree_config(tree_config)
+            .leaf_owner(*leaf_owner)
+            .leaf_delegate(*leaf_owner)
+            .merkle_tree(merkle_tree)
+            .payer(ctx.payer.pubkey())
+            .tree_creator_or_delegate(creator.pubkey())
+            .collection_authority(creator.pubkey())
+            .collection_authority_record_pda(None)
+            .collection_mint(*collection_mint)
+            .collection_metadata(find_metadata(collection_mint).0)
+            .collection_edition(find_master_edition(collection_mint).0)
+            .bubblegum_signer(find_bubblegum_signer().0)
+            .token_metadata_program(mpl_token_metadata::ID)
+            .metadata(metadata.clone())
+            .instruction(),
+    ];
+    process(ctx, &instructions, &[creator]).await.unwrap();
+
+    // Bubblegum verifies the collection of the minted leaf
+    if let Some(collection) = metadata.collection.as_mut() {
+        collection.verified = true;
+    }
+    CompressedNft {
+        merkle_tree,
+        metadata,
+    }
+}
+
+/// Allocates a merkle tree account for the compression program, for Bubblegum to initialize
+pub async fn allocate_merkle_tree(
+    ctx: &mut ProgramTestContext,
+    max_depth: usize,
+    max_buffer_size: usize,
+) -> Pubkey {
+    let merkle_tree = Keypair::new();
+    let space = merkle_tree_size(max_depth, max_buffer_size);
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let instruction = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &merkle_tree.pubkey(),
+        rent.minimum_balance(space),
+        space as u64,
+        &SPL_ACCOUNT_COMPRESSION_ID,
+    );
+    process(ctx, &[instruction], &[&merkle_tree]).await.unwrap();
+    merkle_tree.pubkey()
+}
+
+/// The size of a concurrent merkle tree account without canopy: its header, then the sequence
+/// number, active index and buffer size, the change logs and the rightmost proof
+fn merkle_tree_size(max_depth: usize, max_buffer_size: usize) -> usize {
+    let header = 2 + 54;
+    let path = 32 * max_depth + 32 + 4 + 4;
+    header + 3 * 8 + max_buffer_size * path + path
+}
+
+/// Verifies the collection of an NFT, signed by the collection's update authority
+pub async fn verify_collection(
+    ctx: &mut ProgramTestContext,
+    nft_mint: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_authority: &Keypair,
+) {
+    let instruction = mpl_token_metadata::instruction::verify_collection(
+        mpl_token_metadata::ID,
+        find_metadata(nft_mint).0,
+        collection_authority.pubkey(),
+        ctx.payer.pubkey(),
+        *collection_mint,
+        find_metadata(collection_mint).0,
+        find_master_edition(collection_mint).0,
+        None,
+    );
+    process(ctx, &[instruction], &[collection_authority])
+        .await
+        .unwrap();
+}
+
+/// The amount held by a token account of either token program, 0 if it doesn't exist
+pub async fn token_amount(ctx: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    use spl_token_2022::extension::StateWithExtensions;
+
+    match ctx.banks_client.get_account(*address).await.unwrap() {
+        Some(account) => {
+            StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+                .unwrap()
+                .base
+                .amount
+        }
+        None => 0,
+    }
+}
+
+/// Fetches and deserializes an Anchor account, `None` if it doesn't exist
+pub async fn fetch<T: AccountDeserialize>(
+    ctx: &mut ProgramTestContext,
+    address: &Pubkey,
+) -> Option<T> {
+    let account = ctx.banks_client.get_account(*address).await.unwrap()?;
+    Some(T::try_deserialize(&mut account.data.as_slice()).unwrap())
+}
+
+/// Cuts an account down to its first `len` bytes, as it was stored before its later fields
+/// were added
+pub async fn truncate_account(ctx: &mut ProgramTestContext, address: &Pubkey, len: usize) {
+    let mut account = ctx
+        .banks_client
+        .get_account(*address)
+        .await
+        .unwrap()
+        .unwrap();
+    account.data.truncate(len);
+    account.lamports = ctx
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(len);
+    ctx.set_account(address, &account.into());
+}
+
+/// Rewinds a collection to before per-collection authorities: the global authority holds its
+/// collection NFT and is the update authority of its metadata. Pairs initialized afterwards with
+/// the global authority are legacy pairs
+pub async fn rewind_to_global_authority(ctx: &mut ProgramTestContext, address: &Pubkey) {
+    let global_authority = find_entangler_authority().0;
+    let mut collection: EntangledCollection = fetch(ctx, address).await.unwrap();
+    let scoped_authority = collection.authority().key;
+    collection.scoped_authority = false;
+    let mut account = ctx
+        .banks_client
+        .get_account(*address)
+        .await
+        .unwrap()
+        .unwrap();
+    collection
+        .try_serialize(&mut account.data.as_mut_slice())
+        .unwrap();
+    ctx.set_account(address, &account.into());
+
+    // The update authority follows the key byte of the metadata
+    let collection_mint = collection.entangled_collection_mint;
+    let metadata = find_metadata(&collection_mint).0;
+    let mut account = ctx
+        .banks_client
+        .get_account(metadata)
+        .await
+        .unwrap()
+        .unwrap();
+    account.data[1..33].copy_from_slice(global_authority.as_ref());
+    ctx.set_account(&metadata, &account.into());
+
+    // Move the collection NFT by rewriting the owner of its token account, under the global ATA
+    let scoped_token = get_associated_token_address(&scoped_authority, &collection_mint);
+    let mut account = ctx
+        .banks_client
+        .get_account(scoped_token)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut token = spl_token::state::Account::unpack(&account.data).unwrap();
+    token.owner = global_authority;
+    spl_token::state::Account::pack(token, &mut account.data).unwrap();
+    ctx.set_account(
+        &get_associated_token_address(&global_authority, &collection_mint),
+        &account.into(),
+    );
+    ctx.set_account(&scoped_token, &Default::default());
+}
@@ -0,0 +1,1668 @@
+use anchor_lang::{
+    error::ErrorCode, prelude::Pubkey, system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address, get_associated_token_address_with_program_id,
+    },
+    token::{spl_token, ID as TOKEN_PROGRAM_ID},
+    token_2022::{spl_token_2022, ID as TOKEN_2022_PROGRAM_ID},
+};
+use entangler::errors::EntanglerError;
+use entangler_client::{
+    accounts::{
+        deserialize_custody, CollectionEntry, EntangledCollection, EntangledPair,
+        EntangledStandard, EntanglerState, OriginalRecord,
+    },
+    instructions::{resize_account, set_records_backfilled},
+    pda::{
+        asset_id, find_collection_authority, find_collection_entry, find_entangled_mint,
+        find_entangled_pair, find_entangler_authority, find_entangler_state, find_master_edition,
+        find_metadata, find_original_record,
+    },
+    royalty_hook::{self, RoyaltyHookError, RoyaltyReceipt},
+    EntanglerClient,
+};
+use entangler_tests::{
+    allocate_merkle_tree, assert_error, create_keypair, fetch, mint_compressed_nft, mint_editionless_nft,
+    mint_hooked_token_2022_nft, mint_nft, mint_programmable_nft, mint_token, mint_token_2022_nft,
+    process, rewind_to_global_authority, simulate, start, token_amount, transfer_hook_accounts,
+    truncate_account, verify_collection,
+};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount, TokenStandard};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::AccountSharedData,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction,
+};
+use spl_associated_token_account::instruction::create_associated_token_account;
+use spl_token_2022::extension::StateWithExtensions;
+
+/// A bank with an entangler state, and a verified collection of originals held by the payer
+struct Setup {
+    ctx: ProgramTestContext,
+    creator: Keypair,
+    collection_mint: Pubkey,
+    originals: Vec<Pubkey>,
+    fee_mint: Pubkey,
+}
+
+impl Setup {
+    async fn new(originals: usize) -> Self {
+        let mut ctx = start().await;
+        let admin = ctx.payer.pubkey();
+        let creator = create_keypair(&mut ctx).await;
+        let fee_mint = mint_token(&mut ctx, &creator, &admin, 10_000).await;
+
+        let collection_mint =
+            mint_nft(&mut ctx, "TEST", &creator, &creator.pubkey(), None, 0).await;
+        let mut mints = Vec::new();
+        for _ in 0..originals {
+            let mint = mint_nft(&mut ctx, "TEST", &creator, &admin, Some(collection_mint), 0).await;
+            verify_collection(&mut ctx, &mint, &collection_mint, &creator).await;
+            mints.push(mint);
+        }
+
+        let mut setup = Self {
+            ctx,
+            creator,
+            collection_mint,
+            originals: mints,
+            fee_mint,
+        };
+        let instruction = setup.client(Pubkey::new_unique()).set_entangler_state(
+            admin,
+            setup.creator.pubkey(),
+            fee_mint,
+            0,
+        );
+        process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+        setup
+    }
+
+    fn client(&self, id: Pubkey) -> EntanglerClient {
+        EntanglerClient::new(
+            self.collection_mint,
+            self.ctx.payer.pubkey(),
+            id,
+            self.creator.pubkey(),
+            500,
+        )
+    }
+
+    /// Creates a non-fungible collection with a new id
+    async fn collection(&mut self, one_way: bool) -> EntanglerClient {
+        let client = self.client(Pubkey::new_unique());
+        let instruction = client.create_collection(
+            one_way,
+            false,
+            None,
+            EntangledStandard::NonFungible,
+            None,
+            None,
+            false,
+        );
+        process(&mut self.ctx, &[instruction], &[]).await.unwrap();
+        client
+    }
+
+    /// Creates a collection and entangles the original
+    async fn entangled(&mut self, one_way: bool, original: Pubkey) -> EntanglerClient {
+        let client = self.collection(one_way).await;
+        let instructions = [
+            client.initialize_pair(original, TOKEN_PROGRAM_ID),
+            client.entangle(original, None, None, TOKEN_PROGRAM_ID),
+        ];
+        process(&mut self.ctx, &instructions, &[]).await.unwrap();
+        client
+    }
+
+    async fn amount(&mut self, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        token_amount(&mut self.ctx, &get_associated_token_address(owner, mint)).await
+    }
+
+    async fn token_2022_amount(&mut self, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        let account =
+            get_associated_token_address_with_program_id(owner, mint, &TOKEN_2022_PROGRAM_ID);
+        token_amount(&mut self.ctx, &account).await
+    }
+
+    async fn is_frozen(&mut self, owner: &Pubkey, mint: &Pubkey) -> bool {
+        let address = get_associated_token_address(owner, mint);
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .is_frozen()
+    }
+}
+
+/// Programmable tokens need more than the default compute units
+fn compute_budget() -> Instruction {
+    ComputeBudgetInstruction::set_compute_unit_limit(600_000)
+}
+
+#[tokio::test]
+async fn sets_entangler_state() {
+    let mut setup = Setup::new(0).await;
+
+    let state: EntanglerState = fetch(&mut setup.ctx, &find_entangler_state().0)
+        .await
+        .unwrap();
+    assert_eq!(state.admin, setup.ctx.payer.pubkey());
+    assert_eq!(state.earner, setup.creator.pubkey());
+    assert_eq!(state.fee_mint, setup.fee_mint);
+
+    // Only the admin updates the state once it is set
+    let intruder = create_keypair(&mut setup.ctx).await;
+    let mut client = setup.client(Pubkey::new_unique());
+    client.signer = intruder.pubkey();
+    let instruction =
+        client.set_entangler_state(intruder.pubkey(), intruder.pubkey(), setup.fee_mint, 0);
+    let result = process(&mut setup.ctx, &[instruction], &[&intruder]).await;
+    assert_error(result, ErrorCode::ConstraintRaw);
+}
+
+#[tokio::test]
+async fn creates_collections_and_entries() {
+    let mut setup = Setup::new(0).await;
+    let client = setup.collection(false).await;
+
+    let collection: EntangledCollection = fetch(&mut setup.ctx, &client.entangled_collection)
+        .await
+        .unwrap();
+    assert_eq!(collection.original_collection_mint, setup.collection_mint);
+    assert_eq!(collection.royalties, 500);
+    assert_eq!(
+        token_amount(&mut setup.ctx, &client.entangled_collection_mint_account).await,
+        1
+    );
+
+    let instruction = client.create_collection_entry(
+        "dippies".to_string(),
+        setup.fee_mint,
+        setup.creator.pubkey(),
+    );
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let entry: CollectionEntry = fetch(&mut setup.ctx, &find_collection_entry("dippies").0)
+        .await
+        .unwrap();
+    assert_eq!(entry.id, client.id);
+
+    // Keys only hold alphanumeric characters and underscores
+    let instruction = client.create_collection_entry(
+        "dip-pies".to_string(),
+        setup.fee_mint,
+        setup.creator.pubkey(),
+    );
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, ErrorCode::ConstraintRaw);
+}
+
+#[tokio::test]
+async fn rejects_invalid_collection_settings() {
+    let mut setup = Setup::new(0).await;
+    let client = setup.client(Pubkey::new_unique());
+
+    let instruction = client.create_collection(
+        false,
+        false,
+        None,
+        EntangledStandard::NonFungible,
+        Some(Pubkey::new_unique()),
+        None,
+        false,
+    );
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::RuleSetRequiresProgrammable);
+
+    let instruction = client.create_collection(
+        false,
+        false,
+        None,
+        EntangledStandard::NonFungible,
+        None,
+        Some(Pubkey::new_unique()),
+        false,
+    );
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::TransferHookRequiresToken2022);
+
+    let instruction = client.create_collection(
+        false,
+        false,
+        None,
+        EntangledStandard::Compressed,
+        None,
+        None,
+        true,
+    );
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::NonCustodialCompressed);
+}
+
+#[tokio::test]
+async fn sets_collection_verification() {
+    let mut setup = Setup::new(0).await;
+    let client = setup.collection(false).await;
+
+    let original_creator = setup.creator.pubkey();
+    let instruction = client.set_collection_verification(true, Some(original_creator));
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let collection: EntangledCollection = fetch(&mut setup.ctx, &client.entangled_collection)
+        .await
+        .unwrap();
+    assert!(collection.allow_unverified);
+    assert_eq!(collection.original_creator, Some(original_creator));
+
+    // Only the admin changes verification settings
+    let intruder = create_keypair(&mut setup.ctx).await;
+    let mut intruding = client.clone();
+    intruding.signer = intruder.pubkey();
+    let instruction = intruding.set_collection_verification(false, None);
+    let result = process(&mut setup.ctx, &[instruction], &[&intruder]).await;
+    assert_error(result, ErrorCode::ConstraintRaw);
+}
+
+#[tokio::test]
+async fn resizes_legacy_collections() {
+    let mut setup = Setup::new(1).await;
+    let client = setup.collection(false).await;
+
+    // Collections created before verification settings were 107 bytes long
+    truncate_account(&mut setup.ctx, &client.entangled_collection, 107).await;
+    let instruction = client.set_collection_verification(true, None);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, ErrorCode::AccountDidNotDeserialize);
+
+    let instruction = resize_account(client.signer, client.entangled_collection);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let collection: EntangledCollection = fetch(&mut setup.ctx, &client.entangled_collection)
+        .await
+        .unwrap();
+    assert_eq!(collection.id, client.id);
+    assert!(!collection.allow_unverified);
+    assert_eq!(
+        collection.entangled_standard,
+        EntangledStandard::NonFungible
+    );
+    assert!(!collection.scoped_authority);
+
+    // Resized collections only accept originals whose collection is unverified once opted out
+    let payer = setup.ctx.payer.pubkey();
+    let unverified = mint_nft(
+        &mut setup.ctx,
+        "TEST",
+        &setup.creator,
+        &payer,
+        Some(setup.collection_mint),
+        0,
+    )
+    .await;
+    let instruction = client.initialize_pair(unverified, TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::UnverifiedCollection);
+
+    let instruction = client.set_collection_verification(true, None);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let instruction = client.initialize_pair(unverified, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+
+    // Only the state, collections and pairs can be resized
+    let original = setup.originals[0];
+    let instruction = client.initialize_pair(original, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let instruction = resize_account(client.signer, find_original_record(&original).0);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::UnresizableAccount);
+}
+
+#[tokio::test]
+async fn resizes_legacy_states() {
+    let mut setup = Setup::new(0).await;
+    let admin = setup.ctx.payer.pubkey();
+    let state = find_entangler_state().0;
+
+    // States created before original records were 112 bytes long
+    truncate_account(&mut setup.ctx, &state, 112).await;
+    let instruction = resize_account(admin, state);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let resized: EntanglerState = fetch(&mut setup.ctx, &state).await.unwrap();
+    assert_eq!(resized.admin, admin);
+    assert_eq!(resized.earner, setup.creator.pubkey());
+    assert!(!resized.records_backfilled);
+
+    // Setting the backfill resizes the state too
+    truncate_account(&mut setup.ctx, &state, 112).await;
+    let instruction = set_records_backfilled(admin, true);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let resized: EntanglerState = fetch(&mut setup.ctx, &state).await.unwrap();
+    assert_eq!(resized.fee_mint, setup.fee_mint);
+    assert!(resized.records_backfilled);
+}
+
+#[tokio::test]
+async fn initializes_pairs_of_verified_originals() {
+    let mut setup = Setup::new(1).await;
+    let client = setup.collection(false).await;
+    let original = setup.originals[0];
+
+    let instruction = client.initialize_pair(original, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+    assert!(setup
+        .ctx
+        .banks_client
+        .get_account(entangled_mint)
+        .await
+        .unwrap()
+        .is_some());
+
+    // Tokens outside the collection
+    let payer = setup.ctx.payer.pubkey();
+    let outsider = mint_nft(&mut setup.ctx, "TEST", &setup.creator, &payer, None, 0).await;
+    let instruction = client.initialize_pair(outsider, TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::InvalidCollection);
+
+    // Tokens whose collection is unverified
+    let unverified = mint_nft(
+        &mut setup.ctx,
+        "TEST",
+        &setup.creator,
+        &payer,
+        Some(setup.collection_mint),
+        0,
+    )
+    .await;
+    let instruction = client.initialize_pair(unverified, TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::UnverifiedCollection);
+}
+
+#[tokio::test]
+async fn rejects_entangled_collections_without_creators() {
+    let mut setup = Setup::new(1).await;
+    let client = setup.collection(false).await;
+    let original = setup.originals[0];
+
+    // Drop the creators of the entangled collection, whose option follows the padded name,
+    // symbol, uri and royalties
+    let address = client.entangled_collection_metadata;
+    let mut account = setup
+        .ctx
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    let creators = 1 + 32 + 32 + (4 + 32) + (4 + 10) + (4 + 200) + 2;
+    let len = 1 + 4 + 2 * (32 + 1 + 1);
+    account.data[creators] = 0;
+    account.data.copy_within(creators + len.., creators + 1);
+    let metadata = Metadata::safe_deserialize(&account.data).unwrap();
+    assert!(metadata.data.creators.is_none());
+    setup.ctx.set_account(&address, &account.into());
+
+    let instruction = client.initialize_pair(original, TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::MissingCollectionCreator);
+}
+
+#[tokio::test]
+async fn initializes_pairs_whose_mint_address_is_funded() {
+    let mut setup = Setup::new(1).await;
+    let client = setup.collection(false).await;
+    let original = setup.originals[0];
+
+    // Anyone can send lamports to the address of the entangled mint beforehand
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+    let payer = setup.ctx.payer.pubkey();
+    let transfer = system_instruction::transfer(&payer, &entangled_mint, 1_000);
+    process(&mut setup.ctx, &[transfer], &[]).await.unwrap();
+
+    let instruction = client.initialize_pair(original, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let account = setup
+        .ctx
+        .banks_client
+        .get_account(entangled_mint)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, TOKEN_PROGRAM_ID);
+    assert_eq!(
+        setup
+            .amount(&client.entangler_authority, &entangled_mint)
+            .await,
+        1
+    );
+}
+
+#[tokio::test]
+async fn entangles_and_disentangles() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let client = setup.entangled(false, original).await;
+    let payer = setup.ctx.payer.pubkey();
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+
+    assert_eq!(setup.amount(&payer, &original).await, 0);
+    assert_eq!(setup.amount(&payer, &entangled_mint).await, 1);
+    assert_eq!(
+        setup.amount(&client.entangler_authority, &original).await,
+        1
+    );
+    let pair: EntangledPair = fetch(&mut setup.ctx, &find_entangled_pair(&entangled_mint).0)
+        .await
+        .unwrap();
+    assert_eq!(pair.original_mint, original);
+    assert_eq!(pair.entangled_mint, entangled_mint);
+
+    let instruction = client.disentangle(original, None, None, TOKEN_PROGRAM_ID, None);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    assert_eq!(setup.amount(&payer, &original).await, 1);
+    assert_eq!(setup.amount(&payer, &entangled_mint).await, 0);
+    assert_eq!(
+        setup
+            .amount(&client.entangler_authority, &entangled_mint)
+            .await,
+        1
+    );
+}
+
+#[tokio::test]
+async fn resizes_legacy_pairs() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let client = setup.entangled(false, original).await;
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+    let entangled_pair = find_entangled_pair(&entangled_mint).0;
+
+    // Pairs entangled before non-custodial collections were 72 bytes long
+    truncate_account(&mut setup.ctx, &entangled_pair, 72).await;
+    let instruction = client.disentangle(original, None, None, TOKEN_PROGRAM_ID, None);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, ErrorCode::AccountDidNotDeserialize);
+
+    // Verifying custody needs no resize
+    let instruction = client.verify_pair(original, None, TOKEN_PROGRAM_ID);
+    let custody =
+        deserialize_custody(&simulate(&mut setup.ctx, instruction).await.unwrap()).unwrap();
+    assert!(custody.entangled);
+
+    let instruction = resize_account(client.signer, entangled_pair);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let pair: EntangledPair = fetch(&mut setup.ctx, &entangled_pair).await.unwrap();
+    assert_eq!(pair.original_mint, original);
+    assert_eq!(pair.frozen_account, None);
+
+    let instruction = client.disentangle(original, None, None, TOKEN_PROGRAM_ID, None);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let payer = setup.ctx.payer.pubkey();
+    assert_eq!(setup.amount(&payer, &original).await, 1);
+}
+
+#[tokio::test]
+async fn freezes_originals_of_non_custodial_collections_in_place() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let client = setup.client(Pubkey::new_unique()).with_non_custodial();
+    let payer = setup.ctx.payer.pubkey();
+    let instruction = client.create_collection(
+        false,
+        false,
+        None,
+        EntangledStandard::NonFungible,
+        None,
+        None,
+        true,
+    );
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+
+    // Originals never get an escrow
+    let instruction = client.initialize_pair(original, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let escrow = get_associated_token_address(&client.entangler_authority, &original);
+    assert!(setup
+        .ctx
+        .banks_client
+        .get_account(escrow)
+        .await
+        .unwrap()
+        .is_none());
+
+    let instruction = client.entangle(original, None, None, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    assert_eq!(setup.amount(&payer, &original).await, 1);
+    assert!(setup.is_frozen(&payer, &original).await);
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+    let pair: EntangledPair = fetch(&mut setup.ctx, &find_entangled_pair(&entangled_mint).0)
+        .await
+        .unwrap();
+    let frozen_account = get_associated_token_address(&payer, &original);
+    assert_eq!(pair.frozen_account, Some(frozen_account));
+
+    let instruction =
+        client.disentangle(original, None, None, TOKEN_PROGRAM_ID, Some(frozen_account));
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    assert!(!setup.is_frozen(&payer, &original).await);
+
+    // Custodial collections still need the escrow
+    let custodial = setup.collection(false).await.with_non_custodial();
+    let instruction = custodial.initialize_pair(original, TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::MissingOriginalEscrow);
+}
+
+#[tokio::test]
+async fn keeps_one_way_collections_entangled() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let client = setup.entangled(true, original).await;
+
+    let instruction = client.disentangle(original, None, None, TOKEN_PROGRAM_ID, None);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, ErrorCode::ConstraintRaw);
+
+    let instruction = client.burn_original(original, TOKEN_PROGRAM_ID, None);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, ErrorCode::ConstraintRaw);
+}
+
+#[tokio::test]
+async fn entangles_in_a_single_instruction() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let client = setup.collection(false).await;
+    let payer = setup.ctx.payer.pubkey();
+
+    let instruction = client.initialize_and_entangle(original, None, None, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+    assert_eq!(setup.amount(&payer, &entangled_mint).await, 1);
+    assert_eq!(
+        setup.amount(&client.entangler_authority, &original).await,
+        1
+    );
+}
+
+#[tokio::test]
+async fn entangles_batches() {
+    let mut setup = Setup::new(2).await;
+    let originals = setup.originals.clone();
+    let client = setup.collection(false).await;
+    let payer = setup.ctx.payer.pubkey();
+
+    for original in &originals {
+        let instruction = client.initialize_pair(*original, TOKEN_PROGRAM_ID);
+        process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    }
+
+    let instruction = client.entangle_batch(&originals, TOKEN_PROGRAM_ID, &[]);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    for original in &originals {
+        let entangled_mint = find_entangled_mint(&client.id, original).0;
+        assert_eq!(setup.amount(&payer, &entangled_mint).await, 1);
+    }
+
+    let instruction = client.disentangle_batch(&originals, TOKEN_PROGRAM_ID, &[]);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    for original in &originals {
+        assert_eq!(setup.amount(&payer, original).await, 1);
+    }
+
+    let instruction = client.entangle_batch(&[], TOKEN_PROGRAM_ID, &[]);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::EmptyBatch);
+}
+
+#[tokio::test]
+async fn entangles_batches_of_hooked_token_2022_originals() {
+    let mut setup = Setup::new(0).await;
+    let client = setup.collection(false).await;
+    let creator = setup.creator.insecure_clone();
+    let payer = setup.ctx.payer.pubkey();
+    let collection_mint = setup.collection_mint;
+    let hooked =
+        mint_hooked_token_2022_nft(&mut setup.ctx, &creator, &payer, &collection_mint).await;
+    let plain = mint_token_2022_nft(&mut setup.ctx, &creator, &payer, &collection_mint).await;
+    let originals = [hooked, plain];
+    // The plain original sits past the end of the hook accounts, so it forwards none
+    let hook_accounts = [transfer_hook_accounts(&hooked)];
+
+    for original in originals {
+        let instruction = client.initialize_pair(original, TOKEN_2022_PROGRAM_ID);
+        process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    }
+
+    // Without its hook accounts, the hooked original can't move
+    let instruction = client.entangle_batch(&originals, TOKEN_2022_PROGRAM_ID, &[]);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert!(result.is_err());
+
+    let mut instruction = client.entangle_batch(&originals, TOKEN_2022_PROGRAM_ID, &hook_accounts);
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(Pubkey::new_unique(), false));
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::MalformedBatch);
+
+    // Every group must be signed by the signer of the batch
+    let other = create_keypair(&mut setup.ctx).await;
+    let mut instruction = client.entangle_batch(&originals, TOKEN_2022_PROGRAM_ID, &hook_accounts);
+    instruction.accounts[0] = AccountMeta::new_readonly(other.pubkey(), true);
+    let result = process(&mut setup.ctx, &[instruction], &[&other]).await;
+    assert_error(result, EntanglerError::InvalidBatchSigner);
+
+    let instruction = client.entangle_batch(&originals, TOKEN_2022_PROGRAM_ID, &hook_accounts);
+    process(&mut setup.ctx, &[compute_budget(), instruction], &[])
+        .await
+        .unwrap();
+    for original in &originals {
+        let entangled_mint = find_entangled_mint(&client.id, original).0;
+        assert_eq!(setup.token_2022_amount(&payer, original).await, 0);
+        assert_eq!(setup.amount(&payer, &entangled_mint).await, 1);
+    }
+
+    let instruction = client.disentangle_batch(&originals, TOKEN_2022_PROGRAM_ID, &hook_accounts);
+    process(&mut setup.ctx, &[compute_budget(), instruction], &[])
+        .await
+        .unwrap();
+    for original in &originals {
+        assert_eq!(setup.token_2022_amount(&payer, original).await, 1);
+    }
+}
+
+#[tokio::test]
+async fn entangles_programmable_originals() {
+    let mut setup = Setup::new(0).await;
+    let client = setup.collection(false).await;
+    let creator = setup.creator.insecure_clone();
+    let payer = setup.ctx.payer.pubkey();
+    let collection_mint = setup.collection_mint;
+    let original = mint_programmable_nft(&mut setup.ctx, &creator, &payer, &collection_mint).await;
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+
+    let instruction = client.initialize_pair(original, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let instruction = client.entangle(original, None, None, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[compute_budget(), instruction], &[])
+        .await
+        .unwrap();
+    assert_eq!(setup.amount(&payer, &original).await, 0);
+    assert_eq!(setup.amount(&payer, &entangled_mint).await, 1);
+
+    let instruction = client.disentangle(original, None, None, TOKEN_PROGRAM_ID, None);
+    process(&mut setup.ctx, &[compute_budget(), instruction], &[])
+        .await
+        .unwrap();
+    assert_eq!(setup.amount(&payer, &original).await, 1);
+    assert!(setup.is_frozen(&payer, &original).await);
+
+    // Re entangle and burn
+    let instructions = [
+        compute_budget(),
+        client.entangle(original, None, None, TOKEN_PROGRAM_ID),
+        client.burn_original(original, TOKEN_PROGRAM_ID, None),
+    ];
+    process(&mut setup.ctx, &instructions, &[]).await.unwrap();
+    let metadata = setup
+        .ctx
+        .banks_client
+        .get_account(find_metadata(&original).0)
+        .await
+        .unwrap();
+    assert!(metadata.is_none());
+}
+
+#[tokio::test]
+async fn entangles_token_2022_originals() {
+    let mut setup = Setup::new(0).await;
+    let client = setup.collection(false).await;
+    let creator = setup.creator.insecure_clone();
+    let payer = setup.ctx.payer.pubkey();
+    let collection_mint = setup.collection_mint;
+    let original = mint_token_2022_nft(&mut setup.ctx, &creator, &payer, &collection_mint).await;
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+
+    let instructions = [
+        client.initialize_pair(original, TOKEN_2022_PROGRAM_ID),
+        client.entangle(original, None, None, TOKEN_2022_PROGRAM_ID),
+    ];
+    process(&mut setup.ctx, &instructions, &[]).await.unwrap();
+    assert_eq!(setup.token_2022_amount(&payer, &original).await, 0);
+    assert_eq!(setup.amount(&payer, &entangled_mint).await, 1);
+
+    let instruction = client.disentangle(original, None, None, TOKEN_2022_PROGRAM_ID, None);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    assert_eq!(setup.token_2022_amount(&payer, &original).await, 1);
+}
+
+#[tokio::test]
+async fn mints_programmable_entangled_tokens() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let client = setup.client(Pubkey::new_unique());
+    let payer = setup.ctx.payer.pubkey();
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+
+    let instruction = client.create_collection(
+        false,
+        false,
+        None,
+        EntangledStandard::ProgrammableNonFungible,
+        None,
+        None,
+        false,
+    );
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+
+    let instruction = client.initialize_pair(original, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[compute_budget(), instruction], &[])
+        .await
+        .unwrap();
+    let account = setup
+        .ctx
+        .banks_client
+        .get_account(find_metadata(&entangled_mint).0)
+        .await
+        .unwrap()
+        .unwrap();
+    let metadata = Metadata::safe_deserialize(&account.data).unwrap();
+    assert_eq!(
+        metadata.token_standard,
+        Some(TokenStandard::ProgrammableNonFungible)
+    );
+    assert!(metadata.collection.unwrap().verified);
+    assert_eq!(metadata.data.seller_fee_basis_points, 500);
+
+    let instruction = client.entangle(original, None, None, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[compute_budget(), instruction], &[])
+        .await
+        .unwrap();
+    assert_eq!(setup.amount(&payer, &entangled_mint).await, 1);
+    assert!(setup.is_frozen(&payer, &entangled_mint).await);
+
+    let instruction = client.disentangle(original, None, None, TOKEN_PROGRAM_ID, None);
+    process(&mut setup.ctx, &[compute_budget(), instruction], &[])
+        .await
+        .unwrap();
+    assert_eq!(setup.amount(&payer, &entangled_mint).await, 0);
+}
+
+#[tokio::test]
+async fn mints_token_2022_entangled_tokens() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let client = setup
+        .client(Pubkey::new_unique())
+        .with_entangled_token_program(TOKEN_2022_PROGRAM_ID);
+    let payer = setup.ctx.payer.pubkey();
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+
+    let instruction = client.create_collection(
+        false,
+        false,
+        None,
+        EntangledStandard::Token2022,
+        None,
+        None,
+        false,
+    );
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+
+    let instructions = [
+        client.initialize_pair(original, TOKEN_PROGRAM_ID),
+        client.entangle(original, None, None, TOKEN_PROGRAM_ID),
+    ];
+    process(&mut setup.ctx, &instructions, &[]).await.unwrap();
+    let mint = setup
+        .ctx
+        .banks_client
+        .get_account(entangled_mint)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(mint.owner, TOKEN_2022_PROGRAM_ID);
+    assert_eq!(setup.token_2022_amount(&payer, &entangled_mint).await, 1);
+
+    // Nobody can mint a second entangled token
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint.data).unwrap();
+    assert_eq!(mint.base.supply, 1);
+    assert!(mint.base.mint_authority.is_none());
+
+    let instruction = client.disentangle(original, None, None, TOKEN_PROGRAM_ID, None);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    assert_eq!(setup.token_2022_amount(&payer, &entangled_mint).await, 0);
+    assert_eq!(setup.amount(&payer, &original).await, 1);
+}
+
+#[tokio::test]
+async fn fails_token_2022_transfers_until_royalties_on_the_minimum_price_are_paid() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let client = setup
+        .client(Pubkey::new_unique())
+        .with_entangled_token_program(TOKEN_2022_PROGRAM_ID);
+    let payer = setup.ctx.payer.pubkey();
+    let creator = setup.creator.pubkey();
+    let creator_keypair = setup.creator.insecure_clone();
+    let buyer = create_keypair(&mut setup.ctx).await;
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+    let collection_mint = client.entangled_collection_mint;
+    let hook_accounts = royalty_hook::transfer_hook_accounts(&entangled_mint);
+
+    let instruction = client.create_collection(
+        false,
+        false,
+        None,
+        EntangledStandard::Token2022,
+        None,
+        Some(royalty_hook::ID),
+        false,
+    );
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let instructions = [
+        client.initialize_pair(original, TOKEN_PROGRAM_ID),
+        royalty_hook::initialize_extra_account_meta_list(payer, entangled_mint),
+    ];
+    process(&mut setup.ctx, &instructions, &[]).await.unwrap();
+
+    // Leaving the escrow is free
+    let mut instruction = client.entangle(original, None, None, TOKEN_PROGRAM_ID);
+    instruction.accounts.extend(hook_accounts.clone());
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    assert_eq!(setup.token_2022_amount(&payer, &entangled_mint).await, 1);
+
+    let payer_account = get_associated_token_address_with_program_id(
+        &payer,
+        &entangled_mint,
+        &TOKEN_2022_PROGRAM_ID,
+    );
+    let buyer_account = get_associated_token_address_with_program_id(
+        &buyer.pubkey(),
+        &entangled_mint,
+        &TOKEN_2022_PROGRAM_ID,
+    );
+    let create = create_associated_token_account(
+        &payer,
+        &buyer.pubkey(),
+        &entangled_mint,
+        &TOKEN_2022_PROGRAM_ID,
+    );
+    process(&mut setup.ctx, &[create], &[]).await.unwrap();
+    let transfer = |from: &Pubkey, to: &Pubkey, owner: &Pubkey| {
+        let mut instruction = spl_token_2022::instruction::transfer_checked(
+            &TOKEN_2022_PROGRAM_ID,
+            from,
+            &entangled_mint,
+            to,
+            owner,
+            &[],
+            1,
+            0,
+        )
+        .unwrap();
+        instruction.accounts.extend(hook_accounts.clone());
+        instruction
+    };
+
+    // A transfer without the royalty payment fails
+    let instruction = transfer(&payer_account, &buyer_account, &payer);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, RoyaltyHookError::RoyaltiesUnpaid);
+
+    // Nothing can be paid until the creator sets the minimum price, which only they can
+    let instruction = royalty_hook::pay_royalties(
+        buyer.pubkey(),
+        entangled_mint,
+        creator,
+        collection_mint,
+        1_000_000_000,
+    );
+    let result = process(&mut setup.ctx, &[instruction], &[&buyer]).await;
+    assert_error(result, ErrorCode::AccountNotInitialized);
+
+    let instruction =
+        royalty_hook::set_min_price(buyer.pubkey(), entangled_mint, collection_mint, 0);
+    let result = process(&mut setup.ctx, &[instruction], &[&buyer]).await;
+    assert_error(result, RoyaltyHookError::InvalidCreator);
+
+    let instruction =
+        royalty_hook::set_min_price(creator, entangled_mint, collection_mint, 1_000_000_000);
+    process(&mut setup.ctx, &[instruction], &[&creator_keypair])
+        .await
+        .unwrap();
+
+    let instruction = royalty_hook::pay_royalties(
+        buyer.pubkey(),
+        entangled_mint,
+        Pubkey::new_unique(),
+        collection_mint,
+        1_000_000_000,
+    );
+    let result = process(&mut setup.ctx, &[instruction], &[&buyer]).await;
+    assert_error(result, RoyaltyHookError::InvalidCreator);
+
+    // Paying nothing, or on a price below the minimum, doesn't unlock the transfer
+    for price in [0, 999_999_999] {
+        let instruction = royalty_hook::pay_royalties(
+            buyer.pubkey(),
+            entangled_mint,
+            creator,
+            collection_mint,
+            price,
+        );
+        let result = process(&mut setup.ctx, &[instruction], &[&buyer]).await;
+        assert_error(result, RoyaltyHookError::PriceBelowMinimum);
+
+        let instruction = transfer(&payer_account, &buyer_account, &payer);
+        let result = process(&mut setup.ctx, &[instruction], &[]).await;
+        assert_error(result, RoyaltyHookError::RoyaltiesUnpaid);
+    }
+
+    let creator_lamports = setup.ctx.banks_client.get_balance(creator).await.unwrap();
+    let instruction = royalty_hook::pay_royalties(
+        buyer.pubkey(),
+        entangled_mint,
+        creator,
+        collection_mint,
+        1_000_000_000,
+    );
+    process(&mut setup.ctx, &[instruction], &[&buyer])
+        .await
+        .unwrap();
+    assert_eq!(
+        setup.ctx.banks_client.get_balance(creator).await.unwrap(),
+        creator_lamports + 50_000_000
+    );
+
+    // Only Token-2022 can use the receipt up, in the middle of a transfer
+    let instruction = spl_transfer_hook_interface::instruction::execute_with_extra_account_metas(
+        &royalty_hook::ID,
+        &payer_account,
+        &entangled_mint,
+        &buyer_account,
+        &payer,
+        &hook_accounts[0].pubkey,
+        &hook_accounts[1..2],
+        1,
+    );
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, RoyaltyHookError::NotTransferring);
+
+    let instruction = transfer(&payer_account, &buyer_account, &payer);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    assert_eq!(
+        setup
+            .token_2022_amount(&buyer.pubkey(), &entangled_mint)
+            .await,
+        1
+    );
+    let receipt: RoyaltyReceipt = fetch(&mut setup.ctx, &hook_accounts[1].pubkey)
+        .await
+        .unwrap();
+    assert_eq!(receipt.mint, entangled_mint);
+    assert!(!receipt.paid);
+
+    // Each payment covers a single transfer
+    let instruction = transfer(&buyer_account, &payer_account, &buyer.pubkey());
+    let result = process(&mut setup.ctx, &[instruction], &[&buyer]).await;
+    assert_error(result, RoyaltyHookError::RoyaltiesUnpaid);
+
+    let instructions = [
+        royalty_hook::pay_royalties(
+            payer,
+            entangled_mint,
+            creator,
+            collection_mint,
+            1_000_000_000,
+        ),
+        transfer(&buyer_account, &payer_account, &buyer.pubkey()),
+    ];
+    process(&mut setup.ctx, &instructions, &[&buyer])
+        .await
+        .unwrap();
+
+    // Going back to the escrow is free
+    let mut instruction = client.disentangle(original, None, None, TOKEN_PROGRAM_ID, None);
+    instruction.accounts.extend(hook_accounts);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    assert_eq!(setup.token_2022_amount(&payer, &entangled_mint).await, 0);
+    assert_eq!(setup.amount(&payer, &original).await, 1);
+}
+
+#[tokio::test]
+async fn entangles_compressed_originals() {
+    let mut setup = Setup::new(0).await;
+    let client = setup.collection(false).await;
+    let creator = setup.creator.insecure_clone();
+    let payer = setup.ctx.payer.pubkey();
+    let collection_mint = setup.collection_mint;
+    let nft = mint_compressed_nft(&mut setup.ctx, &creator, &payer, &collection_mint).await;
+    let entangled_mint = find_entangled_mint(&client.id, &asset_id(&nft.merkle_tree, 0)).0;
+
+    let owned = nft.prove(&payer);
+    let instruction = client.initialize_compressed_pair(&owned);
+    process(&mut setup.ctx, &[compute_budget(), instruction], &[])
+        .await
+        .unwrap();
+    let instruction = client.entangle_compressed(&owned, None);
+    process(&mut setup.ctx, &[compute_budget(), instruction], &[])
+        .await
+        .unwrap();
+    assert_eq!(setup.amount(&payer, &entangled_mint).await, 1);
+
+    // Custody of the leaf can only be proven through its tree
+    let instruction = client.verify_pair(asset_id(&nft.merkle_tree, 0), None, TOKEN_PROGRAM_ID);
+    let result = simulate(&mut setup.ctx, instruction).await.map(|_| ());
+    assert_error(result, EntanglerError::CompressedOriginal);
+
+    // The leaf now belongs to the authority
+    let escrowed = nft.prove(&client.entangler_authority);
+    let instruction = client.disentangle_compressed(&escrowed, None);
+    process(&mut setup.ctx, &[compute_budget(), instruction], &[])
+        .await
+        .unwrap();
+    assert_eq!(setup.amount(&payer, &entangled_mint).await, 0);
+}
+
+#[tokio::test]
+async fn burns_escrowed_originals() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let client = setup.entangled(false, original).await;
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+
+    let instruction = client.burn_original(original, TOKEN_PROGRAM_ID, None);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    assert_eq!(
+        setup.amount(&client.entangler_authority, &original).await,
+        0
+    );
+    let pair: Option<EntangledPair> =
+        fetch(&mut setup.ctx, &find_entangled_pair(&entangled_mint).0).await;
+    assert!(pair.is_none());
+}
+
+#[tokio::test]
+async fn keeps_new_collections_on_their_own_authority() {
+    let mut setup = Setup::new(2).await;
+    let originals = setup.originals.clone();
+    let client = setup.entangled(false, originals[0]).await;
+    let collection: EntangledCollection = fetch(&mut setup.ctx, &client.entangled_collection)
+        .await
+        .unwrap();
+    assert!(collection.scoped_authority);
+    assert_eq!(
+        client.entangler_authority,
+        find_collection_authority(&client.id).0
+    );
+
+    let instruction = client.migrate_collection();
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::AlreadyMigrated);
+
+    // The global authority never escrowed anything of the collection
+    let instruction = client.migrate_pair(originals[0], false, None, None, TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, ErrorCode::AccountNotInitialized);
+
+    let instruction = client
+        .clone()
+        .with_global_authority()
+        .initialize_pair(originals[1], TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::InvalidAuthority);
+}
+
+#[tokio::test]
+async fn migrates_legacy_collections() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let client = setup.collection(false).await;
+    let legacy = client.clone().with_global_authority();
+    let global_authority = find_entangler_authority().0;
+    let payer = setup.ctx.payer.pubkey();
+    rewind_to_global_authority(&mut setup.ctx, &client.entangled_collection).await;
+
+    let instructions = [
+        legacy.initialize_pair(original, TOKEN_PROGRAM_ID),
+        legacy.entangle(original, None, None, TOKEN_PROGRAM_ID),
+    ];
+    process(&mut setup.ctx, &instructions, &[]).await.unwrap();
+    assert_eq!(setup.amount(&global_authority, &original).await, 1);
+
+    let instruction = client.migrate_pair(original, true, None, None, TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::UnmigratedCollection);
+
+    // Collections awaiting migration were 107 bytes long
+    truncate_account(&mut setup.ctx, &client.entangled_collection, 107).await;
+    let instruction = client.migrate_collection();
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let collection: EntangledCollection = fetch(&mut setup.ctx, &client.entangled_collection)
+        .await
+        .unwrap();
+    assert!(collection.scoped_authority);
+
+    let instruction = client.migrate_pair(original, true, None, None, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    assert_eq!(setup.amount(&global_authority, &original).await, 0);
+    assert_eq!(
+        setup.amount(&client.entangler_authority, &original).await,
+        1
+    );
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+    let pair: EntangledPair = fetch(&mut setup.ctx, &find_entangled_pair(&entangled_mint).0)
+        .await
+        .unwrap();
+    assert!(pair.migrated);
+
+    let instruction = client.disentangle(original, None, None, TOKEN_PROGRAM_ID, None);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    assert_eq!(setup.amount(&payer, &original).await, 1);
+}
+
+#[tokio::test]
+async fn only_migrates_originals_escrowed_for_the_pair() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let global_authority = find_entangler_authority().0;
+    let payer = setup.ctx.payer.pubkey();
+
+    // Another collection over the same originals swapped the original back and forth first
+    let other = setup.entangled(false, original).await;
+    let instruction = other.disentangle(original, None, None, TOKEN_PROGRAM_ID, None);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+
+    let client = setup.collection(false).await;
+    let legacy = client.clone().with_global_authority();
+    rewind_to_global_authority(&mut setup.ctx, &client.entangled_collection).await;
+    let instructions = [
+        legacy.initialize_pair(original, TOKEN_PROGRAM_ID),
+        legacy.entangle(original, None, None, TOKEN_PROGRAM_ID),
+    ];
+    process(&mut setup.ctx, &instructions, &[]).await.unwrap();
+    assert_eq!(setup.amount(&global_authority, &original).await, 1);
+
+    // The shared escrow holds the original for the legacy collection only
+    let other_mint = find_entangled_mint(&other.id, &original).0;
+    let instruction =
+        create_associated_token_account(&payer, &global_authority, &other_mint, &TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let instruction = other.migrate_pair(original, true, None, None, TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::AlreadyMigratedPair);
+    assert_eq!(setup.amount(&global_authority, &original).await, 1);
+
+    let instructions = [
+        client.migrate_collection(),
+        client.migrate_pair(original, true, None, None, TOKEN_PROGRAM_ID),
+    ];
+    process(&mut setup.ctx, &instructions, &[]).await.unwrap();
+    assert_eq!(
+        setup.amount(&client.entangler_authority, &original).await,
+        1
+    );
+}
+
+#[tokio::test]
+async fn only_migrates_compressed_originals_escrowed_for_the_pair() {
+    let mut setup = Setup::new(0).await;
+    let client = setup.collection(false).await;
+    let legacy = client.clone().with_global_authority();
+    let global_authority = find_entangler_authority().0;
+    rewind_to_global_authority(&mut setup.ctx, &client.entangled_collection).await;
+    let creator = setup.creator.insecure_clone();
+    let payer = setup.ctx.payer.pubkey();
+    let collection_mint = setup.collection_mint;
+    let nft = mint_compressed_nft(&mut setup.ctx, &creator, &payer, &collection_mint).await;
+    let asset = asset_id(&nft.merkle_tree, 0);
+    let entangled_mint = find_entangled_mint(&client.id, &asset).0;
+
+    let owned = nft.prove(&payer);
+    for instruction in [
+        legacy.initialize_compressed_pair(&owned),
+        legacy.entangle_compressed(&owned, None),
+    ] {
+        process(&mut setup.ctx, &[compute_budget(), instruction], &[])
+            .await
+            .unwrap();
+    }
+
+    // Another collection over the same originals can't claim the leaf
+    let escrowed = nft.prove(&global_authority);
+    let other = setup.collection(false).await;
+    let instruction = other.initialize_compressed_pair(&escrowed);
+    process(&mut setup.ctx, &[compute_budget(), instruction], &[])
+        .await
+        .unwrap();
+    let instruction = other.migrate_compressed_original(&escrowed);
+    let result = process(&mut setup.ctx, &[compute_budget(), instruction], &[]).await;
+    assert_error(result, EntanglerError::ForeignOriginal);
+
+    let instruction = client.migrate_collection();
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let instruction = client.migrate_compressed_original(&escrowed);
+    process(&mut setup.ctx, &[compute_budget(), instruction], &[])
+        .await
+        .unwrap();
+    let pair: EntangledPair = fetch(&mut setup.ctx, &find_entangled_pair(&entangled_mint).0)
+        .await
+        .unwrap();
+    assert!(pair.migrated);
+
+    // The leaf now belongs to the collection's authority, and doesn't move twice
+    let migrated = nft.prove(&client.entangler_authority);
+    let instruction = client.migrate_compressed_original(&migrated);
+    let result = process(&mut setup.ctx, &[compute_budget(), instruction], &[]).await;
+    assert_error(result, EntanglerError::ForeignOriginal);
+
+    let instruction = client.migrate_pair(asset, false, None, None, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let instruction = client.disentangle_compressed(&migrated, None);
+    process(&mut setup.ctx, &[compute_budget(), instruction], &[])
+        .await
+        .unwrap();
+    assert_eq!(setup.amount(&payer, &entangled_mint).await, 0);
+}
+
+#[tokio::test]
+async fn verifies_the_custody_of_pairs() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let client = setup.collection(false).await;
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+
+    // No entangled mint yet
+    let instruction = client.verify_pair(original, None, TOKEN_PROGRAM_ID);
+    let result = simulate(&mut setup.ctx, instruction).await.map(|_| ());
+    assert_error(result, ErrorCode::AccountNotInitialized);
+
+    // The pair account only gets created by the first entanglement
+    let instruction = client.initialize_pair(original, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let pair: Option<EntangledPair> =
+        fetch(&mut setup.ctx, &find_entangled_pair(&entangled_mint).0).await;
+    assert!(pair.is_none());
+    let instruction = client.verify_pair(original, None, TOKEN_PROGRAM_ID);
+    let custody =
+        deserialize_custody(&simulate(&mut setup.ctx, instruction).await.unwrap()).unwrap();
+    assert_eq!(custody.original_mint, original);
+    assert_eq!(custody.entangled_mint, entangled_mint);
+    assert_eq!(custody.authority, client.entangler_authority);
+    assert!(!custody.entangled);
+    assert_eq!(
+        custody.custody_account,
+        get_associated_token_address(&client.entangler_authority, &entangled_mint)
+    );
+
+    let instruction = client.entangle(original, None, None, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let instruction = client.verify_pair(original, None, TOKEN_PROGRAM_ID);
+    let custody =
+        deserialize_custody(&simulate(&mut setup.ctx, instruction).await.unwrap()).unwrap();
+    assert!(custody.entangled);
+    assert_eq!(
+        custody.custody_account,
+        get_associated_token_address(&client.entangler_authority, &original)
+    );
+
+    let instruction =
+        client
+            .clone()
+            .with_global_authority()
+            .verify_pair(original, None, TOKEN_PROGRAM_ID);
+    let result = simulate(&mut setup.ctx, instruction).await.map(|_| ());
+    assert_error(result, EntanglerError::InvalidAuthority);
+}
+
+#[tokio::test]
+async fn rescues_tokens_backing_no_pair() {
+    let mut setup = Setup::new(2).await;
+    let originals = setup.originals.clone();
+    let client = setup.entangled(false, originals[0]).await;
+    let entangled_mint = find_entangled_mint(&client.id, &originals[0]).0;
+    let admin = setup.ctx.payer.pubkey();
+
+    // A fungible token sent to the authority by mistake
+    let creator = setup.creator.insecure_clone();
+    let stray_mint = mint_token(&mut setup.ctx, &creator, &client.entangler_authority, 100).await;
+    let instruction =
+        create_associated_token_account(&admin, &admin, &stray_mint, &TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let source = get_associated_token_address(&client.entangler_authority, &stray_mint);
+    let destination = get_associated_token_address(&admin, &stray_mint);
+
+    let not_admin = EntanglerClient {
+        signer: creator.pubkey(),
+        ..client.clone()
+    };
+    let instruction =
+        not_admin.rescue_tokens(stray_mint, source, destination, 100, TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[&creator]).await;
+    assert_error(result, ErrorCode::ConstraintRaw);
+
+    // Originals, entangled tokens and the entangled collection all back pairs
+    for mint in [
+        originals[0],
+        entangled_mint,
+        client.entangled_collection_mint,
+    ] {
+        let escrow = get_associated_token_address(&client.entangler_authority, &mint);
+        let instruction = client.rescue_tokens(mint, escrow, escrow, 1, TOKEN_PROGRAM_ID);
+        let result = process(&mut setup.ctx, &[instruction], &[]).await;
+        assert_error(result, EntanglerError::BackingToken);
+    }
+
+    // Originals of pairs initialized before records are told by their entangled mint
+    let record = find_original_record(&originals[0]).0;
+    setup.ctx.set_account(&record, &AccountSharedData::default());
+    let escrow = get_associated_token_address(&client.entangler_authority, &originals[0]);
+    let instruction = client.rescue_tokens(originals[0], escrow, escrow, 1, TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::BackingToken);
+
+    // Only the collection's authority and the global one can be rescued from
+    let other_authority = EntanglerClient {
+        entangler_authority: find_collection_authority(&Pubkey::new_unique()).0,
+        ..client.clone()
+    };
+    let instruction =
+        other_authority.rescue_tokens(stray_mint, source, destination, 100, TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::InvalidAuthority);
+
+    let instruction = client.rescue_tokens(stray_mint, source, destination, 100, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    assert_eq!(
+        setup.amount(&client.entangler_authority, &stray_mint).await,
+        0
+    );
+    assert_eq!(setup.amount(&admin, &stray_mint).await, 100);
+
+    // Only originals of initialized pairs can be recorded, including in legacy collections
+    truncate_account(&mut setup.ctx, &client.entangled_collection, 107).await;
+    let instruction = client.record_original(originals[1]);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::UninitializedPair);
+    let instruction = client.record_original(originals[0]);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    let record: OriginalRecord = fetch(&mut setup.ctx, &find_original_record(&originals[0]).0)
+        .await
+        .unwrap();
+    assert_eq!(record.original_mint, originals[0]);
+}
+
+#[tokio::test]
+async fn refuses_global_rescues_until_records_are_backfilled() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let admin = setup.ctx.payer.pubkey();
+    let creator = setup.creator.insecure_clone();
+    let global_authority = find_entangler_authority().0;
+
+    // A legacy pair of one collection, initialized before records, escrows the original
+    let client = setup.collection(false).await;
+    rewind_to_global_authority(&mut setup.ctx, &client.entangled_collection).await;
+    let legacy = client.clone().with_global_authority();
+    let instructions = [
+        legacy.initialize_pair(original, TOKEN_PROGRAM_ID),
+        legacy.entangle(original, None, None, TOKEN_PROGRAM_ID),
+    ];
+    process(&mut setup.ctx, &instructions, &[]).await.unwrap();
+    let record = find_original_record(&original).0;
+    setup.ctx.set_account(&record, &AccountSharedData::default());
+
+    // Another legacy collection shares the global escrow, but has no pair of the original
+    let other = setup.collection(false).await;
+    rewind_to_global_authority(&mut setup.ctx, &other.entangled_collection).await;
+    let other = other.with_global_authority();
+    let escrow = get_associated_token_address(&global_authority, &original);
+    let destination = get_associated_token_address(&admin, &original);
+    let instruction = other.rescue_tokens(original, escrow, destination, 1, TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::RecordsNotBackfilled);
+
+    let instruction = set_records_backfilled(creator.pubkey(), true);
+    let result = process(&mut setup.ctx, &[instruction], &[&creator]).await;
+    assert_error(result, EntanglerError::InvalidAdmin);
+
+    // Once every legacy original is recorded, the record protects it through any collection
+    let instructions = [
+        client.record_original(original),
+        set_records_backfilled(admin, true),
+    ];
+    process(&mut setup.ctx, &instructions, &[]).await.unwrap();
+    let instruction = other.rescue_tokens(original, escrow, destination, 1, TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::BackingToken);
+    assert_eq!(setup.amount(&global_authority, &original).await, 1);
+}
+
+#[tokio::test]
+async fn sweeps_authority_lamports_to_the_earner() {
+    let mut setup = Setup::new(1).await;
+    let client = setup.collection(false).await;
+    let authority = client.entangler_authority;
+    let earner = setup.creator.pubkey();
+
+    // Lamports sent to the authority by mistake
+    let transfer =
+        system_instruction::transfer(&setup.ctx.payer.pubkey(), &authority, 1_000_000_000);
+    process(&mut setup.ctx, &[transfer], &[]).await.unwrap();
+
+    let creator = setup.creator.insecure_clone();
+    let not_admin = EntanglerClient {
+        signer: creator.pubkey(),
+        ..client.clone()
+    };
+    let instruction = not_admin.sweep_authority_lamports(earner);
+    let result = process(&mut setup.ctx, &[instruction], &[&creator]).await;
+    assert_error(result, ErrorCode::ConstraintRaw);
+
+    let needed = setup
+        .ctx
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(0);
+    let authority_balance = setup.ctx.banks_client.get_balance(authority).await.unwrap();
+    let earner_balance = setup.ctx.banks_client.get_balance(earner).await.unwrap();
+    let instruction = client.sweep_authority_lamports(earner);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+    assert_eq!(
+        setup.ctx.banks_client.get_balance(authority).await.unwrap(),
+        needed
+    );
+    assert_eq!(
+        setup.ctx.banks_client.get_balance(earner).await.unwrap(),
+        earner_balance + authority_balance - needed
+    );
+
+    // Only the earner can receive the lamports
+    let instruction = client.sweep_authority_lamports(Pubkey::new_unique());
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, ErrorCode::ConstraintAddress);
+}
+
+#[tokio::test]
+async fn only_creates_missing_entangled_editions() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let client = setup.collection(false).await;
+
+    let instruction = client.initialize_pair(original, TOKEN_PROGRAM_ID);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+
+    // Pairs initialized since entangled tokens have master editions already have one, which
+    // holds the mint authority
+    let instruction = client.create_entangled_edition(original);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, ErrorCode::ConstraintMintMintAuthority);
+}
+
+#[tokio::test]
+async fn creates_entangled_editions_in_legacy_collections() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let client = setup.collection(false).await;
+    truncate_account(&mut setup.ctx, &client.entangled_collection, 107).await;
+
+    // An entangled token minted before entangled tokens got master editions
+    let creator = setup.creator.insecure_clone();
+    let payer = setup.ctx.payer.pubkey();
+    let entangled_mint = find_entangled_mint(&client.id, &original).0;
+    mint_editionless_nft(
+        &mut setup.ctx,
+        &creator,
+        &payer,
+        &client.entangler_authority,
+        &entangled_mint,
+    )
+    .await;
+
+    // Mints controlled by the authority but not derived from the original in the collection
+    let forged_mint = Pubkey::new_unique();
+    mint_editionless_nft(
+        &mut setup.ctx,
+        &creator,
+        &payer,
+        &client.entangler_authority,
+        &forged_mint,
+    )
+    .await;
+    let instruction = Instruction {
+        program_id: entangler::ID,
+        accounts: entangler::accounts::CreateEntangledEdition {
+            signer: payer,
+            entangler_authority: client.entangler_authority,
+            entangled_collection: client.entangled_collection,
+            original_mint: original,
+            entangled_mint: forged_mint,
+            entangled_metadata: find_metadata(&forged_mint).0,
+            entangled_master_edition: find_master_edition(&forged_mint).0,
+            metadata_program: mpl_token_metadata::ID,
+            token_program: TOKEN_PROGRAM_ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: entangler::instruction::CreateEntangledEdition {}.data(),
+    };
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, ErrorCode::ConstraintSeeds);
+
+    let instruction = client.create_entangled_edition(original);
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+
+    assert!(setup
+        .ctx
+        .banks_client
+        .get_account(find_master_edition(&entangled_mint).0)
+        .await
+        .unwrap()
+        .is_some());
+    let collection: EntangledCollection = fetch(&mut setup.ctx, &client.entangled_collection)
+        .await
+        .unwrap();
+    assert_eq!(collection.id, client.id);
+}
+
+#[tokio::test]
+async fn entangles_compressed_collections_through_their_tree() {
+    let mut setup = Setup::new(1).await;
+    let original = setup.originals[0];
+    let client = setup.client(Pubkey::new_unique());
+
+    let instruction = client.create_collection(
+        false,
+        false,
+        None,
+        EntangledStandard::Compressed,
+        None,
+        None,
+        false,
+    );
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+
+    let instruction = client.initialize_pair(original, TOKEN_PROGRAM_ID);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::CompressedCollection);
+}
+
+#[tokio::test]
+async fn creates_entangled_trees_for_the_whole_collection() {
+    let mut setup = Setup::new(0).await;
+    let client = setup.client(Pubkey::new_unique());
+    let creator = setup.creator.insecure_clone();
+    let instruction = client.create_collection(
+        false,
+        false,
+        None,
+        EntangledStandard::Compressed,
+        None,
+        None,
+        false,
+    );
+    process(&mut setup.ctx, &[instruction], &[]).await.unwrap();
+
+    // The original collection holds 9 tokens
+    let instruction = mpl_token_metadata::instruction::set_collection_size(
+        mpl_token_metadata::ID,
+        find_metadata(&setup.collection_mint).0,
+        creator.pubkey(),
+        setup.collection_mint,
+        None,
+        9,
+    );
+    process(&mut setup.ctx, &[instruction], &[&creator])
+        .await
+        .unwrap();
+
+    // Only the admin or the collection's creator can set the tree, which happens once
+    let merkle_tree = allocate_merkle_tree(&mut setup.ctx, 5, 8).await;
+    let intruder = create_keypair(&mut setup.ctx).await;
+    let intruding = EntanglerClient {
+        signer: intruder.pubkey(),
+        ..client.clone()
+    };
+    let instruction = intruding.create_entangled_tree(merkle_tree, 5, 8);
+    let result = process(&mut setup.ctx, &[instruction], &[&intruder]).await;
+    assert_error(result, EntanglerError::InvalidTreeCreator);
+
+    // A tree of depth 3 holds 8 leaves
+    let undersized = allocate_merkle_tree(&mut setup.ctx, 3, 8).await;
+    let instruction = client.create_entangled_tree(undersized, 3, 8);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::UndersizedTree);
+
+    let creating = EntanglerClient {
+        signer: creator.pubkey(),
+        ..client.clone()
+    };
+    let instruction = creating.create_entangled_tree(merkle_tree, 5, 8);
+    process(&mut setup.ctx, &[instruction], &[&creator])
+        .await
+        .unwrap();
+    let collection: EntangledCollection = fetch(&mut setup.ctx, &client.entangled_collection)
+        .await
+        .unwrap();
+    assert_eq!(collection.entangled_tree, Some(merkle_tree));
+
+    let instruction = client.create_entangled_tree(undersized, 3, 8);
+    let result = process(&mut setup.ctx, &[instruction], &[]).await;
+    assert_error(result, EntanglerError::InvalidEntangledTree);
+}
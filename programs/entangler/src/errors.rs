@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum EntanglerError {
+    #[msg("The token is not part of the original collection")]
+    InvalidCollection,
+
+    #[msg("The token's collection is not verified")]
+    UnverifiedCollection,
+
+    #[msg("The token is missing the collection's verified creator")]
+    UnverifiedCreator,
+
+    #[msg("Programmable NFTs require their edition, token records and the instructions sysvar")]
+    MissingProgrammableAccounts,
+
+    #[msg("Rule sets can only be enforced on programmable entangled tokens")]
+    RuleSetRequiresProgrammable,
+
+    #[msg("The authorization rules do not match the collection's rule set")]
+    InvalidRuleSet,
+
+    #[msg("Non-transferable tokens can't be entangled")]
+    NonTransferableOriginal,
+
+    #[msg("Tokens charging a transfer fee can't be entangled")]
+    TransferFeeOriginal,
+
+    #[msg("Transfer hooks can only be set on Token-2022 entangled tokens")]
+    TransferHookRequiresToken2022,
+
+    #[msg("The token program doesn't own the collection's entangled tokens")]
+    InvalidEntangledTokenProgram,
+
+    #[msg("The merkle tree is not managed by Bubblegum")]
+    InvalidMerkleTree,
+
+    #[msg("The compressed metadata doesn't deserialize")]
+    InvalidCompressedMetadata,
+
+    #[msg("Compressed collections entangle through their merkle tree")]
+    CompressedCollection,
+
+    #[msg("The merkle tree doesn't hold the collection's entangled tokens")]
+    InvalidEntangledTree,
+
+    #[msg("The leaf is not the pair's entangled token")]
+    InvalidEntangledAsset,
+
+    #[msg("Print editions require their master edition, its mint and token account, and the edition marker")]
+    MissingEditionAccounts,
+
+    #[msg("The master edition accounts don't match the print edition")]
+    InvalidPrintEdition,
+
+    #[msg("Entangled NFTs require their master edition")]
+    MissingEntangledEdition,
+
+    #[msg("Non-custodial collections can't hold compressed tokens")]
+    NonCustodialCompressed,
+
+    #[msg("Only non-programmable SPL originals can be frozen in their owner's account")]
+    NonCustodialOriginal,
+
+    #[msg("Freezing an original requires its edition")]
+    MissingOriginalEdition,
+
+    #[msg("The token account doesn't hold the frozen original")]
+    InvalidFrozenAccount,
+
+    #[msg("Originals of non-custodial collections stay with their owner")]
+    NonCustodialCollection,
+
+    #[msg("The batch holds no token")]
+    EmptyBatch,
+
+    #[msg("The metadata account isn't the Token Metadata PDA of its mint")]
+    InvalidMetadata,
+
+    #[msg("The edition account isn't the Token Metadata PDA of its mint")]
+    InvalidEdition,
+
+    #[msg("The authority isn't the escrow authority of the collection")]
+    InvalidAuthority,
+
+    #[msg("The collection already uses its own authority")]
+    AlreadyMigrated,
+
+    #[msg("Compressed and non-custodial collections keep the global authority")]
+    UnmigratableCollection,
+
+    #[msg("The collection still uses the global authority")]
+    UnmigratedCollection,
+
+    #[msg("The pair holds nothing for the global authority")]
+    AlreadyMigratedPair,
+
+    #[msg("The entangler must hold exactly one token of the pair")]
+    BrokenCustody,
+
+    #[msg("A token of the pair doesn't have a supply of one")]
+    InvalidSupply,
+
+    #[msg("The token is the original or entangled token of a pair")]
+    BackingToken,
+
+    #[msg("The original has no initialized pair")]
+    UninitializedPair,
+
+    #[msg("Only the state, collections and pairs can be resized")]
+    UnresizableAccount,
+
+    #[msg("The global authority doesn't escrow the original for this pair")]
+    ForeignOriginal,
+
+    #[msg("Compressed originals are held in their tree, which verify_pair can't read")]
+    CompressedOriginal,
+
+    #[msg("Custodial collections need the escrow of the original")]
+    MissingOriginalEscrow,
+
+    #[msg("A group of the batch has another signer than the batch")]
+    InvalidBatchSigner,
+
+    #[msg("The batch accounts don't match the transfer hook account counts")]
+    MalformedBatch,
+
+    #[msg("The signer isn't the entangler's admin")]
+    InvalidAdmin,
+
+    #[msg("Originals initialized before original records existed must all be recorded first")]
+    RecordsNotBackfilled,
+
+    #[msg("The entangled collection's metadata lists no creator receiving royalties")]
+    MissingCollectionCreator,
+
+    #[msg("Only the admin or the collection's creator can create its tree")]
+    InvalidTreeCreator,
+
+    #[msg("The tree can't hold an entangled token for every original of the collection")]
+    UndersizedTree,
+}
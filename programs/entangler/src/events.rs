@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+/// Lamports swept from an entangler authority to the earner
+#[event]
+pub struct AuthorityLamportsSwept {
+    /// The authority the lamports were swept from
+    pub authority: Pubkey,
+
+    /// The account earning the fee, which received the lamports
+    pub earner: Pubkey,
+
+    /// The lamports moved
+    pub amount: u64,
+}
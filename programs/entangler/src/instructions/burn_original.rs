@@ -1,22 +1,104 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::program::invoke_signed;
 use anchor_lang::solana_program::system_instruction::transfer;
-use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{Mint, Token, TokenAccount};
-use mpl_token_metadata::instruction::burn_nft;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token::Mint;
+use anchor_spl::token_2022;
+use anchor_spl::token_interface::{self, TokenInterface};
+use mpl_token_metadata::instruction::builders::BurnBuilder;
+use mpl_token_metadata::instruction::{burn_edition_nft, burn_nft, BurnArgs, InstructionBuilder};
+use mpl_token_metadata::state::{Edition, Metadata, TokenMetadataAccount, EDITION_MARKER_BIT_SIZE};
 
-use crate::seeds::{AUTHORITY_SEED, COLLECTION_SEED, ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED};
+use crate::errors::EntanglerError;
+use crate::seeds::{COLLECTION_SEED, ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED};
 use crate::state::{EntangledCollection, EntangledPair};
+use crate::utils::{is_programmable, optional_info, print_edition};
 
 pub fn burn_original(ctx: Context<BurnOriginal>) -> Result<()> {
     msg!("Burn original token");
 
-    let authority_bump = *ctx.bumps.get("entangler_authority").unwrap();
-    let authority_seeds = &[AUTHORITY_SEED.as_bytes(), &[authority_bump]];
+    let authority = ctx.accounts.entangled_collection.authority();
+    let authority_seeds = authority.seeds();
     let authority_signer_seeds = &[&authority_seeds[..]];
 
     let lamports_before = ctx.accounts.entangler_authority.lamports();
 
+    // Token Metadata only burns Token-2022 originals through its V1 instruction
+    let original_metadata = Metadata::from_account_info(&ctx.accounts.original_metadata)?;
+    let programmable = is_programmable(&original_metadata);
+    let print = match print_edition(&ctx.accounts.master_edition) {
+        Some(edition) => Some(PrintAccounts::new(&ctx, &edition)?),
+        None => None,
+    };
+    if programmable || ctx.accounts.original_token_program.key() == token_2022::ID {
+        burn_v1(&ctx, programmable, print, authority_signer_seeds)?;
+    } else if let Some(print) = print {
+        burn_print(&ctx, print, authority_signer_seeds)?;
+    } else {
+        burn_legacy(&ctx, authority_signer_seeds)?;
+    }
+
+    let refund = ctx.accounts.entangler_authority.lamports() - lamports_before;
+    invoke_signed(
+        &transfer(
+            ctx.accounts.entangler_authority.key,
+            ctx.accounts.signer.key,
+            refund,
+        ),
+        &[
+            ctx.accounts.entangler_authority.to_account_info(),
+            ctx.accounts.signer.to_account_info(),
+        ],
+        authority_signer_seeds,
+    )?;
+
+    Ok(())
+}
+
+/// The master edition accounts needed to burn a print edition
+struct PrintAccounts<'info> {
+    parent_mint: AccountInfo<'info>,
+    parent_edition: AccountInfo<'info>,
+    parent_token: AccountInfo<'info>,
+    edition_marker: AccountInfo<'info>,
+}
+
+impl<'info> PrintAccounts<'info> {
+    fn new(ctx: &Context<BurnOriginal<'info>>, edition: &Edition) -> Result<Self> {
+        let (Some(parent_mint), Some(parent_edition), Some(parent_token), Some(edition_marker)) = (
+            optional_info(&ctx.accounts.parent_mint),
+            optional_info(&ctx.accounts.parent_edition),
+            optional_info(&ctx.accounts.parent_token),
+            optional_info(&ctx.accounts.edition_marker),
+        ) else {
+            return err!(EntanglerError::MissingEditionAccounts);
+        };
+
+        let marker_number = (edition.edition / EDITION_MARKER_BIT_SIZE).to_string();
+        require!(
+            edition.parent == parent_edition.key()
+                && parent_edition.key()
+                    == mpl_token_metadata::pda::find_master_edition_account(&parent_mint.key()).0
+                && edition_marker.key()
+                    == mpl_token_metadata::pda::find_edition_account(
+                        &parent_mint.key(),
+                        marker_number,
+                    )
+                    .0,
+            EntanglerError::InvalidPrintEdition
+        );
+
+        Ok(Self {
+            parent_mint,
+            parent_edition,
+            parent_token,
+            edition_marker,
+        })
+    }
+}
+
+fn burn_legacy(ctx: &Context<BurnOriginal>, authority_signer_seeds: &[&[&[u8]]]) -> Result<()> {
     let ix = burn_nft(
         ctx.accounts.metadata_program.key(),
         ctx.accounts.original_metadata.key(),
@@ -24,7 +106,7 @@ pub fn burn_original(ctx: Context<BurnOriginal>) -> Result<()> {
         ctx.accounts.original_mint.key(),
         ctx.accounts.original_mint_escrow.key(),
         ctx.accounts.master_edition.key(),
-        ctx.accounts.token_program.key(),
+        ctx.accounts.original_token_program.key(),
         Some(ctx.accounts.original_collection_metadata.key()),
     );
     invoke_signed(
@@ -35,22 +117,46 @@ pub fn burn_original(ctx: Context<BurnOriginal>) -> Result<()> {
             ctx.accounts.original_mint.to_account_info(),
             ctx.accounts.original_mint_escrow.to_account_info(),
             ctx.accounts.master_edition.to_account_info(),
-            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.original_token_program.to_account_info(),
             ctx.accounts.original_collection_metadata.to_account_info(),
         ],
         authority_signer_seeds,
     )?;
 
-    let refund = ctx.accounts.entangler_authority.lamports() - lamports_before;
+    Ok(())
+}
+
+fn burn_print<'info>(
+    ctx: &Context<BurnOriginal<'info>>,
+    print: PrintAccounts<'info>,
+    authority_signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let ix = burn_edition_nft(
+        ctx.accounts.metadata_program.key(),
+        ctx.accounts.original_metadata.key(),
+        ctx.accounts.entangler_authority.key(),
+        ctx.accounts.original_mint.key(),
+        print.parent_mint.key(),
+        ctx.accounts.original_mint_escrow.key(),
+        print.parent_token.key(),
+        print.parent_edition.key(),
+        ctx.accounts.master_edition.key(),
+        print.edition_marker.key(),
+        ctx.accounts.original_token_program.key(),
+    );
     invoke_signed(
-        &transfer(
-            ctx.accounts.entangler_authority.key,
-            ctx.accounts.signer.key,
-            refund,
-        ),
+        &ix,
         &[
+            ctx.accounts.original_metadata.to_account_info(),
             ctx.accounts.entangler_authority.to_account_info(),
-            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.original_mint.to_account_info(),
+            print.parent_mint,
+            ctx.accounts.original_mint_escrow.to_account_info(),
+            print.parent_token,
+            print.parent_edition,
+            ctx.accounts.master_edition.to_account_info(),
+            print.edition_marker,
+            ctx.accounts.original_token_program.to_account_info(),
         ],
         authority_signer_seeds,
     )?;
@@ -58,6 +164,71 @@ pub fn burn_original(ctx: Context<BurnOriginal>) -> Result<()> {
     Ok(())
 }
 
+fn burn_v1<'info>(
+    ctx: &Context<BurnOriginal<'info>>,
+    programmable: bool,
+    print: Option<PrintAccounts<'info>>,
+    authority_signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let Some(sysvar_instructions) = ctx.accounts.sysvar_instructions.as_ref() else {
+        return err!(EntanglerError::MissingProgrammableAccounts);
+    };
+    let original_mint_escrow_record = optional_info(&ctx.accounts.original_mint_escrow_record);
+    if programmable && original_mint_escrow_record.is_none() {
+        return err!(EntanglerError::MissingProgrammableAccounts);
+    }
+
+    let mut builder = BurnBuilder::new();
+    builder
+        .authority(ctx.accounts.entangler_authority.key())
+        .collection_metadata(ctx.accounts.original_collection_metadata.key())
+        .metadata(ctx.accounts.original_metadata.key())
+        .edition(ctx.accounts.master_edition.key())
+        .mint(ctx.accounts.original_mint.key())
+        .token(ctx.accounts.original_mint_escrow.key())
+        .system_program(ctx.accounts.system_program.key())
+        .sysvar_instructions(sysvar_instructions.key())
+        .spl_token_program(ctx.accounts.original_token_program.key());
+
+    let mut account_infos = vec![
+        ctx.accounts.entangler_authority.to_account_info(),
+        ctx.accounts.original_collection_metadata.to_account_info(),
+        ctx.accounts.original_metadata.to_account_info(),
+        ctx.accounts.master_edition.to_account_info(),
+        ctx.accounts.original_mint.to_account_info(),
+        ctx.accounts.original_mint_escrow.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        sysvar_instructions.to_account_info(),
+        ctx.accounts.original_token_program.to_account_info(),
+        ctx.accounts.metadata_program.to_account_info(),
+    ];
+    if let Some(original_mint_escrow_record) = original_mint_escrow_record {
+        builder.token_record(original_mint_escrow_record.key());
+        account_infos.push(original_mint_escrow_record);
+    }
+    if let Some(print) = print {
+        builder
+            .master_edition(print.parent_edition.key())
+            .master_edition_mint(print.parent_mint.key())
+            .master_edition_token(print.parent_token.key())
+            .edition_marker(print.edition_marker.key());
+        account_infos.extend([
+            print.parent_edition,
+            print.parent_mint,
+            print.parent_token,
+            print.edition_marker,
+        ]);
+    }
+
+    let ix = builder
+        .build(BurnArgs::V1 { amount: 1 })
+        .map_err(|_| EntanglerError::MissingProgrammableAccounts)?
+        .instruction();
+    invoke_signed(&ix, &account_infos, authority_signer_seeds)?;
+
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct BurnOriginal<'info> {
     #[account(mut)]
@@ -66,8 +237,7 @@ pub struct BurnOriginal<'info> {
     /// CHECK: Safe because this read-only account only gets used as a constraint
     #[account(
         mut,
-        seeds = [AUTHORITY_SEED.as_bytes()],
-        bump,
+        address = entangled_collection.authority().key @ EntanglerError::InvalidAuthority,
     )]
     pub entangler_authority: UncheckedAccount<'info>,
 
@@ -80,6 +250,7 @@ pub struct BurnOriginal<'info> {
         has_one = entangled_collection_mint,
         has_one = original_collection_mint,
         constraint = !entangled_collection.one_way,
+        constraint = !entangled_collection.non_custodial @ EntanglerError::NonCustodialCollection,
     )]
     pub entangled_collection: Account<'info, EntangledCollection>,
 
@@ -97,32 +268,69 @@ pub struct BurnOriginal<'info> {
     /// CHECK: Metaplex does the verification
     pub original_collection_mint: UncheckedAccount<'info>,
 
-    /// CHECK: Metaplex does the verification
-    #[account(mut)]
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&original_collection_mint.key()).0 @ EntanglerError::InvalidMetadata,
+    )]
     pub original_collection_metadata: UncheckedAccount<'info>,
 
-    #[account(mut)]
-    pub original_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        mint::token_program = original_token_program,
+    )]
+    pub original_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
     /// CHECK: Using constraints
     #[account(
         mut,
-        address = mpl_token_metadata::pda::find_metadata_account(&original_mint.key()).0,
+        address = mpl_token_metadata::pda::find_metadata_account(&original_mint.key()).0 @ EntanglerError::InvalidMetadata,
         constraint = mpl_token_metadata::check_id(original_metadata.owner),
       )]
     pub original_metadata: UncheckedAccount<'info>,
 
-    /// The master edition of the token
+    /// The master or print edition of the token
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_master_edition_account(&original_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+
+    /// The mint of the master edition, for print edition originals
     /// CHECK: Done by MPL
+    pub parent_mint: Option<UncheckedAccount<'info>>,
+
+    /// The master edition, for print edition originals
+    /// CHECK: Checked in the instruction
     #[account(mut)]
-    pub master_edition: UncheckedAccount<'info>,
+    pub parent_edition: Option<UncheckedAccount<'info>>,
 
+    /// A token account holding the master edition, for print edition originals
+    /// CHECK: Done by MPL
+    pub parent_token: Option<UncheckedAccount<'info>>,
+
+    /// The edition marker of the print, for print edition originals
+    /// CHECK: Checked in the instruction
+    #[account(mut)]
+    pub edition_marker: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &original_mint.key(), &original_token_program.key()),
+        token::mint = original_mint,
+        token::authority = entangler_authority,
+        token::token_program = original_token_program,
+    )]
+    pub original_mint_escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// The token record of the escrow, for programmable originals
+    /// CHECK: Using constraints
     #[account(
         mut,
-        associated_token::mint = original_mint,
-        associated_token::authority = entangler_authority,
+        address = mpl_token_metadata::pda::find_token_record_account(&original_mint.key(), &original_mint_escrow.key()).0,
     )]
-    pub original_mint_escrow: Box<Account<'info, TokenAccount>>,
+    pub original_mint_escrow_record: Option<UncheckedAccount<'info>>,
 
     #[account(
         seeds = [
@@ -131,18 +339,20 @@ pub struct BurnOriginal<'info> {
           &original_mint.key().to_bytes()
         ],
         bump,
+        mint::token_program = token_program,
     )]
-    pub entangled_mint: Account<'info, Mint>,
+    pub entangled_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
     pub entangled_collection_mint: Account<'info, Mint>,
 
     #[account(
         mut,
-        associated_token::mint = entangled_mint,
-        associated_token::authority = signer,
+        token::mint = entangled_mint,
+        token::authority = signer,
+        token::token_program = token_program,
         constraint = entangled_mint_account.amount == 1,
     )]
-    pub entangled_mint_account: Box<Account<'info, TokenAccount>>,
+    pub entangled_mint_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     /// Common Solana programs
     /// CHECK: CPI
@@ -150,7 +360,16 @@ pub struct BurnOriginal<'info> {
         address = mpl_token_metadata::ID
     )]
     pub metadata_program: UncheckedAccount<'info>,
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Sysvar
+    #[account(
+        address = sysvar::instructions::ID
+    )]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+    pub original_token_program: Interface<'info, TokenInterface>,
+    #[account(
+        address = entangled_collection.entangled_token_program() @ EntanglerError::InvalidEntangledTokenProgram,
+    )]
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
@@ -5,17 +5,38 @@ use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
 use mpl_token_metadata::instruction::{create_master_edition_v3, create_metadata_accounts_v3};
 use mpl_token_metadata::state::{Creator, Metadata, TokenMetadataAccount};
 
-use crate::seeds::{AUTHORITY_SEED, COLLECTION_MINT_SEED, COLLECTION_SEED};
-use crate::state::EntangledCollection;
+use crate::errors::EntanglerError;
+use crate::seeds::{COLLECTION_AUTHORITY_SEED, COLLECTION_MINT_SEED, COLLECTION_SEED};
+use crate::state::{EntangledCollection, EntangledStandard};
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_collection(
     ctx: Context<CreateCollection>,
     id: Pubkey,
     royalties: u16,
     one_way: bool,
+    allow_unverified: bool,
+    original_creator: Option<Pubkey>,
+    entangled_standard: EntangledStandard,
+    rule_set: Option<Pubkey>,
+    transfer_hook_program: Option<Pubkey>,
+    non_custodial: bool,
 ) -> Result<()> {
     msg!("Creating the collection");
 
+    require!(
+        rule_set.is_none() || entangled_standard == EntangledStandard::ProgrammableNonFungible,
+        EntanglerError::RuleSetRequiresProgrammable
+    );
+    require!(
+        transfer_hook_program.is_none() || entangled_standard == EntangledStandard::Token2022,
+        EntanglerError::TransferHookRequiresToken2022
+    );
+    require!(
+        !non_custodial || entangled_standard != EntangledStandard::Compressed,
+        EntanglerError::NonCustodialCompressed
+    );
+
     let entanglement_collection = &mut ctx.accounts.entangled_collection;
     entanglement_collection.id = id;
     entanglement_collection.original_collection_mint = ctx.accounts.original_collection_mint.key();
@@ -23,12 +44,24 @@ pub fn create_collection(
         ctx.accounts.entangled_collection_mint.key();
     entanglement_collection.royalties = royalties;
     entanglement_collection.one_way = one_way;
-
-    let original_metadata =
-        Metadata::from_account_info(&ctx.accounts.original_collection_metadata).unwrap();
+    entanglement_collection.allow_unverified = allow_unverified;
+    entanglement_collection.original_creator = original_creator;
+    entanglement_collection.entangled_standard = entangled_standard;
+    entanglement_collection.rule_set = rule_set;
+    entanglement_collection.transfer_hook_program = transfer_hook_program;
+    entanglement_collection.entangled_tree = None;
+    entanglement_collection.non_custodial = non_custodial;
+    entanglement_collection.scoped_authority = true;
+
+    let original_metadata = Metadata::from_account_info(&ctx.accounts.original_collection_metadata)
+        .map_err(|_| EntanglerError::InvalidMetadata)?;
 
     let authority_bump = *ctx.bumps.get("entangler_authority").unwrap();
-    let authority_seeds = &[AUTHORITY_SEED.as_bytes(), &[authority_bump]];
+    let authority_seeds = &[
+        COLLECTION_AUTHORITY_SEED.as_bytes(),
+        id.as_ref(),
+        &[authority_bump],
+    ];
     let authority_signer_seeds = &[&authority_seeds[..]];
 
     // Mint the token
@@ -128,11 +161,11 @@ pub struct CreateCollection<'info> {
     /// CHECK: None needed
     pub creator: AccountInfo<'info>,
 
-    /// The PDA that has authority over entangled minted
+    /// The PDA of the collection that has authority over entangled minted
     /// CHECK: Safe because this read-only account only gets used as a constraint
     #[account(
         mut,
-        seeds = [AUTHORITY_SEED.as_bytes()],
+        seeds = [COLLECTION_AUTHORITY_SEED.as_bytes(), &id.to_bytes()],
         bump,
     )]
     pub entangler_authority: UncheckedAccount<'info>,
@@ -151,8 +184,11 @@ pub struct CreateCollection<'info> {
     pub entangled_collection: Account<'info, EntangledCollection>,
 
     /// The master edition of the collection
-    /// CHECK: Done by MPL
-    #[account(mut)]
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_master_edition_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
     pub master_edition: UncheckedAccount<'info>,
 
     /// The original collection mint
@@ -161,7 +197,7 @@ pub struct CreateCollection<'info> {
     /// The original collection metadata
     /// CHECK: Using constraints
     #[account(
-      address = mpl_token_metadata::pda::find_metadata_account(&original_collection_mint.key()).0,
+      address = mpl_token_metadata::pda::find_metadata_account(&original_collection_mint.key()).0 @ EntanglerError::InvalidMetadata,
       constraint = mpl_token_metadata::check_id(original_collection_metadata.owner),
     )]
     pub original_collection_metadata: UncheckedAccount<'info>,
@@ -183,7 +219,10 @@ pub struct CreateCollection<'info> {
 
     /// The entangled collection metadata
     /// CHECK: Using constraints
-    #[account(mut)]
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidMetadata,
+    )]
     pub entangled_collection_metadata: UncheckedAccount<'info>,
 
     /// The ATA storing the entangled mint
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+use crate::errors::EntanglerError;
+use crate::seeds::ENTANGLED_MINT_SEED;
+use crate::state::EntangledCollection;
+use crate::utils::{load_resized, MasterEditionCreation};
+
+pub fn create_entangled_edition(ctx: Context<CreateEntangledEdition>) -> Result<()> {
+    msg!("Creating the entangled edition");
+
+    // Pairs missing an edition belong to collections that may predate their current layout
+    let entangled_collection = load_resized::<EntangledCollection>(
+        &ctx.accounts.entangled_collection,
+        EntangledCollection::LEN,
+        &ctx.accounts.signer,
+        &ctx.accounts.system_program,
+    )?;
+    let (entangled_mint, _) = Pubkey::find_program_address(
+        &[
+            ENTANGLED_MINT_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+            &ctx.accounts.original_mint.key().to_bytes(),
+        ],
+        &crate::ID,
+    );
+    require_keys_eq!(
+        ctx.accounts.entangled_mint.key(),
+        entangled_mint,
+        ErrorCode::ConstraintSeeds
+    );
+
+    let authority = entangled_collection.authority();
+    require_keys_eq!(
+        ctx.accounts.entangler_authority.key(),
+        authority.key,
+        EntanglerError::InvalidAuthority
+    );
+    let authority_seeds = authority.seeds();
+    let authority_signer_seeds = &[&authority_seeds[..]];
+
+    MasterEditionCreation {
+        edition: ctx.accounts.entangled_master_edition.to_account_info(),
+        mint: ctx.accounts.entangled_mint.to_account_info(),
+        metadata: ctx.accounts.entangled_metadata.to_account_info(),
+        authority: ctx.accounts.entangler_authority.to_account_info(),
+        payer: ctx.accounts.signer.to_account_info(),
+        metadata_program: ctx.accounts.metadata_program.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    }
+    .invoke_signed(authority_signer_seeds)
+}
+
+#[derive(Accounts)]
+pub struct CreateEntangledEdition<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: Safe because this read-only account is checked against the collection's authority
+    pub entangler_authority: UncheckedAccount<'info>,
+
+    /// The collection of the entangled mint, resized if it predates the current layout
+    /// CHECK: Loaded with `load_resized`
+    #[account(mut)]
+    pub entangled_collection: UncheckedAccount<'info>,
+
+    /// The original mint, or the asset id of a compressed original
+    /// CHECK: Only its key is used, to derive the entangled mint
+    pub original_mint: UncheckedAccount<'info>,
+
+    /// The entangled mint of the original in the collection, still controlled by the authority
+    #[account(
+        mut,
+        mint::authority = entangler_authority,
+    )]
+    pub entangled_mint: Account<'info, Mint>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_mint.key()).0 @ EntanglerError::InvalidMetadata,
+        constraint = mpl_token_metadata::check_id(entangled_metadata.owner),
+    )]
+    pub entangled_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Created in the instruction, using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_master_edition_account(&entangled_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub entangled_master_edition: UncheckedAccount<'info>,
+
+    /// Common Solana programs
+    /// CHECK: CPI
+    #[account(
+        address = mpl_token_metadata::ID
+    )]
+    pub metadata_program: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
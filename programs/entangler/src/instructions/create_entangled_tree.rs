@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use mpl_bubblegum::instructions::{
+    CreateTreeConfigCpi, CreateTreeConfigCpiAccounts, CreateTreeConfigInstructionArgs,
+};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+
+use crate::errors::EntanglerError;
+use crate::seeds::{COLLECTION_SEED, STATE_SEED};
+use crate::state::{EntangledCollection, EntanglerState};
+use crate::utils::{collection_creator, collection_size};
+
+pub fn create_entangled_tree(
+    ctx: Context<CreateEntangledTree>,
+    max_depth: u32,
+    max_buffer_size: u32,
+) -> Result<()> {
+    msg!("Creating the entangled tree");
+
+    // Every original of a sized collection needs a leaf, unsized ones can't tell their size
+    let original_metadata =
+        Metadata::from_account_info(&ctx.accounts.original_collection_metadata)?;
+    if let Some(size) = collection_size(&original_metadata) {
+        require!(
+            max_depth >= u64::BITS || 1 << max_depth >= size,
+            EntanglerError::UndersizedTree
+        );
+    }
+
+    let authority = ctx.accounts.entangled_collection.authority();
+    let authority_seeds = authority.seeds();
+    let authority_signer_seeds = &[&authority_seeds[..]];
+
+    // The authority creates the tree so only the entangler can mint into it
+    CreateTreeConfigCpi::new(
+        &ctx.accounts.bubblegum_program,
+        CreateTreeConfigCpiAccounts {
+            tree_config: &ctx.accounts.tree_config,
+            merkle_tree: &ctx.accounts.merkle_tree,
+            payer: &ctx.accounts.signer,
+            tree_creator: &ctx.accounts.entangler_authority,
+            log_wrapper: &ctx.accounts.log_wrapper,
+            compression_program: &ctx.accounts.compression_program,
+            system_program: &ctx.accounts.system_program,
+        },
+        CreateTreeConfigInstructionArgs {
+            max_depth,
+            max_buffer_size,
+            public: Some(false),
+        },
+    )
+    .invoke_signed(authority_signer_seeds)?;
+
+    ctx.accounts.entangled_collection.entangled_tree = Some(ctx.accounts.merkle_tree.key());
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateEntangledTree<'info> {
+    /// The admin or the collection's creator, since the tree can only be set once
+    #[account(
+        mut,
+        constraint = signer.key() == state.admin
+            || signer.key() == collection_creator(&entangled_collection_metadata)?
+            @ EntanglerError::InvalidTreeCreator,
+    )]
+    pub signer: Signer<'info>,
+
+    /// The entangler's state
+    #[account(
+        seeds = [
+            STATE_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub state: Box<Account<'info, EntanglerState>>,
+
+    #[account(
+      address = entangled_collection.authority().key @ EntanglerError::InvalidAuthority,
+    )]
+    /// CHECK: Safe because this read-only account only gets used as a constraint
+    pub entangler_authority: UncheckedAccount<'info>,
+
+    /// The account storing the collection's data
+    #[account(
+        mut,
+        seeds = [
+            COLLECTION_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+        ],
+        bump,
+        constraint = entangled_collection.is_compressed() && entangled_collection.entangled_tree.is_none() @ EntanglerError::InvalidEntangledTree,
+    )]
+    pub entangled_collection: Account<'info, EntangledCollection>,
+
+    /// The metadata of the entangled collection, listing its creator
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_collection.entangled_collection_mint).0 @ EntanglerError::InvalidMetadata,
+    )]
+    pub entangled_collection_metadata: UncheckedAccount<'info>,
+
+    /// The metadata of the original collection, holding its size
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_collection.original_collection_mint).0 @ EntanglerError::InvalidMetadata,
+    )]
+    pub original_collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Created by Bubblegum
+    #[account(mut)]
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// The merkle tree, allocated beforehand for the compression program
+    /// CHECK: Initialized by Bubblegum
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// Common Solana programs
+    /// CHECK: CPI
+    #[account(
+        address = mpl_bubblegum::ID
+    )]
+    pub bubblegum_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = SPL_ACCOUNT_COMPRESSION_ID
+    )]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = SPL_NOOP_ID
+    )]
+    pub log_wrapper: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
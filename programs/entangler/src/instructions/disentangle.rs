@@ -1,39 +1,171 @@
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::{
+    self, get_associated_token_address_with_program_id, AssociatedToken,
+};
+use anchor_spl::token::{self, Mint};
+use anchor_spl::token_interface::{self, TokenInterface};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
 
-use crate::seeds::{AUTHORITY_SEED, COLLECTION_SEED, ENTANGLED_MINT_SEED};
-use crate::state::EntangledCollection;
+use crate::errors::EntanglerError;
+use crate::seeds::{COLLECTION_SEED, ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED};
+use crate::state::{EntangledCollection, EntangledPair};
+use crate::utils::{
+    check_transferable, is_programmable, optional_info, DelegatedFreeze, NftTransfer,
+};
 
-pub fn disentangle(ctx: Context<Disentangle>) -> Result<()> {
+pub fn disentangle<'info>(ctx: Context<'_, '_, '_, 'info, Disentangle<'info>>) -> Result<()> {
     msg!("Disentangle");
 
-    // Transfer the original token to an escrow
-    let original_transfer_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            authority: ctx.accounts.signer.to_account_info(),
-            from: ctx.accounts.entangled_mint_account.to_account_info(),
-            to: ctx.accounts.entangled_mint_escrow.to_account_info(),
-        },
-    );
-    token::transfer(original_transfer_ctx, 1)?;
+    // Transfer the entangled token to an escrow
+    NftTransfer {
+        token: ctx.accounts.entangled_mint_account.to_account_info(),
+        token_owner: ctx.accounts.signer.to_account_info(),
+        destination: ctx.accounts.entangled_mint_escrow.to_account_info(),
+        destination_owner: ctx.accounts.entangler_authority.to_account_info(),
+        mint: ctx.accounts.entangled_mint.to_account_info(),
+        metadata: ctx.accounts.entangled_metadata.to_account_info(),
+        authority: ctx.accounts.signer.to_account_info(),
+        payer: ctx.accounts.signer.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        metadata_program: ctx.accounts.metadata_program.to_account_info(),
+        hook_accounts: ctx.remaining_accounts.to_vec(),
+        edition: optional_info(&ctx.accounts.entangled_master_edition),
+        owner_token_record: optional_info(&ctx.accounts.entangled_mint_account_record),
+        destination_token_record: optional_info(&ctx.accounts.entangled_mint_escrow_record),
+        sysvar_instructions: optional_info(&ctx.accounts.sysvar_instructions),
+        authorization_rules_program: optional_info(&ctx.accounts.authorization_rules_program),
+        authorization_rules: optional_info(&ctx.accounts.entangled_authorization_rules),
+    }
+    .invoke_signed(ctx.accounts.entangled_collection.is_programmable(), &[])?;
 
-    let authority_bump = *ctx.bumps.get("entangler_authority").unwrap();
-    let authority_seeds = &[AUTHORITY_SEED.as_bytes(), &[authority_bump]];
+    let authority = ctx.accounts.entangled_collection.authority();
+    let authority_seeds = authority.seeds();
     let authority_signer_seeds = &[&authority_seeds[..]];
 
+    check_transferable(&ctx.accounts.original_mint.to_account_info())?;
+    if let Some(frozen_account) = ctx.accounts.entangled_pair.frozen_account {
+        release_original(&ctx, frozen_account, authority_signer_seeds)?;
+        ctx.accounts.entangled_pair.frozen_account = None;
+        return Ok(());
+    }
+
     // Transfer from the escrow
-    let transfer_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            authority: ctx.accounts.entangler_authority.to_account_info(),
-            from: ctx.accounts.original_mint_escrow.to_account_info(),
-            to: ctx.accounts.original_mint_account.to_account_info(),
+    associated_token::create_idempotent(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: ctx.accounts.signer.to_account_info(),
+            associated_token: ctx.accounts.original_mint_account.to_account_info(),
+            authority: ctx.accounts.signer.to_account_info(),
+            mint: ctx.accounts.original_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.original_token_program.to_account_info(),
         },
-        authority_signer_seeds,
+    ))?;
+    let Some(original_mint_escrow) = ctx
+        .accounts
+        .original_mint_escrow
+        .as_ref()
+        .map(|escrow| escrow.to_account_info())
+    else {
+        return err!(EntanglerError::MissingOriginalEscrow);
+    };
+    let original_metadata = Metadata::from_account_info(&ctx.accounts.original_metadata)?;
+    NftTransfer {
+        token: original_mint_escrow,
+        token_owner: ctx.accounts.entangler_authority.to_account_info(),
+        destination: ctx.accounts.original_mint_account.to_account_info(),
+        destination_owner: ctx.accounts.signer.to_account_info(),
+        mint: ctx.accounts.original_mint.to_account_info(),
+        metadata: ctx.accounts.original_metadata.to_account_info(),
+        authority: ctx.accounts.entangler_authority.to_account_info(),
+        payer: ctx.accounts.signer.to_account_info(),
+        token_program: ctx.accounts.original_token_program.to_account_info(),
+        associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        metadata_program: ctx.accounts.metadata_program.to_account_info(),
+        hook_accounts: ctx.remaining_accounts.to_vec(),
+        edition: optional_info(&ctx.accounts.original_master_edition),
+        owner_token_record: optional_info(&ctx.accounts.original_mint_escrow_record),
+        destination_token_record: optional_info(&ctx.accounts.original_mint_account_record),
+        sysvar_instructions: optional_info(&ctx.accounts.sysvar_instructions),
+        authorization_rules_program: optional_info(&ctx.accounts.authorization_rules_program),
+        authorization_rules: optional_info(&ctx.accounts.authorization_rules),
+    }
+    .invoke_signed(is_programmable(&original_metadata), authority_signer_seeds)?;
+
+    Ok(())
+}
+
+/// Thaws the original frozen in its owner's account, and hands it over to the signer
+/// when they are not that owner
+fn release_original<'info>(
+    ctx: &Context<'_, '_, '_, 'info, Disentangle<'info>>,
+    frozen_account: Pubkey,
+    authority_signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let Some(token) = optional_info(&ctx.accounts.frozen_account) else {
+        return err!(EntanglerError::InvalidFrozenAccount);
+    };
+    require_keys_eq!(
+        token.key(),
+        frozen_account,
+        EntanglerError::InvalidFrozenAccount
     );
-    token::transfer(transfer_ctx, 1)?;
+    let Some(edition) = optional_info(&ctx.accounts.original_master_edition) else {
+        return err!(EntanglerError::MissingOriginalEdition);
+    };
+
+    DelegatedFreeze {
+        delegate: ctx.accounts.entangler_authority.to_account_info(),
+        token: token.clone(),
+        edition,
+        mint: ctx.accounts.original_mint.to_account_info(),
+        token_program: ctx.accounts.original_token_program.to_account_info(),
+        metadata_program: ctx.accounts.metadata_program.to_account_info(),
+    }
+    .thaw(authority_signer_seeds)?;
+
+    let owner = token::TokenAccount::try_deserialize(&mut &token.try_borrow_data()?[..])?.owner;
+    if owner == ctx.accounts.signer.key() {
+        // Give the owner back full control over the original
+        token_interface::revoke(CpiContext::new(
+            ctx.accounts.original_token_program.to_account_info(),
+            token_interface::Revoke {
+                source: token,
+                authority: ctx.accounts.signer.to_account_info(),
+            },
+        ))?;
+    } else {
+        // Transfer as the delegate, which ends the delegation
+        associated_token::create_idempotent(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.signer.to_account_info(),
+                associated_token: ctx.accounts.original_mint_account.to_account_info(),
+                authority: ctx.accounts.signer.to_account_info(),
+                mint: ctx.accounts.original_mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.original_token_program.to_account_info(),
+            },
+        ))?;
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.original_token_program.to_account_info(),
+                token_interface::TransferChecked {
+                    from: token,
+                    mint: ctx.accounts.original_mint.to_account_info(),
+                    to: ctx.accounts.original_mint_account.to_account_info(),
+                    authority: ctx.accounts.entangler_authority.to_account_info(),
+                },
+                authority_signer_seeds,
+            ),
+            1,
+            0,
+        )?;
+    }
 
     Ok(())
 }
@@ -44,8 +176,7 @@ pub struct Disentangle<'info> {
     pub signer: Signer<'info>,
 
     #[account(
-      seeds = [AUTHORITY_SEED.as_bytes()],
-      bump,
+      address = entangled_collection.authority().key @ EntanglerError::InvalidAuthority,
     )]
     /// CHECK: Safe because this read-only account only gets used as a constraint
     pub entangler_authority: UncheckedAccount<'info>,
@@ -61,39 +192,88 @@ pub struct Disentangle<'info> {
     )]
     pub entangled_collection: Account<'info, EntangledCollection>,
 
+    #[account(
+        mut,
+        seeds = [
+            ENTANGLED_PAIR_SEED.as_bytes(),
+            &entangled_mint.key().to_bytes(),
+        ],
+        bump
+    )]
+    pub entangled_pair: Account<'info, EntangledPair>,
+
     pub entangled_collection_mint: Account<'info, Mint>,
 
     /// CHECK: Using constraints
     #[account(
-        address = mpl_token_metadata::pda::find_metadata_account(&entangled_collection_mint.key()).0,
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidMetadata,
         constraint = mpl_token_metadata::check_id(entangled_collection_metadata.owner),
       )]
     pub entangled_collection_metadata: UncheckedAccount<'info>,
 
-    #[account(mut)]
-    pub original_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        mint::token_program = original_token_program,
+    )]
+    pub original_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
     /// CHECK: Using constraints
     #[account(
-        address = mpl_token_metadata::pda::find_metadata_account(&original_mint.key()).0,
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&original_mint.key()).0 @ EntanglerError::InvalidMetadata,
         constraint = mpl_token_metadata::check_id(original_metadata.owner),
       )]
     pub original_metadata: UncheckedAccount<'info>,
 
+    /// The edition of the original, for programmable originals and non-custodial collections
+    /// CHECK: Using constraints
     #[account(
-        init_if_needed,
-        payer = signer,
-        associated_token::mint = original_mint,
-        associated_token::authority = signer,
+        address = mpl_token_metadata::pda::find_master_edition_account(&original_mint.key()).0 @ EntanglerError::InvalidEdition,
     )]
-    pub original_mint_account: Box<Account<'info, TokenAccount>>,
+    pub original_master_edition: Option<UncheckedAccount<'info>>,
 
+    /// The rule set of the original, for programmable originals
+    /// CHECK: Done by MPL
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Created in the instruction, using constraints
     #[account(
         mut,
-        associated_token::mint = original_mint,
-        associated_token::authority = entangler_authority,
+        address = get_associated_token_address_with_program_id(&signer.key(), &original_mint.key(), &original_token_program.key()),
     )]
-    pub original_mint_escrow: Box<Account<'info, TokenAccount>>,
+    pub original_mint_account: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&original_mint.key(), &original_mint_account.key()).0,
+    )]
+    pub original_mint_account_record: Option<UncheckedAccount<'info>>,
+
+    /// The escrow of the original, unless it is frozen in an account
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &original_mint.key(), &original_token_program.key()),
+        token::mint = original_mint,
+        token::authority = entangler_authority,
+        token::token_program = original_token_program,
+    )]
+    pub original_mint_escrow: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(
+            &original_mint.key(),
+            &get_associated_token_address_with_program_id(&entangler_authority.key(), &original_mint.key(), &original_token_program.key()),
+        ).0,
+    )]
+    pub original_mint_escrow_record: Option<UncheckedAccount<'info>>,
+
+    /// The token account the original is frozen in, for non-custodial collections
+    /// CHECK: Checked against the pair
+    #[account(mut)]
+    pub frozen_account: Option<UncheckedAccount<'info>>,
 
     #[account(
         seeds = [
@@ -102,27 +282,62 @@ pub struct Disentangle<'info> {
           &original_mint.key().to_bytes()
         ],
         bump,
+        mint::token_program = token_program,
     )]
-    pub entangled_mint: Account<'info, Mint>,
+    pub entangled_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// The master edition of the entangled token, for programmable collections
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_master_edition_account(&entangled_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub entangled_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// The rule set of the entangled token, for programmable collections
+    /// CHECK: Using constraints
+    #[account(
+        constraint = entangled_collection.rule_set == Some(entangled_authorization_rules.key()) @ EntanglerError::InvalidRuleSet,
+    )]
+    pub entangled_authorization_rules: Option<UncheckedAccount<'info>>,
 
     #[account(
         mut,
-        associated_token::mint = entangled_mint,
-        associated_token::authority = signer,
+        token::mint = entangled_mint,
+        token::authority = signer,
+        token::token_program = token_program,
         constraint = entangled_mint_account.amount == 1,
     )]
-    pub entangled_mint_account: Box<Account<'info, TokenAccount>>,
+    pub entangled_mint_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
     /// CHECK: Using constraints
-    #[account(mut)]
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&entangled_mint.key(), &entangled_mint_account.key()).0,
+    )]
+    pub entangled_mint_account_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_mint.key()).0 @ EntanglerError::InvalidMetadata,
+    )]
     pub entangled_metadata: UncheckedAccount<'info>,
 
     #[account(
         mut,
-        associated_token::mint = entangled_mint,
-        associated_token::authority = entangler_authority,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &entangled_mint.key(), &token_program.key()),
+        token::mint = entangled_mint,
+        token::authority = entangler_authority,
+        token::token_program = token_program,
     )]
-    pub entangled_mint_escrow: Box<Account<'info, TokenAccount>>,
+    pub entangled_mint_escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&entangled_mint.key(), &entangled_mint_escrow.key()).0,
+    )]
+    pub entangled_mint_escrow_record: Option<UncheckedAccount<'info>>,
 
     /// Common Solana programs
     /// CHECK: CPI
@@ -130,7 +345,21 @@ pub struct Disentangle<'info> {
         address = mpl_token_metadata::ID
     )]
     pub metadata_program: UncheckedAccount<'info>,
-    pub token_program: Program<'info, Token>,
+    /// CHECK: CPI
+    #[account(
+        address = mpl_token_auth_rules::ID
+    )]
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: Sysvar
+    #[account(
+        address = sysvar::instructions::ID
+    )]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+    pub original_token_program: Interface<'info, TokenInterface>,
+    #[account(
+        address = entangled_collection.entangled_token_program() @ EntanglerError::InvalidEntangledTokenProgram,
+    )]
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
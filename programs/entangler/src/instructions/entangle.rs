@@ -1,45 +1,212 @@
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::{
+    self, get_associated_token_address_with_program_id, AssociatedToken,
+};
+use anchor_spl::token::{self, Mint};
+use anchor_spl::token_interface::{self, TokenInterface};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
 
-use crate::seeds::{AUTHORITY_SEED, COLLECTION_SEED, ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED};
+use crate::errors::EntanglerError;
+use crate::seeds::{COLLECTION_SEED, ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED};
 use crate::state::{EntangledCollection, EntangledPair};
+use crate::utils::{
+    check_transferable, is_programmable, optional_info, DelegatedFreeze, NftTransfer,
+};
 
-pub fn entangle(ctx: Context<Entangle>) -> Result<()> {
+pub fn entangle<'info>(ctx: Context<'_, '_, '_, 'info, Entangle<'info>>) -> Result<()> {
     msg!("Entangle");
 
+    let authority = ctx.accounts.entangled_collection.authority();
+    let authority_seeds = authority.seeds();
+    let authority_signer_seeds = &[&authority_seeds[..]];
+
+    let frozen_account = EntangleSwap {
+        signer: ctx.accounts.signer.to_account_info(),
+        entangler_authority: ctx.accounts.entangler_authority.to_account_info(),
+        original_mint: ctx.accounts.original_mint.to_account_info(),
+        original_metadata: ctx.accounts.original_metadata.to_account_info(),
+        original_master_edition: optional_info(&ctx.accounts.original_master_edition),
+        authorization_rules: optional_info(&ctx.accounts.authorization_rules),
+        original_mint_account: ctx.accounts.original_mint_account.to_account_info(),
+        original_mint_account_record: optional_info(&ctx.accounts.original_mint_account_record),
+        original_mint_escrow: ctx
+            .accounts
+            .original_mint_escrow
+            .as_ref()
+            .map(|escrow| escrow.to_account_info()),
+        original_mint_escrow_record: optional_info(&ctx.accounts.original_mint_escrow_record),
+        entangled_mint: ctx.accounts.entangled_mint.to_account_info(),
+        entangled_metadata: ctx.accounts.entangled_metadata.to_account_info(),
+        entangled_master_edition: optional_info(&ctx.accounts.entangled_master_edition),
+        entangled_authorization_rules: optional_info(&ctx.accounts.entangled_authorization_rules),
+        entangled_mint_account: ctx.accounts.entangled_mint_account.to_account_info(),
+        entangled_mint_account_record: optional_info(&ctx.accounts.entangled_mint_account_record),
+        entangled_mint_escrow: ctx.accounts.entangled_mint_escrow.to_account_info(),
+        entangled_mint_escrow_record: optional_info(&ctx.accounts.entangled_mint_escrow_record),
+        metadata_program: ctx.accounts.metadata_program.to_account_info(),
+        authorization_rules_program: optional_info(&ctx.accounts.authorization_rules_program),
+        sysvar_instructions: optional_info(&ctx.accounts.sysvar_instructions),
+        original_token_program: ctx.accounts.original_token_program.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        hook_accounts: ctx.remaining_accounts.to_vec(),
+    }
+    .invoke_signed(&ctx.accounts.entangled_collection, authority_signer_seeds)?;
+
     let pair = &mut ctx.accounts.entangled_pair;
     pair.original_mint = ctx.accounts.original_mint.key();
     pair.entangled_mint = ctx.accounts.entangled_mint.key();
+    pair.frozen_account = frozen_account;
+    pair.migrated = ctx.accounts.entangled_collection.scoped_authority;
 
-    // Transfer the original token to an escrow
-    let original_transfer_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            authority: ctx.accounts.signer.to_account_info(),
-            from: ctx.accounts.original_mint_account.to_account_info(),
-            to: ctx.accounts.original_mint_escrow.to_account_info(),
-        },
-    );
-    token::transfer(original_transfer_ctx, 1)?;
-
-    let authority_bump = *ctx.bumps.get("entangler_authority").unwrap();
-    let authority_seeds = &[AUTHORITY_SEED.as_bytes(), &[authority_bump]];
-    let authority_signer_seeds = &[&authority_seeds[..]];
+    Ok(())
+}
 
-    // Transfer from the escrow
-    let transfer_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            authority: ctx.accounts.entangler_authority.to_account_info(),
-            from: ctx.accounts.entangled_mint_escrow.to_account_info(),
-            to: ctx.accounts.entangled_mint_account.to_account_info(),
-        },
-        authority_signer_seeds,
-    );
-    token::transfer(transfer_ctx, 1)?;
+/// The accounts needed to swap an original for its entangled token
+pub struct EntangleSwap<'info> {
+    pub signer: AccountInfo<'info>,
+    pub entangler_authority: AccountInfo<'info>,
+    pub original_mint: AccountInfo<'info>,
+    pub original_metadata: AccountInfo<'info>,
+    pub original_master_edition: Option<AccountInfo<'info>>,
+    pub authorization_rules: Option<AccountInfo<'info>>,
+    pub original_mint_account: AccountInfo<'info>,
+    pub original_mint_account_record: Option<AccountInfo<'info>>,
+    pub original_mint_escrow: Option<AccountInfo<'info>>,
+    pub original_mint_escrow_record: Option<AccountInfo<'info>>,
+    pub entangled_mint: AccountInfo<'info>,
+    pub entangled_metadata: AccountInfo<'info>,
+    pub entangled_master_edition: Option<AccountInfo<'info>>,
+    pub entangled_authorization_rules: Option<AccountInfo<'info>>,
+    pub entangled_mint_account: AccountInfo<'info>,
+    pub entangled_mint_account_record: Option<AccountInfo<'info>>,
+    pub entangled_mint_escrow: AccountInfo<'info>,
+    pub entangled_mint_escrow_record: Option<AccountInfo<'info>>,
+    pub metadata_program: AccountInfo<'info>,
+    pub authorization_rules_program: Option<AccountInfo<'info>>,
+    pub sysvar_instructions: Option<AccountInfo<'info>>,
+    pub original_token_program: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
 
-    Ok(())
+    /// Extra accounts required by Token-2022 transfer hooks
+    pub hook_accounts: Vec<AccountInfo<'info>>,
+}
+
+impl<'info> EntangleSwap<'info> {
+    /// Takes the original and gives the entangled token to the signer.
+    /// Returns the account the original is frozen in, for non-custodial collections
+    pub fn invoke_signed(
+        self,
+        entangled_collection: &EntangledCollection,
+        authority_signer_seeds: &[&[&[u8]]],
+    ) -> Result<Option<Pubkey>> {
+        check_transferable(&self.original_mint)?;
+        let original_metadata = Metadata::from_account_info(&self.original_metadata)?;
+        let mut frozen_account = None;
+        if entangled_collection.non_custodial {
+            // Freeze the original token in the owner's account
+            require!(
+                !is_programmable(&original_metadata)
+                    && self.original_token_program.key() == token::ID,
+                EntanglerError::NonCustodialOriginal
+            );
+            let Some(edition) = self.original_master_edition.clone() else {
+                return err!(EntanglerError::MissingOriginalEdition);
+            };
+            token_interface::approve(
+                CpiContext::new(
+                    self.original_token_program.clone(),
+                    token_interface::Approve {
+                        to: self.original_mint_account.clone(),
+                        delegate: self.entangler_authority.clone(),
+                        authority: self.signer.clone(),
+                    },
+                ),
+                1,
+            )?;
+            DelegatedFreeze {
+                delegate: self.entangler_authority.clone(),
+                token: self.original_mint_account.clone(),
+                edition,
+                mint: self.original_mint.clone(),
+                token_program: self.original_token_program.clone(),
+                metadata_program: self.metadata_program.clone(),
+            }
+            .freeze(authority_signer_seeds)?;
+            frozen_account = Some(self.original_mint_account.key());
+        } else {
+            // Transfer the original token to an escrow
+            let Some(original_mint_escrow) = self.original_mint_escrow.clone() else {
+                return err!(EntanglerError::MissingOriginalEscrow);
+            };
+            NftTransfer {
+                token: self.original_mint_account.clone(),
+                token_owner: self.signer.clone(),
+                destination: original_mint_escrow,
+                destination_owner: self.entangler_authority.clone(),
+                mint: self.original_mint.clone(),
+                metadata: self.original_metadata.clone(),
+                authority: self.signer.clone(),
+                payer: self.signer.clone(),
+                token_program: self.original_token_program.clone(),
+                associated_token_program: self.associated_token_program.clone(),
+                system_program: self.system_program.clone(),
+                metadata_program: self.metadata_program.clone(),
+                hook_accounts: self.hook_accounts.clone(),
+                edition: self.original_master_edition.clone(),
+                owner_token_record: self.original_mint_account_record.clone(),
+                destination_token_record: self.original_mint_escrow_record.clone(),
+                sysvar_instructions: self.sysvar_instructions.clone(),
+                authorization_rules_program: self.authorization_rules_program.clone(),
+                authorization_rules: self.authorization_rules.clone(),
+            }
+            .invoke_signed(is_programmable(&original_metadata), &[])?;
+        }
+
+        // Transfer from the escrow
+        associated_token::create_idempotent(CpiContext::new(
+            self.associated_token_program.clone(),
+            associated_token::Create {
+                payer: self.signer.clone(),
+                associated_token: self.entangled_mint_account.clone(),
+                authority: self.signer.clone(),
+                mint: self.entangled_mint.clone(),
+                system_program: self.system_program.clone(),
+                token_program: self.token_program.clone(),
+            },
+        ))?;
+        NftTransfer {
+            token: self.entangled_mint_escrow,
+            token_owner: self.entangler_authority.clone(),
+            destination: self.entangled_mint_account,
+            destination_owner: self.signer.clone(),
+            mint: self.entangled_mint,
+            metadata: self.entangled_metadata,
+            authority: self.entangler_authority,
+            payer: self.signer,
+            token_program: self.token_program,
+            associated_token_program: self.associated_token_program,
+            system_program: self.system_program,
+            metadata_program: self.metadata_program,
+            hook_accounts: self.hook_accounts,
+            edition: self.entangled_master_edition,
+            owner_token_record: self.entangled_mint_escrow_record,
+            destination_token_record: self.entangled_mint_account_record,
+            sysvar_instructions: self.sysvar_instructions,
+            authorization_rules_program: self.authorization_rules_program,
+            authorization_rules: self.entangled_authorization_rules,
+        }
+        .invoke_signed(
+            entangled_collection.is_programmable(),
+            authority_signer_seeds,
+        )?;
+
+        Ok(frozen_account)
+    }
 }
 
 #[derive(Accounts)]
@@ -48,8 +215,7 @@ pub struct Entangle<'info> {
     pub signer: Signer<'info>,
 
     #[account(
-      seeds = [AUTHORITY_SEED.as_bytes()],
-      bump,
+      address = entangled_collection.authority().key @ EntanglerError::InvalidAuthority,
     )]
     /// CHECK: Safe because this read-only account only gets used as a constraint
     pub entangler_authority: UncheckedAccount<'info>,
@@ -80,35 +246,71 @@ pub struct Entangle<'info> {
 
     /// CHECK: Using constraints
     #[account(
-        address = mpl_token_metadata::pda::find_metadata_account(&entangled_collection_mint.key()).0,
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidMetadata,
         constraint = mpl_token_metadata::check_id(entangled_collection_metadata.owner),
       )]
     pub entangled_collection_metadata: UncheckedAccount<'info>,
 
-    #[account(mut)]
-    pub original_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        mint::token_program = original_token_program,
+    )]
+    pub original_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
     /// CHECK: Using constraints
     #[account(
-        address = mpl_token_metadata::pda::find_metadata_account(&original_mint.key()).0,
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&original_mint.key()).0 @ EntanglerError::InvalidMetadata,
         constraint = mpl_token_metadata::check_id(original_metadata.owner),
       )]
     pub original_metadata: UncheckedAccount<'info>,
 
+    /// The edition of the original, for programmable originals and non-custodial collections
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_master_edition_account(&original_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub original_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// The rule set of the original, for programmable originals
+    /// CHECK: Done by MPL
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
     #[account(
         mut,
-        associated_token::mint = original_mint,
-        associated_token::authority = signer,
+        token::mint = original_mint,
+        token::authority = signer,
+        token::token_program = original_token_program,
         constraint = original_mint_account.amount == 1,
     )]
-    pub original_mint_account: Box<Account<'info, TokenAccount>>,
+    pub original_mint_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&original_mint.key(), &original_mint_account.key()).0,
+    )]
+    pub original_mint_account_record: Option<UncheckedAccount<'info>>,
+
+    /// The escrow of the original, for custodial collections
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &original_mint.key(), &original_token_program.key()),
+        token::mint = original_mint,
+        token::authority = entangler_authority,
+        token::token_program = original_token_program,
+    )]
+    pub original_mint_escrow: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
 
+    /// CHECK: Using constraints
     #[account(
         mut,
-        associated_token::mint = original_mint,
-        associated_token::authority = entangler_authority,
+        address = mpl_token_metadata::pda::find_token_record_account(
+            &original_mint.key(),
+            &get_associated_token_address_with_program_id(&entangler_authority.key(), &original_mint.key(), &original_token_program.key()),
+        ).0,
     )]
-    pub original_mint_escrow: Box<Account<'info, TokenAccount>>,
+    pub original_mint_escrow_record: Option<UncheckedAccount<'info>>,
 
     #[account(
         seeds = [
@@ -117,27 +319,60 @@ pub struct Entangle<'info> {
           &original_mint.key().to_bytes()
         ],
         bump,
+        mint::token_program = token_program,
     )]
-    pub entangled_mint: Account<'info, Mint>,
+    pub entangled_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
+    /// The master edition of the entangled token, for programmable collections
+    /// CHECK: Using constraints
     #[account(
-        init_if_needed,
-        payer = signer,
-        associated_token::mint = entangled_mint,
-        associated_token::authority = signer,
+        address = mpl_token_metadata::pda::find_master_edition_account(&entangled_mint.key()).0 @ EntanglerError::InvalidEdition,
     )]
-    pub entangled_mint_account: Box<Account<'info, TokenAccount>>,
+    pub entangled_master_edition: Option<UncheckedAccount<'info>>,
 
+    /// The rule set of the entangled token, for programmable collections
     /// CHECK: Using constraints
-    #[account(mut)]
+    #[account(
+        constraint = entangled_collection.rule_set == Some(entangled_authorization_rules.key()) @ EntanglerError::InvalidRuleSet,
+    )]
+    pub entangled_authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Created in the instruction, using constraints
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&signer.key(), &entangled_mint.key(), &token_program.key()),
+    )]
+    pub entangled_mint_account: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&entangled_mint.key(), &entangled_mint_account.key()).0,
+    )]
+    pub entangled_mint_account_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_mint.key()).0 @ EntanglerError::InvalidMetadata,
+    )]
     pub entangled_metadata: UncheckedAccount<'info>,
 
     #[account(
         mut,
-        associated_token::mint = entangled_mint,
-        associated_token::authority = entangler_authority,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &entangled_mint.key(), &token_program.key()),
+        token::mint = entangled_mint,
+        token::authority = entangler_authority,
+        token::token_program = token_program,
+    )]
+    pub entangled_mint_escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&entangled_mint.key(), &entangled_mint_escrow.key()).0,
     )]
-    pub entangled_mint_escrow: Box<Account<'info, TokenAccount>>,
+    pub entangled_mint_escrow_record: Option<UncheckedAccount<'info>>,
 
     /// Common Solana programs
     /// CHECK: CPI
@@ -145,7 +380,21 @@ pub struct Entangle<'info> {
         address = mpl_token_metadata::ID
     )]
     pub metadata_program: UncheckedAccount<'info>,
-    pub token_program: Program<'info, Token>,
+    /// CHECK: CPI
+    #[account(
+        address = mpl_token_auth_rules::ID
+    )]
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: Sysvar
+    #[account(
+        address = sysvar::instructions::ID
+    )]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+    pub original_token_program: Interface<'info, TokenInterface>,
+    #[account(
+        address = entangled_collection.entangled_token_program() @ EntanglerError::InvalidEntangledTokenProgram,
+    )]
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
@@ -0,0 +1,53 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anchor_lang::prelude::*;
+
+use crate::errors::EntanglerError;
+use crate::instructions::{entangle, Entangle};
+
+pub fn entangle_batch<'info>(
+    ctx: Context<'_, '_, '_, 'info, EntangleBatch<'info>>,
+    hook_accounts: Vec<u8>,
+) -> Result<()> {
+    msg!("Entangle batch");
+
+    let mut accounts = ctx.remaining_accounts;
+    require!(!hook_accounts.is_empty(), EntanglerError::EmptyBatch);
+
+    // Every group goes through the same checks as a single `entangle`, and is followed
+    // by the transfer hook accounts of its own swap
+    for hook_len in hook_accounts {
+        let mut bumps = BTreeMap::new();
+        let mut group = Entangle::try_accounts(
+            ctx.program_id,
+            &mut accounts,
+            &[],
+            &mut bumps,
+            &mut BTreeSet::new(),
+        )?;
+        require_keys_eq!(
+            group.signer.key(),
+            ctx.accounts.signer.key(),
+            EntanglerError::InvalidBatchSigner
+        );
+        require_gte!(
+            accounts.len(),
+            hook_len as usize,
+            EntanglerError::MalformedBatch
+        );
+        let (hooks, rest) = accounts.split_at(hook_len as usize);
+        accounts = rest;
+
+        entangle(Context::new(ctx.program_id, &mut group, hooks, bumps))?;
+        group.exit(ctx.program_id)?;
+    }
+    require!(accounts.is_empty(), EntanglerError::MalformedBatch);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct EntangleBatch<'info> {
+    /// Must sign every group of the batch
+    pub signer: Signer<'info>,
+}
@@ -0,0 +1,252 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::{
+    self, get_associated_token_address_with_program_id, AssociatedToken,
+};
+use anchor_spl::token::Mint;
+use anchor_spl::token_interface::{self, TokenInterface};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use mpl_bubblegum::utils::get_asset_id;
+
+use crate::errors::EntanglerError;
+use crate::seeds::{COLLECTION_SEED, ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED};
+use crate::state::{EntangledCollection, EntangledPair};
+use crate::utils::{compressed_metadata, optional_info, CompressedTransfer, NftTransfer};
+
+pub fn entangle_compressed<'info>(
+    ctx: Context<'_, '_, '_, 'info, EntangleCompressed<'info>>,
+    root: [u8; 32],
+    metadata: Vec<u8>,
+    nonce: u64,
+    index: u32,
+    proof_len: u8,
+) -> Result<()> {
+    msg!("Entangle compressed");
+
+    let asset_id = get_asset_id(ctx.accounts.merkle_tree.key, nonce);
+    let pair = &mut ctx.accounts.entangled_pair;
+    pair.original_mint = asset_id;
+    pair.entangled_mint = ctx.accounts.entangled_mint.key();
+    pair.frozen_account = None;
+    pair.migrated = ctx.accounts.entangled_collection.scoped_authority;
+
+    // Transfer the original leaf to the authority
+    let proof_len = (proof_len as usize).min(ctx.remaining_accounts.len());
+    let (proof, hook_accounts) = ctx.remaining_accounts.split_at(proof_len);
+    let original_metadata = compressed_metadata(&metadata)?;
+    CompressedTransfer {
+        tree_config: ctx.accounts.tree_config.to_account_info(),
+        leaf_owner: ctx.accounts.signer.to_account_info(),
+        leaf_delegate: ctx.accounts.leaf_delegate.to_account_info(),
+        new_leaf_owner: ctx.accounts.entangler_authority.to_account_info(),
+        merkle_tree: ctx.accounts.merkle_tree.to_account_info(),
+        log_wrapper: ctx.accounts.log_wrapper.to_account_info(),
+        compression_program: ctx.accounts.compression_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        bubblegum_program: ctx.accounts.bubblegum_program.to_account_info(),
+        proof: proof.to_vec(),
+    }
+    .invoke_signed(root, &original_metadata, nonce, index, &[])?;
+
+    let authority = ctx.accounts.entangled_collection.authority();
+    let authority_seeds = authority.seeds();
+    let authority_signer_seeds = &[&authority_seeds[..]];
+
+    // Transfer from the escrow
+    associated_token::create_idempotent(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: ctx.accounts.signer.to_account_info(),
+            associated_token: ctx.accounts.entangled_mint_account.to_account_info(),
+            authority: ctx.accounts.signer.to_account_info(),
+            mint: ctx.accounts.entangled_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+    ))?;
+    NftTransfer {
+        token: ctx.accounts.entangled_mint_escrow.to_account_info(),
+        token_owner: ctx.accounts.entangler_authority.to_account_info(),
+        destination: ctx.accounts.entangled_mint_account.to_account_info(),
+        destination_owner: ctx.accounts.signer.to_account_info(),
+        mint: ctx.accounts.entangled_mint.to_account_info(),
+        metadata: ctx.accounts.entangled_metadata.to_account_info(),
+        authority: ctx.accounts.entangler_authority.to_account_info(),
+        payer: ctx.accounts.signer.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        metadata_program: ctx.accounts.metadata_program.to_account_info(),
+        hook_accounts: hook_accounts.to_vec(),
+        edition: optional_info(&ctx.accounts.entangled_master_edition),
+        owner_token_record: optional_info(&ctx.accounts.entangled_mint_escrow_record),
+        destination_token_record: optional_info(&ctx.accounts.entangled_mint_account_record),
+        sysvar_instructions: optional_info(&ctx.accounts.sysvar_instructions),
+        authorization_rules_program: optional_info(&ctx.accounts.authorization_rules_program),
+        authorization_rules: optional_info(&ctx.accounts.entangled_authorization_rules),
+    }
+    .invoke_signed(
+        ctx.accounts.entangled_collection.is_programmable(),
+        authority_signer_seeds,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], metadata: Vec<u8>, nonce: u64)]
+pub struct EntangleCompressed<'info> {
+    /// The owner of the original leaf
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+      address = entangled_collection.authority().key @ EntanglerError::InvalidAuthority,
+    )]
+    /// CHECK: Safe because this read-only account only gets used as a constraint
+    pub entangler_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            COLLECTION_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+        ],
+        bump,
+        has_one = entangled_collection_mint,
+    )]
+    pub entangled_collection: Account<'info, EntangledCollection>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = EntangledPair::LEN,
+        seeds = [
+            ENTANGLED_PAIR_SEED.as_bytes(),
+            &entangled_mint.key().to_bytes(),
+        ],
+        bump
+    )]
+    pub entangled_pair: Account<'info, EntangledPair>,
+
+    pub entangled_collection_mint: Account<'info, Mint>,
+
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidMetadata,
+        constraint = mpl_token_metadata::check_id(entangled_collection_metadata.owner),
+      )]
+    pub entangled_collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by Bubblegum
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// The Bubblegum tree holding the original
+    /// CHECK: Checked by Bubblegum
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// The delegate of the original, part of the proven leaf
+    /// CHECK: Checked by Bubblegum
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+          ENTANGLED_MINT_SEED.as_bytes(),
+          &entangled_collection.id.to_bytes(),
+          &get_asset_id(merkle_tree.key, nonce).to_bytes()
+        ],
+        bump,
+        mint::token_program = token_program,
+    )]
+    pub entangled_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// The master edition of the entangled token, for programmable collections
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_master_edition_account(&entangled_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub entangled_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// The rule set of the entangled token, for programmable collections
+    /// CHECK: Using constraints
+    #[account(
+        constraint = entangled_collection.rule_set == Some(entangled_authorization_rules.key()) @ EntanglerError::InvalidRuleSet,
+    )]
+    pub entangled_authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Created in the instruction, using constraints
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&signer.key(), &entangled_mint.key(), &token_program.key()),
+    )]
+    pub entangled_mint_account: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&entangled_mint.key(), &entangled_mint_account.key()).0,
+    )]
+    pub entangled_mint_account_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_mint.key()).0 @ EntanglerError::InvalidMetadata,
+    )]
+    pub entangled_metadata: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &entangled_mint.key(), &token_program.key()),
+        token::mint = entangled_mint,
+        token::authority = entangler_authority,
+        token::token_program = token_program,
+    )]
+    pub entangled_mint_escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&entangled_mint.key(), &entangled_mint_escrow.key()).0,
+    )]
+    pub entangled_mint_escrow_record: Option<UncheckedAccount<'info>>,
+
+    /// Common Solana programs
+    /// CHECK: CPI
+    #[account(
+        address = mpl_token_metadata::ID
+    )]
+    pub metadata_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = mpl_token_auth_rules::ID
+    )]
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: CPI
+    #[account(
+        address = mpl_bubblegum::ID
+    )]
+    pub bubblegum_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = SPL_ACCOUNT_COMPRESSION_ID
+    )]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = SPL_NOOP_ID
+    )]
+    pub log_wrapper: UncheckedAccount<'info>,
+    /// CHECK: Sysvar
+    #[account(
+        address = sysvar::instructions::ID
+    )]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+    #[account(
+        address = entangled_collection.entangled_token_program() @ EntanglerError::InvalidEntangledTokenProgram,
+    )]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
@@ -0,0 +1,227 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token_interface::{self, TokenInterface};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use mpl_bubblegum::utils::get_asset_id;
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+
+use crate::errors::EntanglerError;
+use crate::seeds::{COLLECTION_SEED, ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED};
+use crate::state::{EntangledCollection, EntangledPair};
+use crate::utils::{
+    check_transferable, compressed_metadata, is_programmable, optional_info, CompressedTransfer,
+    NftTransfer,
+};
+
+pub fn entangle_tree_pair<'info>(
+    ctx: Context<'_, '_, '_, 'info, EntangleTreePair<'info>>,
+    root: [u8; 32],
+    metadata: Vec<u8>,
+    nonce: u64,
+    index: u32,
+    proof_len: u8,
+) -> Result<()> {
+    msg!("Entangle tree pair");
+
+    // Transfer the original token to an escrow
+    let proof_len = (proof_len as usize).min(ctx.remaining_accounts.len());
+    let (proof, hook_accounts) = ctx.remaining_accounts.split_at(proof_len);
+    check_transferable(&ctx.accounts.original_mint.to_account_info())?;
+    let original_metadata = Metadata::from_account_info(&ctx.accounts.original_metadata)?;
+    NftTransfer {
+        token: ctx.accounts.original_mint_account.to_account_info(),
+        token_owner: ctx.accounts.signer.to_account_info(),
+        destination: ctx.accounts.original_mint_escrow.to_account_info(),
+        destination_owner: ctx.accounts.entangler_authority.to_account_info(),
+        mint: ctx.accounts.original_mint.to_account_info(),
+        metadata: ctx.accounts.original_metadata.to_account_info(),
+        authority: ctx.accounts.signer.to_account_info(),
+        payer: ctx.accounts.signer.to_account_info(),
+        token_program: ctx.accounts.original_token_program.to_account_info(),
+        associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        metadata_program: ctx.accounts.metadata_program.to_account_info(),
+        hook_accounts: hook_accounts.to_vec(),
+        edition: optional_info(&ctx.accounts.original_master_edition),
+        owner_token_record: optional_info(&ctx.accounts.original_mint_account_record),
+        destination_token_record: optional_info(&ctx.accounts.original_mint_escrow_record),
+        sysvar_instructions: optional_info(&ctx.accounts.sysvar_instructions),
+        authorization_rules_program: optional_info(&ctx.accounts.authorization_rules_program),
+        authorization_rules: optional_info(&ctx.accounts.authorization_rules),
+    }
+    .invoke_signed(is_programmable(&original_metadata), &[])?;
+
+    let authority = ctx.accounts.entangled_collection.authority();
+    let authority_seeds = authority.seeds();
+    let authority_signer_seeds = &[&authority_seeds[..]];
+
+    // Transfer the entangled leaf from the authority
+    let entangled_metadata = compressed_metadata(&metadata)?;
+    CompressedTransfer {
+        tree_config: ctx.accounts.tree_config.to_account_info(),
+        leaf_owner: ctx.accounts.entangler_authority.to_account_info(),
+        leaf_delegate: ctx.accounts.entangler_authority.to_account_info(),
+        new_leaf_owner: ctx.accounts.signer.to_account_info(),
+        merkle_tree: ctx.accounts.merkle_tree.to_account_info(),
+        log_wrapper: ctx.accounts.log_wrapper.to_account_info(),
+        compression_program: ctx.accounts.compression_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        bubblegum_program: ctx.accounts.bubblegum_program.to_account_info(),
+        proof: proof.to_vec(),
+    }
+    .invoke_signed(
+        root,
+        &entangled_metadata,
+        nonce,
+        index,
+        authority_signer_seeds,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], metadata: Vec<u8>, nonce: u64)]
+pub struct EntangleTreePair<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+      address = entangled_collection.authority().key @ EntanglerError::InvalidAuthority,
+    )]
+    /// CHECK: Safe because this read-only account only gets used as a constraint
+    pub entangler_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            COLLECTION_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+        ],
+        bump,
+        constraint = entangled_collection.entangled_tree == Some(merkle_tree.key()) @ EntanglerError::InvalidEntangledTree,
+    )]
+    pub entangled_collection: Box<Account<'info, EntangledCollection>>,
+
+    #[account(
+        seeds = [
+            ENTANGLED_PAIR_SEED.as_bytes(),
+            &entangled_mint.key().to_bytes(),
+        ],
+        bump,
+        constraint = entangled_pair.entangled_mint == get_asset_id(merkle_tree.key, nonce) @ EntanglerError::InvalidEntangledAsset,
+    )]
+    pub entangled_pair: Box<Account<'info, EntangledPair>>,
+
+    #[account(
+        mut,
+        mint::token_program = original_token_program,
+    )]
+    pub original_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&original_mint.key()).0 @ EntanglerError::InvalidMetadata,
+        constraint = mpl_token_metadata::check_id(original_metadata.owner),
+      )]
+    pub original_metadata: UncheckedAccount<'info>,
+
+    /// The master edition of the original, for programmable originals
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_master_edition_account(&original_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub original_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// The rule set of the original, for programmable originals
+    /// CHECK: Done by MPL
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        token::mint = original_mint,
+        token::authority = signer,
+        token::token_program = original_token_program,
+        constraint = original_mint_account.amount == 1,
+    )]
+    pub original_mint_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&original_mint.key(), &original_mint_account.key()).0,
+    )]
+    pub original_mint_account_record: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &original_mint.key(), &original_token_program.key()),
+        token::mint = original_mint,
+        token::authority = entangler_authority,
+        token::token_program = original_token_program,
+    )]
+    pub original_mint_escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&original_mint.key(), &original_mint_escrow.key()).0,
+    )]
+    pub original_mint_escrow_record: Option<UncheckedAccount<'info>>,
+
+    /// The address an SPL entangled mint would have, keying the pair
+    /// CHECK: Using constraints
+    #[account(
+        seeds = [
+          ENTANGLED_MINT_SEED.as_bytes(),
+          &entangled_collection.id.to_bytes(),
+          &original_mint.key().to_bytes()
+        ],
+        bump,
+    )]
+    pub entangled_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by Bubblegum
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// The collection's entangled tree
+    /// CHECK: Using constraints
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// Common Solana programs
+    /// CHECK: CPI
+    #[account(
+        address = mpl_token_metadata::ID
+    )]
+    pub metadata_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = mpl_token_auth_rules::ID
+    )]
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: CPI
+    #[account(
+        address = mpl_bubblegum::ID
+    )]
+    pub bubblegum_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = SPL_ACCOUNT_COMPRESSION_ID
+    )]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = SPL_NOOP_ID
+    )]
+    pub log_wrapper: UncheckedAccount<'info>,
+    /// CHECK: Sysvar
+    #[account(
+        address = sysvar::instructions::ID
+    )]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+    pub original_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
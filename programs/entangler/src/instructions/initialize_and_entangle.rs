@@ -0,0 +1,339 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::{
+    self, get_associated_token_address_with_program_id, AssociatedToken,
+};
+use anchor_spl::token::Mint;
+use anchor_spl::token_interface::{self, TokenInterface};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+
+use crate::errors::EntanglerError;
+use crate::instructions::{EntangleSwap, EntangledMintCreation, OriginalData};
+use crate::seeds::{
+    COLLECTION_SEED, ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED, ORIGINAL_RECORD_SEED,
+};
+use crate::state::{EntangledCollection, EntangledPair, OriginalRecord};
+use crate::utils::{check_transferable, collection_creator, optional_info, print_edition};
+
+pub fn initialize_and_entangle<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeAndEntangle<'info>>,
+) -> Result<()> {
+    msg!("Init pair and entangle");
+
+    let authority = ctx.accounts.entangled_collection.authority();
+    let authority_seeds = authority.seeds();
+    let authority_signer_seeds = &[&authority_seeds[..]];
+
+    // Create the entangled token on first use
+    if ctx.accounts.entangled_mint.data_is_empty() {
+        check_transferable(&ctx.accounts.original_mint.to_account_info())?;
+        if let Some(original_mint_escrow) = optional_info(&ctx.accounts.original_mint_escrow) {
+            associated_token::create_idempotent(CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: ctx.accounts.signer.to_account_info(),
+                    associated_token: original_mint_escrow,
+                    authority: ctx.accounts.entangler_authority.to_account_info(),
+                    mint: ctx.accounts.original_mint.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.original_token_program.to_account_info(),
+                },
+            ))?;
+        }
+
+        let original_metadata = Metadata::from_account_info(&ctx.accounts.original_metadata)?;
+        ctx.accounts
+            .entangled_collection
+            .check_original(&original_metadata)?;
+        let edition = optional_info(&ctx.accounts.original_edition)
+            .and_then(|original_edition| print_edition(&original_edition))
+            .map(|original_edition| original_edition.edition);
+
+        EntangledMintCreation {
+            signer: ctx.accounts.signer.to_account_info(),
+            creator: ctx.accounts.creator.to_account_info(),
+            entangler_authority: ctx.accounts.entangler_authority.to_account_info(),
+            master_edition: ctx.accounts.master_edition.to_account_info(),
+            entangled_collection: &ctx.accounts.entangled_collection,
+            entangled_collection_mint: ctx.accounts.entangled_collection_mint.to_account_info(),
+            entangled_collection_metadata: ctx
+                .accounts
+                .entangled_collection_metadata
+                .to_account_info(),
+            entangled_mint: ctx.accounts.entangled_mint.to_account_info(),
+            entangled_metadata: ctx.accounts.entangled_metadata.to_account_info(),
+            entangled_master_edition: optional_info(&ctx.accounts.entangled_master_edition),
+            entangled_mint_escrow: ctx.accounts.entangled_mint_escrow.to_account_info(),
+            entangled_mint_escrow_record: optional_info(&ctx.accounts.entangled_mint_escrow_record),
+            metadata_program: ctx.accounts.metadata_program.to_account_info(),
+            sysvar_instructions: optional_info(&ctx.accounts.sysvar_instructions),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+            original: ctx.accounts.original_mint.key(),
+            mint_bump: *ctx.bumps.get("entangled_mint").unwrap(),
+        }
+        .create(
+            &OriginalData::from(original_metadata).with_edition(edition),
+            authority_signer_seeds,
+        )?;
+    }
+
+    let frozen_account = EntangleSwap {
+        signer: ctx.accounts.signer.to_account_info(),
+        entangler_authority: ctx.accounts.entangler_authority.to_account_info(),
+        original_mint: ctx.accounts.original_mint.to_account_info(),
+        original_metadata: ctx.accounts.original_metadata.to_account_info(),
+        original_master_edition: optional_info(&ctx.accounts.original_edition),
+        authorization_rules: optional_info(&ctx.accounts.authorization_rules),
+        original_mint_account: ctx.accounts.original_mint_account.to_account_info(),
+        original_mint_account_record: optional_info(&ctx.accounts.original_mint_account_record),
+        original_mint_escrow: optional_info(&ctx.accounts.original_mint_escrow),
+        original_mint_escrow_record: optional_info(&ctx.accounts.original_mint_escrow_record),
+        entangled_mint: ctx.accounts.entangled_mint.to_account_info(),
+        entangled_metadata: ctx.accounts.entangled_metadata.to_account_info(),
+        entangled_master_edition: optional_info(&ctx.accounts.entangled_master_edition),
+        entangled_authorization_rules: optional_info(&ctx.accounts.entangled_authorization_rules),
+        entangled_mint_account: ctx.accounts.entangled_mint_account.to_account_info(),
+        entangled_mint_account_record: optional_info(&ctx.accounts.entangled_mint_account_record),
+        entangled_mint_escrow: ctx.accounts.entangled_mint_escrow.to_account_info(),
+        entangled_mint_escrow_record: optional_info(&ctx.accounts.entangled_mint_escrow_record),
+        metadata_program: ctx.accounts.metadata_program.to_account_info(),
+        authorization_rules_program: optional_info(&ctx.accounts.authorization_rules_program),
+        sysvar_instructions: optional_info(&ctx.accounts.sysvar_instructions),
+        original_token_program: ctx.accounts.original_token_program.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        hook_accounts: ctx.remaining_accounts.to_vec(),
+    }
+    .invoke_signed(&ctx.accounts.entangled_collection, authority_signer_seeds)?;
+
+    let pair = &mut ctx.accounts.entangled_pair;
+    pair.original_mint = ctx.accounts.original_mint.key();
+    pair.entangled_mint = ctx.accounts.entangled_mint.key();
+    pair.frozen_account = frozen_account;
+    pair.migrated = ctx.accounts.entangled_collection.scoped_authority;
+    ctx.accounts.original_record.original_mint = ctx.accounts.original_mint.key();
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeAndEntangle<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// The creator receiving the collection's royalties
+    /// CHECK: Constraint
+    #[account(
+        constraint = creator.key() == collection_creator(&entangled_collection_metadata)?,
+    )]
+    pub creator: AccountInfo<'info>,
+
+    /// CHECK: Safe because this read-only account only gets used as a constraint
+    #[account(
+        mut,
+        address = entangled_collection.authority().key @ EntanglerError::InvalidAuthority,
+    )]
+    pub entangler_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_master_edition_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub master_edition: AccountInfo<'info>,
+
+    #[account(
+        seeds = [
+            COLLECTION_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+        ],
+        bump,
+        has_one = entangled_collection_mint,
+    )]
+    pub entangled_collection: Box<Account<'info, EntangledCollection>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = EntangledPair::LEN,
+        seeds = [
+            ENTANGLED_PAIR_SEED.as_bytes(),
+            &entangled_mint.key().to_bytes(),
+        ],
+        bump
+    )]
+    pub entangled_pair: Box<Account<'info, EntangledPair>>,
+
+    #[account(mut)]
+    pub entangled_collection_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidMetadata,
+        constraint = mpl_token_metadata::check_id(entangled_collection_metadata.owner),
+    )]
+    pub entangled_collection_metadata: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        mint::token_program = original_token_program,
+    )]
+    pub original_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&original_mint.key()).0 @ EntanglerError::InvalidMetadata,
+        constraint = mpl_token_metadata::check_id(original_metadata.owner),
+    )]
+    pub original_metadata: UncheckedAccount<'info>,
+
+    /// The master or print edition of the original
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_master_edition_account(&original_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub original_edition: Option<UncheckedAccount<'info>>,
+
+    /// The rule set of the original, for programmable originals
+    /// CHECK: Done by MPL
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        token::mint = original_mint,
+        token::authority = signer,
+        token::token_program = original_token_program,
+        constraint = original_mint_account.amount == 1,
+    )]
+    pub original_mint_account: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&original_mint.key(), &original_mint_account.key()).0,
+    )]
+    pub original_mint_account_record: Option<UncheckedAccount<'info>>,
+
+    /// The escrow of the original, for custodial collections
+    /// CHECK: Created on first use, using constraints
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &original_mint.key(), &original_token_program.key()),
+    )]
+    pub original_mint_escrow: Option<UncheckedAccount<'info>>,
+
+    /// Marks the original as backing a pair, created by the first of its pairs
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = OriginalRecord::LEN,
+        seeds = [
+            ORIGINAL_RECORD_SEED.as_bytes(),
+            &original_mint.key().to_bytes(),
+        ],
+        bump
+    )]
+    pub original_record: Box<Account<'info, OriginalRecord>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(
+            &original_mint.key(),
+            &get_associated_token_address_with_program_id(&entangler_authority.key(), &original_mint.key(), &original_token_program.key()),
+        ).0,
+    )]
+    pub original_mint_escrow_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Created on first use, using constraints
+    #[account(
+        mut,
+        seeds = [
+          ENTANGLED_MINT_SEED.as_bytes(),
+          &entangled_collection.id.to_bytes(),
+          &original_mint.key().to_bytes()
+        ],
+        bump,
+    )]
+    pub entangled_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_mint.key()).0 @ EntanglerError::InvalidMetadata,
+    )]
+    pub entangled_metadata: UncheckedAccount<'info>,
+
+    /// The master edition of the entangled token, except for Token-2022 collections
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_master_edition_account(&entangled_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub entangled_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// The rule set of the entangled token, for programmable collections
+    /// CHECK: Using constraints
+    #[account(
+        constraint = entangled_collection.rule_set == Some(entangled_authorization_rules.key()) @ EntanglerError::InvalidRuleSet,
+    )]
+    pub entangled_authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Created in the instruction, using constraints
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&signer.key(), &entangled_mint.key(), &token_program.key()),
+    )]
+    pub entangled_mint_account: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&entangled_mint.key(), &entangled_mint_account.key()).0,
+    )]
+    pub entangled_mint_account_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Created on first use, using constraints
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &entangled_mint.key(), &token_program.key()),
+    )]
+    pub entangled_mint_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&entangled_mint.key(), &entangled_mint_escrow.key()).0,
+    )]
+    pub entangled_mint_escrow_record: Option<UncheckedAccount<'info>>,
+
+    /// Common Solana programs
+    /// CHECK: CPI
+    #[account(
+        address = mpl_token_metadata::ID
+    )]
+    pub metadata_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = mpl_token_auth_rules::ID
+    )]
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: Sysvar
+    #[account(
+        address = sysvar::instructions::ID
+    )]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+    pub original_token_program: Interface<'info, TokenInterface>,
+    #[account(
+        address = entangled_collection.entangled_token_program() @ EntanglerError::InvalidEntangledTokenProgram,
+    )]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
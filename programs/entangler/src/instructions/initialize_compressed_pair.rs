@@ -0,0 +1,226 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::{get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token::Mint;
+use anchor_spl::token_interface::TokenInterface;
+use mpl_bubblegum::instructions::{
+    VerifyLeafCpi, VerifyLeafCpiAccounts, VerifyLeafInstructionArgs,
+};
+use mpl_bubblegum::programs::SPL_ACCOUNT_COMPRESSION_ID;
+use mpl_bubblegum::types::LeafSchema;
+use mpl_bubblegum::utils::get_asset_id;
+
+use crate::errors::EntanglerError;
+use crate::instructions::{EntangledMintCreation, OriginalData};
+use crate::seeds::{COLLECTION_SEED, ENTANGLED_MINT_SEED};
+use crate::state::EntangledCollection;
+use crate::utils::{
+    check_bubblegum_tree, collection_creator, compressed_hashes, compressed_metadata, optional_info,
+};
+
+pub fn initialize_compressed_pair<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeCompressedPair<'info>>,
+    root: [u8; 32],
+    metadata: Vec<u8>,
+    nonce: u64,
+    index: u32,
+) -> Result<()> {
+    msg!("Init compressed pair");
+
+    let authority = ctx.accounts.entangled_collection.authority();
+    let authority_seeds = authority.seeds();
+    let authority_signer_seeds = &[&authority_seeds[..]];
+
+    // Check the original is a genuine member of the collection
+    let original_metadata = compressed_metadata(&metadata)?;
+    ctx.accounts
+        .entangled_collection
+        .check_compressed_original(&original_metadata)?;
+
+    // Prove the metadata belongs to a leaf of a Bubblegum tree
+    check_bubblegum_tree(&ctx.accounts.merkle_tree)?;
+    let asset_id = get_asset_id(ctx.accounts.merkle_tree.key, nonce);
+    let (data_hash, creator_hash) = compressed_hashes(&original_metadata)?;
+    let leaf = LeafSchema::V1 {
+        id: asset_id,
+        owner: ctx.accounts.leaf_owner.key(),
+        delegate: ctx.accounts.leaf_delegate.key(),
+        nonce,
+        data_hash,
+        creator_hash,
+    };
+    let proof = ctx
+        .remaining_accounts
+        .iter()
+        .map(|node| (node, false, false))
+        .collect::<Vec<_>>();
+    VerifyLeafCpi::new(
+        &ctx.accounts.compression_program,
+        VerifyLeafCpiAccounts {
+            merkle_tree: &ctx.accounts.merkle_tree,
+        },
+        VerifyLeafInstructionArgs {
+            root,
+            leaf: leaf.hash(),
+            index,
+        },
+    )
+    .invoke_with_remaining_accounts(&proof)?;
+
+    EntangledMintCreation {
+        signer: ctx.accounts.signer.to_account_info(),
+        creator: ctx.accounts.creator.to_account_info(),
+        entangler_authority: ctx.accounts.entangler_authority.to_account_info(),
+        master_edition: ctx.accounts.master_edition.to_account_info(),
+        entangled_collection: &ctx.accounts.entangled_collection,
+        entangled_collection_mint: ctx.accounts.entangled_collection_mint.to_account_info(),
+        entangled_collection_metadata: ctx.accounts.entangled_collection_metadata.to_account_info(),
+        entangled_mint: ctx.accounts.entangled_mint.to_account_info(),
+        entangled_metadata: ctx.accounts.entangled_metadata.to_account_info(),
+        entangled_master_edition: optional_info(&ctx.accounts.entangled_master_edition),
+        entangled_mint_escrow: ctx.accounts.entangled_mint_escrow.to_account_info(),
+        entangled_mint_escrow_record: optional_info(&ctx.accounts.entangled_mint_escrow_record),
+        metadata_program: ctx.accounts.metadata_program.to_account_info(),
+        sysvar_instructions: optional_info(&ctx.accounts.sysvar_instructions),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+        original: asset_id,
+        mint_bump: *ctx.bumps.get("entangled_mint").unwrap(),
+    }
+    .create(
+        &OriginalData {
+            name: original_metadata.name,
+            symbol: original_metadata.symbol,
+            uri: original_metadata.uri,
+        },
+        authority_signer_seeds,
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], metadata: Vec<u8>, nonce: u64)]
+pub struct InitializeCompressedPair<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// The creator receiving the collection's royalties
+    /// CHECK: Constraint
+    #[account(
+        constraint = creator.key() == collection_creator(&entangled_collection_metadata)?,
+    )]
+    pub creator: AccountInfo<'info>,
+
+    /// CHECK: Safe because this read-only account only gets used as a constraint
+    #[account(
+        mut,
+        address = entangled_collection.authority().key @ EntanglerError::InvalidAuthority,
+    )]
+    pub entangler_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_master_edition_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub master_edition: AccountInfo<'info>,
+
+    #[account(
+        seeds = [
+            COLLECTION_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+        ],
+        bump,
+        has_one = entangled_collection_mint,
+        constraint = !entangled_collection.non_custodial @ EntanglerError::NonCustodialCompressed,
+    )]
+    pub entangled_collection: Box<Account<'info, EntangledCollection>>,
+
+    #[account(mut)]
+    pub entangled_collection_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidMetadata,
+        constraint = mpl_token_metadata::check_id(entangled_collection_metadata.owner),
+    )]
+    pub entangled_collection_metadata: UncheckedAccount<'info>,
+
+    /// The Bubblegum tree holding the original
+    /// CHECK: Checked in the instruction
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// The owner of the original, part of the proven leaf
+    /// CHECK: Checked by the compression program
+    pub leaf_owner: UncheckedAccount<'info>,
+
+    /// The delegate of the original, part of the proven leaf
+    /// CHECK: Checked by the compression program
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    /// CHECK: Created in the instruction, using constraints
+    #[account(
+        mut,
+        seeds = [
+          ENTANGLED_MINT_SEED.as_bytes(),
+          &entangled_collection.id.to_bytes(),
+          &get_asset_id(merkle_tree.key, nonce).to_bytes()
+        ],
+        bump,
+    )]
+    pub entangled_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_mint.key()).0 @ EntanglerError::InvalidMetadata,
+    )]
+    pub entangled_metadata: UncheckedAccount<'info>,
+
+    /// The master edition of the entangled token, except for Token-2022 collections
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_master_edition_account(&entangled_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub entangled_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Created in the instruction, using constraints
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &entangled_mint.key(), &token_program.key()),
+    )]
+    pub entangled_mint_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&entangled_mint.key(), &entangled_mint_escrow.key()).0,
+    )]
+    pub entangled_mint_escrow_record: Option<UncheckedAccount<'info>>,
+
+    /// Common Solana programs
+    /// CHECK: CPI
+    #[account(
+        address = mpl_token_metadata::ID
+    )]
+    pub metadata_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = SPL_ACCOUNT_COMPRESSION_ID
+    )]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: Sysvar
+    #[account(
+        address = sysvar::instructions::ID
+    )]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+    #[account(
+        address = entangled_collection.entangled_token_program() @ EntanglerError::InvalidEntangledTokenProgram,
+    )]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
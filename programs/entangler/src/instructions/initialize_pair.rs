@@ -1,103 +1,652 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::program::invoke_signed;
-use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
-use mpl_token_metadata::instruction::{create_metadata_accounts_v3, verify_collection};
-use mpl_token_metadata::state::{Collection, Creator, Metadata, TokenMetadataAccount};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{
+    self, get_associated_token_address_with_program_id, AssociatedToken,
+};
+use anchor_spl::token::{self, Mint, MintTo};
+use anchor_spl::token_interface::{self, TokenInterface};
+use mpl_token_metadata::instruction::builders::{CreateBuilder, MintBuilder, VerifyBuilder};
+use mpl_token_metadata::instruction::{
+    create_metadata_accounts_v3, verify_collection, CreateArgs, InstructionBuilder, MintArgs,
+    VerificationArgs,
+};
+use mpl_token_metadata::state::{
+    AssetData, Collection, Creator, Metadata, PrintSupply, TokenMetadataAccount, TokenStandard,
+    MAX_NAME_LENGTH,
+};
+use spl_token_2022::extension::{metadata_pointer, transfer_hook, ExtensionType};
+use spl_token_2022::instruction::AuthorityType;
+use spl_token_metadata_interface::instruction as token_metadata_instruction;
+use spl_token_metadata_interface::state::{Field, TokenMetadata};
 
-use crate::seeds::{AUTHORITY_SEED, COLLECTION_SEED, ENTANGLED_MINT_SEED};
-use crate::state::EntangledCollection;
+use crate::errors::EntanglerError;
+use crate::seeds::{COLLECTION_SEED, ENTANGLED_MINT_SEED, ORIGINAL_RECORD_SEED};
+use crate::state::{EntangledCollection, EntangledStandard, OriginalRecord};
+use crate::utils::{
+    check_transferable, collection_creator, optional_info, print_edition, MasterEditionCreation,
+};
 
 pub fn initialize_pair(ctx: Context<InitializePair>) -> Result<()> {
     msg!("Init pair");
 
-    let authority_bump = *ctx.bumps.get("entangler_authority").unwrap();
-    let authority_seeds = &[AUTHORITY_SEED.as_bytes(), &[authority_bump]];
+    let authority = ctx.accounts.entangled_collection.authority();
+    let authority_seeds = authority.seeds();
     let authority_signer_seeds = &[&authority_seeds[..]];
 
-    // Mint the token
-    let mint_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        MintTo {
-            mint: ctx.accounts.entangled_mint.to_account_info(),
-            authority: ctx.accounts.entangler_authority.to_account_info(),
-            to: ctx.accounts.entangled_mint_escrow.to_account_info(),
-        },
+    // Check the original can be held in escrow
+    check_transferable(&ctx.accounts.original_mint.to_account_info())?;
+
+    // Create the escrow of the original, whatever its token program. Non-custodial collections
+    // leave originals in their owner's account
+    if !ctx.accounts.entangled_collection.non_custodial {
+        let Some(original_mint_escrow) = optional_info(&ctx.accounts.original_mint_escrow) else {
+            return err!(EntanglerError::MissingOriginalEscrow);
+        };
+        associated_token::create_idempotent(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.signer.to_account_info(),
+                associated_token: original_mint_escrow,
+                authority: ctx.accounts.entangler_authority.to_account_info(),
+                mint: ctx.accounts.original_mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.original_token_program.to_account_info(),
+            },
+        ))?;
+    }
+
+    // Check the original is a genuine member of the collection
+    let original_metadata = Metadata::from_account_info(&ctx.accounts.original_metadata)?;
+    ctx.accounts
+        .entangled_collection
+        .check_original(&original_metadata)?;
+
+    // Keep the escrowed original from being rescued
+    ctx.accounts.original_record.original_mint = ctx.accounts.original_mint.key();
+
+    // Print editions get their number so they can be told apart
+    let edition = optional_info(&ctx.accounts.original_edition)
+        .and_then(|original_edition| print_edition(&original_edition))
+        .map(|original_edition| original_edition.edition);
+
+    EntangledMintCreation {
+        signer: ctx.accounts.signer.to_account_info(),
+        creator: ctx.accounts.creator.to_account_info(),
+        entangler_authority: ctx.accounts.entangler_authority.to_account_info(),
+        master_edition: ctx.accounts.master_edition.to_account_info(),
+        entangled_collection: &ctx.accounts.entangled_collection,
+        entangled_collection_mint: ctx.accounts.entangled_collection_mint.to_account_info(),
+        entangled_collection_metadata: ctx.accounts.entangled_collection_metadata.to_account_info(),
+        entangled_mint: ctx.accounts.entangled_mint.to_account_info(),
+        entangled_metadata: ctx.accounts.entangled_metadata.to_account_info(),
+        entangled_master_edition: optional_info(&ctx.accounts.entangled_master_edition),
+        entangled_mint_escrow: ctx.accounts.entangled_mint_escrow.to_account_info(),
+        entangled_mint_escrow_record: optional_info(&ctx.accounts.entangled_mint_escrow_record),
+        metadata_program: ctx.accounts.metadata_program.to_account_info(),
+        sysvar_instructions: optional_info(&ctx.accounts.sysvar_instructions),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        rent: ctx.accounts.rent.to_account_info(),
+        original: ctx.accounts.original_mint.key(),
+        mint_bump: *ctx.bumps.get("entangled_mint").unwrap(),
+    }
+    .create(
+        &OriginalData::from(original_metadata).with_edition(edition),
         authority_signer_seeds,
-    );
-    token::mint_to(mint_ctx, 1)?;
-
-    // Create metadata
-    let original_metadata = Metadata::from_account_info(&ctx.accounts.original_metadata).unwrap();
-    invoke_signed(
-        &create_metadata_accounts_v3(
-            ctx.accounts.metadata_program.key(),
-            ctx.accounts.entangled_metadata.key(),
-            ctx.accounts.entangled_mint.key(),
-            ctx.accounts.entangler_authority.key(),
-            ctx.accounts.signer.key(),
-            ctx.accounts.entangler_authority.key(),
-            original_metadata.data.name,
-            original_metadata.data.symbol,
-            original_metadata.data.uri,
-            Some(vec![
-                Creator {
-                    address: ctx.accounts.entangler_authority.key(),
-                    verified: true,
-                    share: 0,
+    )
+}
+
+/// What an entangled token copies from its original
+pub struct OriginalData {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+impl OriginalData {
+    /// Numbers the name of print editions, which otherwise share their master's
+    pub fn with_edition(mut self, edition: Option<u64>) -> Self {
+        if let Some(edition) = edition {
+            let suffix = format!(" #{edition}");
+            let mut name = self.name.trim_end_matches('\0').to_string();
+            while name.len() + suffix.len() > MAX_NAME_LENGTH {
+                name.pop();
+            }
+            self.name = name + &suffix;
+        }
+        self
+    }
+}
+
+impl From<Metadata> for OriginalData {
+    fn from(metadata: Metadata) -> Self {
+        Self {
+            name: metadata.data.name,
+            symbol: metadata.data.symbol,
+            uri: metadata.data.uri,
+        }
+    }
+}
+
+/// The accounts needed to create the entangled token of a pair, whatever its original
+pub struct EntangledMintCreation<'a, 'info> {
+    pub signer: AccountInfo<'info>,
+    pub creator: AccountInfo<'info>,
+    pub entangler_authority: AccountInfo<'info>,
+    pub master_edition: AccountInfo<'info>,
+    pub entangled_collection: &'a EntangledCollection,
+    pub entangled_collection_mint: AccountInfo<'info>,
+    pub entangled_collection_metadata: AccountInfo<'info>,
+    pub entangled_mint: AccountInfo<'info>,
+    pub entangled_metadata: AccountInfo<'info>,
+    pub entangled_master_edition: Option<AccountInfo<'info>>,
+    pub entangled_mint_escrow: AccountInfo<'info>,
+    pub entangled_mint_escrow_record: Option<AccountInfo<'info>>,
+    pub metadata_program: AccountInfo<'info>,
+    pub sysvar_instructions: Option<AccountInfo<'info>>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub rent: AccountInfo<'info>,
+
+    /// The key the entangled mint is derived from: the original mint or asset id
+    pub original: Pubkey,
+    pub mint_bump: u8,
+}
+
+impl<'a, 'info> EntangledMintCreation<'a, 'info> {
+    /// Creates the entangled mint and mints its token to the escrow
+    pub fn create(
+        &self,
+        original: &OriginalData,
+        authority_signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        match self.entangled_collection.entangled_standard {
+            EntangledStandard::NonFungible => {
+                self.create_mint(Mint::LEN, Mint::LEN)?;
+                self.initialize_mint()?;
+                self.create_escrow()?;
+                self.create_legacy(original, authority_signer_seeds)
+            }
+            EntangledStandard::ProgrammableNonFungible => {
+                self.create_mint(Mint::LEN, Mint::LEN)?;
+                self.initialize_mint()?;
+                self.create_escrow()?;
+                self.create_programmable(original, authority_signer_seeds)
+            }
+            EntangledStandard::Token2022 => {
+                self.create_token_2022(original, authority_signer_seeds)
+            }
+            EntangledStandard::Compressed => err!(EntanglerError::CompressedCollection),
+        }
+    }
+
+    /// Allocates the entangled mint, funding `rent_space` bytes for Token-2022 to grow into
+    fn create_mint(&self, space: usize, rent_space: usize) -> Result<()> {
+        let entangled_collection_id = self.entangled_collection.id.to_bytes();
+        let original = self.original.to_bytes();
+        let mint_seeds = &[
+            ENTANGLED_MINT_SEED.as_bytes(),
+            &entangled_collection_id,
+            &original,
+            &[self.mint_bump],
+        ];
+        let mint_signer_seeds = &[&mint_seeds[..]];
+
+        let rent = Rent::get()?.minimum_balance(rent_space);
+        let lamports = self.entangled_mint.lamports();
+        if lamports == 0 {
+            return system_program::create_account(
+                CpiContext::new_with_signer(
+                    self.system_program.to_account_info(),
+                    system_program::CreateAccount {
+                        from: self.signer.to_account_info(),
+                        to: self.entangled_mint.to_account_info(),
+                    },
+                    mint_signer_seeds,
+                ),
+                rent,
+                space as u64,
+                self.token_program.key,
+            );
+        }
+
+        // Anyone can fund the mint's address beforehand, which `create_account` refuses
+        let missing = rent.saturating_sub(lamports);
+        if missing > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: self.signer.to_account_info(),
+                        to: self.entangled_mint.to_account_info(),
+                    },
+                ),
+                missing,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                system_program::Allocate {
+                    account_to_allocate: self.entangled_mint.to_account_info(),
                 },
-                Creator {
-                    address: ctx.accounts.creator.key(),
-                    verified: false,
-                    share: 100,
+                mint_signer_seeds,
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                system_program::Assign {
+                    account_to_assign: self.entangled_mint.to_account_info(),
+                },
+                mint_signer_seeds,
+            ),
+            self.token_program.key,
+        )
+    }
+
+    fn initialize_mint(&self) -> Result<()> {
+        token_interface::initialize_mint2(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                token_interface::InitializeMint2 {
+                    mint: self.entangled_mint.to_account_info(),
                 },
-            ]),
-            ctx.accounts.entangled_collection.royalties,
-            false,
-            true,
-            Some(Collection {
-                key: ctx.accounts.entangled_collection_mint.key(),
+            ),
+            0,
+            self.entangler_authority.key,
+            Some(self.entangler_authority.key),
+        )
+    }
+
+    fn create_escrow(&self) -> Result<()> {
+        associated_token::create_idempotent(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: self.signer.to_account_info(),
+                associated_token: self.entangled_mint_escrow.to_account_info(),
+                authority: self.entangler_authority.to_account_info(),
+                mint: self.entangled_mint.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+            },
+        ))
+    }
+
+    fn entangled_creators(&self) -> Vec<Creator> {
+        vec![
+            Creator {
+                address: self.entangler_authority.key(),
+                verified: true,
+                share: 0,
+            },
+            Creator {
+                address: self.creator.key(),
                 verified: false,
-            }),
-            None,
-            None,
-        ),
-        &[
-            ctx.accounts.entangled_metadata.to_account_info(), // Metadata
-            ctx.accounts.entangled_mint.to_account_info(),     // Mint
-            ctx.accounts.entangler_authority.to_account_info(), // Mint authority
-            ctx.accounts.entangler_authority.to_account_info(), // Update authority
-            ctx.accounts.signer.to_account_info(),             // Payer
-            ctx.accounts.system_program.to_account_info(),     // System program
-            ctx.accounts.rent.to_account_info(),               // Rent
-        ],
-        authority_signer_seeds,
-    )?;
-
-    // Verify
-    invoke_signed(
-        &verify_collection(
-            ctx.accounts.metadata_program.key(),
-            ctx.accounts.entangled_metadata.key(),
-            ctx.accounts.entangler_authority.key(),
-            ctx.accounts.signer.key(),
-            ctx.accounts.entangled_collection_mint.key(),
-            ctx.accounts.entangled_collection_metadata.key(),
-            ctx.accounts.master_edition.key(),
-            None,
-        ),
-        &[
-            ctx.accounts.entangled_metadata.to_account_info(),
-            ctx.accounts.entangler_authority.to_account_info(),
-            ctx.accounts.signer.to_account_info(),
-            ctx.accounts.entangled_collection_mint.to_account_info(),
-            ctx.accounts.entangled_collection_metadata.to_account_info(),
-            ctx.accounts.master_edition.to_account_info(),
-        ],
-        authority_signer_seeds,
-    )?;
+                share: 100,
+            },
+        ]
+    }
+
+    fn create_legacy(
+        &self,
+        original: &OriginalData,
+        authority_signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        // Mint the token
+        let mint_cpi = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.entangled_mint.to_account_info(),
+                authority: self.entangler_authority.to_account_info(),
+                to: self.entangled_mint_escrow.to_account_info(),
+            },
+            authority_signer_seeds,
+        );
+        token::mint_to(mint_cpi, 1)?;
+
+        // Create metadata
+        invoke_signed(
+            &create_metadata_accounts_v3(
+                self.metadata_program.key(),
+                self.entangled_metadata.key(),
+                self.entangled_mint.key(),
+                self.entangler_authority.key(),
+                self.signer.key(),
+                self.entangler_authority.key(),
+                original.name.clone(),
+                original.symbol.clone(),
+                original.uri.clone(),
+                Some(self.entangled_creators()),
+                self.entangled_collection.royalties,
+                false,
+                true,
+                Some(Collection {
+                    key: self.entangled_collection_mint.key(),
+                    verified: false,
+                }),
+                None,
+                None,
+            ),
+            &[
+                self.entangled_metadata.to_account_info(),  // Metadata
+                self.entangled_mint.to_account_info(),      // Mint
+                self.entangler_authority.to_account_info(), // Mint authority
+                self.entangler_authority.to_account_info(), // Update authority
+                self.signer.to_account_info(),              // Payer
+                self.system_program.to_account_info(),      // System program
+                self.rent.to_account_info(),                // Rent
+            ],
+            authority_signer_seeds,
+        )?;
+
+        // Create master edition
+        let Some(entangled_master_edition) = self.entangled_master_edition.as_ref() else {
+            return err!(EntanglerError::MissingEntangledEdition);
+        };
+        MasterEditionCreation {
+            edition: entangled_master_edition.to_account_info(),
+            mint: self.entangled_mint.to_account_info(),
+            metadata: self.entangled_metadata.to_account_info(),
+            authority: self.entangler_authority.to_account_info(),
+            payer: self.signer.to_account_info(),
+            metadata_program: self.metadata_program.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        }
+        .invoke_signed(authority_signer_seeds)?;
+
+        // Verify
+        invoke_signed(
+            &verify_collection(
+                self.metadata_program.key(),
+                self.entangled_metadata.key(),
+                self.entangler_authority.key(),
+                self.signer.key(),
+                self.entangled_collection_mint.key(),
+                self.entangled_collection_metadata.key(),
+                self.master_edition.key(),
+                None,
+            ),
+            &[
+                self.entangled_metadata.to_account_info(),
+                self.entangler_authority.to_account_info(),
+                self.signer.to_account_info(),
+                self.entangled_collection_mint.to_account_info(),
+                self.entangled_collection_metadata.to_account_info(),
+                self.master_edition.to_account_info(),
+            ],
+            authority_signer_seeds,
+        )?;
+
+        Ok(())
+    }
 
-    Ok(())
+    fn create_programmable(
+        &self,
+        original: &OriginalData,
+        authority_signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let (
+            Some(entangled_master_edition),
+            Some(entangled_mint_escrow_record),
+            Some(sysvar_instructions),
+        ) = (
+            self.entangled_master_edition.as_ref(),
+            self.entangled_mint_escrow_record.as_ref(),
+            self.sysvar_instructions.as_ref(),
+        ) else {
+            return err!(EntanglerError::MissingProgrammableAccounts);
+        };
+
+        // Create metadata and master edition
+        let mut asset_data = AssetData::new(
+            TokenStandard::ProgrammableNonFungible,
+            original.name.clone(),
+            original.symbol.clone(),
+            original.uri.clone(),
+        );
+        asset_data.seller_fee_basis_points = self.entangled_collection.royalties;
+        asset_data.creators = Some(self.entangled_creators());
+        asset_data.collection = Some(Collection {
+            key: self.entangled_collection_mint.key(),
+            verified: false,
+        });
+        asset_data.rule_set = self.entangled_collection.rule_set;
+
+        let ix = CreateBuilder::new()
+            .metadata(self.entangled_metadata.key())
+            .master_edition(entangled_master_edition.key())
+            .mint(self.entangled_mint.key())
+            .authority(self.entangler_authority.key())
+            .payer(self.signer.key())
+            .update_authority(self.entangler_authority.key())
+            .system_program(self.system_program.key())
+            .sysvar_instructions(sysvar_instructions.key())
+            .spl_token_program(self.token_program.key())
+            .initialize_mint(false)
+            .update_authority_as_signer(true)
+            .build(CreateArgs::V1 {
+                asset_data,
+                decimals: Some(0),
+                print_supply: Some(PrintSupply::Zero),
+            })
+            .map_err(|_| EntanglerError::MissingProgrammableAccounts)?
+            .instruction();
+        invoke_signed(
+            &ix,
+            &[
+                self.entangled_metadata.to_account_info(),
+                entangled_master_edition.to_account_info(),
+                self.entangled_mint.to_account_info(),
+                self.entangler_authority.to_account_info(),
+                self.signer.to_account_info(),
+                self.system_program.to_account_info(),
+                sysvar_instructions.to_account_info(),
+                self.token_program.to_account_info(),
+                self.metadata_program.to_account_info(),
+            ],
+            authority_signer_seeds,
+        )?;
+
+        // Mint the token
+        let ix = MintBuilder::new()
+            .token(self.entangled_mint_escrow.key())
+            .token_owner(self.entangler_authority.key())
+            .metadata(self.entangled_metadata.key())
+            .master_edition(entangled_master_edition.key())
+            .token_record(entangled_mint_escrow_record.key())
+            .mint(self.entangled_mint.key())
+            .authority(self.entangler_authority.key())
+            .payer(self.signer.key())
+            .system_program(self.system_program.key())
+            .sysvar_instructions(sysvar_instructions.key())
+            .spl_token_program(self.token_program.key())
+            .spl_ata_program(self.associated_token_program.key())
+            .build(MintArgs::V1 {
+                amount: 1,
+                authorization_data: None,
+            })
+            .map_err(|_| EntanglerError::MissingProgrammableAccounts)?
+            .instruction();
+        invoke_signed(
+            &ix,
+            &[
+                self.entangled_mint_escrow.to_account_info(),
+                self.entangler_authority.to_account_info(),
+                self.entangled_metadata.to_account_info(),
+                entangled_master_edition.to_account_info(),
+                entangled_mint_escrow_record.to_account_info(),
+                self.entangled_mint.to_account_info(),
+                self.signer.to_account_info(),
+                self.system_program.to_account_info(),
+                sysvar_instructions.to_account_info(),
+                self.token_program.to_account_info(),
+                self.associated_token_program.to_account_info(),
+                self.metadata_program.to_account_info(),
+            ],
+            authority_signer_seeds,
+        )?;
+
+        // Verify
+        let ix = VerifyBuilder::new()
+            .authority(self.entangler_authority.key())
+            .metadata(self.entangled_metadata.key())
+            .collection_mint(self.entangled_collection_mint.key())
+            .collection_metadata(self.entangled_collection_metadata.key())
+            .collection_master_edition(self.master_edition.key())
+            .system_program(self.system_program.key())
+            .sysvar_instructions(sysvar_instructions.key())
+            .build(VerificationArgs::CollectionV1)
+            .map_err(|_| EntanglerError::MissingProgrammableAccounts)?
+            .instruction();
+        invoke_signed(
+            &ix,
+            &[
+                self.entangler_authority.to_account_info(),
+                self.entangled_metadata.to_account_info(),
+                self.entangled_collection_mint.to_account_info(),
+                self.entangled_collection_metadata.to_account_info(),
+                self.master_edition.to_account_info(),
+                self.system_program.to_account_info(),
+                sysvar_instructions.to_account_info(),
+                self.metadata_program.to_account_info(),
+            ],
+            authority_signer_seeds,
+        )?;
+
+        Ok(())
+    }
+
+    fn create_token_2022(
+        &self,
+        original: &OriginalData,
+        authority_signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let entangled_collection = self.entangled_collection;
+        let mint = self.entangled_mint.key();
+        let authority = self.entangler_authority.key();
+
+        // The mint holds its own metadata, which Token-2022 reallocates into
+        let fields = vec![
+            (
+                "royalties".to_string(),
+                entangled_collection.royalties.to_string(),
+            ),
+            ("creator".to_string(), self.creator.key().to_string()),
+            (
+                "collection".to_string(),
+                entangled_collection.entangled_collection_mint.to_string(),
+            ),
+        ];
+        let token_metadata = TokenMetadata {
+            mint,
+            name: original.name.trim_end_matches('\0').to_string(),
+            symbol: original.symbol.trim_end_matches('\0').to_string(),
+            uri: original.uri.trim_end_matches('\0').to_string(),
+            additional_metadata: fields.clone(),
+            ..Default::default()
+        };
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::MetadataPointer,
+            ExtensionType::TransferHook,
+        ])?;
+        self.create_mint(space, space + token_metadata.tlv_size_of()?)?;
+
+        let mint_infos = [
+            self.entangled_mint.to_account_info(),
+            self.token_program.to_account_info(),
+        ];
+        invoke(
+            &metadata_pointer::instruction::initialize(
+                self.token_program.key,
+                &mint,
+                Some(authority),
+                Some(mint),
+            )?,
+            &mint_infos,
+        )?;
+        invoke(
+            &transfer_hook::instruction::initialize(
+                self.token_program.key,
+                &mint,
+                Some(authority),
+                entangled_collection.transfer_hook_program,
+            )?,
+            &mint_infos,
+        )?;
+        self.initialize_mint()?;
+
+        // Write the metadata
+        let metadata_infos = [
+            self.entangled_mint.to_account_info(),
+            self.entangler_authority.to_account_info(),
+            self.token_program.to_account_info(),
+        ];
+        invoke_signed(
+            &token_metadata_instruction::initialize(
+                self.token_program.key,
+                &mint,
+                &authority,
+                &mint,
+                &authority,
+                token_metadata.name,
+                token_metadata.symbol,
+                token_metadata.uri,
+            ),
+            &metadata_infos,
+            authority_signer_seeds,
+        )?;
+        for (key, value) in fields {
+            invoke_signed(
+                &token_metadata_instruction::update_field(
+                    self.token_program.key,
+                    &mint,
+                    &authority,
+                    Field::Key(key),
+                    value,
+                ),
+                &metadata_infos,
+                authority_signer_seeds,
+            )?;
+        }
+
+        // Mint the token
+        self.create_escrow()?;
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                token_interface::MintTo {
+                    mint: self.entangled_mint.to_account_info(),
+                    authority: self.entangler_authority.to_account_info(),
+                    to: self.entangled_mint_escrow.to_account_info(),
+                },
+                authority_signer_seeds,
+            ),
+            1,
+        )?;
+
+        // Nothing like a master edition caps the supply, so nobody may mint again
+        invoke_signed(
+            &spl_token_2022::instruction::set_authority(
+                self.token_program.key,
+                &mint,
+                None,
+                AuthorityType::MintTokens,
+                &authority,
+                &[],
+            )?,
+            &metadata_infos,
+            authority_signer_seeds,
+        )?;
+
+        Ok(())
+    }
 }
 
 #[derive(Accounts)]
@@ -105,23 +654,25 @@ pub struct InitializePair<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
 
-    /// The update authority of thee collection
+    /// The creator receiving the collection's royalties
     /// CHECK: Constraint
     #[account(
-        constraint = creator.key() == Metadata::from_account_info(&entangled_collection_metadata).unwrap().data.creators.unwrap().get(1).unwrap().address,
+        constraint = creator.key() == collection_creator(&entangled_collection_metadata)?,
     )]
     pub creator: AccountInfo<'info>,
 
     /// CHECK: Safe because this read-only account only gets used as a constraint
     #[account(
         mut,
-        seeds = [AUTHORITY_SEED.as_bytes()],
-        bump,
+        address = entangled_collection.authority().key @ EntanglerError::InvalidAuthority,
     )]
     pub entangler_authority: UncheckedAccount<'info>,
 
-    /// CHECK: Constraint
-    #[account(mut)]
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_master_edition_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
     pub master_edition: AccountInfo<'info>,
 
     #[account(
@@ -139,55 +690,92 @@ pub struct InitializePair<'info> {
 
     /// CHECK: Using constraints
     #[account(
-        address = mpl_token_metadata::pda::find_metadata_account(&entangled_collection_mint.key()).0,
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidMetadata,
         constraint = mpl_token_metadata::check_id(entangled_collection_metadata.owner),
     )]
     pub entangled_collection_metadata: UncheckedAccount<'info>,
 
-    #[account(mut)]
-    pub original_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        mint::token_program = original_token_program,
+    )]
+    pub original_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
     /// CHECK: Using constraints
     #[account(
-        address = mpl_token_metadata::pda::find_metadata_account(&original_mint.key()).0,
+        address = mpl_token_metadata::pda::find_metadata_account(&original_mint.key()).0 @ EntanglerError::InvalidMetadata,
         constraint = mpl_token_metadata::check_id(original_metadata.owner),
-        constraint = Metadata::from_account_info(&original_metadata).unwrap().collection.unwrap().key == entangled_collection.original_collection_mint,
     )]
     pub original_metadata: AccountInfo<'info>,
 
+    /// The master or print edition of the original
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_master_edition_account(&original_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub original_edition: Option<UncheckedAccount<'info>>,
+
+    /// The escrow of the original, for custodial collections
+    /// CHECK: Created in the instruction, using constraints
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &original_mint.key(), &original_token_program.key()),
+    )]
+    pub original_mint_escrow: Option<UncheckedAccount<'info>>,
+
+    /// Marks the original as backing a pair, created by the first of its pairs
     #[account(
         init_if_needed,
         payer = signer,
-        associated_token::mint = original_mint,
-        associated_token::authority = entangler_authority,
+        space = OriginalRecord::LEN,
+        seeds = [
+            ORIGINAL_RECORD_SEED.as_bytes(),
+            &original_mint.key().to_bytes(),
+        ],
+        bump
     )]
-    pub original_mint_escrow: Box<Account<'info, TokenAccount>>,
+    pub original_record: Box<Account<'info, OriginalRecord>>,
 
+    /// CHECK: Created in the instruction, using constraints
     #[account(
-        init,
-        payer = signer,
+        mut,
         seeds = [
           ENTANGLED_MINT_SEED.as_bytes(),
           &entangled_collection.id.to_bytes(),
           &original_mint.key().to_bytes()
         ],
         bump,
-        mint::decimals = 0,
-        mint::authority = entangler_authority,
     )]
-    pub entangled_mint: Account<'info, Mint>,
+    pub entangled_mint: UncheckedAccount<'info>,
 
     /// CHECK: Using constraints
-    #[account(mut)]
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_mint.key()).0 @ EntanglerError::InvalidMetadata,
+    )]
     pub entangled_metadata: UncheckedAccount<'info>,
 
+    /// The master edition of the entangled token, except for Token-2022 collections
+    /// CHECK: Using constraints
     #[account(
-        init_if_needed,
-        payer = signer,
-        associated_token::mint = entangled_mint,
-        associated_token::authority = entangler_authority,
+        mut,
+        address = mpl_token_metadata::pda::find_master_edition_account(&entangled_mint.key()).0 @ EntanglerError::InvalidEdition,
     )]
-    pub entangled_mint_escrow: Box<Account<'info, TokenAccount>>,
+    pub entangled_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Created in the instruction, using constraints
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &entangled_mint.key(), &token_program.key()),
+    )]
+    pub entangled_mint_escrow: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&entangled_mint.key(), &entangled_mint_escrow.key()).0,
+    )]
+    pub entangled_mint_escrow_record: Option<UncheckedAccount<'info>>,
 
     /// Common Solana programs
     /// CHECK: CPI
@@ -195,7 +783,16 @@ pub struct InitializePair<'info> {
         address = mpl_token_metadata::ID
     )]
     pub metadata_program: UncheckedAccount<'info>,
-    pub token_program: Program<'info, Token>,
+    /// CHECK: Sysvar
+    #[account(
+        address = sysvar::instructions::ID
+    )]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+    pub original_token_program: Interface<'info, TokenInterface>,
+    #[account(
+        address = entangled_collection.entangled_token_program() @ EntanglerError::InvalidEntangledTokenProgram,
+    )]
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
@@ -0,0 +1,272 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{
+    self, get_associated_token_address_with_program_id, AssociatedToken,
+};
+use anchor_spl::token::Mint;
+use anchor_spl::token_interface::{self, TokenInterface};
+use mpl_bubblegum::accounts::TreeConfig;
+use mpl_bubblegum::instructions::{
+    MintToCollectionV1Cpi, MintToCollectionV1CpiAccounts, MintToCollectionV1InstructionArgs,
+};
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use mpl_bubblegum::types::{Collection, Creator, MetadataArgs, TokenProgramVersion, TokenStandard};
+use mpl_bubblegum::utils::get_asset_id;
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+
+use crate::errors::EntanglerError;
+use crate::instructions::OriginalData;
+use crate::seeds::{
+    COLLECTION_SEED, ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED, ORIGINAL_RECORD_SEED,
+};
+use crate::state::{EntangledCollection, EntangledPair, OriginalRecord};
+use crate::utils::{check_transferable, collection_creator, optional_info, print_edition};
+
+pub fn initialize_tree_pair(ctx: Context<InitializeTreePair>) -> Result<()> {
+    msg!("Init tree pair");
+
+    let authority = ctx.accounts.entangled_collection.authority();
+    let authority_seeds = authority.seeds();
+    let authority_signer_seeds = &[&authority_seeds[..]];
+
+    // Check the original can be held in escrow
+    check_transferable(&ctx.accounts.original_mint.to_account_info())?;
+
+    // Create the escrow of the original, whatever its token program
+    associated_token::create_idempotent(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: ctx.accounts.signer.to_account_info(),
+            associated_token: ctx.accounts.original_mint_escrow.to_account_info(),
+            authority: ctx.accounts.entangler_authority.to_account_info(),
+            mint: ctx.accounts.original_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.original_token_program.to_account_info(),
+        },
+    ))?;
+
+    // Check the original is a genuine member of the collection
+    let original_metadata = Metadata::from_account_info(&ctx.accounts.original_metadata)?;
+    ctx.accounts
+        .entangled_collection
+        .check_original(&original_metadata)?;
+    let edition = optional_info(&ctx.accounts.original_edition)
+        .and_then(|original_edition| print_edition(&original_edition))
+        .map(|original_edition| original_edition.edition);
+    let original = OriginalData::from(original_metadata).with_edition(edition);
+
+    // The next leaf of the tree becomes the entangled token
+    let tree_config = TreeConfig::from_bytes(&ctx.accounts.tree_config.try_borrow_data()?)?;
+    let pair = &mut ctx.accounts.entangled_pair;
+    pair.original_mint = ctx.accounts.original_mint.key();
+    pair.entangled_mint = get_asset_id(ctx.accounts.merkle_tree.key, tree_config.num_minted);
+    pair.frozen_account = None;
+    pair.migrated = ctx.accounts.entangled_collection.scoped_authority;
+    ctx.accounts.original_record.original_mint = ctx.accounts.original_mint.key();
+
+    let metadata = MetadataArgs {
+        name: original.name,
+        symbol: original.symbol,
+        uri: original.uri,
+        seller_fee_basis_points: ctx.accounts.entangled_collection.royalties,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: Some(TokenStandard::NonFungible),
+        collection: Some(Collection {
+            verified: false,
+            key: ctx.accounts.entangled_collection_mint.key(),
+        }),
+        uses: None,
+        token_program_version: TokenProgramVersion::Original,
+        creators: vec![
+            Creator {
+                address: ctx.accounts.entangler_authority.key(),
+                verified: true,
+                share: 0,
+            },
+            Creator {
+                address: ctx.accounts.creator.key(),
+                verified: false,
+                share: 100,
+            },
+        ],
+    };
+
+    // Mint the leaf to the authority, verified in the entangled collection
+    MintToCollectionV1Cpi::new(
+        &ctx.accounts.bubblegum_program,
+        MintToCollectionV1CpiAccounts {
+            tree_config: &ctx.accounts.tree_config,
+            leaf_owner: &ctx.accounts.entangler_authority,
+            leaf_delegate: &ctx.accounts.entangler_authority,
+            merkle_tree: &ctx.accounts.merkle_tree,
+            payer: &ctx.accounts.signer,
+            tree_creator_or_delegate: &ctx.accounts.entangler_authority,
+            collection_authority: &ctx.accounts.entangler_authority,
+            collection_authority_record_pda: None,
+            collection_mint: &ctx.accounts.entangled_collection_mint.to_account_info(),
+            collection_metadata: &ctx.accounts.entangled_collection_metadata,
+            collection_edition: &ctx.accounts.master_edition,
+            bubblegum_signer: &ctx.accounts.bubblegum_signer,
+            log_wrapper: &ctx.accounts.log_wrapper,
+            compression_program: &ctx.accounts.compression_program,
+            token_metadata_program: &ctx.accounts.metadata_program,
+            system_program: &ctx.accounts.system_program,
+        },
+        MintToCollectionV1InstructionArgs { metadata },
+    )
+    .invoke_signed(authority_signer_seeds)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeTreePair<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// The creator receiving the collection's royalties
+    /// CHECK: Constraint
+    #[account(
+        constraint = creator.key() == collection_creator(&entangled_collection_metadata)?,
+    )]
+    pub creator: AccountInfo<'info>,
+
+    /// CHECK: Safe because this read-only account only gets used as a constraint
+    #[account(
+        address = entangled_collection.authority().key @ EntanglerError::InvalidAuthority,
+    )]
+    pub entangler_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_master_edition_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub master_edition: AccountInfo<'info>,
+
+    #[account(
+        seeds = [
+            COLLECTION_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+        ],
+        bump,
+        has_one = entangled_collection_mint,
+        constraint = entangled_collection.entangled_tree == Some(merkle_tree.key()) @ EntanglerError::InvalidEntangledTree,
+    )]
+    pub entangled_collection: Box<Account<'info, EntangledCollection>>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = EntangledPair::LEN,
+        seeds = [
+            ENTANGLED_PAIR_SEED.as_bytes(),
+            &entangled_mint.key().to_bytes(),
+        ],
+        bump
+    )]
+    pub entangled_pair: Box<Account<'info, EntangledPair>>,
+
+    pub entangled_collection_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidMetadata,
+        constraint = mpl_token_metadata::check_id(entangled_collection_metadata.owner),
+    )]
+    pub entangled_collection_metadata: UncheckedAccount<'info>,
+
+    #[account(
+        mint::token_program = original_token_program,
+    )]
+    pub original_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_metadata_account(&original_mint.key()).0 @ EntanglerError::InvalidMetadata,
+        constraint = mpl_token_metadata::check_id(original_metadata.owner),
+    )]
+    pub original_metadata: AccountInfo<'info>,
+
+    /// The master or print edition of the original
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_master_edition_account(&original_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub original_edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Created in the instruction, using constraints
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &original_mint.key(), &original_token_program.key()),
+    )]
+    pub original_mint_escrow: UncheckedAccount<'info>,
+
+    /// Marks the original as backing a pair, created by the first of its pairs
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = OriginalRecord::LEN,
+        seeds = [
+            ORIGINAL_RECORD_SEED.as_bytes(),
+            &original_mint.key().to_bytes(),
+        ],
+        bump
+    )]
+    pub original_record: Box<Account<'info, OriginalRecord>>,
+
+    /// The address an SPL entangled mint would have, keying the pair
+    /// CHECK: Using constraints
+    #[account(
+        seeds = [
+          ENTANGLED_MINT_SEED.as_bytes(),
+          &entangled_collection.id.to_bytes(),
+          &original_mint.key().to_bytes()
+        ],
+        bump,
+    )]
+    pub entangled_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Checked by Bubblegum
+    #[account(mut)]
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// The collection's entangled tree
+    /// CHECK: Using constraints
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        seeds = [b"collection_cpi"],
+        seeds::program = mpl_bubblegum::ID,
+        bump,
+    )]
+    pub bubblegum_signer: UncheckedAccount<'info>,
+
+    /// Common Solana programs
+    /// CHECK: CPI
+    #[account(
+        address = mpl_token_metadata::ID
+    )]
+    pub metadata_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = mpl_bubblegum::ID
+    )]
+    pub bubblegum_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = SPL_ACCOUNT_COMPRESSION_ID
+    )]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = SPL_NOOP_ID
+    )]
+    pub log_wrapper: UncheckedAccount<'info>,
+    pub original_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
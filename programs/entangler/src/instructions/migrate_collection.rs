@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::{self, get_associated_token_address, AssociatedToken};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::errors::EntanglerError;
+use crate::seeds::{AUTHORITY_SEED, STATE_SEED};
+use crate::state::{EntangledCollection, EntanglerAuthority, EntanglerState};
+use crate::utils::{load_resized, UpdateAuthorityChange};
+
+pub fn migrate_collection(ctx: Context<MigrateCollection>) -> Result<()> {
+    msg!("Migrating the collection");
+
+    // Collections awaiting migration predate the current layout
+    let mut entangled_collection = load_resized::<EntangledCollection>(
+        &ctx.accounts.entangled_collection,
+        EntangledCollection::LEN,
+        &ctx.accounts.signer,
+        &ctx.accounts.system_program,
+    )?;
+    require_keys_eq!(
+        entangled_collection.entangled_collection_mint,
+        ctx.accounts.entangled_collection_mint.key(),
+        EntanglerError::InvalidCollection
+    );
+    require!(
+        !entangled_collection.scoped_authority,
+        EntanglerError::AlreadyMigrated
+    );
+    require!(
+        !entangled_collection.non_custodial && !entangled_collection.is_compressed(),
+        EntanglerError::UnmigratableCollection
+    );
+    require_keys_eq!(
+        ctx.accounts.entangler_authority.key(),
+        EntanglerAuthority::collection(&entangled_collection.id).key,
+        EntanglerError::InvalidAuthority
+    );
+
+    let global_bump = *ctx.bumps.get("global_authority").unwrap();
+    let global_seeds = &[AUTHORITY_SEED.as_bytes(), &[global_bump]];
+    let global_signer_seeds = &[&global_seeds[..]];
+
+    associated_token::create_idempotent(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: ctx.accounts.signer.to_account_info(),
+            associated_token: ctx
+                .accounts
+                .entangled_collection_mint_account
+                .to_account_info(),
+            authority: ctx.accounts.entangler_authority.to_account_info(),
+            mint: ctx.accounts.entangled_collection_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+    ))?;
+
+    // Move the collection NFT
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx
+                    .accounts
+                    .legacy_collection_mint_account
+                    .to_account_info(),
+                to: ctx
+                    .accounts
+                    .entangled_collection_mint_account
+                    .to_account_info(),
+                authority: ctx.accounts.global_authority.to_account_info(),
+            },
+            global_signer_seeds,
+        ),
+        1,
+    )?;
+
+    // Hand the collection over, so that only the new authority verifies entangled tokens
+    UpdateAuthorityChange {
+        authority: ctx.accounts.global_authority.to_account_info(),
+        mint: ctx.accounts.entangled_collection_mint.to_account_info(),
+        metadata: ctx.accounts.entangled_collection_metadata.to_account_info(),
+        edition: Some(ctx.accounts.master_edition.to_account_info()),
+        payer: ctx.accounts.signer.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        sysvar_instructions: ctx.accounts.sysvar_instructions.to_account_info(),
+        metadata_program: ctx.accounts.metadata_program.to_account_info(),
+    }
+    .invoke_signed(ctx.accounts.entangler_authority.key(), global_signer_seeds)?;
+
+    entangled_collection.scoped_authority = true;
+    entangled_collection.exit(&crate::ID)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateCollection<'info> {
+    #[account(
+        mut,
+        constraint = signer.key() == state.admin,
+    )]
+    pub signer: Signer<'info>,
+
+    /// The entangler's state
+    #[account(
+        seeds = [
+            STATE_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub state: Box<Account<'info, EntanglerState>>,
+
+    /// The authority shared by collections created before per-collection authorities
+    /// CHECK: Safe because this read-only account only signs CPIs
+    #[account(
+        seeds = [AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: UncheckedAccount<'info>,
+
+    /// The collection's own authority
+    /// CHECK: Safe because this read-only account is checked against the collection's id
+    pub entangler_authority: UncheckedAccount<'info>,
+
+    /// The account storing the collection's data, resized if it predates the current layout
+    /// CHECK: Loaded with `load_resized`
+    #[account(mut)]
+    pub entangled_collection: UncheckedAccount<'info>,
+
+    pub entangled_collection_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidMetadata,
+        constraint = mpl_token_metadata::check_id(entangled_collection_metadata.owner),
+    )]
+    pub entangled_collection_metadata: UncheckedAccount<'info>,
+
+    /// The master edition of the collection
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_master_edition_account(&entangled_collection_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub master_edition: UncheckedAccount<'info>,
+
+    /// The ATA of the global authority storing the collection NFT
+    #[account(
+        mut,
+        associated_token::mint = entangled_collection_mint,
+        associated_token::authority = global_authority,
+    )]
+    pub legacy_collection_mint_account: Box<Account<'info, TokenAccount>>,
+
+    /// The ATA of the collection's authority receiving the collection NFT
+    /// CHECK: Created in the instruction, using constraints
+    #[account(
+        mut,
+        address = get_associated_token_address(&entangler_authority.key(), &entangled_collection_mint.key()),
+    )]
+    pub entangled_collection_mint_account: UncheckedAccount<'info>,
+
+    /// Common Solana programs
+    /// CHECK: CPI
+    #[account(
+        address = mpl_token_metadata::ID
+    )]
+    pub metadata_program: UncheckedAccount<'info>,
+    /// CHECK: Sysvar
+    #[account(
+        address = sysvar::instructions::ID
+    )]
+    pub sysvar_instructions: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
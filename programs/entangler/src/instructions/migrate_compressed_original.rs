@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface;
+use mpl_bubblegum::programs::{SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID};
+use mpl_bubblegum::utils::get_asset_id;
+
+use crate::errors::EntanglerError;
+use crate::seeds::{AUTHORITY_SEED, COLLECTION_SEED, ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED};
+use crate::state::{EntangledCollection, EntangledPair};
+use crate::utils::{compressed_metadata, escrows_original, load_resized, CompressedTransfer};
+
+pub fn migrate_compressed_original<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateCompressedOriginal<'info>>,
+    root: [u8; 32],
+    metadata: Vec<u8>,
+    nonce: u64,
+    index: u32,
+) -> Result<()> {
+    msg!("Migrating the compressed original");
+
+    let global_bump = *ctx.bumps.get("global_authority").unwrap();
+    let global_seeds = &[AUTHORITY_SEED.as_bytes(), &[global_bump]];
+    let global_signer_seeds = &[&global_seeds[..]];
+
+    // The global authority is shared by every legacy collection, so the original only moves if
+    // this collection's pair is the one holding it
+    let pair_info = ctx.accounts.entangled_pair.to_account_info();
+    require!(!pair_info.data_is_empty(), EntanglerError::ForeignOriginal);
+    let mut entangled_pair = load_resized::<EntangledPair>(
+        &pair_info,
+        EntangledPair::LEN,
+        &ctx.accounts.signer,
+        &ctx.accounts.system_program,
+    )?;
+    let escrows_original = escrows_original(
+        &entangled_pair,
+        &ctx.accounts.entangled_mint,
+        [
+            &ctx.accounts.entangled_mint_escrow.to_account_info(),
+            &ctx.accounts.entangled_mint_destination.to_account_info(),
+        ],
+    )?;
+    require!(escrows_original, EntanglerError::ForeignOriginal);
+
+    let original_metadata = compressed_metadata(&metadata)?;
+    CompressedTransfer {
+        tree_config: ctx.accounts.tree_config.to_account_info(),
+        leaf_owner: ctx.accounts.global_authority.to_account_info(),
+        leaf_delegate: ctx.accounts.global_authority.to_account_info(),
+        new_leaf_owner: ctx.accounts.entangler_authority.to_account_info(),
+        merkle_tree: ctx.accounts.merkle_tree.to_account_info(),
+        log_wrapper: ctx.accounts.log_wrapper.to_account_info(),
+        compression_program: ctx.accounts.compression_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        bubblegum_program: ctx.accounts.bubblegum_program.to_account_info(),
+        proof: ctx.remaining_accounts.to_vec(),
+    }
+    .invoke_signed(root, &original_metadata, nonce, index, global_signer_seeds)?;
+
+    entangled_pair.migrated = true;
+    entangled_pair.exit(&crate::ID)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], metadata: Vec<u8>, nonce: u64)]
+pub struct MigrateCompressedOriginal<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// The authority shared by collections created before per-collection authorities
+    /// CHECK: Safe because this read-only account only signs CPIs
+    #[account(
+        seeds = [AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: UncheckedAccount<'info>,
+
+    /// The collection's own authority
+    /// CHECK: Safe because this read-only account only gets used as a constraint
+    #[account(
+        address = entangled_collection.authority().key @ EntanglerError::InvalidAuthority,
+    )]
+    pub entangler_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            COLLECTION_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+        ],
+        bump,
+        constraint = entangled_collection.scoped_authority @ EntanglerError::UnmigratedCollection,
+    )]
+    pub entangled_collection: Account<'info, EntangledCollection>,
+
+    /// CHECK: Checked by Bubblegum
+    pub tree_config: UncheckedAccount<'info>,
+
+    /// The Bubblegum tree holding the original
+    /// CHECK: Checked by Bubblegum
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// The entangled mint of the original, proving it belongs to the collection
+    #[account(
+        seeds = [
+          ENTANGLED_MINT_SEED.as_bytes(),
+          &entangled_collection.id.to_bytes(),
+          &get_asset_id(merkle_tree.key, nonce).to_bytes()
+        ],
+        bump,
+    )]
+    pub entangled_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// The pair of the entangled mint, resized if it predates the current layout
+    /// CHECK: Loaded with `load_resized`
+    #[account(
+        mut,
+        seeds = [
+            ENTANGLED_PAIR_SEED.as_bytes(),
+            &entangled_mint.key().to_bytes(),
+        ],
+        bump,
+    )]
+    pub entangled_pair: UncheckedAccount<'info>,
+
+    /// The escrow of the global authority for the entangled token
+    /// CHECK: Using constraints, only its balance is read
+    #[account(
+        address = get_associated_token_address_with_program_id(&global_authority.key(), &entangled_mint.key(), &entangled_collection.entangled_token_program()),
+    )]
+    pub entangled_mint_escrow: UncheckedAccount<'info>,
+
+    /// The escrow of the collection's authority for the entangled token
+    /// CHECK: Using constraints, only its balance is read
+    #[account(
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &entangled_mint.key(), &entangled_collection.entangled_token_program()),
+    )]
+    pub entangled_mint_destination: UncheckedAccount<'info>,
+
+    /// Common Solana programs
+    /// CHECK: CPI
+    #[account(
+        address = mpl_bubblegum::ID
+    )]
+    pub bubblegum_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = SPL_ACCOUNT_COMPRESSION_ID
+    )]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = SPL_NOOP_ID
+    )]
+    pub log_wrapper: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
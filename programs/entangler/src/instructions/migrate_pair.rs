@@ -0,0 +1,435 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::{
+    self, get_associated_token_address_with_program_id, AssociatedToken,
+};
+use anchor_spl::token_interface::{self, TokenInterface};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+use spl_token_2022::extension::metadata_pointer::MetadataPointer;
+use spl_token_2022::extension::transfer_hook::TransferHook;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::instruction::AuthorityType;
+use spl_token_metadata_interface::instruction as token_metadata_instruction;
+use spl_token_metadata_interface::state::TokenMetadata;
+
+use crate::errors::EntanglerError;
+use crate::seeds::{AUTHORITY_SEED, COLLECTION_SEED, ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED};
+use crate::state::{EntangledCollection, EntangledPair};
+use crate::utils::{
+    escrows_original, is_programmable, load_resized, optional_info, NftTransfer,
+    UpdateAuthorityChange,
+};
+
+pub fn migrate_pair<'info>(ctx: Context<'_, '_, '_, 'info, MigratePair<'info>>) -> Result<()> {
+    msg!("Migrating the pair");
+
+    let global_bump = *ctx.bumps.get("global_authority").unwrap();
+    let global_seeds = &[AUTHORITY_SEED.as_bytes(), &[global_bump]];
+    let global_signer_seeds = &[&global_seeds[..]];
+
+    let accounts = &ctx.accounts;
+    let mut migrated = false;
+
+    // The global escrow is shared by every legacy collection, so the original only moves if this
+    // collection's pair is the one holding it
+    let pair_info = accounts.entangled_pair.to_account_info();
+    let mut entangled_pair = None;
+    if !pair_info.data_is_empty() {
+        entangled_pair = Some(load_resized::<EntangledPair>(
+            &pair_info,
+            EntangledPair::LEN,
+            &accounts.signer,
+            &accounts.system_program,
+        )?);
+    }
+    let escrows_original = match entangled_pair.as_ref() {
+        Some(pair) => escrows_original(
+            pair,
+            &accounts.entangled_mint,
+            [
+                &accounts.entangled_mint_escrow.to_account_info(),
+                &accounts.entangled_mint_destination.to_account_info(),
+            ],
+        )?,
+        None => false,
+    };
+
+    // Move the escrowed original
+    if let Some(original_mint_escrow) = accounts.original_mint_escrow.as_ref() {
+        if escrows_original && original_mint_escrow.amount == 1 {
+            let original_metadata = Metadata::from_account_info(&accounts.original_metadata)?;
+            associated_token::create_idempotent(CpiContext::new(
+                accounts.associated_token_program.to_account_info(),
+                associated_token::Create {
+                    payer: accounts.signer.to_account_info(),
+                    associated_token: accounts.original_mint_destination.to_account_info(),
+                    authority: accounts.entangler_authority.to_account_info(),
+                    mint: accounts.original_mint.to_account_info(),
+                    system_program: accounts.system_program.to_account_info(),
+                    token_program: accounts.original_token_program.to_account_info(),
+                },
+            ))?;
+            NftTransfer {
+                token: original_mint_escrow.to_account_info(),
+                token_owner: accounts.global_authority.to_account_info(),
+                destination: accounts.original_mint_destination.to_account_info(),
+                destination_owner: accounts.entangler_authority.to_account_info(),
+                mint: accounts.original_mint.to_account_info(),
+                metadata: accounts.original_metadata.to_account_info(),
+                authority: accounts.global_authority.to_account_info(),
+                payer: accounts.signer.to_account_info(),
+                token_program: accounts.original_token_program.to_account_info(),
+                associated_token_program: accounts.associated_token_program.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+                metadata_program: accounts.metadata_program.to_account_info(),
+                hook_accounts: ctx.remaining_accounts.to_vec(),
+                edition: optional_info(&accounts.original_master_edition),
+                owner_token_record: optional_info(&accounts.original_mint_escrow_record),
+                destination_token_record: optional_info(&accounts.original_mint_destination_record),
+                sysvar_instructions: optional_info(&accounts.sysvar_instructions),
+                authorization_rules_program: optional_info(&accounts.authorization_rules_program),
+                authorization_rules: optional_info(&accounts.authorization_rules),
+            }
+            .invoke_signed(is_programmable(&original_metadata), global_signer_seeds)?;
+            if let Some(mut pair) = entangled_pair {
+                pair.migrated = true;
+                pair.exit(&crate::ID)?;
+            }
+            migrated = true;
+        }
+    }
+
+    // Move the escrowed entangled token
+    if accounts.entangled_mint_escrow.amount == 1 {
+        associated_token::create_idempotent(CpiContext::new(
+            accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: accounts.signer.to_account_info(),
+                associated_token: accounts.entangled_mint_destination.to_account_info(),
+                authority: accounts.entangler_authority.to_account_info(),
+                mint: accounts.entangled_mint.to_account_info(),
+                system_program: accounts.system_program.to_account_info(),
+                token_program: accounts.token_program.to_account_info(),
+            },
+        ))?;
+        NftTransfer {
+            token: accounts.entangled_mint_escrow.to_account_info(),
+            token_owner: accounts.global_authority.to_account_info(),
+            destination: accounts.entangled_mint_destination.to_account_info(),
+            destination_owner: accounts.entangler_authority.to_account_info(),
+            mint: accounts.entangled_mint.to_account_info(),
+            metadata: accounts.entangled_metadata.to_account_info(),
+            authority: accounts.global_authority.to_account_info(),
+            payer: accounts.signer.to_account_info(),
+            token_program: accounts.token_program.to_account_info(),
+            associated_token_program: accounts.associated_token_program.to_account_info(),
+            system_program: accounts.system_program.to_account_info(),
+            metadata_program: accounts.metadata_program.to_account_info(),
+            hook_accounts: ctx.remaining_accounts.to_vec(),
+            edition: optional_info(&accounts.entangled_master_edition),
+            owner_token_record: optional_info(&accounts.entangled_mint_escrow_record),
+            destination_token_record: optional_info(&accounts.entangled_mint_destination_record),
+            sysvar_instructions: optional_info(&accounts.sysvar_instructions),
+            authorization_rules_program: optional_info(&accounts.authorization_rules_program),
+            authorization_rules: optional_info(&accounts.entangled_authorization_rules),
+        }
+        .invoke_signed(
+            accounts.entangled_collection.is_programmable(),
+            global_signer_seeds,
+        )?;
+        migrated = true;
+    }
+
+    migrated |= accounts.migrate_authorities(global_signer_seeds)?;
+
+    require!(migrated, EntanglerError::AlreadyMigratedPair);
+
+    Ok(())
+}
+
+impl<'info> MigratePair<'info> {
+    /// Hands every authority the global PDA holds over the entangled mint to the collection's
+    /// authority. Returns whether any authority moved
+    fn migrate_authorities(&self, global_signer_seeds: &[&[&[u8]]]) -> Result<bool> {
+        let global_authority = self.global_authority.key();
+        let new_authority = self.entangler_authority.key();
+        let mint = self.entangled_mint.key();
+
+        // Legacy entangled tokens without a master edition, and Token-2022 ones
+        let mut authority_types = vec![];
+        if self.entangled_mint.mint_authority == COption::Some(global_authority) {
+            authority_types.push(AuthorityType::MintTokens);
+        }
+        if self.entangled_mint.freeze_authority == COption::Some(global_authority) {
+            authority_types.push(AuthorityType::FreezeAccount);
+        }
+
+        let token_2022 = self.token_program.key() == spl_token_2022::ID;
+        let mut token_metadata_authority = None;
+        if token_2022 {
+            let mint_info = self.entangled_mint.to_account_info();
+            let data = mint_info.try_borrow_data()?;
+            let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+            if let Ok(pointer) = mint.get_extension::<MetadataPointer>() {
+                if Option::<Pubkey>::from(pointer.authority) == Some(global_authority) {
+                    authority_types.push(AuthorityType::MetadataPointer);
+                }
+            }
+            if let Ok(hook) = mint.get_extension::<TransferHook>() {
+                if Option::<Pubkey>::from(hook.authority) == Some(global_authority) {
+                    authority_types.push(AuthorityType::TransferHookProgramId);
+                }
+            }
+            if let Ok(token_metadata) = mint.get_variable_len_extension::<TokenMetadata>() {
+                token_metadata_authority = Option::<Pubkey>::from(token_metadata.update_authority);
+            }
+        }
+
+        let mut migrated = !authority_types.is_empty();
+        let mint_infos = [
+            self.entangled_mint.to_account_info(),
+            self.global_authority.to_account_info(),
+            self.token_program.to_account_info(),
+        ];
+        for authority_type in authority_types {
+            invoke_signed(
+                &spl_token_2022::instruction::set_authority(
+                    self.token_program.key,
+                    &mint,
+                    Some(&new_authority),
+                    authority_type,
+                    &global_authority,
+                    &[],
+                )?,
+                &mint_infos,
+                global_signer_seeds,
+            )?;
+        }
+
+        if token_2022 {
+            if token_metadata_authority == Some(global_authority) {
+                invoke_signed(
+                    &token_metadata_instruction::update_authority(
+                        self.token_program.key,
+                        &mint,
+                        &global_authority,
+                        Some(new_authority).try_into()?,
+                    ),
+                    &mint_infos,
+                    global_signer_seeds,
+                )?;
+                migrated = true;
+            }
+            return Ok(migrated);
+        }
+
+        // Metaplex entangled tokens
+        let entangled_metadata = Metadata::from_account_info(&self.entangled_metadata)?;
+        if entangled_metadata.update_authority != global_authority {
+            return Ok(migrated);
+        }
+        let Some(sysvar_instructions) = optional_info(&self.sysvar_instructions) else {
+            return err!(EntanglerError::MissingProgrammableAccounts);
+        };
+        UpdateAuthorityChange {
+            authority: self.global_authority.to_account_info(),
+            mint: self.entangled_mint.to_account_info(),
+            metadata: self.entangled_metadata.to_account_info(),
+            edition: optional_info(&self.entangled_master_edition),
+            payer: self.signer.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            sysvar_instructions,
+            metadata_program: self.metadata_program.to_account_info(),
+        }
+        .invoke_signed(new_authority, global_signer_seeds)?;
+
+        Ok(true)
+    }
+}
+
+#[derive(Accounts)]
+pub struct MigratePair<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// The authority shared by collections created before per-collection authorities
+    /// CHECK: Safe because this read-only account only signs CPIs
+    #[account(
+        seeds = [AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub global_authority: UncheckedAccount<'info>,
+
+    /// The collection's own authority
+    /// CHECK: Safe because this read-only account only gets used as a constraint
+    #[account(
+        address = entangled_collection.authority().key @ EntanglerError::InvalidAuthority,
+    )]
+    pub entangler_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            COLLECTION_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+        ],
+        bump,
+        constraint = entangled_collection.scoped_authority @ EntanglerError::UnmigratedCollection,
+    )]
+    pub entangled_collection: Box<Account<'info, EntangledCollection>>,
+
+    /// The original mint, or the asset id of a compressed original
+    /// CHECK: Only seeds the entangled mint, the escrow constraints check the rest
+    pub original_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&original_mint.key()).0 @ EntanglerError::InvalidMetadata,
+    )]
+    pub original_metadata: UncheckedAccount<'info>,
+
+    /// The edition of the original, for programmable originals
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_master_edition_account(&original_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub original_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// The rule set of the original, for programmable originals
+    /// CHECK: Done by MPL
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// The escrow of the global authority, once the original got entangled
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&global_authority.key(), &original_mint.key(), &original_token_program.key()),
+        token::authority = global_authority,
+        token::token_program = original_token_program,
+    )]
+    pub original_mint_escrow: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(
+            &original_mint.key(),
+            &get_associated_token_address_with_program_id(&global_authority.key(), &original_mint.key(), &original_token_program.key()),
+        ).0,
+    )]
+    pub original_mint_escrow_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Created in the instruction, using constraints
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &original_mint.key(), &original_token_program.key()),
+    )]
+    pub original_mint_destination: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&original_mint.key(), &original_mint_destination.key()).0,
+    )]
+    pub original_mint_destination_record: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [
+          ENTANGLED_MINT_SEED.as_bytes(),
+          &entangled_collection.id.to_bytes(),
+          &original_mint.key().to_bytes()
+        ],
+        bump,
+        mint::token_program = token_program,
+    )]
+    pub entangled_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// The pair of the entangled mint, once entangled, resized if it predates the current layout
+    /// CHECK: Loaded with `load_resized`
+    #[account(
+        mut,
+        seeds = [
+            ENTANGLED_PAIR_SEED.as_bytes(),
+            &entangled_mint.key().to_bytes(),
+        ],
+        bump,
+    )]
+    pub entangled_pair: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_metadata_account(&entangled_mint.key()).0 @ EntanglerError::InvalidMetadata,
+    )]
+    pub entangled_metadata: UncheckedAccount<'info>,
+
+    /// The master edition of the entangled token, for Metaplex collections
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_master_edition_account(&entangled_mint.key()).0 @ EntanglerError::InvalidEdition,
+    )]
+    pub entangled_master_edition: Option<UncheckedAccount<'info>>,
+
+    /// The rule set of the entangled token, for programmable collections
+    /// CHECK: Using constraints
+    #[account(
+        constraint = entangled_collection.rule_set == Some(entangled_authorization_rules.key()) @ EntanglerError::InvalidRuleSet,
+    )]
+    pub entangled_authorization_rules: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&global_authority.key(), &entangled_mint.key(), &token_program.key()),
+        token::mint = entangled_mint,
+        token::authority = global_authority,
+        token::token_program = token_program,
+    )]
+    pub entangled_mint_escrow: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&entangled_mint.key(), &entangled_mint_escrow.key()).0,
+    )]
+    pub entangled_mint_escrow_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Created in the instruction, using constraints
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &entangled_mint.key(), &token_program.key()),
+    )]
+    pub entangled_mint_destination: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        mut,
+        address = mpl_token_metadata::pda::find_token_record_account(&entangled_mint.key(), &entangled_mint_destination.key()).0,
+    )]
+    pub entangled_mint_destination_record: Option<UncheckedAccount<'info>>,
+
+    /// Common Solana programs
+    /// CHECK: CPI
+    #[account(
+        address = mpl_token_metadata::ID
+    )]
+    pub metadata_program: UncheckedAccount<'info>,
+    /// CHECK: CPI
+    #[account(
+        address = mpl_token_auth_rules::ID
+    )]
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: Sysvar
+    #[account(
+        address = sysvar::instructions::ID
+    )]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+    pub original_token_program: Interface<'info, TokenInterface>,
+    #[account(
+        address = entangled_collection.entangled_token_program() @ EntanglerError::InvalidEntangledTokenProgram,
+    )]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,15 +1,57 @@
 mod burn_original;
 mod create_collection;
 mod create_collection_entry;
+mod create_entangled_edition;
+mod create_entangled_tree;
 mod disentangle;
+mod disentangle_batch;
+mod disentangle_compressed;
+mod disentangle_tree_pair;
 mod entangle;
+mod entangle_batch;
+mod entangle_compressed;
+mod entangle_tree_pair;
+mod initialize_and_entangle;
+mod initialize_compressed_pair;
 mod initialize_pair;
+mod initialize_tree_pair;
+mod migrate_collection;
+mod migrate_compressed_original;
+mod migrate_pair;
+mod record_original;
+mod rescue_tokens;
+mod resize_account;
+mod set_collection_verification;
 mod set_entangler_state;
+mod set_records_backfilled;
+mod sweep_authority_lamports;
+mod verify_pair;
 
 pub use burn_original::*;
 pub use create_collection::*;
 pub use create_collection_entry::*;
+pub use create_entangled_edition::*;
+pub use create_entangled_tree::*;
 pub use disentangle::*;
+pub use disentangle_batch::*;
+pub use disentangle_compressed::*;
+pub use disentangle_tree_pair::*;
 pub use entangle::*;
+pub use entangle_batch::*;
+pub use entangle_compressed::*;
+pub use entangle_tree_pair::*;
+pub use initialize_and_entangle::*;
+pub use initialize_compressed_pair::*;
 pub use initialize_pair::*;
+pub use initialize_tree_pair::*;
+pub use migrate_collection::*;
+pub use migrate_compressed_original::*;
+pub use migrate_pair::*;
+pub use record_original::*;
+pub use rescue_tokens::*;
+pub use resize_account::*;
+pub use set_collection_verification::*;
 pub use set_entangler_state::*;
+pub use set_records_backfilled::*;
+pub use sweep_authority_lamports::*;
+pub use verify_pair::*;
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::EntanglerError;
+use crate::seeds::{ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED, ORIGINAL_RECORD_SEED};
+use crate::state::{EntangledCollection, OriginalRecord};
+use crate::utils::load_resized;
+
+pub fn record_original(ctx: Context<RecordOriginal>) -> Result<()> {
+    msg!("Recording the original");
+
+    // Originals missing a record belong to collections that may predate their current layout
+    let entangled_collection = load_resized::<EntangledCollection>(
+        &ctx.accounts.entangled_collection,
+        EntangledCollection::LEN,
+        &ctx.accounts.signer,
+        &ctx.accounts.system_program,
+    )?;
+    let (entangled_mint, _) = Pubkey::find_program_address(
+        &[
+            ENTANGLED_MINT_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+            &ctx.accounts.original_mint.key().to_bytes(),
+        ],
+        &crate::ID,
+    );
+    require_keys_eq!(
+        ctx.accounts.entangled_mint.key(),
+        entangled_mint,
+        ErrorCode::ConstraintSeeds
+    );
+
+    // Pairs own their entangled mint, or only their pair account for tree pairs
+    require!(
+        !ctx.accounts.entangled_mint.data_is_empty()
+            || !ctx.accounts.entangled_pair.data_is_empty(),
+        EntanglerError::UninitializedPair
+    );
+
+    ctx.accounts.original_record.original_mint = ctx.accounts.original_mint.key();
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RecordOriginal<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// The collection of the pair, resized if it predates the current layout
+    /// CHECK: Loaded with `load_resized`
+    #[account(mut)]
+    pub entangled_collection: UncheckedAccount<'info>,
+
+    /// The original mint, or the asset id of a compressed original
+    /// CHECK: Only its key is used, to derive the entangled mint
+    pub original_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Derived in the instruction from the collection's id
+    pub entangled_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Using constraints
+    #[account(
+        seeds = [
+            ENTANGLED_PAIR_SEED.as_bytes(),
+            &entangled_mint.key().to_bytes(),
+        ],
+        bump
+    )]
+    pub entangled_pair: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = OriginalRecord::LEN,
+        seeds = [
+            ORIGINAL_RECORD_SEED.as_bytes(),
+            &original_mint.key().to_bytes(),
+        ],
+        bump
+    )]
+    pub original_record: Box<Account<'info, OriginalRecord>>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_interface::{self, TokenInterface};
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+
+use crate::errors::EntanglerError;
+use crate::seeds::{
+    COLLECTION_SEED, ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED, ORIGINAL_RECORD_SEED, STATE_SEED,
+};
+use crate::state::{EntangledCollection, EntanglerAuthority, EntanglerState};
+
+pub fn rescue_tokens<'info>(
+    ctx: Context<'_, '_, '_, 'info, RescueTokens<'info>>,
+    amount: u64,
+) -> Result<()> {
+    msg!("Rescuing tokens");
+
+    // Tokens of a collection migrating to its own authority may still sit with the global one
+    let collection_authority = ctx.accounts.entangled_collection.authority();
+    let authority = if ctx.accounts.entangler_authority.key() == collection_authority.key {
+        collection_authority
+    } else {
+        EntanglerAuthority::global()
+    };
+
+    // The global authority escrows the originals of every legacy collection, whose pairs
+    // initialized before records can only be told from the named collection's
+    let global = EntanglerAuthority::global().key;
+    require!(
+        authority.key != global || ctx.accounts.state.records_backfilled,
+        EntanglerError::RecordsNotBackfilled
+    );
+    let authority_seeds = authority.seeds();
+    let authority_signer_seeds = &[&authority_seeds[..]];
+
+    // Entangled tokens and entangled collections are controlled by an entangler authority,
+    // either directly or through their metadata
+    let controlled =
+        |key: COption<Pubkey>| key == COption::Some(authority.key) || key == COption::Some(global);
+    let mint = &ctx.accounts.mint;
+    require!(
+        !controlled(mint.mint_authority) && !controlled(mint.freeze_authority),
+        EntanglerError::BackingToken
+    );
+    if mpl_token_metadata::check_id(ctx.accounts.metadata.owner) {
+        let metadata = Metadata::from_account_info(&ctx.accounts.metadata)?;
+        require!(
+            !controlled(COption::Some(metadata.update_authority)),
+            EntanglerError::BackingToken
+        );
+    }
+
+    spl_token_2022::onchain::invoke_transfer_checked(
+        ctx.accounts.token_program.key,
+        ctx.accounts.source.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.destination.to_account_info(),
+        ctx.accounts.entangler_authority.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        mint.decimals,
+        authority_signer_seeds,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RescueTokens<'info> {
+    #[account(
+        constraint = signer.key() == state.admin,
+    )]
+    pub signer: Signer<'info>,
+
+    /// The entangler's state, telling whether the global authority can be rescued from
+    #[account(
+        seeds = [
+            STATE_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub state: Box<Account<'info, EntanglerState>>,
+
+    /// The collection the mint is proven to back no pair of
+    #[account(
+        seeds = [
+            COLLECTION_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+        ],
+        bump,
+    )]
+    pub entangled_collection: Box<Account<'info, EntangledCollection>>,
+
+    /// The collection's authority or the global one, owning the source account
+    /// CHECK: Safe because this read-only account only gets used as a signer
+    #[account(
+        constraint = entangler_authority.key() == entangled_collection.authority().key
+            || entangler_authority.key() == EntanglerAuthority::global().key
+            @ EntanglerError::InvalidAuthority,
+    )]
+    pub entangler_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        address = mpl_token_metadata::pda::find_metadata_account(&mint.key()).0 @ EntanglerError::InvalidMetadata,
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// Exists for the originals of pairs initialized since records, whatever their collection
+    /// CHECK: Using constraints
+    #[account(
+        seeds = [
+            ORIGINAL_RECORD_SEED.as_bytes(),
+            &mint.key().to_bytes(),
+        ],
+        bump,
+        constraint = original_record.data_is_empty() @ EntanglerError::BackingToken,
+    )]
+    pub original_record: UncheckedAccount<'info>,
+
+    /// The entangled mint of the mint as an original of the collection, which exists for its
+    /// pairs, including those initialized before records
+    /// CHECK: Using constraints
+    #[account(
+        seeds = [
+            ENTANGLED_MINT_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+            &mint.key().to_bytes(),
+        ],
+        bump,
+        constraint = entangled_mint.data_is_empty() @ EntanglerError::BackingToken,
+    )]
+    pub entangled_mint: UncheckedAccount<'info>,
+
+    /// The pair of that entangled mint, the only account tree pairs have
+    /// CHECK: Using constraints
+    #[account(
+        seeds = [
+            ENTANGLED_PAIR_SEED.as_bytes(),
+            &entangled_mint.key().to_bytes(),
+        ],
+        bump,
+        constraint = entangled_pair.data_is_empty() @ EntanglerError::BackingToken,
+    )]
+    pub entangled_pair: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = entangler_authority,
+        token::token_program = token_program,
+    )]
+    pub source: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub destination: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
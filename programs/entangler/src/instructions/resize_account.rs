@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::errors::EntanglerError;
+use crate::state::{EntangledCollection, EntangledPair, EntanglerState};
+use crate::utils::resize;
+
+pub fn resize_account(ctx: Context<ResizeAccount>) -> Result<()> {
+    msg!("Resizing the account");
+
+    let account = ctx.accounts.account.to_account_info();
+    let payer = ctx.accounts.signer.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+
+    let discriminator = account.try_borrow_data()?.get(..8).map(<[u8]>::to_vec);
+    match discriminator {
+        Some(d) if d == EntanglerState::discriminator() => {
+            resize::<EntanglerState>(&account, EntanglerState::LEN, &payer, &system_program)?;
+        }
+        Some(d) if d == EntangledCollection::discriminator() => {
+            resize::<EntangledCollection>(
+                &account,
+                EntangledCollection::LEN,
+                &payer,
+                &system_program,
+            )?;
+        }
+        Some(d) if d == EntangledPair::discriminator() => {
+            resize::<EntangledPair>(&account, EntangledPair::LEN, &payer, &system_program)?;
+        }
+        _ => return err!(EntanglerError::UnresizableAccount),
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResizeAccount<'info> {
+    /// Pays for the rent of the new bytes
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// The state, a collection or a pair created with an older, shorter layout
+    /// CHECK: Checked by `resize`
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
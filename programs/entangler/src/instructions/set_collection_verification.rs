@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::seeds::{COLLECTION_SEED, STATE_SEED};
+use crate::state::{EntangledCollection, EntanglerState};
+
+pub fn set_collection_verification(
+    ctx: Context<SetCollectionVerification>,
+    allow_unverified: bool,
+    original_creator: Option<Pubkey>,
+) -> Result<()> {
+    msg!("Setting the collection verification");
+
+    let entangled_collection = &mut ctx.accounts.entangled_collection;
+    entangled_collection.allow_unverified = allow_unverified;
+    entangled_collection.original_creator = original_creator;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetCollectionVerification<'info> {
+    #[account(
+        constraint = signer.key() == state.admin,
+    )]
+    pub signer: Signer<'info>,
+
+    /// The entangler's state
+    #[account(
+        seeds = [
+            STATE_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub state: Box<Account<'info, EntanglerState>>,
+
+    /// The account storing the collection's data
+    #[account(
+        mut,
+        seeds = [
+            COLLECTION_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+        ],
+        bump,
+    )]
+    pub entangled_collection: Account<'info, EntangledCollection>,
+}
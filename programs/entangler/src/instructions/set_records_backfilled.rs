@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::EntanglerError;
+use crate::seeds::STATE_SEED;
+use crate::state::EntanglerState;
+use crate::utils::load_resized;
+
+pub fn set_records_backfilled(
+    ctx: Context<SetRecordsBackfilled>,
+    records_backfilled: bool,
+) -> Result<()> {
+    msg!("Setting the records backfill");
+
+    // The state may predate the flag
+    let mut state = load_resized::<EntanglerState>(
+        &ctx.accounts.state,
+        EntanglerState::LEN,
+        &ctx.accounts.signer,
+        &ctx.accounts.system_program,
+    )?;
+    require_keys_eq!(
+        ctx.accounts.signer.key(),
+        state.admin,
+        EntanglerError::InvalidAdmin
+    );
+
+    state.records_backfilled = records_backfilled;
+    state.exit(&crate::ID)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetRecordsBackfilled<'info> {
+    /// The admin, paying for the state's resize
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// The entangler's state, resized if it predates the current layout
+    /// CHECK: Loaded with `load_resized`
+    #[account(
+        mut,
+        seeds = [
+            STATE_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
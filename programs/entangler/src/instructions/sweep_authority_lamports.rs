@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::system_instruction::transfer;
+
+use crate::errors::EntanglerError;
+use crate::events::AuthorityLamportsSwept;
+use crate::seeds::{COLLECTION_SEED, STATE_SEED};
+use crate::state::{EntangledCollection, EntanglerState};
+use crate::utils::escrow_authority;
+
+pub fn sweep_authority_lamports(ctx: Context<SweepAuthorityLamports>) -> Result<()> {
+    msg!("Sweeping the authority's lamports");
+
+    let authority = escrow_authority(&ctx.accounts.entangled_collection);
+    let authority_seeds = authority.seeds();
+    let authority_signer_seeds = &[&authority_seeds[..]];
+
+    // The authority keeps the rent exemption of its own data, if any
+    let entangler_authority = &ctx.accounts.entangler_authority;
+    let needed = Rent::get()?.minimum_balance(entangler_authority.data_len());
+    let amount = entangler_authority.lamports().saturating_sub(needed);
+    if amount == 0 {
+        return Ok(());
+    }
+
+    invoke_signed(
+        &transfer(entangler_authority.key, ctx.accounts.earner.key, amount),
+        &[
+            entangler_authority.to_account_info(),
+            ctx.accounts.earner.to_account_info(),
+        ],
+        authority_signer_seeds,
+    )?;
+
+    emit!(AuthorityLamportsSwept {
+        authority: authority.key,
+        earner: ctx.accounts.earner.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SweepAuthorityLamports<'info> {
+    #[account(
+        constraint = signer.key() == state.admin,
+    )]
+    pub signer: Signer<'info>,
+
+    /// The entangler's state
+    #[account(
+        seeds = [
+            STATE_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub state: Box<Account<'info, EntanglerState>>,
+
+    /// The collection whose authority gets swept, none for the global authority
+    #[account(
+        seeds = [
+            COLLECTION_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+        ],
+        bump,
+    )]
+    pub entangled_collection: Option<Box<Account<'info, EntangledCollection>>>,
+
+    /// CHECK: Safe because this account only gets used as the signer of the transfer
+    #[account(
+        mut,
+        address = escrow_authority(&entangled_collection).key @ EntanglerError::InvalidAuthority,
+    )]
+    pub entangler_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Safe because the address is the state's earner
+    #[account(
+        mut,
+        address = state.earner,
+    )]
+    pub earner: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
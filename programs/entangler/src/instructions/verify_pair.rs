@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{self, TokenInterface};
+
+use crate::errors::EntanglerError;
+use crate::seeds::{COLLECTION_SEED, ENTANGLED_MINT_SEED, ENTANGLED_PAIR_SEED};
+use crate::state::{EntangledCollection, EntangledPair, PairCustody};
+use crate::utils::optional_info;
+
+pub fn verify_pair(ctx: Context<VerifyPair>) -> Result<PairCustody> {
+    msg!("Verifying the pair");
+
+    let accounts = &ctx.accounts;
+    let authority = accounts.entangler_authority.key();
+
+    // The asset id of a compressed original holds no account
+    require!(
+        !accounts.original_mint.data_is_empty(),
+        EntanglerError::CompressedOriginal
+    );
+    let original_mint =
+        InterfaceAccount::<token_interface::Mint>::try_from(&accounts.original_mint)?;
+    require_keys_eq!(
+        *accounts.original_mint.owner,
+        accounts.original_token_program.key(),
+        ErrorCode::ConstraintMintTokenProgram
+    );
+    require!(
+        original_mint.supply == 1 && accounts.entangled_mint.supply == 1,
+        EntanglerError::InvalidSupply
+    );
+
+    // Pairs get created when first entangled, before which the entangler holds the entangled token
+    let frozen_account = load_pair(&accounts.entangled_pair)?.and_then(|pair| pair.frozen_account);
+
+    // Non-custodial collections keep entangled originals frozen in their owner's account
+    let original_custody = match frozen_account {
+        Some(frozen_account) => {
+            let Some(token) = optional_info(&accounts.frozen_account) else {
+                return err!(EntanglerError::InvalidFrozenAccount);
+            };
+            require_keys_eq!(
+                token.key(),
+                frozen_account,
+                EntanglerError::InvalidFrozenAccount
+            );
+            token
+        }
+        None => accounts.original_mint_escrow.to_account_info(),
+    };
+    let original_held = holds(
+        &original_custody,
+        &accounts.original_mint.key(),
+        &authority,
+    )?;
+    let entangled_held = holds(
+        &accounts.entangled_mint_escrow,
+        &accounts.entangled_mint.key(),
+        &authority,
+    )?;
+    require!(original_held != entangled_held, EntanglerError::BrokenCustody);
+
+    let custody_account = if original_held {
+        original_custody.key()
+    } else {
+        accounts.entangled_mint_escrow.key()
+    };
+
+    Ok(PairCustody {
+        original_mint: accounts.original_mint.key(),
+        entangled_mint: accounts.entangled_mint.key(),
+        authority,
+        entangled: original_held,
+        custody_account,
+    })
+}
+
+/// Reads a pair whatever its layout, since older layouts are prefixes of the current one. None
+/// until the pair gets entangled
+fn load_pair(account: &AccountInfo) -> Result<Option<EntangledPair>> {
+    if account.data_is_empty() {
+        return Ok(None);
+    }
+
+    let mut data = account.try_borrow_data()?.to_vec();
+    data.resize(data.len().max(EntangledPair::LEN), 0);
+    Ok(Some(EntangledPair::try_deserialize(&mut &data[..])?))
+}
+
+/// Whether a token account holds the token for the authority, either as its owner or
+/// as the delegate it is frozen for. Accounts that don't exist hold nothing
+fn holds(account: &AccountInfo, mint: &Pubkey, authority: &Pubkey) -> Result<bool> {
+    if account.data_is_empty() {
+        return Ok(false);
+    }
+
+    let token =
+        token_interface::TokenAccount::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    let held_for_authority = token.owner == *authority
+        || (token.is_frozen() && token.delegate == COption::Some(*authority));
+
+    Ok(token.mint == *mint && token.amount == 1 && held_for_authority)
+}
+
+#[derive(Accounts)]
+pub struct VerifyPair<'info> {
+    /// CHECK: Safe because this read-only account only gets used as a constraint
+    #[account(
+        address = entangled_collection.authority().key @ EntanglerError::InvalidAuthority,
+    )]
+    pub entangler_authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            COLLECTION_SEED.as_bytes(),
+            &entangled_collection.id.to_bytes(),
+        ],
+        bump,
+        constraint = !entangled_collection.is_compressed() @ EntanglerError::CompressedCollection,
+    )]
+    pub entangled_collection: Account<'info, EntangledCollection>,
+
+    /// The pair, which doesn't exist until the original gets entangled
+    /// CHECK: Using constraints, read whatever its layout
+    #[account(
+        seeds = [
+            ENTANGLED_PAIR_SEED.as_bytes(),
+            &entangled_mint.key().to_bytes(),
+        ],
+        bump
+    )]
+    pub entangled_pair: UncheckedAccount<'info>,
+
+    /// The original mint, or the asset id of a compressed original, which gets rejected
+    /// CHECK: Deserialized in the instruction, once compressed originals are ruled out
+    pub original_mint: UncheckedAccount<'info>,
+
+    /// The escrow of the original, which doesn't exist until the original gets entangled
+    /// CHECK: Using constraints
+    #[account(
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &original_mint.key(), &original_token_program.key()),
+    )]
+    pub original_mint_escrow: UncheckedAccount<'info>,
+
+    /// The token account the original is frozen in, for non-custodial collections
+    /// CHECK: Checked against the pair
+    pub frozen_account: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        seeds = [
+          ENTANGLED_MINT_SEED.as_bytes(),
+          &entangled_collection.id.to_bytes(),
+          &original_mint.key().to_bytes()
+        ],
+        bump,
+        mint::token_program = token_program,
+    )]
+    pub entangled_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+    /// CHECK: Using constraints
+    #[account(
+        address = get_associated_token_address_with_program_id(&entangler_authority.key(), &entangled_mint.key(), &token_program.key()),
+    )]
+    pub entangled_mint_escrow: UncheckedAccount<'info>,
+
+    /// Common Solana programs
+    pub original_token_program: Interface<'info, TokenInterface>,
+    #[account(
+        address = entangled_collection.entangled_token_program() @ EntanglerError::InvalidEntangledTokenProgram,
+    )]
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -1,10 +1,16 @@
+#![allow(clippy::result_large_err)]
+
 use anchor_lang::prelude::*;
 
+pub mod errors;
+pub mod events;
 mod instructions;
-mod seeds;
-mod state;
+pub mod seeds;
+pub mod state;
+mod utils;
 
 use instructions::*;
+use state::{EntangledStandard, PairCustody};
 
 declare_id!("ABseVbbB9Dd2NaonudphxWJWc3Hq12C7PjGQ89HRkPaB");
 
@@ -24,13 +30,47 @@ pub mod entangler {
 
     /// Creates an entangled collection from an existing collection.
     /// No need to have authority over the original collection
+    #[allow(clippy::too_many_arguments)]
     pub fn create_collection(
         ctx: Context<CreateCollection>,
         id: Pubkey,
         royalties: u16,
         one_way: bool,
+        allow_unverified: bool,
+        original_creator: Option<Pubkey>,
+        entangled_standard: EntangledStandard,
+        rule_set: Option<Pubkey>,
+        transfer_hook_program: Option<Pubkey>,
+        non_custodial: bool,
+    ) -> Result<()> {
+        instructions::create_collection(
+            ctx,
+            id,
+            royalties,
+            one_way,
+            allow_unverified,
+            original_creator,
+            entangled_standard,
+            rule_set,
+            transfer_hook_program,
+            non_custodial,
+        )
+    }
+
+    /// Sets how strictly originals are checked against the collection
+    pub fn set_collection_verification(
+        ctx: Context<SetCollectionVerification>,
+        allow_unverified: bool,
+        original_creator: Option<Pubkey>,
     ) -> Result<()> {
-        instructions::create_collection(ctx, id, royalties, one_way)
+        instructions::set_collection_verification(ctx, allow_unverified, original_creator)
+    }
+
+    /// Grows the state, a collection or a pair created with an older layout to the current one,
+    /// so that it can be deserialized again. Fields added since take their default, so resized
+    /// collections only accept verified originals until the admin opts them out. Permissionless
+    pub fn resize_account(ctx: Context<ResizeAccount>) -> Result<()> {
+        instructions::resize_account(ctx)
     }
 
     /// Creates an entry in the collection map
@@ -43,18 +83,192 @@ pub mod entangler {
         instructions::initialize_pair(ctx)
     }
 
+    /// Creates the missing master edition of an entangled token, for pairs
+    /// initialized before entangled tokens got one. Resizes a legacy collection on the way
+    pub fn create_entangled_edition(ctx: Context<CreateEntangledEdition>) -> Result<()> {
+        instructions::create_entangled_edition(ctx)
+    }
+
     /// Swap from the original token to the entangled one
-    pub fn entangle(ctx: Context<Entangle>) -> Result<()> {
+    pub fn entangle<'info>(ctx: Context<'_, '_, '_, 'info, Entangle<'info>>) -> Result<()> {
         instructions::entangle(ctx)
     }
 
+    /// Swaps many original tokens at once. The remaining accounts hold one group of
+    /// `entangle` accounts per token, each followed by its `hook_accounts` transfer hook accounts
+    pub fn entangle_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, EntangleBatch<'info>>,
+        hook_accounts: Vec<u8>,
+    ) -> Result<()> {
+        instructions::entangle_batch(ctx, hook_accounts)
+    }
+
+    /// Creates the entanglement pair on first use and swaps in the same instruction
+    pub fn initialize_and_entangle<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeAndEntangle<'info>>,
+    ) -> Result<()> {
+        instructions::initialize_and_entangle(ctx)
+    }
+
     /// Swap from the entangled token to the original one
-    pub fn disentangle(ctx: Context<Disentangle>) -> Result<()> {
+    pub fn disentangle<'info>(ctx: Context<'_, '_, '_, 'info, Disentangle<'info>>) -> Result<()> {
         instructions::disentangle(ctx)
     }
 
+    /// Swaps many entangled tokens back at once. The remaining accounts hold one group of
+    /// `disentangle` accounts per token, each followed by its `hook_accounts` transfer hook accounts
+    pub fn disentangle_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, DisentangleBatch<'info>>,
+        hook_accounts: Vec<u8>,
+    ) -> Result<()> {
+        instructions::disentangle_batch(ctx, hook_accounts)
+    }
+
+    /// Creates an entanglement pair for a compressed token of the collection.
+    /// The remaining accounts hold the merkle proof of the leaf
+    pub fn initialize_compressed_pair<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeCompressedPair<'info>>,
+        root: [u8; 32],
+        metadata: Vec<u8>,
+        nonce: u64,
+        index: u32,
+    ) -> Result<()> {
+        instructions::initialize_compressed_pair(ctx, root, metadata, nonce, index)
+    }
+
+    /// Swap from the compressed original token to the entangled one.
+    /// The remaining accounts hold the first `proof_len` nodes of the merkle proof,
+    /// followed by the transfer hook accounts of the entangled mint
+    pub fn entangle_compressed<'info>(
+        ctx: Context<'_, '_, '_, 'info, EntangleCompressed<'info>>,
+        root: [u8; 32],
+        metadata: Vec<u8>,
+        nonce: u64,
+        index: u32,
+        proof_len: u8,
+    ) -> Result<()> {
+        instructions::entangle_compressed(ctx, root, metadata, nonce, index, proof_len)
+    }
+
+    /// Swap from the entangled token to the compressed original one.
+    /// The remaining accounts hold the first `proof_len` nodes of the merkle proof,
+    /// followed by the transfer hook accounts of the entangled mint
+    pub fn disentangle_compressed<'info>(
+        ctx: Context<'_, '_, '_, 'info, DisentangleCompressed<'info>>,
+        root: [u8; 32],
+        metadata: Vec<u8>,
+        nonce: u64,
+        index: u32,
+        proof_len: u8,
+    ) -> Result<()> {
+        instructions::disentangle_compressed(ctx, root, metadata, nonce, index, proof_len)
+    }
+
+    /// Creates the tree holding the entangled tokens of a compressed collection, once. The merkle
+    /// tree must be allocated beforehand for the compression program, and hold a leaf for every
+    /// original of a sized collection. Admin or collection creator only
+    pub fn create_entangled_tree(
+        ctx: Context<CreateEntangledTree>,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Result<()> {
+        instructions::create_entangled_tree(ctx, max_depth, max_buffer_size)
+    }
+
+    /// Creates an entanglement pair whose entangled token is a leaf of the collection's tree
+    pub fn initialize_tree_pair(ctx: Context<InitializeTreePair>) -> Result<()> {
+        instructions::initialize_tree_pair(ctx)
+    }
+
+    /// Swap from the original token to the compressed entangled one.
+    /// The remaining accounts hold the first `proof_len` nodes of the merkle proof,
+    /// followed by the transfer hook accounts of the original mint
+    pub fn entangle_tree_pair<'info>(
+        ctx: Context<'_, '_, '_, 'info, EntangleTreePair<'info>>,
+        root: [u8; 32],
+        metadata: Vec<u8>,
+        nonce: u64,
+        index: u32,
+        proof_len: u8,
+    ) -> Result<()> {
+        instructions::entangle_tree_pair(ctx, root, metadata, nonce, index, proof_len)
+    }
+
+    /// Swap from the compressed entangled token to the original one.
+    /// The remaining accounts hold the first `proof_len` nodes of the merkle proof,
+    /// followed by the transfer hook accounts of the original mint
+    pub fn disentangle_tree_pair<'info>(
+        ctx: Context<'_, '_, '_, 'info, DisentangleTreePair<'info>>,
+        root: [u8; 32],
+        metadata: Vec<u8>,
+        nonce: u64,
+        index: u32,
+        proof_len: u8,
+    ) -> Result<()> {
+        instructions::disentangle_tree_pair(ctx, root, metadata, nonce, index, proof_len)
+    }
+
     /// Burn original token but prevents future disentanglement
     pub fn burn_original(ctx: Context<BurnOriginal>) -> Result<()> {
         instructions::burn_original(ctx)
     }
+
+    /// Moves a collection from the global authority to its own one. Pairs then need
+    /// `migrate_pair` before they can be swapped again
+    pub fn migrate_collection(ctx: Context<MigrateCollection>) -> Result<()> {
+        instructions::migrate_collection(ctx)
+    }
+
+    /// Moves the escrowed token and the authorities of a pair to its collection's authority.
+    /// The remaining accounts hold the transfer hook accounts of the escrowed token
+    pub fn migrate_pair<'info>(ctx: Context<'_, '_, '_, 'info, MigratePair<'info>>) -> Result<()> {
+        instructions::migrate_pair(ctx)
+    }
+
+    /// Moves an escrowed compressed original to its collection's authority.
+    /// The remaining accounts hold the merkle proof of the leaf
+    pub fn migrate_compressed_original<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateCompressedOriginal<'info>>,
+        root: [u8; 32],
+        metadata: Vec<u8>,
+        nonce: u64,
+        index: u32,
+    ) -> Result<()> {
+        instructions::migrate_compressed_original(ctx, root, metadata, nonce, index)
+    }
+
+    /// Checks that the entangler holds exactly one token of a pair, and returns its custody.
+    /// Permissionless, for auditors and other programs
+    pub fn verify_pair(ctx: Context<VerifyPair>) -> Result<PairCustody> {
+        instructions::verify_pair(ctx)
+    }
+
+    /// Records an original initialized before originals got a record, so that it can't be
+    /// rescued. Permissionless
+    pub fn record_original(ctx: Context<RecordOriginal>) -> Result<()> {
+        instructions::record_original(ctx)
+    }
+
+    /// Declares whether every original initialized before original records existed got one
+    /// from `record_original`, which rescues from the global authority require. Admin only
+    pub fn set_records_backfilled(
+        ctx: Context<SetRecordsBackfilled>,
+        records_backfilled: bool,
+    ) -> Result<()> {
+        instructions::set_records_backfilled(ctx, records_backfilled)
+    }
+
+    /// Moves tokens sent by mistake out of an account of an entangler authority, as long as
+    /// they back no pair. The remaining accounts hold the transfer hook accounts of the mint
+    pub fn rescue_tokens<'info>(
+        ctx: Context<'_, '_, '_, 'info, RescueTokens<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::rescue_tokens(ctx, amount)
+    }
+
+    /// Moves the lamports an entangler authority holds beyond its rent exemption to the earner
+    pub fn sweep_authority_lamports(ctx: Context<SweepAuthorityLamports>) -> Result<()> {
+        instructions::sweep_authority_lamports(ctx)
+    }
 }
@@ -18,5 +18,11 @@ pub const ENTANGLED_MINT_SEED: &str = "entanglement-mint";
 #[constant]
 pub const ENTANGLED_PAIR_SEED: &str = "entanglement-pair";
 
+#[constant]
+pub const ORIGINAL_RECORD_SEED: &str = "original-record";
+
 #[constant]
 pub const AUTHORITY_SEED: &str = "authority";
+
+#[constant]
+pub const COLLECTION_AUTHORITY_SEED: &str = "collection-authority";
@@ -1,4 +1,10 @@
 use anchor_lang::prelude::*;
+use anchor_spl::{token, token_2022};
+use mpl_bubblegum::types::MetadataArgs;
+use mpl_token_metadata::state::Metadata;
+
+use crate::errors::EntanglerError;
+use crate::seeds::{AUTHORITY_SEED, COLLECTION_AUTHORITY_SEED};
 
 #[account]
 pub struct EntanglerState {
@@ -13,10 +19,14 @@ pub struct EntanglerState {
 
     /// The cost to create an entry
     pub price: u64,
+
+    /// Whether every original initialized before original records existed got one, so that a
+    /// missing record proves a token held by the global authority backs no pair
+    pub records_backfilled: bool,
 }
 
 impl EntanglerState {
-    pub const LEN: usize = 8 + 3 * 32 + 8;
+    pub const LEN: usize = 8 + 3 * 32 + 8 + 1;
 }
 
 #[account]
@@ -35,10 +45,179 @@ pub struct EntangledCollection {
 
     /// Whether it is possible to disentangle
     pub one_way: bool,
+
+    /// Whether originals with an unverified collection are accepted (legacy collections)
+    pub allow_unverified: bool,
+
+    /// A creator that must be verified on every original
+    pub original_creator: Option<Pubkey>,
+
+    /// The token standard of the entangled tokens
+    pub entangled_standard: EntangledStandard,
+
+    /// The rule set enforced on programmable entangled tokens
+    pub rule_set: Option<Pubkey>,
+
+    /// The transfer hook program of Token-2022 entangled tokens. The `royalty-hook` program makes
+    /// every transfer outside the entangler's escrows pay royalties first, on a declared price no
+    /// lower than the minimum the creator set
+    pub transfer_hook_program: Option<Pubkey>,
+
+    /// The Bubblegum tree holding compressed entangled tokens
+    pub entangled_tree: Option<Pubkey>,
+
+    /// Whether originals stay frozen in their owner's account instead of an escrow
+    pub non_custodial: bool,
+
+    /// Whether escrows and authorities belong to the collection's own PDA instead of the
+    /// global one. False for collections created before per-collection authorities
+    pub scoped_authority: bool,
 }
 
 impl EntangledCollection {
-    pub const LEN: usize = 8 + 3 * 32 + 2 + 1;
+    pub const LEN: usize = 8 + 3 * 32 + 2 + 1 + 1 + (1 + 32) + 1 + 3 * (1 + 32) + 1 + 1;
+
+    /// The PDA holding the collection's escrows and the authorities of its entangled tokens
+    pub fn authority(&self) -> EntanglerAuthority {
+        if self.scoped_authority {
+            EntanglerAuthority::collection(&self.id)
+        } else {
+            EntanglerAuthority::global()
+        }
+    }
+
+    /// Checks that a token's metadata proves its membership to the original collection
+    pub fn check_original(&self, metadata: &Metadata) -> Result<()> {
+        let collection = metadata
+            .collection
+            .as_ref()
+            .ok_or(EntanglerError::InvalidCollection)?;
+        let creators = metadata.data.creators.iter().flatten();
+        self.check_membership(
+            collection.key,
+            collection.verified,
+            creators.map(|creator| (creator.address, creator.verified)),
+        )
+    }
+
+    /// Checks that a compressed token's metadata proves its membership to the original collection
+    pub fn check_compressed_original(&self, metadata: &MetadataArgs) -> Result<()> {
+        let collection = metadata
+            .collection
+            .as_ref()
+            .ok_or(EntanglerError::InvalidCollection)?;
+        let creators = metadata.creators.iter();
+        self.check_membership(
+            collection.key,
+            collection.verified,
+            creators.map(|creator| (creator.address, creator.verified)),
+        )
+    }
+
+    fn check_membership(
+        &self,
+        collection: Pubkey,
+        collection_verified: bool,
+        mut creators: impl Iterator<Item = (Pubkey, bool)>,
+    ) -> Result<()> {
+        require_keys_eq!(
+            collection,
+            self.original_collection_mint,
+            EntanglerError::InvalidCollection
+        );
+        require!(
+            collection_verified || self.allow_unverified,
+            EntanglerError::UnverifiedCollection
+        );
+
+        if let Some(original_creator) = self.original_creator {
+            let verified =
+                creators.any(|(address, verified)| address == original_creator && verified);
+            require!(verified, EntanglerError::UnverifiedCreator);
+        }
+
+        Ok(())
+    }
+
+    /// Whether entangled tokens must be moved through Token Metadata
+    pub fn is_programmable(&self) -> bool {
+        self.entangled_standard == EntangledStandard::ProgrammableNonFungible
+    }
+
+    /// Whether entangled tokens are leaves of the collection's tree
+    pub fn is_compressed(&self) -> bool {
+        self.entangled_standard == EntangledStandard::Compressed
+    }
+
+    /// The token program owning the entangled mints
+    pub fn entangled_token_program(&self) -> Pubkey {
+        match self.entangled_standard {
+            EntangledStandard::Token2022 => token_2022::ID,
+            _ => token::ID,
+        }
+    }
+}
+
+/// A PDA signing for the entangler, either global or scoped to a collection
+pub struct EntanglerAuthority {
+    pub key: Pubkey,
+    id: Option<Pubkey>,
+    bump: [u8; 1],
+}
+
+impl EntanglerAuthority {
+    /// The authority shared by collections created before per-collection authorities
+    pub fn global() -> Self {
+        let (key, bump) = Pubkey::find_program_address(&[AUTHORITY_SEED.as_bytes()], &crate::ID);
+        Self {
+            key,
+            id: None,
+            bump: [bump],
+        }
+    }
+
+    /// The authority of a single collection
+    pub fn collection(id: &Pubkey) -> Self {
+        let (key, bump) = Pubkey::find_program_address(
+            &[COLLECTION_AUTHORITY_SEED.as_bytes(), id.as_ref()],
+            &crate::ID,
+        );
+        Self {
+            key,
+            id: Some(*id),
+            bump: [bump],
+        }
+    }
+
+    /// The seeds to sign with
+    pub fn seeds(&self) -> Vec<&[u8]> {
+        match &self.id {
+            Some(id) => vec![
+                COLLECTION_AUTHORITY_SEED.as_bytes(),
+                id.as_ref(),
+                &self.bump,
+            ],
+            None => vec![AUTHORITY_SEED.as_bytes(), &self.bump],
+        }
+    }
+}
+
+/// How the entangled tokens of a collection are minted
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EntangledStandard {
+    /// Plain Metaplex NFTs
+    NonFungible,
+
+    /// Programmable NFTs enforcing the collection's rule set
+    ProgrammableNonFungible,
+
+    /// Token-2022 mints holding their own metadata, with the collection's transfer hook if any.
+    /// Their royalties are only collected if that hook is the royalty hook, and their supply is
+    /// capped at one
+    Token2022,
+
+    /// Bubblegum leaves of a tree owned by the entangler
+    Compressed,
 }
 
 pub const MAX_KEY_SIZE: usize = 32;
@@ -58,13 +237,51 @@ impl CollectionEntry {
 
 #[account]
 pub struct EntangledPair {
-    /// The original collection mint
+    /// The original mint, or the asset id of a compressed original
     pub original_mint: Pubkey,
 
-    /// The mint of the entangled tokens
+    /// The mint of the entangled tokens, or the asset id of a compressed entangled token
     pub entangled_mint: Pubkey,
+
+    /// The token account the original is frozen in, for non-custodial collections
+    pub frozen_account: Option<Pubkey>,
+
+    /// Whether the global authority no longer escrows the original, because the pair got migrated
+    /// or got entangled by the collection's own authority
+    pub migrated: bool,
 }
 
 impl EntangledPair {
-    pub const LEN: usize = 8 + 2 * 32;
+    pub const LEN: usize = 8 + 2 * 32 + (1 + 32) + 1;
+}
+
+/// Marks a mint as the original of an initialized pair, so that it can't be rescued from
+/// an escrow whatever collection it was entangled into
+#[account]
+pub struct OriginalRecord {
+    /// The original mint
+    pub original_mint: Pubkey,
+}
+
+impl OriginalRecord {
+    pub const LEN: usize = 8 + 32;
+}
+
+/// The custody of a pair, as returned by `verify_pair`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PairCustody {
+    /// The original mint
+    pub original_mint: Pubkey,
+
+    /// The mint of the entangled token
+    pub entangled_mint: Pubkey,
+
+    /// The PDA holding the token
+    pub authority: Pubkey,
+
+    /// Whether the entangler holds the original rather than the entangled token
+    pub entangled: bool,
+
+    /// The escrow holding the token, or the account the original is frozen in
+    pub custody_account: Pubkey,
 }
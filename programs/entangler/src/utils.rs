@@ -0,0 +1,549 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface;
+use mpl_bubblegum::accounts::TreeConfig;
+use mpl_bubblegum::hash::{hash_creators, hash_metadata};
+use mpl_bubblegum::instructions::{TransferCpi, TransferCpiAccounts, TransferInstructionArgs};
+use mpl_bubblegum::programs::SPL_ACCOUNT_COMPRESSION_ID;
+use mpl_bubblegum::types::{
+    CompressionAccountType, ConcurrentMerkleTreeHeader, ConcurrentMerkleTreeHeaderData,
+    MetadataArgs,
+};
+use mpl_token_metadata::instruction::builders::{TransferBuilder, UpdateBuilder};
+use mpl_token_metadata::instruction::{
+    create_master_edition_v3, freeze_delegated_account, thaw_delegated_account, InstructionBuilder,
+    TransferArgs, UpdateArgs,
+};
+use mpl_token_metadata::state::{
+    CollectionDetails, Edition, Metadata, TokenMetadataAccount, TokenStandard,
+};
+use spl_token_2022::extension::non_transferable::NonTransferable;
+use spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+
+use crate::errors::EntanglerError;
+use crate::state::{EntangledCollection, EntangledPair, EntanglerAuthority};
+
+/// Whether a token must be moved through Token Metadata instead of SPL Token
+pub fn is_programmable(metadata: &Metadata) -> bool {
+    matches!(
+        metadata.token_standard,
+        Some(TokenStandard::ProgrammableNonFungible)
+    )
+}
+
+/// The print edition stored at an original's edition address, if it is one
+pub fn print_edition(edition: &AccountInfo) -> Option<Edition> {
+    if !mpl_token_metadata::check_id(edition.owner) {
+        return None;
+    }
+    Edition::from_account_info(edition).ok()
+}
+
+/// Rejects Token-2022 originals that can't be escrowed one for one
+pub fn check_transferable(mint: &AccountInfo) -> Result<()> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    require!(
+        mint.get_extension::<NonTransferable>().is_err(),
+        EntanglerError::NonTransferableOriginal
+    );
+    if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() {
+        let fee = transfer_fee_config.calculate_epoch_fee(Clock::get()?.epoch, 1);
+        require!(fee == Some(0), EntanglerError::TransferFeeOriginal);
+    }
+
+    Ok(())
+}
+
+/// Deserializes the Bubblegum metadata of a compressed original
+pub fn compressed_metadata(data: &[u8]) -> Result<MetadataArgs> {
+    MetadataArgs::try_from_slice(data)
+        .map_err(|_| error!(EntanglerError::InvalidCompressedMetadata))
+}
+
+/// The data and creator hashes of a compressed original's leaf
+pub fn compressed_hashes(metadata: &MetadataArgs) -> Result<([u8; 32], [u8; 32])> {
+    let data_hash =
+        hash_metadata(metadata).map_err(|_| error!(EntanglerError::InvalidCompressedMetadata))?;
+    Ok((data_hash, hash_creators(&metadata.creators)))
+}
+
+/// Rejects merkle trees whose leaves weren't minted through Bubblegum
+pub fn check_bubblegum_tree(merkle_tree: &AccountInfo) -> Result<()> {
+    require_keys_eq!(
+        *merkle_tree.owner,
+        SPL_ACCOUNT_COMPRESSION_ID,
+        EntanglerError::InvalidMerkleTree
+    );
+
+    let data = merkle_tree.try_borrow_data()?;
+    let header = ConcurrentMerkleTreeHeader::deserialize(&mut &data[..])
+        .map_err(|_| error!(EntanglerError::InvalidMerkleTree))?;
+    let ConcurrentMerkleTreeHeaderData::V1 { authority, .. } = header.header;
+    require!(
+        header.account_type == CompressionAccountType::ConcurrentMerkleTree
+            && authority == TreeConfig::find_pda(merkle_tree.key).0,
+        EntanglerError::InvalidMerkleTree
+    );
+
+    Ok(())
+}
+
+/// The creator receiving the royalties of an entangled collection, listed after its authority
+pub fn collection_creator(metadata: &AccountInfo) -> Result<Pubkey> {
+    let metadata = Metadata::from_account_info(metadata)?;
+    metadata
+        .data
+        .creators
+        .and_then(|creators| creators.get(1).map(|creator| creator.address))
+        .ok_or_else(|| error!(EntanglerError::MissingCollectionCreator))
+}
+
+/// The number of tokens in a sized collection, none for unsized ones
+#[allow(deprecated)]
+pub fn collection_size(metadata: &Metadata) -> Option<u64> {
+    metadata
+        .collection_details
+        .as_ref()
+        .map(|CollectionDetails::V1 { size }| *size)
+}
+
+/// The authority of an optional collection, the global one when there is none
+pub fn escrow_authority(
+    entangled_collection: &Option<Box<Account<EntangledCollection>>>,
+) -> EntanglerAuthority {
+    entangled_collection
+        .as_ref()
+        .map_or_else(EntanglerAuthority::global, |collection| {
+            collection.authority()
+        })
+}
+
+/// The balance of a token account of either token program, zero until it gets created
+pub fn token_balance(account: &AccountInfo) -> Result<u64> {
+    if account.data_is_empty() {
+        return Ok(0);
+    }
+    let data = account.try_borrow_data()?;
+    let token = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    Ok(token.base.amount)
+}
+
+/// Whether the original the global authority escrows belongs to a pair, rather than to the same
+/// original entangled into another collection: the pair predates its migration and its entangled
+/// token circulates, out of the escrows of both authorities
+pub fn escrows_original(
+    pair: &EntangledPair,
+    entangled_mint: &InterfaceAccount<token_interface::Mint>,
+    entangled_escrows: [&AccountInfo; 2],
+) -> Result<bool> {
+    if pair.migrated || entangled_mint.supply != 1 {
+        return Ok(false);
+    }
+    for escrow in entangled_escrows {
+        if token_balance(escrow)? > 0 {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Grows an account of `T` created with an older, shorter layout to `len`, topping up its rent
+/// from `payer`. The new bytes are zeroed, so every field added since reads as its default.
+/// Returns whether the account grew
+pub fn resize<'info, T: Discriminator>(
+    account: &AccountInfo<'info>,
+    len: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<bool> {
+    require_keys_eq!(
+        *account.owner,
+        crate::ID,
+        ErrorCode::AccountOwnedByWrongProgram
+    );
+    require!(
+        account.try_borrow_data()?.get(..8) == Some(&T::discriminator()[..]),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    if account.data_len() >= len {
+        return Ok(false);
+    }
+
+    let missing = Rent::get()?
+        .minimum_balance(len)
+        .saturating_sub(account.lamports());
+    if missing > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            missing,
+        )?;
+    }
+    account.realloc(len, true)?;
+
+    Ok(true)
+}
+
+/// Loads an account of `T` that may predate its current layout, growing it to `len` first.
+/// Anchor can't deserialize older layouts, so such accounts come in as `UncheckedAccount`s and
+/// `resize` checks their owner and discriminator instead
+pub fn load_resized<'info, T>(
+    account: &AccountInfo<'info>,
+    len: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<Account<'info, T>>
+where
+    T: AccountSerialize + AccountDeserialize + Owner + Clone + Discriminator,
+{
+    resize::<T>(account, len, payer, system_program)?;
+    Account::try_from(account)
+}
+
+/// Turns an optional account into an optional `AccountInfo`
+pub fn optional_info<'info, T: ToAccountInfo<'info>>(
+    account: &Option<T>,
+) -> Option<AccountInfo<'info>> {
+    account.as_ref().map(|a| a.to_account_info())
+}
+
+/// The accounts needed to move an NFT, whatever its token standard
+pub struct NftTransfer<'info> {
+    pub token: AccountInfo<'info>,
+    pub token_owner: AccountInfo<'info>,
+    pub destination: AccountInfo<'info>,
+    pub destination_owner: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub metadata: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub metadata_program: AccountInfo<'info>,
+
+    /// Extra accounts required by a Token-2022 transfer hook
+    pub hook_accounts: Vec<AccountInfo<'info>>,
+
+    /// Programmable NFTs only
+    pub edition: Option<AccountInfo<'info>>,
+    pub owner_token_record: Option<AccountInfo<'info>>,
+    pub destination_token_record: Option<AccountInfo<'info>>,
+    pub sysvar_instructions: Option<AccountInfo<'info>>,
+    pub authorization_rules_program: Option<AccountInfo<'info>>,
+    pub authorization_rules: Option<AccountInfo<'info>>,
+}
+
+impl<'info> NftTransfer<'info> {
+    /// Moves one token, signing with `signer_seeds` when the authority is a PDA
+    pub fn invoke_signed(self, programmable: bool, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        if !programmable {
+            spl_token_2022::onchain::invoke_transfer_checked(
+                self.token_program.key,
+                self.token,
+                self.mint,
+                self.destination,
+                self.authority,
+                &self.hook_accounts,
+                1,
+                0,
+                signer_seeds,
+            )?;
+            return Ok(());
+        }
+
+        let (
+            Some(edition),
+            Some(owner_token_record),
+            Some(destination_token_record),
+            Some(sysvar_instructions),
+        ) = (
+            self.edition,
+            self.owner_token_record,
+            self.destination_token_record,
+            self.sysvar_instructions,
+        )
+        else {
+            return err!(EntanglerError::MissingProgrammableAccounts);
+        };
+
+        let mut builder = TransferBuilder::new();
+        builder
+            .token(self.token.key())
+            .token_owner(self.token_owner.key())
+            .destination(self.destination.key())
+            .destination_owner(self.destination_owner.key())
+            .mint(self.mint.key())
+            .metadata(self.metadata.key())
+            .edition(edition.key())
+            .owner_token_record(owner_token_record.key())
+            .destination_token_record(destination_token_record.key())
+            .authority(self.authority.key())
+            .payer(self.payer.key())
+            .system_program(self.system_program.key())
+            .sysvar_instructions(sysvar_instructions.key())
+            .spl_token_program(self.token_program.key())
+            .spl_ata_program(self.associated_token_program.key());
+
+        let mut account_infos = vec![
+            self.token,
+            self.token_owner,
+            self.destination,
+            self.destination_owner,
+            self.mint,
+            self.metadata,
+            edition,
+            owner_token_record,
+            destination_token_record,
+            self.authority,
+            self.payer,
+            self.system_program,
+            sysvar_instructions,
+            self.token_program,
+            self.associated_token_program,
+            self.metadata_program,
+        ];
+        if let (Some(authorization_rules_program), Some(authorization_rules)) =
+            (self.authorization_rules_program, self.authorization_rules)
+        {
+            builder
+                .authorization_rules_program(authorization_rules_program.key())
+                .authorization_rules(authorization_rules.key());
+            account_infos.push(authorization_rules_program);
+            account_infos.push(authorization_rules);
+        }
+
+        let ix = builder
+            .build(TransferArgs::V1 {
+                amount: 1,
+                authorization_data: None,
+            })
+            .map_err(|_| EntanglerError::MissingProgrammableAccounts)?
+            .instruction();
+        invoke_signed(&ix, &account_infos, signer_seeds)?;
+
+        Ok(())
+    }
+}
+
+/// The accounts needed to give a legacy entangled token its master edition
+pub struct MasterEditionCreation<'info> {
+    pub edition: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub metadata: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub metadata_program: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'info> MasterEditionCreation<'info> {
+    /// Creates a master edition with a max supply of 0, which takes over the mint authority
+    pub fn invoke_signed(self, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        invoke_signed(
+            &create_master_edition_v3(
+                self.metadata_program.key(),
+                self.edition.key(),
+                self.mint.key(),
+                self.authority.key(),
+                self.authority.key(),
+                self.metadata.key(),
+                self.payer.key(),
+                Some(0),
+            ),
+            &[
+                self.edition,
+                self.mint,
+                self.authority,
+                self.payer,
+                self.metadata,
+                self.metadata_program,
+                self.token_program,
+                self.system_program,
+            ],
+            signer_seeds,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// The accounts needed to freeze an NFT in its owner's account, as its delegate
+pub struct DelegatedFreeze<'info> {
+    pub delegate: AccountInfo<'info>,
+    pub token: AccountInfo<'info>,
+    pub edition: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub metadata_program: AccountInfo<'info>,
+}
+
+impl<'info> DelegatedFreeze<'info> {
+    /// Freezes the token, signing with `signer_seeds` when the delegate is a PDA
+    pub fn freeze(self, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        self.invoke_signed(freeze_delegated_account, signer_seeds)
+    }
+
+    /// Thaws the token, signing with `signer_seeds` when the delegate is a PDA
+    pub fn thaw(self, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        self.invoke_signed(thaw_delegated_account, signer_seeds)
+    }
+
+    fn invoke_signed(
+        self,
+        instruction: fn(Pubkey, Pubkey, Pubkey, Pubkey, Pubkey) -> Instruction,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        invoke_signed(
+            &instruction(
+                self.metadata_program.key(),
+                self.delegate.key(),
+                self.token.key(),
+                self.edition.key(),
+                self.mint.key(),
+            ),
+            &[
+                self.delegate,
+                self.token,
+                self.edition,
+                self.mint,
+                self.token_program,
+                self.metadata_program,
+            ],
+            signer_seeds,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// The accounts needed to hand the update authority of a Metaplex NFT over
+pub struct UpdateAuthorityChange<'info> {
+    pub authority: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub metadata: AccountInfo<'info>,
+    pub edition: Option<AccountInfo<'info>>,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub sysvar_instructions: AccountInfo<'info>,
+    pub metadata_program: AccountInfo<'info>,
+}
+
+impl<'info> UpdateAuthorityChange<'info> {
+    /// Sets `new_authority` as update authority, signing with `signer_seeds` when the
+    /// current authority is a PDA. Works for programmable NFTs too
+    pub fn invoke_signed(self, new_authority: Pubkey, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        let mut args = UpdateArgs::default_v1();
+        if let UpdateArgs::V1 {
+            new_update_authority,
+            ..
+        } = &mut args
+        {
+            *new_update_authority = Some(new_authority);
+        }
+
+        let mut builder = UpdateBuilder::new();
+        builder
+            .authority(self.authority.key())
+            .mint(self.mint.key())
+            .metadata(self.metadata.key())
+            .payer(self.payer.key())
+            .system_program(self.system_program.key())
+            .sysvar_instructions(self.sysvar_instructions.key());
+        let mut account_infos = vec![
+            self.authority,
+            self.mint,
+            self.metadata,
+            self.payer,
+            self.system_program,
+            self.sysvar_instructions,
+            self.metadata_program,
+        ];
+        if let Some(edition) = self.edition {
+            builder.edition(edition.key());
+            account_infos.push(edition);
+        }
+
+        let ix = builder
+            .build(args)
+            .map_err(|_| EntanglerError::MissingProgrammableAccounts)?
+            .instruction();
+        invoke_signed(&ix, &account_infos, signer_seeds)?;
+
+        Ok(())
+    }
+}
+
+/// The accounts needed to move a compressed NFT through Bubblegum
+pub struct CompressedTransfer<'info> {
+    pub tree_config: AccountInfo<'info>,
+    pub leaf_owner: AccountInfo<'info>,
+    pub leaf_delegate: AccountInfo<'info>,
+    pub new_leaf_owner: AccountInfo<'info>,
+    pub merkle_tree: AccountInfo<'info>,
+    pub log_wrapper: AccountInfo<'info>,
+    pub compression_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub bubblegum_program: AccountInfo<'info>,
+
+    /// The merkle proof of the leaf, minus the tree's canopy
+    pub proof: Vec<AccountInfo<'info>>,
+}
+
+impl<'info> CompressedTransfer<'info> {
+    /// Moves the leaf, signing with `signer_seeds` when the owner is a PDA
+    pub fn invoke_signed(
+        self,
+        root: [u8; 32],
+        metadata: &MetadataArgs,
+        nonce: u64,
+        index: u32,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let (data_hash, creator_hash) = compressed_hashes(metadata)?;
+        let proof = self
+            .proof
+            .iter()
+            .map(|node| (node, false, false))
+            .collect::<Vec<_>>();
+
+        TransferCpi::new(
+            &self.bubblegum_program,
+            TransferCpiAccounts {
+                tree_config: &self.tree_config,
+                leaf_owner: (&self.leaf_owner, true),
+                leaf_delegate: (&self.leaf_delegate, false),
+                new_leaf_owner: &self.new_leaf_owner,
+                merkle_tree: &self.merkle_tree,
+                log_wrapper: &self.log_wrapper,
+                compression_program: &self.compression_program,
+                system_program: &self.system_program,
+            },
+            TransferInstructionArgs {
+                root,
+                data_hash,
+                creator_hash,
+                nonce,
+                index,
+            },
+        )
+        .invoke_signed_with_remaining_accounts(signer_seeds, &proof)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum RoyaltyHookError {
+    #[msg("The mint is not a Token-2022 mint using this program as its transfer hook")]
+    InvalidMint,
+
+    #[msg("The mint's metadata is missing its royalties, creator or collection")]
+    MissingRoyalties,
+
+    #[msg("The creator does not match the mint's metadata")]
+    InvalidCreator,
+
+    #[msg("The configuration is not the one of the mint's collection")]
+    InvalidConfig,
+
+    #[msg("The price is below the collection's minimum, or pays no royalties")]
+    PriceBelowMinimum,
+
+    #[msg("The token account is not a Token-2022 account of the mint")]
+    InvalidTokenAccount,
+
+    #[msg("The hook only executes during a Token-2022 transfer")]
+    NotTransferring,
+
+    #[msg("The royalties of the transfer have not been paid")]
+    RoyaltiesUnpaid,
+}
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use spl_token_2022::extension::transfer_hook::TransferHookAccount;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_transfer_hook_interface::instruction::TransferHookInstruction;
+
+use crate::errors::RoyaltyHookError;
+use crate::seeds::RECEIPT_SEED;
+use crate::state::{HookedMint, RoyaltyReceipt};
+
+/// Accounts, in order: source, mint, destination, source owner, validation account, receipt
+pub fn execute(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> Result<()> {
+    let TransferHookInstruction::Execute { .. } = TransferHookInstruction::unpack(data)? else {
+        return Err(ProgramError::InvalidInstructionData.into());
+    };
+    let [source, mint, destination, _owner, _extra_account_meta_list, receipt, ..] = accounts
+    else {
+        return err!(ErrorCode::AccountNotEnoughKeys);
+    };
+
+    let hooked_mint = HookedMint::unpack(mint)?;
+    let (source_owner, transferring) = unpack_token_account(source, mint.key)?;
+    let (destination_owner, _) = unpack_token_account(destination, mint.key)?;
+
+    // Only Token-2022 may use up a receipt, while it moves the token
+    require!(transferring, RoyaltyHookError::NotTransferring);
+
+    // Moves in and out of the entangler's escrows are entanglements, not sales
+    if hooked_mint
+        .authority
+        .is_some_and(|authority| source_owner == authority || destination_owner == authority)
+    {
+        return Ok(());
+    }
+
+    let (receipt_address, _) =
+        Pubkey::find_program_address(&[RECEIPT_SEED.as_bytes(), mint.key.as_ref()], program_id);
+    require_keys_eq!(
+        *receipt.key,
+        receipt_address,
+        RoyaltyHookError::RoyaltiesUnpaid
+    );
+    require!(
+        receipt.owner == program_id && receipt.is_writable,
+        RoyaltyHookError::RoyaltiesUnpaid
+    );
+
+    let mut data = receipt.try_borrow_mut_data()?;
+    let mut royalty_receipt = RoyaltyReceipt::try_deserialize(&mut &data[..])?;
+    require!(royalty_receipt.paid, RoyaltyHookError::RoyaltiesUnpaid);
+    royalty_receipt.paid = false;
+    royalty_receipt.try_serialize(&mut &mut data[..])?;
+
+    Ok(())
+}
+
+/// The owner of a token account of the mint, and whether Token-2022 is transferring from it
+fn unpack_token_account(account: &AccountInfo, mint: &Pubkey) -> Result<(Pubkey, bool)> {
+    require_keys_eq!(
+        *account.owner,
+        spl_token_2022::ID,
+        RoyaltyHookError::InvalidTokenAccount
+    );
+    let data = account.try_borrow_data()?;
+    let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    require_keys_eq!(
+        account.base.mint,
+        *mint,
+        RoyaltyHookError::InvalidTokenAccount
+    );
+    let transferring = account
+        .get_extension::<TransferHookAccount>()
+        .is_ok_and(|extension| extension.transferring.into());
+    Ok((account.base.owner, transferring))
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use spl_tlv_account_resolution::account::ExtraAccountMeta;
+use spl_tlv_account_resolution::seeds::Seed;
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+use crate::seeds::{EXTRA_ACCOUNT_METAS_SEED, RECEIPT_SEED};
+use crate::state::HookedMint;
+
+pub fn initialize_extra_account_meta_list(
+    ctx: Context<InitializeExtraAccountMetaList>,
+) -> Result<()> {
+    msg!("Initializing the extra account metas");
+
+    HookedMint::unpack(&ctx.accounts.mint)?;
+
+    let mut data = ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?;
+    ExtraAccountMetaList::init::<ExecuteInstruction>(&mut data, &extra_account_metas()?)?;
+
+    Ok(())
+}
+
+/// The receipt of the mint, which the hook clears once a paid transfer uses it
+pub fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    Ok(vec![ExtraAccountMeta::new_with_seeds(
+        &[
+            Seed::Literal {
+                bytes: RECEIPT_SEED.as_bytes().to_vec(),
+            },
+            Seed::AccountKey { index: 1 },
+        ],
+        false,
+        true,
+    )?])
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The Token-2022 entangled mint
+    /// CHECK: Checked in the instruction
+    pub mint: UncheckedAccount<'info>,
+
+    /// The validation account of the transfer hook interface
+    /// CHECK: Created in the instruction with the interface's layout
+    #[account(
+        init,
+        payer = payer,
+        space = ExtraAccountMetaList::size_of(extra_account_metas()?.len())?,
+        seeds = [
+            EXTRA_ACCOUNT_METAS_SEED.as_bytes(),
+            mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,9 @@
+mod execute;
+mod initialize_extra_account_meta_list;
+mod pay_royalties;
+mod set_min_price;
+
+pub use execute::*;
+pub use initialize_extra_account_meta_list::*;
+pub use pay_royalties::*;
+pub use set_min_price::*;
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::errors::RoyaltyHookError;
+use crate::seeds::{CONFIG_SEED, RECEIPT_SEED};
+use crate::state::{HookedMint, RoyaltyConfig, RoyaltyReceipt};
+
+pub fn pay_royalties(ctx: Context<PayRoyalties>, price: u64) -> Result<()> {
+    msg!("Paying royalties");
+
+    let mint = HookedMint::unpack(&ctx.accounts.mint)?;
+    require_keys_eq!(
+        ctx.accounts.creator.key(),
+        mint.creator,
+        RoyaltyHookError::InvalidCreator
+    );
+    require_keys_eq!(
+        ctx.accounts.config.collection,
+        mint.collection,
+        RoyaltyHookError::InvalidConfig
+    );
+
+    // A declared price can't be checked against the sale, so the creator's floor bounds it
+    let amount = (price as u128 * mint.royalties as u128 / 10_000) as u64;
+    require!(
+        price >= ctx.accounts.config.min_price && amount > 0,
+        RoyaltyHookError::PriceBelowMinimum
+    );
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: ctx.accounts.creator.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let receipt = &mut ctx.accounts.receipt;
+    receipt.mint = ctx.accounts.mint.key();
+    receipt.paid = true;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PayRoyalties<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The Token-2022 entangled mint
+    /// CHECK: Checked in the instruction
+    pub mint: UncheckedAccount<'info>,
+
+    /// The creator written in the mint's metadata
+    /// CHECK: Checked in the instruction
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+
+    /// The minimum price the creator set for the mint's collection
+    #[account(
+        seeds = [
+            CONFIG_SEED.as_bytes(),
+            config.collection.as_ref(),
+        ],
+        bump,
+    )]
+    pub config: Account<'info, RoyaltyConfig>,
+
+    /// The receipt the next transfer of the mint uses
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = RoyaltyReceipt::LEN,
+        seeds = [
+            RECEIPT_SEED.as_bytes(),
+            mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub receipt: Account<'info, RoyaltyReceipt>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::RoyaltyHookError;
+use crate::seeds::CONFIG_SEED;
+use crate::state::{HookedMint, RoyaltyConfig};
+
+pub fn set_min_price(ctx: Context<SetMinPrice>, min_price: u64) -> Result<()> {
+    msg!("Setting the minimum price");
+
+    let mint = HookedMint::unpack(&ctx.accounts.mint)?;
+    require_keys_eq!(
+        ctx.accounts.creator.key(),
+        mint.creator,
+        RoyaltyHookError::InvalidCreator
+    );
+    require_keys_eq!(
+        ctx.accounts.collection_mint.key(),
+        mint.collection,
+        RoyaltyHookError::InvalidConfig
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.collection = mint.collection;
+    config.min_price = min_price;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetMinPrice<'info> {
+    /// The creator written in the metadata of the collection's mints
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Any Token-2022 entangled mint of the collection
+    /// CHECK: Checked in the instruction
+    pub mint: UncheckedAccount<'info>,
+
+    /// The entangled collection mint
+    /// CHECK: Checked in the instruction against the mint's metadata
+    pub collection_mint: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = RoyaltyConfig::LEN,
+        seeds = [
+            CONFIG_SEED.as_bytes(),
+            collection_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub config: Account<'info, RoyaltyConfig>,
+
+    pub system_program: Program<'info, System>,
+}
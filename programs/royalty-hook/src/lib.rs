@@ -0,0 +1,44 @@
+#![allow(clippy::result_large_err)]
+
+use anchor_lang::prelude::*;
+
+pub mod errors;
+mod instructions;
+pub mod seeds;
+pub mod state;
+
+use instructions::*;
+
+declare_id!("HhnhoTiXnNoAT5tjQddCab7iVJuhCSTuHGfydv7Fwd4A");
+
+/// Transfer hook of Token-2022 entangled tokens. Every transfer outside the entangler's escrows
+/// needs the royalties written in the mint's metadata to be paid first, on a price the payer
+/// declares but that can't be lower than the minimum the collection's creator set
+#[program]
+pub mod royalty_hook {
+    use super::*;
+
+    /// Writes the accounts Token-2022 forwards to the hook on every transfer of the mint
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+    ) -> Result<()> {
+        instructions::initialize_extra_account_meta_list(ctx)
+    }
+
+    /// Sets the lowest sale price royalties can be paid on for the mint's collection. Only the
+    /// creator in the mint's metadata can set it
+    pub fn set_min_price(ctx: Context<SetMinPrice>, min_price: u64) -> Result<()> {
+        instructions::set_min_price(ctx, min_price)
+    }
+
+    /// Pays the royalties of a sale at `price` lamports to the creator, which allows the next
+    /// transfer of the mint. The price must reach the collection's minimum
+    pub fn pay_royalties(ctx: Context<PayRoyalties>, price: u64) -> Result<()> {
+        instructions::pay_royalties(ctx, price)
+    }
+
+    /// Handles the transfer hook interface's `Execute`, whose discriminator is not Anchor's
+    pub fn fallback(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> Result<()> {
+        instructions::execute(program_id, accounts, data)
+    }
+}
@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+/// The validation account of the transfer hook interface
+#[constant]
+pub const EXTRA_ACCOUNT_METAS_SEED: &str = "extra-account-metas";
+
+#[constant]
+pub const RECEIPT_SEED: &str = "royalty-receipt";
+
+#[constant]
+pub const CONFIG_SEED: &str = "royalty-config";
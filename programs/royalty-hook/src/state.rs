@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use spl_token_2022::extension::transfer_hook::TransferHook;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+use crate::errors::RoyaltyHookError;
+
+#[account]
+pub struct RoyaltyReceipt {
+    /// The mint the royalties were paid for
+    pub mint: Pubkey,
+
+    /// Whether the royalties of the mint's next transfer are paid
+    pub paid: bool,
+}
+
+impl RoyaltyReceipt {
+    pub const LEN: usize = 8 + 32 + 1;
+}
+
+#[account]
+pub struct RoyaltyConfig {
+    /// The entangled collection mint
+    pub collection: Pubkey,
+
+    /// The lowest sale price royalties can be paid on, in lamports
+    pub min_price: u64,
+}
+
+impl RoyaltyConfig {
+    pub const LEN: usize = 8 + 32 + 8;
+}
+
+/// What the hook reads from a Token-2022 entangled mint
+pub struct HookedMint {
+    /// The transfer hook authority, which owns the entangler's escrows
+    pub authority: Option<Pubkey>,
+
+    /// Royalties in basis points, from the `royalties` metadata field
+    pub royalties: u16,
+
+    /// The account receiving the royalties, from the `creator` metadata field
+    pub creator: Pubkey,
+
+    /// The entangled collection mint, from the `collection` metadata field
+    pub collection: Pubkey,
+}
+
+impl HookedMint {
+    /// Reads a mint whose transfer hook is this program
+    pub fn unpack(mint: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(
+            *mint.owner,
+            spl_token_2022::ID,
+            RoyaltyHookError::InvalidMint
+        );
+        let data = mint.try_borrow_data()?;
+        let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+        let hook = mint
+            .get_extension::<TransferHook>()
+            .map_err(|_| RoyaltyHookError::InvalidMint)?;
+        require!(
+            Option::<Pubkey>::from(hook.program_id) == Some(crate::ID),
+            RoyaltyHookError::InvalidMint
+        );
+
+        let metadata = mint
+            .get_variable_len_extension::<TokenMetadata>()
+            .map_err(|_| RoyaltyHookError::MissingRoyalties)?;
+        let field = |key: &str| {
+            metadata
+                .additional_metadata
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value.as_str())
+        };
+        let royalties = field("royalties").and_then(|value| value.parse().ok());
+        let creator = field("creator").and_then(|value| value.parse().ok());
+        let collection = field("collection").and_then(|value| value.parse().ok());
+        let (Some(royalties), Some(creator), Some(collection)) = (royalties, creator, collection)
+        else {
+            return err!(RoyaltyHookError::MissingRoyalties);
+        };
+
+        Ok(Self {
+            authority: hook.authority.into(),
+            royalties,
+            creator,
+            collection,
+        })
+    }
+}
@@ -1,4 +1,4 @@
-import * as anchor from "@project-serum/anchor";
+import * as anchor from "@coral-xyz/anchor";
 
 import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -12,7 +12,7 @@ import { PROGRAM_ID as METADATA_PROGRAM_ID } from "@metaplex-foundation/mpl-toke
 import { getTokenMetadata } from "../tests/utils";
 import { serializeInstructionToBase64 } from "@solana/spl-governance";
 
-const AUTHORITY_SEED = "authority";
+const COLLECTION_AUTHORITY_SEED = "collection-authority";
 const COLLECTION_SEED = "collection";
 const COLLECTION_MINT_SEED = "collection-mint";
 
@@ -70,7 +70,7 @@ export default async function main() {
   );
 
   const [entanglerAuthority] = PublicKey.findProgramAddressSync(
-    [Buffer.from(AUTHORITY_SEED)],
+    [Buffer.from(COLLECTION_AUTHORITY_SEED), DIPPIES_KEY.toBuffer()],
     program.programId
   );
   const [entangledCollection] = PublicKey.findProgramAddressSync(
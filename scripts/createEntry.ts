@@ -1,4 +1,4 @@
-import * as anchor from "@project-serum/anchor";
+import * as anchor from "@coral-xyz/anchor";
 
 import { EntanglerWrapper } from "./../ts/index";
 import { NATIVE_MINT } from "@solana/spl-token";
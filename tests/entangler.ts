@@ -1,23 +1,100 @@
-import * as anchor from "@project-serum/anchor";
+import * as anchor from "@coral-xyz/anchor";
 
 import {
   Keypair,
   PublicKey,
+  SystemProgram,
   TransactionMessage,
   VersionedTransaction,
 } from "@solana/web3.js";
-import { createKeypairs, mintNft, mintToken, verifyCollection } from "./utils";
 import {
+  allocCompressedTree,
+  createKeypairs,
+  createLookupTable,
+  mintCompressedNft,
+  mintNft,
+  mintProgrammableNft,
+  mintToken,
+  mintToken2022Nft,
+  printEdition,
+  proveCompressedNft,
+  simulateReturnData,
+  verifyCollection,
+} from "./utils";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  createAssociatedTokenAccountInstruction,
+  createTransferInstruction,
   getAccount,
   getAssociatedTokenAddress,
   getAssociatedTokenAddressSync,
+  getMint,
 } from "@solana/spl-token";
 
 import { Entangler } from "../target/types/entangler";
-import { EntanglerWrapper } from "../ts";
+import {
+  EntangledCollection,
+  EntangledPair,
+  EntangledStandard,
+  EntanglerWrapper,
+  OriginalRecord,
+  PairCustody,
+  fromTxError,
+} from "../ts";
+import { ConstraintRaw } from "../ts/errors/anchor";
+import {
+  AlreadyMigrated,
+  BackingToken,
+  CompressedCollection,
+  EmptyBatch,
+  InvalidAdmin,
+  InvalidAuthority,
+  InvalidCollection,
+  InvalidEdition,
+  InvalidMetadata,
+  RecordsNotBackfilled,
+  RuleSetRequiresProgrammable,
+  TransferHookRequiresToken2022,
+  UnverifiedCollection,
+  UninitializedPair,
+  UnverifiedCreator,
+} from "../ts/errors/custom";
+import {
+  MasterEditionV2,
+  Metadata,
+  TokenStandard,
+} from "@metaplex-foundation/mpl-token-metadata";
+import {
+  MetadataArgs,
+  TokenProgramVersion,
+  TokenStandard as CompressedTokenStandard,
+} from "@metaplex-foundation/mpl-bubblegum";
 import { Metaplex } from "@metaplex-foundation/js";
 import { expect } from "chai";
-import { getEntangledMint } from "./../ts/pda";
+import {
+  getAssetId,
+  getCollectionAuthority,
+  getEntangledCollection,
+  getEntangledMint,
+  getEntangledPair,
+  getEntanglerAuthority,
+  getMasterEdition,
+  getMetadata,
+  getOriginalRecord,
+} from "./../ts/pda";
+
+const expectCustomError = async (
+  promise: Promise<unknown>,
+  error: { code: number }
+) => {
+  try {
+    await promise;
+  } catch (e) {
+    expect(fromTxError(e)?.code).to.equal(error.code);
+    return;
+  }
+  expect.fail(`Expected error ${error.code}`);
+};
 
 describe("entangler", () => {
   let provider: anchor.AnchorProvider = anchor.AnchorProvider.local();
@@ -261,4 +338,1454 @@ describe("entangler", () => {
       )
     );
   });
+
+  it("Rejects forged collection members", async () => {
+    const royalties = 500;
+    const [forger] = await createKeypairs(provider, 1);
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      id.publicKey,
+      creator.publicKey,
+      royalties
+    );
+
+    // Claims the collection without being verified
+    const { mint: forgedMint } = await mintNft(
+      provider,
+      "TEST",
+      forger,
+      admin.publicKey,
+      collectionMint
+    );
+    await expectCustomError(
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          entangler.instruction.initializePair(forgedMint)
+        ),
+        [admin]
+      ),
+      UnverifiedCollection
+    );
+
+    // Verified in a look-alike collection
+    const { mint: fakeCollectionMint } = await mintNft(
+      provider,
+      "TEST",
+      forger,
+      forger.publicKey
+    );
+    const { mint: fakeMint } = await mintNft(
+      provider,
+      "TEST",
+      forger,
+      admin.publicKey,
+      fakeCollectionMint
+    );
+    await verifyCollection(provider, fakeMint, fakeCollectionMint, forger);
+    await expectCustomError(
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          entangler.instruction.initializePair(fakeMint)
+        ),
+        [admin]
+      ),
+      InvalidCollection
+    );
+
+    // Genuine member but missing the required creator
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.setCollectionVerification(
+          false,
+          forger.publicKey
+        )
+      ),
+      [admin]
+    );
+    await expectCustomError(
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          entangler.instruction.initializePair(originalCollectionMints[1])
+        ),
+        [admin]
+      ),
+      UnverifiedCreator
+    );
+
+    // Legacy collections can opt out of the verification
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.setCollectionVerification(true, null)
+      ),
+      [admin]
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.initializePair(forgedMint)
+      ),
+      [admin]
+    );
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.setCollectionVerification(
+          false,
+          creator.publicKey
+        )
+      ),
+      [admin]
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.initializePair(originalCollectionMints[1])
+      ),
+      [admin]
+    );
+  });
+
+  it("Rejects misrouted Metaplex accounts", async () => {
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      id.publicKey,
+      creator.publicKey,
+      500
+    );
+    const { mint: originalMint } = await mintNft(
+      provider,
+      "TEST",
+      creator,
+      admin.publicKey,
+      collectionMint
+    );
+    await verifyCollection(provider, originalMint, collectionMint, creator);
+
+    const entangledMint = getEntangledMint(id.publicKey, originalMint);
+    const misrouted = (from: PublicKey, to: PublicKey) => {
+      const ix = entangler.instruction.initializePair(originalMint);
+      ix.keys.find((key) => key.pubkey.equals(from)).pubkey = to;
+      return provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(ix),
+        [admin]
+      );
+    };
+
+    await expectCustomError(
+      misrouted(getMetadata(entangledMint), getMetadata(originalMint)),
+      InvalidMetadata
+    );
+    await expectCustomError(
+      misrouted(
+        getMasterEdition(entangler.entangledCollectionMint),
+        getMasterEdition(collectionMint)
+      ),
+      InvalidEdition
+    );
+  });
+
+  it("Keeps new collections on their own authority", async () => {
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      id.publicKey,
+      creator.publicKey,
+      500
+    );
+    const collection = await EntangledCollection.fetch(
+      provider.connection,
+      getEntangledCollection(id.publicKey)
+    );
+    expect(collection?.scopedAuthority).to.be.true;
+    expect(entangler.entanglerAuthority).to.deep.equal(
+      getCollectionAuthority(id.publicKey)
+    );
+    expect(entangler.entanglerAuthority).to.not.deep.equal(
+      getEntanglerAuthority()
+    );
+
+    await expectCustomError(
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          entangler.instruction.migrateCollection()
+        ),
+        [admin]
+      ),
+      new AlreadyMigrated()
+    );
+
+    const { mint: originalMint } = await mintNft(
+      provider,
+      "TEST",
+      creator,
+      admin.publicKey,
+      collectionMint
+    );
+    await verifyCollection(provider, originalMint, collectionMint, creator);
+    await expectCustomError(
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          entangler
+            .withGlobalAuthority()
+            .instruction.initializePair(originalMint)
+        ),
+        [admin]
+      ),
+      new InvalidAuthority()
+    );
+  });
+
+  it("Rescues tokens backing no pair", async () => {
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      id.publicKey,
+      creator.publicKey,
+      500
+    );
+    const authority = entangler.entanglerAuthority;
+
+    // A fungible token sent to the authority by mistake
+    const strayMint = await mintToken(
+      provider,
+      admin,
+      admin.publicKey,
+      new anchor.BN(100)
+    );
+    const adminAccount = getAssociatedTokenAddressSync(
+      strayMint,
+      admin.publicKey
+    );
+    const strayAccount = getAssociatedTokenAddressSync(
+      strayMint,
+      authority,
+      true
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          admin.publicKey,
+          strayAccount,
+          authority,
+          strayMint
+        ),
+        createTransferInstruction(
+          adminAccount,
+          strayAccount,
+          admin.publicKey,
+          100
+        )
+      ),
+      [admin]
+    );
+
+    await expectCustomError(
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          new EntanglerWrapper(
+            collectionMint,
+            creator.publicKey,
+            id.publicKey,
+            creator.publicKey,
+            500
+          ).instruction.rescueTokens(
+            strayMint,
+            adminAccount,
+            new anchor.BN(100)
+          )
+        ),
+        [creator]
+      ),
+      ConstraintRaw
+    );
+
+    // Originals and entangled tokens back their pair
+    const originalMint = originalCollectionMints[1];
+    for (const mint of [
+      originalMint,
+      getEntangledMint(id.publicKey, originalMint),
+    ]) {
+      const escrow = getAssociatedTokenAddressSync(mint, authority, true);
+      await expectCustomError(
+        provider.sendAndConfirm(
+          new anchor.web3.Transaction().add(
+            entangler.instruction.rescueTokens(mint, escrow, new anchor.BN(1))
+          ),
+          [admin]
+        ),
+        BackingToken
+      );
+    }
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.rescueTokens(
+          strayMint,
+          adminAccount,
+          new anchor.BN(100)
+        )
+      ),
+      [admin]
+    );
+    expect(
+      Number((await getAccount(provider.connection, strayAccount)).amount)
+    ).to.equal(0);
+    expect(
+      Number((await getAccount(provider.connection, adminAccount)).amount)
+    ).to.equal(100);
+
+    // The global authority escrows the originals of every legacy collection, so rescues from it
+    // wait until all of them have a record
+    const legacy = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      id.publicKey,
+      creator.publicKey,
+      500
+    ).withGlobalAuthority();
+    const globalAccount = getAssociatedTokenAddressSync(
+      strayMint,
+      getEntanglerAuthority(),
+      true
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          admin.publicKey,
+          globalAccount,
+          getEntanglerAuthority(),
+          strayMint
+        ),
+        createTransferInstruction(
+          adminAccount,
+          globalAccount,
+          admin.publicKey,
+          100
+        )
+      ),
+      [admin]
+    );
+    await expectCustomError(
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          legacy.instruction.rescueTokens(
+            strayMint,
+            adminAccount,
+            new anchor.BN(100)
+          )
+        ),
+        [admin]
+      ),
+      RecordsNotBackfilled
+    );
+    await expectCustomError(
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          new EntanglerWrapper(
+            collectionMint,
+            creator.publicKey,
+            id.publicKey,
+            creator.publicKey,
+            500
+          ).instruction.setRecordsBackfilled(true)
+        ),
+        [creator]
+      ),
+      InvalidAdmin
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        legacy.instruction.setRecordsBackfilled(true),
+        legacy.instruction.rescueTokens(
+          strayMint,
+          adminAccount,
+          new anchor.BN(100)
+        )
+      ),
+      [admin]
+    );
+    expect(
+      Number((await getAccount(provider.connection, adminAccount)).amount)
+    ).to.equal(100);
+
+    // Only originals of initialized pairs can be recorded
+    const { mint: uninitializedMint } = await mintNft(
+      provider,
+      "TEST",
+      creator,
+      admin.publicKey,
+      collectionMint
+    );
+    await expectCustomError(
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          entangler.instruction.recordOriginal(uninitializedMint)
+        ),
+        [admin]
+      ),
+      UninitializedPair
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.recordOriginal(originalMint)
+      ),
+      [admin]
+    );
+    const record = await OriginalRecord.fetch(
+      provider.connection,
+      getOriginalRecord(originalMint)
+    );
+    expect(record?.originalMint).to.deep.equal(originalMint);
+  });
+
+  it("Sweeps the authority's lamports to the earner", async () => {
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      id.publicKey,
+      creator.publicKey,
+      500
+    );
+    const authority = entangler.entanglerAuthority;
+    const earner = creator.publicKey;
+
+    // Lamports sent to the authority by mistake
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: admin.publicKey,
+          toPubkey: authority,
+          lamports: anchor.web3.LAMPORTS_PER_SOL,
+        })
+      ),
+      [admin]
+    );
+
+    await expectCustomError(
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          new EntanglerWrapper(
+            collectionMint,
+            creator.publicKey,
+            id.publicKey,
+            creator.publicKey,
+            500
+          ).instruction.sweepAuthorityLamports(earner)
+        ),
+        [creator]
+      ),
+      ConstraintRaw
+    );
+
+    const needed =
+      await provider.connection.getMinimumBalanceForRentExemption(0);
+    const authorityBalance = await provider.connection.getBalance(authority);
+    const earnerBalance = await provider.connection.getBalance(earner);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.sweepAuthorityLamports(earner)
+      ),
+      [admin]
+    );
+    expect(await provider.connection.getBalance(authority)).to.equal(needed);
+    expect(await provider.connection.getBalance(earner)).to.equal(
+      earnerBalance + authorityBalance - needed
+    );
+
+    // Nothing is left to sweep
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.sweepAuthorityLamports(earner)
+      ),
+      [admin]
+    );
+    expect(await provider.connection.getBalance(authority)).to.equal(needed);
+  });
+
+  it("Entangles programmable originals", async () => {
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      id.publicKey,
+      creator.publicKey,
+      500
+    );
+    const { mint: originalMint } = await mintProgrammableNft(
+      provider,
+      "TEST",
+      creator,
+      admin.publicKey,
+      collectionMint
+    );
+    const entangledMint = getEntangledMint(id.publicKey, originalMint);
+    const originalMintAccount = getAssociatedTokenAddressSync(
+      originalMint,
+      admin.publicKey
+    );
+    const entangledMintAccount = getAssociatedTokenAddressSync(
+      entangledMint,
+      admin.publicKey
+    );
+    const computeBudget = anchor.web3.ComputeBudgetProgram.setComputeUnitLimit(
+      { units: 600_000 }
+    );
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.initializePair(originalMint)
+      ),
+      [admin]
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.entangle(originalMint)
+      ),
+      [admin]
+    );
+    expect(
+      (
+        await getAccount(provider.connection, originalMintAccount)
+      ).amount.toString()
+    ).to.equal("0");
+    expect(
+      (
+        await getAccount(provider.connection, entangledMintAccount)
+      ).amount.toString()
+    ).to.equal("1");
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.disentangle(originalMint)
+      ),
+      [admin]
+    );
+    const originalAccount = await getAccount(
+      provider.connection,
+      originalMintAccount
+    );
+    expect(originalAccount.amount.toString()).to.equal("1");
+    expect(originalAccount.isFrozen).to.be.true;
+
+    // Re entangle and burn
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.entangle(originalMint)
+      ),
+      [admin]
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.burnOriginal(originalMint)
+      ),
+      [admin]
+    );
+    expect(
+      await provider.connection.getAccountInfo(getMetadata(originalMint))
+    ).to.be.null;
+  });
+
+  it("Entangles Token-2022 originals", async () => {
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      id.publicKey,
+      creator.publicKey,
+      500
+    );
+    const { mint: originalMint } = await mintToken2022Nft(
+      provider,
+      "TEST",
+      creator,
+      admin.publicKey,
+      collectionMint
+    );
+    const originalMintAccount = getAssociatedTokenAddressSync(
+      originalMint,
+      admin.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const entangledMintAccount = getAssociatedTokenAddressSync(
+      getEntangledMint(id.publicKey, originalMint),
+      admin.publicKey
+    );
+    const getOriginalAmount = async () =>
+      (
+        await getAccount(
+          provider.connection,
+          originalMintAccount,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).amount.toString();
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.initializePair(
+          originalMint,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        entangler.instruction.entangle(
+          originalMint,
+          null,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ),
+      [admin]
+    );
+    expect(await getOriginalAmount()).to.equal("0");
+    expect(
+      (
+        await getAccount(provider.connection, entangledMintAccount)
+      ).amount.toString()
+    ).to.equal("1");
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.disentangle(
+          originalMint,
+          null,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ),
+      [admin]
+    );
+    expect(await getOriginalAmount()).to.equal("1");
+  });
+
+  it("Mints programmable entangled tokens", async () => {
+    const programmableId = Keypair.generate();
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      programmableId.publicKey,
+      creator.publicKey,
+      500
+    );
+    const computeBudget = anchor.web3.ComputeBudgetProgram.setComputeUnitLimit(
+      { units: 600_000 }
+    );
+
+    // Rule sets need programmable entangled tokens
+    await expectCustomError(
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          entangler.instruction.createCollection(
+            false,
+            false,
+            null,
+            new EntangledStandard.NonFungible(),
+            Keypair.generate().publicKey
+          )
+        ),
+        [admin]
+      ),
+      new RuleSetRequiresProgrammable()
+    );
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.createCollection(
+          false,
+          false,
+          null,
+          new EntangledStandard.ProgrammableNonFungible()
+        )
+      ),
+      [admin]
+    );
+
+    const { mint: originalMint } = await mintNft(
+      provider,
+      "TEST",
+      creator,
+      admin.publicKey,
+      collectionMint
+    );
+    await verifyCollection(provider, originalMint, collectionMint, creator);
+    const entangledMint = getEntangledMint(
+      programmableId.publicKey,
+      originalMint
+    );
+    const entangledMintAccount = getAssociatedTokenAddressSync(
+      entangledMint,
+      admin.publicKey
+    );
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.initializePair(originalMint)
+      ),
+      [admin]
+    );
+    const metadata = await Metadata.fromAccountAddress(
+      provider.connection,
+      getMetadata(entangledMint)
+    );
+    expect(metadata.tokenStandard).to.equal(
+      TokenStandard.ProgrammableNonFungible
+    );
+    expect(metadata.collection?.verified).to.be.true;
+    expect(metadata.data.sellerFeeBasisPoints).to.equal(500);
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.entangle(originalMint)
+      ),
+      [admin]
+    );
+    const entangledAccount = await getAccount(
+      provider.connection,
+      entangledMintAccount
+    );
+    expect(entangledAccount.amount.toString()).to.equal("1");
+    expect(entangledAccount.isFrozen).to.be.true;
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.disentangle(originalMint)
+      ),
+      [admin]
+    );
+    expect(
+      (
+        await getAccount(provider.connection, entangledMintAccount)
+      ).amount.toString()
+    ).to.equal("0");
+  });
+
+  it("Mints Token-2022 entangled tokens", async () => {
+    const token2022Id = Keypair.generate();
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      token2022Id.publicKey,
+      creator.publicKey,
+      500,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // Transfer hooks need Token-2022 entangled tokens
+    await expectCustomError(
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          entangler.instruction.createCollection(
+            false,
+            false,
+            null,
+            new EntangledStandard.NonFungible(),
+            null,
+            Keypair.generate().publicKey
+          )
+        ),
+        [admin]
+      ),
+      new TransferHookRequiresToken2022()
+    );
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.createCollection(
+          false,
+          false,
+          null,
+          new EntangledStandard.Token2022()
+        )
+      ),
+      [admin]
+    );
+
+    const { mint: originalMint } = await mintNft(
+      provider,
+      "TEST",
+      creator,
+      admin.publicKey,
+      collectionMint
+    );
+    await verifyCollection(provider, originalMint, collectionMint, creator);
+    const entangledMint = getEntangledMint(token2022Id.publicKey, originalMint);
+    const entangledMintAccount = getAssociatedTokenAddressSync(
+      entangledMint,
+      admin.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const getEntangledAmount = async () =>
+      (
+        await getAccount(
+          provider.connection,
+          entangledMintAccount,
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).amount.toString();
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.initializePair(originalMint),
+        entangler.instruction.entangle(originalMint)
+      ),
+      [admin]
+    );
+    const mintInfo = await provider.connection.getAccountInfo(entangledMint);
+    expect(mintInfo?.owner.toString()).to.equal(
+      TOKEN_2022_PROGRAM_ID.toString()
+    );
+    expect(await getEntangledAmount()).to.equal("1");
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.disentangle(originalMint)
+      ),
+      [admin]
+    );
+    expect(await getEntangledAmount()).to.equal("0");
+  });
+
+  it("Entangles compressed originals", async () => {
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      id.publicKey,
+      creator.publicKey,
+      500
+    );
+    const computeBudget = anchor.web3.ComputeBudgetProgram.setComputeUnitLimit(
+      { units: 600_000 }
+    );
+    const { merkleTree, metadata } = await mintCompressedNft(
+      provider,
+      "TEST",
+      creator,
+      admin.publicKey,
+      collectionMint
+    );
+    const entangledMintAccount = getAssociatedTokenAddressSync(
+      getEntangledMint(id.publicKey, getAssetId(merkleTree, new anchor.BN(0))),
+      admin.publicKey
+    );
+
+    const owned = proveCompressedNft(merkleTree, metadata, admin.publicKey);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.initializeCompressedPair(owned)
+      ),
+      [admin]
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.entangleCompressed(owned)
+      ),
+      [admin]
+    );
+    expect(
+      (
+        await getAccount(provider.connection, entangledMintAccount)
+      ).amount.toString()
+    ).to.equal("1");
+
+    const escrowed = proveCompressedNft(
+      merkleTree,
+      metadata,
+      entangler.entanglerAuthority
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.disentangleCompressed(escrowed)
+      ),
+      [admin]
+    );
+    expect(
+      (
+        await getAccount(provider.connection, entangledMintAccount)
+      ).amount.toString()
+    ).to.equal("0");
+  });
+
+  it("Entangles into a compressed collection", async () => {
+    const compressedId = Keypair.generate();
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      compressedId.publicKey,
+      creator.publicKey,
+      500
+    );
+    const computeBudget = anchor.web3.ComputeBudgetProgram.setComputeUnitLimit(
+      { units: 600_000 }
+    );
+
+    // The tree is created with the collection so no one else can set it
+    const tree = await allocCompressedTree(provider, admin.publicKey);
+    const merkleTree = tree.merkleTree.publicKey;
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.createCollection(
+          false,
+          false,
+          null,
+          new EntangledStandard.Compressed()
+        ),
+        tree.instruction,
+        entangler.instruction.createEntangledTree(
+          merkleTree,
+          tree.maxDepth,
+          tree.maxBufferSize
+        )
+      ),
+      [admin, tree.merkleTree]
+    );
+
+    const { mint: originalMint } = await mintNft(
+      provider,
+      "TEST",
+      creator,
+      admin.publicKey,
+      collectionMint
+    );
+    await verifyCollection(provider, originalMint, collectionMint, creator);
+    const originalMintAccount = getAssociatedTokenAddressSync(
+      originalMint,
+      admin.publicKey
+    );
+    const getOriginalAmount = async () =>
+      (
+        await getAccount(provider.connection, originalMintAccount)
+      ).amount.toString();
+
+    // Compressed collections don't have entangled mints
+    await expectCustomError(
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          entangler.instruction.initializePair(originalMint)
+        ),
+        [admin]
+      ),
+      new CompressedCollection()
+    );
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.initializeTreePair(originalMint, merkleTree)
+      ),
+      [admin]
+    );
+
+    const original = await Metadata.fromAccountAddress(
+      provider.connection,
+      getMetadata(originalMint)
+    );
+    const metadata: MetadataArgs = {
+      name: original.data.name,
+      symbol: original.data.symbol,
+      uri: original.data.uri,
+      sellerFeeBasisPoints: 500,
+      primarySaleHappened: false,
+      isMutable: true,
+      editionNonce: null,
+      tokenStandard: CompressedTokenStandard.NonFungible,
+      collection: { key: entangler.entangledCollectionMint, verified: true },
+      uses: null,
+      tokenProgramVersion: TokenProgramVersion.Original,
+      creators: [
+        { address: entangler.entanglerAuthority, verified: true, share: 0 },
+        { address: creator.publicKey, verified: false, share: 100 },
+      ],
+    };
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.entangleTreePair(
+          originalMint,
+          proveCompressedNft(
+            merkleTree,
+            metadata,
+            entangler.entanglerAuthority
+          )
+        )
+      ),
+      [admin]
+    );
+    expect(await getOriginalAmount()).to.equal("0");
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.disentangleTreePair(
+          originalMint,
+          proveCompressedNft(merkleTree, metadata, admin.publicKey)
+        )
+      ),
+      [admin]
+    );
+    expect(await getOriginalAmount()).to.equal("1");
+  });
+
+  it("Entangles print editions", async () => {
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      id.publicKey,
+      creator.publicKey,
+      500
+    );
+    const computeBudget = anchor.web3.ComputeBudgetProgram.setComputeUnitLimit(
+      { units: 600_000 }
+    );
+    const { mint: masterMint } = await mintNft(
+      provider,
+      "TEST",
+      creator,
+      creator.publicKey,
+      collectionMint,
+      10
+    );
+    const { mint: originalMint, masterToken } = await printEdition(
+      provider,
+      creator,
+      admin.publicKey,
+      masterMint,
+      1
+    );
+    await verifyCollection(provider, originalMint, collectionMint, creator);
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.initializePair(originalMint)
+      ),
+      [admin]
+    );
+    const entangledMetadata = await Metadata.fromAccountAddress(
+      provider.connection,
+      getMetadata(getEntangledMint(id.publicKey, originalMint))
+    );
+    expect(entangledMetadata.data.name.replace(/\0/g, "")).to.equal(
+      "Pretty Cool NFT #1"
+    );
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.entangle(originalMint)
+      ),
+      [admin]
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.burnOriginal(originalMint, undefined, {
+          masterMint,
+          masterToken,
+          edition: new anchor.BN(1),
+        })
+      ),
+      [admin]
+    );
+    expect(
+      await provider.connection.getAccountInfo(getMetadata(originalMint))
+    ).to.be.null;
+  });
+
+  it("Gives entangled tokens a master edition", async () => {
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      id.publicKey,
+      creator.publicKey,
+      500
+    );
+    const { mint: originalMint } = await mintNft(
+      provider,
+      "TEST",
+      creator,
+      admin.publicKey,
+      collectionMint
+    );
+    await verifyCollection(provider, originalMint, collectionMint, creator);
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.initializePair(originalMint)
+      ),
+      [admin]
+    );
+    const entangledMint = getEntangledMint(id.publicKey, originalMint);
+    const edition = await MasterEditionV2.fromAccountAddress(
+      provider.connection,
+      getMasterEdition(entangledMint)
+    );
+    expect(edition.maxSupply.toString()).to.equal("0");
+    expect(
+      (await getMint(provider.connection, entangledMint)).mintAuthority
+    ).to.deep.equal(getMasterEdition(entangledMint));
+  });
+
+  it("Freezes originals in non-custodial collections", async () => {
+    const nonCustodialId = Keypair.generate();
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      nonCustodialId.publicKey,
+      creator.publicKey,
+      500
+    ).withNonCustodial();
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.createCollection(
+          false,
+          false,
+          null,
+          new EntangledStandard.NonFungible(),
+          null,
+          null,
+          true
+        )
+      ),
+      [admin]
+    );
+
+    const { mint: originalMint } = await mintNft(
+      provider,
+      "TEST",
+      creator,
+      admin.publicKey,
+      collectionMint
+    );
+    await verifyCollection(provider, originalMint, collectionMint, creator);
+    const originalMintAccount = getAssociatedTokenAddressSync(
+      originalMint,
+      admin.publicKey
+    );
+    const entangledMint = getEntangledMint(
+      nonCustodialId.publicKey,
+      originalMint
+    );
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.initializePair(originalMint)
+      ),
+      [admin]
+    );
+    // Originals never get an escrow
+    expect(
+      await provider.connection.getAccountInfo(
+        getAssociatedTokenAddressSync(
+          originalMint,
+          entangler.entanglerAuthority,
+          true
+        )
+      )
+    ).to.be.null;
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.entangle(originalMint)
+      ),
+      [admin]
+    );
+    let originalAccount = await getAccount(
+      provider.connection,
+      originalMintAccount
+    );
+    expect(originalAccount.amount.toString()).to.equal("1");
+    expect(originalAccount.isFrozen).to.be.true;
+    expect(originalAccount.delegate).to.deep.equal(
+      entangler.entanglerAuthority
+    );
+    const pair = await EntangledPair.fetch(
+      provider.connection,
+      getEntangledPair(entangledMint)
+    );
+    expect(pair?.frozenAccount).to.deep.equal(originalMintAccount);
+    const verifyCustody = async (frozenAccount: PublicKey | null) =>
+      PairCustody.fromDecoded(
+        PairCustody.layout().decode(
+          await simulateReturnData(
+            provider,
+            admin.publicKey,
+            entangler.instruction.verifyPair(
+              originalMint,
+              undefined,
+              frozenAccount
+            )
+          )
+        )
+      );
+    let custody = await verifyCustody(originalMintAccount);
+    expect(custody.entangled).to.be.true;
+    expect(custody.custodyAccount).to.deep.equal(originalMintAccount);
+
+    // The owner gets their original back in place
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.disentangle(
+          originalMint,
+          null,
+          null,
+          undefined,
+          originalMintAccount
+        )
+      ),
+      [admin]
+    );
+    originalAccount = await getAccount(provider.connection, originalMintAccount);
+    expect(originalAccount.amount.toString()).to.equal("1");
+    expect(originalAccount.isFrozen).to.be.false;
+    expect(originalAccount.delegate).to.be.null;
+    custody = await verifyCustody(null);
+    expect(custody.entangled).to.be.false;
+    expect(custody.custodyAccount).to.deep.equal(
+      getAssociatedTokenAddressSync(
+        entangledMint,
+        entangler.entanglerAuthority,
+        true
+      )
+    );
+
+    // Anyone else holding the entangled token receives the original
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.entangle(originalMint)
+      ),
+      [admin]
+    );
+    const creatorEntangledAccount = getAssociatedTokenAddressSync(
+      entangledMint,
+      creator.publicKey
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          admin.publicKey,
+          creatorEntangledAccount,
+          creator.publicKey,
+          entangledMint
+        ),
+        createTransferInstruction(
+          getAssociatedTokenAddressSync(entangledMint, admin.publicKey),
+          creatorEntangledAccount,
+          admin.publicKey,
+          1
+        )
+      ),
+      [admin]
+    );
+    const creatorEntangler = new EntanglerWrapper(
+      collectionMint,
+      creator.publicKey,
+      nonCustodialId.publicKey,
+      creator.publicKey,
+      500
+    ).withNonCustodial();
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        creatorEntangler.instruction.disentangle(
+          originalMint,
+          null,
+          null,
+          undefined,
+          originalMintAccount
+        )
+      ),
+      [creator]
+    );
+    expect(
+      (
+        await getAccount(
+          provider.connection,
+          getAssociatedTokenAddressSync(originalMint, creator.publicKey)
+        )
+      ).amount.toString()
+    ).to.equal("1");
+    originalAccount = await getAccount(provider.connection, originalMintAccount);
+    expect(originalAccount.amount.toString()).to.equal("0");
+    expect(originalAccount.isFrozen).to.be.false;
+    expect(originalAccount.delegate).to.be.null;
+  });
+
+  it("Entangles in a single transaction", async () => {
+    const lazyId = Keypair.generate();
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      lazyId.publicKey,
+      creator.publicKey,
+      500
+    );
+    const computeBudget = anchor.web3.ComputeBudgetProgram.setComputeUnitLimit(
+      { units: 600_000 }
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.createCollection(false)
+      ),
+      [admin]
+    );
+
+    const { mint: originalMint } = await mintNft(
+      provider,
+      "TEST",
+      creator,
+      admin.publicKey,
+      collectionMint
+    );
+    await verifyCollection(provider, originalMint, collectionMint, creator);
+    const originalMintAccount = getAssociatedTokenAddressSync(
+      originalMint,
+      admin.publicKey
+    );
+    const entangledMintAccount = getAssociatedTokenAddressSync(
+      getEntangledMint(lazyId.publicKey, originalMint),
+      admin.publicKey
+    );
+    const getAmounts = async () =>
+      (
+        await Promise.all([
+          getAccount(provider.connection, originalMintAccount),
+          getAccount(provider.connection, entangledMintAccount),
+        ])
+      ).map((account) => account.amount.toString());
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.initializeAndEntangle(originalMint)
+      ),
+      [admin]
+    );
+    expect(await getAmounts()).to.deep.equal(["0", "1"]);
+
+    // The existing pair is reused
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.disentangle(originalMint)
+      ),
+      [admin]
+    );
+    expect(await getAmounts()).to.deep.equal(["1", "0"]);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        computeBudget,
+        entangler.instruction.initializeAndEntangle(originalMint)
+      ),
+      [admin]
+    );
+    expect(await getAmounts()).to.deep.equal(["0", "1"]);
+  });
+
+  it("Entangles batches of tokens", async () => {
+    const batchId = Keypair.generate();
+    const entangler = new EntanglerWrapper(
+      collectionMint,
+      admin.publicKey,
+      batchId.publicKey,
+      creator.publicKey,
+      500
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.createCollection(false)
+      ),
+      [admin]
+    );
+
+    const originalMints: PublicKey[] = [];
+    for (let i = 0; i < 3; i++) {
+      const { mint: originalMint } = await mintNft(
+        provider,
+        "TEST",
+        creator,
+        admin.publicKey,
+        collectionMint
+      );
+      await verifyCollection(provider, originalMint, collectionMint, creator);
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          entangler.instruction.initializePair(originalMint)
+        ),
+        [admin]
+      );
+      originalMints.push(originalMint);
+    }
+    const getAmounts = async () =>
+      await Promise.all(
+        originalMints.map(async (originalMint) =>
+          (
+            await getAccount(
+              provider.connection,
+              getAssociatedTokenAddressSync(originalMint, admin.publicKey)
+            )
+          ).amount.toString()
+        )
+      );
+
+    const send = async (instruction: anchor.web3.TransactionInstruction) => {
+      const lookupTable = await createLookupTable(
+        provider,
+        admin,
+        instruction.keys.map((key) => key.pubkey)
+      );
+      const tx = new VersionedTransaction(
+        new TransactionMessage({
+          payerKey: admin.publicKey,
+          recentBlockhash: (await provider.connection.getLatestBlockhash())
+            .blockhash,
+          instructions: [
+            anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+              units: 1_000_000,
+            }),
+            instruction,
+          ],
+        }).compileToV0Message([lookupTable])
+      );
+      tx.sign([admin]);
+      await provider.connection.confirmTransaction(
+        await provider.connection.sendTransaction(tx)
+      );
+    };
+
+    await send(entangler.instruction.entangleBatch(originalMints));
+    expect(await getAmounts()).to.deep.equal(["0", "0", "0"]);
+
+    await send(entangler.instruction.disentangleBatch(originalMints));
+    expect(await getAmounts()).to.deep.equal(["1", "1", "1"]);
+
+    // A token that can't be swapped fails the whole batch
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        entangler.instruction.entangle(originalMints[2])
+      ),
+      [admin]
+    );
+    let failed = false;
+    await send(entangler.instruction.entangleBatch(originalMints)).catch(
+      () => (failed = true)
+    );
+    expect(failed).to.be.true;
+    expect(await getAmounts()).to.deep.equal(["1", "1", "0"]);
+
+    await expectCustomError(
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          entangler.instruction.disentangleBatch([])
+        ),
+        [admin]
+      ),
+      new EmptyBatch()
+    );
+  });
 });
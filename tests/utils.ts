@@ -1,21 +1,48 @@
-import * as anchor from "@project-serum/anchor";
+import * as anchor from "@coral-xyz/anchor";
 
+import {
+  MetadataArgs,
+  PROGRAM_ID as BUBBLEGUM_PROGRAM_ID,
+  TokenProgramVersion,
+  TokenStandard as CompressedTokenStandard,
+  computeCompressedNFTHash,
+  createCreateTreeInstruction,
+  createMintToCollectionV1Instruction,
+  metadataArgsBeet,
+} from "@metaplex-foundation/mpl-bubblegum";
+import {
+  MerkleTree,
+  SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+  SPL_NOOP_PROGRAM_ID,
+  createAllocTreeIx,
+} from "@solana/spl-account-compression";
 import {
   PROGRAM_ID as METADATA_PROGRAM_ID,
+  TokenStandard,
+  VerificationArgs,
+  createCreateInstruction,
   createCreateMasterEditionV3Instruction,
   createCreateMetadataAccountInstruction,
   createCreateMetadataAccountV2Instruction,
   createCreateMetadataAccountV3Instruction,
+  createMintInstruction,
+  createMintNewEditionFromMasterEditionViaTokenInstruction,
   createVerifyCollectionInstruction,
+  createVerifyInstruction,
 } from "@metaplex-foundation/mpl-token-metadata";
 import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
   createAssociatedTokenAccount,
   createMint,
   getAccount,
+  getAssociatedTokenAddressSync,
   mintToChecked,
 } from "@solana/spl-token";
 
+import { getAssetId, getEditionMarker } from "../ts/pda";
+
 export const provider = anchor.getProvider() as anchor.AnchorProvider;
 
 export const createKeypair = async (provider: anchor.Provider) => {
@@ -102,7 +129,8 @@ export const mintNft = async (
   symbol: string,
   creator: anchor.web3.Keypair,
   destination: anchor.web3.PublicKey,
-  collectionMint?: anchor.web3.PublicKey
+  collectionMint?: anchor.web3.PublicKey,
+  maxSupply = 0
 ) => {
   const mint = await createMint(
     provider.connection,
@@ -184,7 +212,7 @@ export const mintNft = async (
         systemProgram: anchor.web3.SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
       },
-      { createMasterEditionArgs: { maxSupply: 0 } }
+      { createMasterEditionArgs: { maxSupply } }
     )
   );
 
@@ -193,6 +221,344 @@ export const mintNft = async (
   return { mint, metadata };
 };
 
+/** Prints an edition of a master edition held by the creator */
+export const printEdition = async (
+  provider: anchor.AnchorProvider,
+  creator: anchor.web3.Keypair,
+  destination: anchor.web3.PublicKey,
+  masterMint: anchor.web3.PublicKey,
+  edition: number
+) => {
+  const mint = await createMint(
+    provider.connection,
+    creator,
+    creator.publicKey,
+    creator.publicKey,
+    0
+  );
+  const tokenAccount = await createAssociatedTokenAccount(
+    provider.connection,
+    creator,
+    mint,
+    destination
+  );
+  await mintToChecked(
+    provider.connection,
+    creator,
+    mint,
+    tokenAccount,
+    creator.publicKey,
+    1,
+    0
+  );
+
+  const masterToken = getAssociatedTokenAddressSync(
+    masterMint,
+    creator.publicKey
+  );
+  const transaction = new anchor.web3.Transaction().add(
+    createMintNewEditionFromMasterEditionViaTokenInstruction(
+      {
+        newMetadata: await getTokenMetadata(mint),
+        newEdition: await getTokenEdition(mint),
+        masterEdition: await getTokenEdition(masterMint),
+        newMint: mint,
+        editionMarkPda: getEditionMarker(masterMint, new anchor.BN(edition)),
+        newMintAuthority: creator.publicKey,
+        payer: creator.publicKey,
+        tokenAccountOwner: creator.publicKey,
+        tokenAccount: masterToken,
+        newMetadataUpdateAuthority: creator.publicKey,
+        metadata: await getTokenMetadata(masterMint),
+      },
+      { mintNewEditionFromMasterEditionViaTokenArgs: { edition } }
+    )
+  );
+  await provider.sendAndConfirm(transaction, [creator]);
+
+  return { mint, masterToken };
+};
+
+export const mintProgrammableNft = async (
+  provider: anchor.AnchorProvider,
+  symbol: string,
+  creator: anchor.web3.Keypair,
+  destination: anchor.web3.PublicKey,
+  collectionMint: anchor.web3.PublicKey,
+  ruleSet: anchor.web3.PublicKey | null = null
+) =>
+  mintNftV1(
+    provider,
+    symbol,
+    creator,
+    destination,
+    collectionMint,
+    TokenStandard.ProgrammableNonFungible,
+    TOKEN_PROGRAM_ID,
+    ruleSet
+  );
+
+export const mintToken2022Nft = async (
+  provider: anchor.AnchorProvider,
+  symbol: string,
+  creator: anchor.web3.Keypair,
+  destination: anchor.web3.PublicKey,
+  collectionMint: anchor.web3.PublicKey
+) =>
+  mintNftV1(
+    provider,
+    symbol,
+    creator,
+    destination,
+    collectionMint,
+    TokenStandard.NonFungible,
+    TOKEN_2022_PROGRAM_ID
+  );
+
+const mintNftV1 = async (
+  provider: anchor.AnchorProvider,
+  symbol: string,
+  creator: anchor.web3.Keypair,
+  destination: anchor.web3.PublicKey,
+  collectionMint: anchor.web3.PublicKey,
+  tokenStandard: TokenStandard,
+  tokenProgram: anchor.web3.PublicKey,
+  ruleSet: anchor.web3.PublicKey | null = null
+) => {
+  const mint = anchor.web3.Keypair.generate();
+  const metadata = await getTokenMetadata(mint.publicKey);
+  const masterEdition = await getTokenEdition(mint.publicKey);
+  const token = getAssociatedTokenAddressSync(
+    mint.publicKey,
+    destination,
+    false,
+    tokenProgram
+  );
+  const tokenRecord =
+    tokenStandard === TokenStandard.ProgrammableNonFungible
+      ? anchor.web3.PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("metadata"),
+            METADATA_PROGRAM_ID.toBuffer(),
+            mint.publicKey.toBuffer(),
+            Buffer.from("token_record"),
+            token.toBuffer(),
+          ],
+          METADATA_PROGRAM_ID
+        )[0]
+      : undefined;
+
+  const transaction = new anchor.web3.Transaction();
+  transaction.add(
+    anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    createCreateInstruction(
+      {
+        metadata,
+        masterEdition,
+        mint: mint.publicKey,
+        authority: creator.publicKey,
+        payer: creator.publicKey,
+        updateAuthority: creator.publicKey,
+        splTokenProgram: tokenProgram,
+        sysvarInstructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+      },
+      {
+        createArgs: {
+          __kind: "V1",
+          assetData: {
+            name: "Pretty Cool NFT",
+            symbol,
+            uri: "https://pretty-cool-nft.xyz/metadata",
+            sellerFeeBasisPoints: 100,
+            creators: [
+              {
+                address: creator.publicKey,
+                share: 100,
+                verified: true,
+              },
+            ],
+            primarySaleHappened: false,
+            isMutable: true,
+            tokenStandard,
+            collection: { key: collectionMint, verified: false },
+            uses: null,
+            collectionDetails: null,
+            ruleSet,
+          },
+          decimals: 0,
+          printSupply: { __kind: "Zero" },
+        },
+      }
+    ),
+    createMintInstruction(
+      {
+        token,
+        tokenOwner: destination,
+        metadata,
+        masterEdition,
+        tokenRecord,
+        mint: mint.publicKey,
+        authority: creator.publicKey,
+        payer: creator.publicKey,
+        sysvarInstructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        splTokenProgram: tokenProgram,
+        splAtaProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      },
+      { mintArgs: { __kind: "V1", amount: 1, authorizationData: null } }
+    ),
+    createVerifyInstruction(
+      {
+        authority: creator.publicKey,
+        metadata,
+        collectionMint,
+        collectionMetadata: await getTokenMetadata(collectionMint),
+        collectionMasterEdition: await getTokenEdition(collectionMint),
+        sysvarInstructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+      },
+      { verificationArgs: VerificationArgs.CollectionV1 }
+    )
+  );
+  await provider.sendAndConfirm(transaction, [creator, mint]);
+
+  return { mint: mint.publicKey, metadata };
+};
+
+const COMPRESSED_TREE_DEPTH = 3;
+
+export const mintCompressedNft = async (
+  provider: anchor.AnchorProvider,
+  symbol: string,
+  creator: anchor.web3.Keypair,
+  leafOwner: anchor.web3.PublicKey,
+  collectionMint: anchor.web3.PublicKey
+) => {
+  const merkleTree = anchor.web3.Keypair.generate();
+  const [treeConfig] = anchor.web3.PublicKey.findProgramAddressSync(
+    [merkleTree.publicKey.toBuffer()],
+    BUBBLEGUM_PROGRAM_ID
+  );
+  const [bubblegumSigner] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("collection_cpi")],
+    BUBBLEGUM_PROGRAM_ID
+  );
+
+  const metadata: MetadataArgs = {
+    name: "Pretty Cool NFT",
+    symbol,
+    uri: "https://pretty-cool-nft.xyz/metadata",
+    sellerFeeBasisPoints: 100,
+    primarySaleHappened: false,
+    isMutable: true,
+    editionNonce: null,
+    tokenStandard: CompressedTokenStandard.NonFungible,
+    collection: { key: collectionMint, verified: false },
+    uses: null,
+    tokenProgramVersion: TokenProgramVersion.Original,
+    creators: [{ address: creator.publicKey, verified: false, share: 100 }],
+  };
+
+  const transaction = new anchor.web3.Transaction();
+  transaction.add(
+    await createAllocTreeIx(
+      provider.connection,
+      merkleTree.publicKey,
+      creator.publicKey,
+      { maxDepth: COMPRESSED_TREE_DEPTH, maxBufferSize: 8 },
+      0
+    ),
+    createCreateTreeInstruction(
+      {
+        treeAuthority: treeConfig,
+        merkleTree: merkleTree.publicKey,
+        payer: creator.publicKey,
+        treeCreator: creator.publicKey,
+        logWrapper: SPL_NOOP_PROGRAM_ID,
+        compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+      },
+      { maxDepth: COMPRESSED_TREE_DEPTH, maxBufferSize: 8, public: false }
+    ),
+    createMintToCollectionV1Instruction(
+      {
+        treeAuthority: treeConfig,
+        leafOwner,
+        leafDelegate: leafOwner,
+        merkleTree: merkleTree.publicKey,
+        payer: creator.publicKey,
+        treeDelegate: creator.publicKey,
+        collectionAuthority: creator.publicKey,
+        collectionAuthorityRecordPda: BUBBLEGUM_PROGRAM_ID,
+        collectionMint,
+        collectionMetadata: await getTokenMetadata(collectionMint),
+        editionAccount: await getTokenEdition(collectionMint),
+        bubblegumSigner,
+        logWrapper: SPL_NOOP_PROGRAM_ID,
+        compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        tokenMetadataProgram: METADATA_PROGRAM_ID,
+      },
+      { metadataArgs: metadata }
+    )
+  );
+  await provider.sendAndConfirm(transaction, [creator, merkleTree]);
+
+  // Bubblegum verifies the collection of the minted leaf
+  metadata.collection = { key: collectionMint, verified: true };
+  return { merkleTree: merkleTree.publicKey, metadata };
+};
+
+/** Allocates an empty tree, to be created by a Bubblegum instruction */
+export const allocCompressedTree = async (
+  provider: anchor.AnchorProvider,
+  payer: anchor.web3.PublicKey
+) => {
+  const merkleTree = anchor.web3.Keypair.generate();
+  const maxBufferSize = 8;
+  const instruction = await createAllocTreeIx(
+    provider.connection,
+    merkleTree.publicKey,
+    payer,
+    { maxDepth: COMPRESSED_TREE_DEPTH, maxBufferSize },
+    0
+  );
+  return {
+    merkleTree,
+    maxDepth: COMPRESSED_TREE_DEPTH,
+    maxBufferSize,
+    instruction,
+  };
+};
+
+/** Proves the only leaf of a tree created by `mintCompressedNft` */
+export const proveCompressedNft = (
+  merkleTree: anchor.web3.PublicKey,
+  metadata: MetadataArgs,
+  leafOwner: anchor.web3.PublicKey
+) => {
+  const nonce = new anchor.BN(0);
+  const leaf = computeCompressedNFTHash(
+    getAssetId(merkleTree, nonce),
+    leafOwner,
+    leafOwner,
+    nonce,
+    metadata
+  );
+  const tree = MerkleTree.sparseMerkleTreeFromLeaves(
+    [leaf],
+    COMPRESSED_TREE_DEPTH
+  );
+  const { root, proof } = tree.getProof(0);
+
+  return {
+    merkleTree,
+    root: Array.from(root),
+    metadata: metadataArgsBeet.serialize(metadata)[0],
+    nonce,
+    index: 0,
+    leafOwner,
+    leafDelegate: leafOwner,
+    proof: proof.map((node) => new anchor.web3.PublicKey(node)),
+  };
+};
+
 export const verifyCollection = async (
   provider: anchor.AnchorProvider,
   nftMint: anchor.web3.PublicKey,
@@ -227,3 +593,64 @@ export const printAccounts = (accounts: {
     })
   );
 };
+
+/** Creates a lookup table holding `addresses`, ready to be used */
+export const createLookupTable = async (
+  provider: anchor.AnchorProvider,
+  payer: anchor.web3.Keypair,
+  addresses: anchor.web3.PublicKey[]
+) => {
+  const [instruction, lookupTable] =
+    anchor.web3.AddressLookupTableProgram.createLookupTable({
+      authority: payer.publicKey,
+      payer: payer.publicKey,
+      recentSlot: await provider.connection.getSlot("finalized"),
+    });
+  await provider.sendAndConfirm(
+    new anchor.web3.Transaction().add(instruction),
+    [payer]
+  );
+  for (let i = 0; i < addresses.length; i += 20) {
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.AddressLookupTableProgram.extendLookupTable({
+          lookupTable,
+          authority: payer.publicKey,
+          payer: payer.publicKey,
+          addresses: addresses.slice(i, i + 20),
+        })
+      ),
+      [payer]
+    );
+  }
+
+  // Extended addresses can only be looked up from the next slot
+  const slot = await provider.connection.getSlot();
+  while ((await provider.connection.getSlot()) <= slot) {
+    await new Promise((resolve) => setTimeout(resolve, 100));
+  }
+  return (await provider.connection.getAddressLookupTable(lookupTable)).value;
+};
+
+/** Simulates an instruction paid by `payer`, and returns the data it returned */
+export const simulateReturnData = async (
+  provider: anchor.AnchorProvider,
+  payer: anchor.web3.PublicKey,
+  instruction: anchor.web3.TransactionInstruction
+) => {
+  const tx = new anchor.web3.VersionedTransaction(
+    new anchor.web3.TransactionMessage({
+      payerKey: payer,
+      recentBlockhash: (await provider.connection.getLatestBlockhash())
+        .blockhash,
+      instructions: [instruction],
+    }).compileToV0Message()
+  );
+  const { value } = await provider.connection.simulateTransaction(tx, {
+    sigVerify: false,
+  });
+  if (value.err) {
+    throw new Error(`Simulation failed: ${JSON.stringify(value.err)}`);
+  }
+  return Buffer.from(value.returnData?.data[0] ?? "", "base64");
+};
@@ -1,6 +1,7 @@
 import { PublicKey, Connection } from "@solana/web3.js"
 import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
-import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
 import { PROGRAM_ID } from "../programId"
 
 export interface EntangledCollectionFields {
@@ -14,6 +15,28 @@ export interface EntangledCollectionFields {
   royalties: number
   /** Whether it is possible to disentangle */
   oneWay: boolean
+  /** Whether originals with an unverified collection are accepted (legacy collections) */
+  allowUnverified: boolean
+  /** A creator that must be verified on every original */
+  originalCreator: PublicKey | null
+  /** The token standard of the entangled tokens */
+  entangledStandard: types.EntangledStandardKind
+  /** The rule set enforced on programmable entangled tokens */
+  ruleSet: PublicKey | null
+  /**
+   * The transfer hook program of Token-2022 entangled tokens. The entangler ships none, so
+   * royalties are only enforced if the collection's own hook enforces them
+   */
+  transferHookProgram: PublicKey | null
+  /** The Bubblegum tree holding compressed entangled tokens */
+  entangledTree: PublicKey | null
+  /** Whether originals stay frozen in their owner's account instead of an escrow */
+  nonCustodial: boolean
+  /**
+   * Whether escrows and authorities belong to the collection's own PDA instead of the
+   * global one. False for collections created before per-collection authorities
+   */
+  scopedAuthority: boolean
 }
 
 export interface EntangledCollectionJSON {
@@ -27,6 +50,28 @@ export interface EntangledCollectionJSON {
   royalties: number
   /** Whether it is possible to disentangle */
   oneWay: boolean
+  /** Whether originals with an unverified collection are accepted (legacy collections) */
+  allowUnverified: boolean
+  /** A creator that must be verified on every original */
+  originalCreator: string | null
+  /** The token standard of the entangled tokens */
+  entangledStandard: types.EntangledStandardJSON
+  /** The rule set enforced on programmable entangled tokens */
+  ruleSet: string | null
+  /**
+   * The transfer hook program of Token-2022 entangled tokens. The entangler ships none, so
+   * royalties are only enforced if the collection's own hook enforces them
+   */
+  transferHookProgram: string | null
+  /** The Bubblegum tree holding compressed entangled tokens */
+  entangledTree: string | null
+  /** Whether originals stay frozen in their owner's account instead of an escrow */
+  nonCustodial: boolean
+  /**
+   * Whether escrows and authorities belong to the collection's own PDA instead of the
+   * global one. False for collections created before per-collection authorities
+   */
+  scopedAuthority: boolean
 }
 
 export class EntangledCollection {
@@ -40,6 +85,28 @@ export class EntangledCollection {
   readonly royalties: number
   /** Whether it is possible to disentangle */
   readonly oneWay: boolean
+  /** Whether originals with an unverified collection are accepted (legacy collections) */
+  readonly allowUnverified: boolean
+  /** A creator that must be verified on every original */
+  readonly originalCreator: PublicKey | null
+  /** The token standard of the entangled tokens */
+  readonly entangledStandard: types.EntangledStandardKind
+  /** The rule set enforced on programmable entangled tokens */
+  readonly ruleSet: PublicKey | null
+  /**
+   * The transfer hook program of Token-2022 entangled tokens. The entangler ships none, so
+   * royalties are only enforced if the collection's own hook enforces them
+   */
+  readonly transferHookProgram: PublicKey | null
+  /** The Bubblegum tree holding compressed entangled tokens */
+  readonly entangledTree: PublicKey | null
+  /** Whether originals stay frozen in their owner's account instead of an escrow */
+  readonly nonCustodial: boolean
+  /**
+   * Whether escrows and authorities belong to the collection's own PDA instead of the
+   * global one. False for collections created before per-collection authorities
+   */
+  readonly scopedAuthority: boolean
 
   static readonly discriminator = Buffer.from([
     185, 244, 55, 234, 11, 82, 36, 28,
@@ -51,6 +118,14 @@ export class EntangledCollection {
     borsh.publicKey("entangledCollectionMint"),
     borsh.u16("royalties"),
     borsh.bool("oneWay"),
+    borsh.bool("allowUnverified"),
+    borsh.option(borsh.publicKey(), "originalCreator"),
+    types.EntangledStandard.layout("entangledStandard"),
+    borsh.option(borsh.publicKey(), "ruleSet"),
+    borsh.option(borsh.publicKey(), "transferHookProgram"),
+    borsh.option(borsh.publicKey(), "entangledTree"),
+    borsh.bool("nonCustodial"),
+    borsh.bool("scopedAuthority"),
   ])
 
   constructor(fields: EntangledCollectionFields) {
@@ -59,6 +134,14 @@ export class EntangledCollection {
     this.entangledCollectionMint = fields.entangledCollectionMint
     this.royalties = fields.royalties
     this.oneWay = fields.oneWay
+    this.allowUnverified = fields.allowUnverified
+    this.originalCreator = fields.originalCreator
+    this.entangledStandard = fields.entangledStandard
+    this.ruleSet = fields.ruleSet
+    this.transferHookProgram = fields.transferHookProgram
+    this.entangledTree = fields.entangledTree
+    this.nonCustodial = fields.nonCustodial
+    this.scopedAuthority = fields.scopedAuthority
   }
 
   static async fetch(
@@ -108,6 +191,16 @@ export class EntangledCollection {
       entangledCollectionMint: dec.entangledCollectionMint,
       royalties: dec.royalties,
       oneWay: dec.oneWay,
+      allowUnverified: dec.allowUnverified,
+      originalCreator: dec.originalCreator,
+      entangledStandard: types.EntangledStandard.fromDecoded(
+        dec.entangledStandard
+      ),
+      ruleSet: dec.ruleSet,
+      transferHookProgram: dec.transferHookProgram,
+      entangledTree: dec.entangledTree,
+      nonCustodial: dec.nonCustodial,
+      scopedAuthority: dec.scopedAuthority,
     })
   }
 
@@ -118,6 +211,18 @@ export class EntangledCollection {
       entangledCollectionMint: this.entangledCollectionMint.toString(),
       royalties: this.royalties,
       oneWay: this.oneWay,
+      allowUnverified: this.allowUnverified,
+      originalCreator:
+        (this.originalCreator && this.originalCreator.toString()) || null,
+      entangledStandard: this.entangledStandard.toJSON(),
+      ruleSet: (this.ruleSet && this.ruleSet.toString()) || null,
+      transferHookProgram:
+        (this.transferHookProgram && this.transferHookProgram.toString()) ||
+        null,
+      entangledTree:
+        (this.entangledTree && this.entangledTree.toString()) || null,
+      nonCustodial: this.nonCustodial,
+      scopedAuthority: this.scopedAuthority,
     }
   }
 
@@ -128,6 +233,20 @@ export class EntangledCollection {
       entangledCollectionMint: new PublicKey(obj.entangledCollectionMint),
       royalties: obj.royalties,
       oneWay: obj.oneWay,
+      allowUnverified: obj.allowUnverified,
+      originalCreator:
+        (obj.originalCreator && new PublicKey(obj.originalCreator)) || null,
+      entangledStandard: types.EntangledStandard.fromJSON(
+        obj.entangledStandard
+      ),
+      ruleSet: (obj.ruleSet && new PublicKey(obj.ruleSet)) || null,
+      transferHookProgram:
+        (obj.transferHookProgram && new PublicKey(obj.transferHookProgram)) ||
+        null,
+      entangledTree:
+        (obj.entangledTree && new PublicKey(obj.entangledTree)) || null,
+      nonCustodial: obj.nonCustodial,
+      scopedAuthority: obj.scopedAuthority,
     })
   }
 }
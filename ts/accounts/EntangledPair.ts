@@ -1,27 +1,48 @@
 import { PublicKey, Connection } from "@solana/web3.js"
 import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
-import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
 import { PROGRAM_ID } from "../programId"
 
 export interface EntangledPairFields {
-  /** The original collection mint */
+  /** The original mint, or the asset id of a compressed original */
   originalMint: PublicKey
-  /** The mint of the entangled tokens */
+  /** The mint of the entangled tokens, or the asset id of a compressed entangled token */
   entangledMint: PublicKey
+  /** The token account the original is frozen in, for non-custodial collections */
+  frozenAccount: PublicKey | null
+  /**
+   * Whether the global authority no longer escrows the original, because the pair got migrated
+   * or got entangled by the collection's own authority
+   */
+  migrated: boolean
 }
 
 export interface EntangledPairJSON {
-  /** The original collection mint */
+  /** The original mint, or the asset id of a compressed original */
   originalMint: string
-  /** The mint of the entangled tokens */
+  /** The mint of the entangled tokens, or the asset id of a compressed entangled token */
   entangledMint: string
+  /** The token account the original is frozen in, for non-custodial collections */
+  frozenAccount: string | null
+  /**
+   * Whether the global authority no longer escrows the original, because the pair got migrated
+   * or got entangled by the collection's own authority
+   */
+  migrated: boolean
 }
 
 export class EntangledPair {
-  /** The original collection mint */
+  /** The original mint, or the asset id of a compressed original */
   readonly originalMint: PublicKey
-  /** The mint of the entangled tokens */
+  /** The mint of the entangled tokens, or the asset id of a compressed entangled token */
   readonly entangledMint: PublicKey
+  /** The token account the original is frozen in, for non-custodial collections */
+  readonly frozenAccount: PublicKey | null
+  /**
+   * Whether the global authority no longer escrows the original, because the pair got migrated
+   * or got entangled by the collection's own authority
+   */
+  readonly migrated: boolean
 
   static readonly discriminator = Buffer.from([
     133, 118, 20, 210, 1, 54, 172, 116,
@@ -30,11 +51,15 @@ export class EntangledPair {
   static readonly layout = borsh.struct([
     borsh.publicKey("originalMint"),
     borsh.publicKey("entangledMint"),
+    borsh.option(borsh.publicKey(), "frozenAccount"),
+    borsh.bool("migrated"),
   ])
 
   constructor(fields: EntangledPairFields) {
     this.originalMint = fields.originalMint
     this.entangledMint = fields.entangledMint
+    this.frozenAccount = fields.frozenAccount
+    this.migrated = fields.migrated
   }
 
   static async fetch(
@@ -81,6 +106,8 @@ export class EntangledPair {
     return new EntangledPair({
       originalMint: dec.originalMint,
       entangledMint: dec.entangledMint,
+      frozenAccount: dec.frozenAccount,
+      migrated: dec.migrated,
     })
   }
 
@@ -88,6 +115,9 @@ export class EntangledPair {
     return {
       originalMint: this.originalMint.toString(),
       entangledMint: this.entangledMint.toString(),
+      frozenAccount:
+        (this.frozenAccount && this.frozenAccount.toString()) || null,
+      migrated: this.migrated,
     }
   }
 
@@ -95,6 +125,9 @@ export class EntangledPair {
     return new EntangledPair({
       originalMint: new PublicKey(obj.originalMint),
       entangledMint: new PublicKey(obj.entangledMint),
+      frozenAccount:
+        (obj.frozenAccount && new PublicKey(obj.frozenAccount)) || null,
+      migrated: obj.migrated,
     })
   }
 }
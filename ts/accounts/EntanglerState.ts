@@ -1,6 +1,6 @@
 import { PublicKey, Connection } from "@solana/web3.js"
 import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
-import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
 import { PROGRAM_ID } from "../programId"
 
 export interface EntanglerStateFields {
@@ -12,6 +12,11 @@ export interface EntanglerStateFields {
   feeMint: PublicKey
   /** The cost to create an entry */
   price: BN
+  /**
+   * Whether every original initialized before original records existed got one, so that a
+   * missing record proves a token held by the global authority backs no pair
+   */
+  recordsBackfilled: boolean
 }
 
 export interface EntanglerStateJSON {
@@ -23,6 +28,11 @@ export interface EntanglerStateJSON {
   feeMint: string
   /** The cost to create an entry */
   price: string
+  /**
+   * Whether every original initialized before original records existed got one, so that a
+   * missing record proves a token held by the global authority backs no pair
+   */
+  recordsBackfilled: boolean
 }
 
 export class EntanglerState {
@@ -34,6 +44,11 @@ export class EntanglerState {
   readonly feeMint: PublicKey
   /** The cost to create an entry */
   readonly price: BN
+  /**
+   * Whether every original initialized before original records existed got one, so that a
+   * missing record proves a token held by the global authority backs no pair
+   */
+  readonly recordsBackfilled: boolean
 
   static readonly discriminator = Buffer.from([
     111, 22, 90, 132, 143, 229, 18, 246,
@@ -44,6 +59,7 @@ export class EntanglerState {
     borsh.publicKey("earner"),
     borsh.publicKey("feeMint"),
     borsh.u64("price"),
+    borsh.bool("recordsBackfilled"),
   ])
 
   constructor(fields: EntanglerStateFields) {
@@ -51,6 +67,7 @@ export class EntanglerState {
     this.earner = fields.earner
     this.feeMint = fields.feeMint
     this.price = fields.price
+    this.recordsBackfilled = fields.recordsBackfilled
   }
 
   static async fetch(
@@ -99,6 +116,7 @@ export class EntanglerState {
       earner: dec.earner,
       feeMint: dec.feeMint,
       price: dec.price,
+      recordsBackfilled: dec.recordsBackfilled,
     })
   }
 
@@ -108,6 +126,7 @@ export class EntanglerState {
       earner: this.earner.toString(),
       feeMint: this.feeMint.toString(),
       price: this.price.toString(),
+      recordsBackfilled: this.recordsBackfilled,
     }
   }
 
@@ -117,6 +136,7 @@ export class EntanglerState {
       earner: new PublicKey(obj.earner),
       feeMint: new PublicKey(obj.feeMint),
       price: new BN(obj.price),
+      recordsBackfilled: obj.recordsBackfilled,
     })
   }
 }
@@ -0,0 +1,87 @@
+import { PublicKey, Connection } from "@solana/web3.js"
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface OriginalRecordFields {
+  /** The original mint */
+  originalMint: PublicKey
+}
+
+export interface OriginalRecordJSON {
+  /** The original mint */
+  originalMint: string
+}
+
+export class OriginalRecord {
+  /** The original mint */
+  readonly originalMint: PublicKey
+
+  static readonly discriminator = Buffer.from([
+    137, 11, 57, 153, 211, 112, 31, 48,
+  ])
+
+  static readonly layout = borsh.struct([borsh.publicKey("originalMint")])
+
+  constructor(fields: OriginalRecordFields) {
+    this.originalMint = fields.originalMint
+  }
+
+  static async fetch(
+    c: Connection,
+    address: PublicKey
+  ): Promise<OriginalRecord | null> {
+    const info = await c.getAccountInfo(address)
+
+    if (info === null) {
+      return null
+    }
+    if (!info.owner.equals(PROGRAM_ID)) {
+      throw new Error("account doesn't belong to this program")
+    }
+
+    return this.decode(info.data)
+  }
+
+  static async fetchMultiple(
+    c: Connection,
+    addresses: PublicKey[]
+  ): Promise<Array<OriginalRecord | null>> {
+    const infos = await c.getMultipleAccountsInfo(addresses)
+
+    return infos.map((info) => {
+      if (info === null) {
+        return null
+      }
+      if (!info.owner.equals(PROGRAM_ID)) {
+        throw new Error("account doesn't belong to this program")
+      }
+
+      return this.decode(info.data)
+    })
+  }
+
+  static decode(data: Buffer): OriginalRecord {
+    if (!data.slice(0, 8).equals(OriginalRecord.discriminator)) {
+      throw new Error("invalid account discriminator")
+    }
+
+    const dec = OriginalRecord.layout.decode(data.slice(8))
+
+    return new OriginalRecord({
+      originalMint: dec.originalMint,
+    })
+  }
+
+  toJSON(): OriginalRecordJSON {
+    return {
+      originalMint: this.originalMint.toString(),
+    }
+  }
+
+  static fromJSON(obj: OriginalRecordJSON): OriginalRecord {
+    return new OriginalRecord({
+      originalMint: new PublicKey(obj.originalMint),
+    })
+  }
+}
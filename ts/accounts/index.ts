@@ -12,3 +12,5 @@ export type {
 } from "./CollectionEntry"
 export { EntangledPair } from "./EntangledPair"
 export type { EntangledPairFields, EntangledPairJSON } from "./EntangledPair"
+export { OriginalRecord } from "./OriginalRecord"
+export type { OriginalRecordFields, OriginalRecordJSON } from "./OriginalRecord"
@@ -3,12 +3,26 @@ import { PublicKey } from "@solana/web3.js";
 
 export { ENTANGLER_PROGRAM_ID };
 export const AUTHORITY_SEED = "authority";
+export const COLLECTION_AUTHORITY_SEED = "collection-authority";
 export const STATE_SEED = "state";
 export const COLLECTION_SEED = "collection";
 export const COLLECTION_ENTRY_SEED = "collection-entry";
 export const COLLECTION_MINT_SEED = "collection-mint";
 export const ENTANGLEMENT_PAIR_SEED = "entanglement-pair";
 export const ENTANGLEMENT_MINT_SEED = "entanglement-mint";
+export const ORIGINAL_RECORD_SEED = "original-record";
+export const AUTHORIZATION_RULES_PROGRAM_ID = new PublicKey(
+  "auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg"
+);
+export const BUBBLEGUM_PROGRAM_ID = new PublicKey(
+  "BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY"
+);
+export const SPL_ACCOUNT_COMPRESSION_PROGRAM_ID = new PublicKey(
+  "cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK"
+);
+export const SPL_NOOP_PROGRAM_ID = new PublicKey(
+  "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV"
+);
 export const DIPPIES_DAO_KEY = new PublicKey(
   "3h2CFnu8w7NRemnX9ybVeXsXAP3agkMuC1Kz8TnERYUi"
 );
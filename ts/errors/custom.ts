@@ -0,0 +1,652 @@
+export type CustomError =
+  | InvalidCollection
+  | UnverifiedCollection
+  | UnverifiedCreator
+  | MissingProgrammableAccounts
+  | RuleSetRequiresProgrammable
+  | InvalidRuleSet
+  | NonTransferableOriginal
+  | TransferFeeOriginal
+  | TransferHookRequiresToken2022
+  | InvalidEntangledTokenProgram
+  | InvalidMerkleTree
+  | InvalidCompressedMetadata
+  | CompressedCollection
+  | InvalidEntangledTree
+  | InvalidEntangledAsset
+  | MissingEditionAccounts
+  | InvalidPrintEdition
+  | MissingEntangledEdition
+  | NonCustodialCompressed
+  | NonCustodialOriginal
+  | MissingOriginalEdition
+  | InvalidFrozenAccount
+  | NonCustodialCollection
+  | EmptyBatch
+  | InvalidMetadata
+  | InvalidEdition
+  | InvalidAuthority
+  | AlreadyMigrated
+  | UnmigratableCollection
+  | UnmigratedCollection
+  | AlreadyMigratedPair
+  | BrokenCustody
+  | InvalidSupply
+  | BackingToken
+  | UninitializedPair
+  | UnresizableAccount
+  | ForeignOriginal
+  | CompressedOriginal
+  | MissingOriginalEscrow
+  | InvalidBatchSigner
+  | MalformedBatch
+  | InvalidAdmin
+  | RecordsNotBackfilled
+  | MissingCollectionCreator
+  | InvalidTreeCreator
+  | UndersizedTree
+
+export class InvalidCollection extends Error {
+  static readonly code = 6000
+  readonly code = 6000
+  readonly name = "InvalidCollection"
+  readonly msg = "The token is not part of the original collection"
+
+  constructor(readonly logs?: string[]) {
+    super("6000: The token is not part of the original collection")
+  }
+}
+
+export class UnverifiedCollection extends Error {
+  static readonly code = 6001
+  readonly code = 6001
+  readonly name = "UnverifiedCollection"
+  readonly msg = "The token's collection is not verified"
+
+  constructor(readonly logs?: string[]) {
+    super("6001: The token's collection is not verified")
+  }
+}
+
+export class UnverifiedCreator extends Error {
+  static readonly code = 6002
+  readonly code = 6002
+  readonly name = "UnverifiedCreator"
+  readonly msg = "The token is missing the collection's verified creator"
+
+  constructor(readonly logs?: string[]) {
+    super("6002: The token is missing the collection's verified creator")
+  }
+}
+
+export class MissingProgrammableAccounts extends Error {
+  static readonly code = 6003
+  readonly code = 6003
+  readonly name = "MissingProgrammableAccounts"
+  readonly msg = "Programmable NFTs require their edition, token records and the instructions sysvar"
+
+  constructor(readonly logs?: string[]) {
+    super("6003: Programmable NFTs require their edition, token records and the instructions sysvar")
+  }
+}
+
+export class RuleSetRequiresProgrammable extends Error {
+  static readonly code = 6004
+  readonly code = 6004
+  readonly name = "RuleSetRequiresProgrammable"
+  readonly msg = "Rule sets can only be enforced on programmable entangled tokens"
+
+  constructor(readonly logs?: string[]) {
+    super("6004: Rule sets can only be enforced on programmable entangled tokens")
+  }
+}
+
+export class InvalidRuleSet extends Error {
+  static readonly code = 6005
+  readonly code = 6005
+  readonly name = "InvalidRuleSet"
+  readonly msg = "The authorization rules do not match the collection's rule set"
+
+  constructor(readonly logs?: string[]) {
+    super("6005: The authorization rules do not match the collection's rule set")
+  }
+}
+
+export class NonTransferableOriginal extends Error {
+  static readonly code = 6006
+  readonly code = 6006
+  readonly name = "NonTransferableOriginal"
+  readonly msg = "Non-transferable tokens can't be entangled"
+
+  constructor(readonly logs?: string[]) {
+    super("6006: Non-transferable tokens can't be entangled")
+  }
+}
+
+export class TransferFeeOriginal extends Error {
+  static readonly code = 6007
+  readonly code = 6007
+  readonly name = "TransferFeeOriginal"
+  readonly msg = "Tokens charging a transfer fee can't be entangled"
+
+  constructor(readonly logs?: string[]) {
+    super("6007: Tokens charging a transfer fee can't be entangled")
+  }
+}
+
+export class TransferHookRequiresToken2022 extends Error {
+  static readonly code = 6008
+  readonly code = 6008
+  readonly name = "TransferHookRequiresToken2022"
+  readonly msg = "Transfer hooks can only be set on Token-2022 entangled tokens"
+
+  constructor(readonly logs?: string[]) {
+    super("6008: Transfer hooks can only be set on Token-2022 entangled tokens")
+  }
+}
+
+export class InvalidEntangledTokenProgram extends Error {
+  static readonly code = 6009
+  readonly code = 6009
+  readonly name = "InvalidEntangledTokenProgram"
+  readonly msg = "The token program doesn't own the collection's entangled tokens"
+
+  constructor(readonly logs?: string[]) {
+    super("6009: The token program doesn't own the collection's entangled tokens")
+  }
+}
+
+export class InvalidMerkleTree extends Error {
+  static readonly code = 6010
+  readonly code = 6010
+  readonly name = "InvalidMerkleTree"
+  readonly msg = "The merkle tree is not managed by Bubblegum"
+
+  constructor(readonly logs?: string[]) {
+    super("6010: The merkle tree is not managed by Bubblegum")
+  }
+}
+
+export class InvalidCompressedMetadata extends Error {
+  static readonly code = 6011
+  readonly code = 6011
+  readonly name = "InvalidCompressedMetadata"
+  readonly msg = "The compressed metadata doesn't deserialize"
+
+  constructor(readonly logs?: string[]) {
+    super("6011: The compressed metadata doesn't deserialize")
+  }
+}
+
+export class CompressedCollection extends Error {
+  static readonly code = 6012
+  readonly code = 6012
+  readonly name = "CompressedCollection"
+  readonly msg = "Compressed collections entangle through their merkle tree"
+
+  constructor(readonly logs?: string[]) {
+    super("6012: Compressed collections entangle through their merkle tree")
+  }
+}
+
+export class InvalidEntangledTree extends Error {
+  static readonly code = 6013
+  readonly code = 6013
+  readonly name = "InvalidEntangledTree"
+  readonly msg = "The merkle tree doesn't hold the collection's entangled tokens"
+
+  constructor(readonly logs?: string[]) {
+    super("6013: The merkle tree doesn't hold the collection's entangled tokens")
+  }
+}
+
+export class InvalidEntangledAsset extends Error {
+  static readonly code = 6014
+  readonly code = 6014
+  readonly name = "InvalidEntangledAsset"
+  readonly msg = "The leaf is not the pair's entangled token"
+
+  constructor(readonly logs?: string[]) {
+    super("6014: The leaf is not the pair's entangled token")
+  }
+}
+
+export class MissingEditionAccounts extends Error {
+  static readonly code = 6015
+  readonly code = 6015
+  readonly name = "MissingEditionAccounts"
+  readonly msg = "Print editions require their master edition, its mint and token account, and the edition marker"
+
+  constructor(readonly logs?: string[]) {
+    super("6015: Print editions require their master edition, its mint and token account, and the edition marker")
+  }
+}
+
+export class InvalidPrintEdition extends Error {
+  static readonly code = 6016
+  readonly code = 6016
+  readonly name = "InvalidPrintEdition"
+  readonly msg = "The master edition accounts don't match the print edition"
+
+  constructor(readonly logs?: string[]) {
+    super("6016: The master edition accounts don't match the print edition")
+  }
+}
+
+export class MissingEntangledEdition extends Error {
+  static readonly code = 6017
+  readonly code = 6017
+  readonly name = "MissingEntangledEdition"
+  readonly msg = "Entangled NFTs require their master edition"
+
+  constructor(readonly logs?: string[]) {
+    super("6017: Entangled NFTs require their master edition")
+  }
+}
+
+export class NonCustodialCompressed extends Error {
+  static readonly code = 6018
+  readonly code = 6018
+  readonly name = "NonCustodialCompressed"
+  readonly msg = "Non-custodial collections can't hold compressed tokens"
+
+  constructor(readonly logs?: string[]) {
+    super("6018: Non-custodial collections can't hold compressed tokens")
+  }
+}
+
+export class NonCustodialOriginal extends Error {
+  static readonly code = 6019
+  readonly code = 6019
+  readonly name = "NonCustodialOriginal"
+  readonly msg = "Only non-programmable SPL originals can be frozen in their owner's account"
+
+  constructor(readonly logs?: string[]) {
+    super("6019: Only non-programmable SPL originals can be frozen in their owner's account")
+  }
+}
+
+export class MissingOriginalEdition extends Error {
+  static readonly code = 6020
+  readonly code = 6020
+  readonly name = "MissingOriginalEdition"
+  readonly msg = "Freezing an original requires its edition"
+
+  constructor(readonly logs?: string[]) {
+    super("6020: Freezing an original requires its edition")
+  }
+}
+
+export class InvalidFrozenAccount extends Error {
+  static readonly code = 6021
+  readonly code = 6021
+  readonly name = "InvalidFrozenAccount"
+  readonly msg = "The token account doesn't hold the frozen original"
+
+  constructor(readonly logs?: string[]) {
+    super("6021: The token account doesn't hold the frozen original")
+  }
+}
+
+export class NonCustodialCollection extends Error {
+  static readonly code = 6022
+  readonly code = 6022
+  readonly name = "NonCustodialCollection"
+  readonly msg = "Originals of non-custodial collections stay with their owner"
+
+  constructor(readonly logs?: string[]) {
+    super("6022: Originals of non-custodial collections stay with their owner")
+  }
+}
+
+export class EmptyBatch extends Error {
+  static readonly code = 6023
+  readonly code = 6023
+  readonly name = "EmptyBatch"
+  readonly msg = "The batch holds no token"
+
+  constructor(readonly logs?: string[]) {
+    super("6023: The batch holds no token")
+  }
+}
+
+export class InvalidMetadata extends Error {
+  static readonly code = 6024
+  readonly code = 6024
+  readonly name = "InvalidMetadata"
+  readonly msg = "The metadata account isn't the Token Metadata PDA of its mint"
+
+  constructor(readonly logs?: string[]) {
+    super("6024: The metadata account isn't the Token Metadata PDA of its mint")
+  }
+}
+
+export class InvalidEdition extends Error {
+  static readonly code = 6025
+  readonly code = 6025
+  readonly name = "InvalidEdition"
+  readonly msg = "The edition account isn't the Token Metadata PDA of its mint"
+
+  constructor(readonly logs?: string[]) {
+    super("6025: The edition account isn't the Token Metadata PDA of its mint")
+  }
+}
+
+export class InvalidAuthority extends Error {
+  static readonly code = 6026
+  readonly code = 6026
+  readonly name = "InvalidAuthority"
+  readonly msg = "The authority isn't the escrow authority of the collection"
+
+  constructor(readonly logs?: string[]) {
+    super("6026: The authority isn't the escrow authority of the collection")
+  }
+}
+
+export class AlreadyMigrated extends Error {
+  static readonly code = 6027
+  readonly code = 6027
+  readonly name = "AlreadyMigrated"
+  readonly msg = "The collection already uses its own authority"
+
+  constructor(readonly logs?: string[]) {
+    super("6027: The collection already uses its own authority")
+  }
+}
+
+export class UnmigratableCollection extends Error {
+  static readonly code = 6028
+  readonly code = 6028
+  readonly name = "UnmigratableCollection"
+  readonly msg = "Compressed and non-custodial collections keep the global authority"
+
+  constructor(readonly logs?: string[]) {
+    super("6028: Compressed and non-custodial collections keep the global authority")
+  }
+}
+
+export class UnmigratedCollection extends Error {
+  static readonly code = 6029
+  readonly code = 6029
+  readonly name = "UnmigratedCollection"
+  readonly msg = "The collection still uses the global authority"
+
+  constructor(readonly logs?: string[]) {
+    super("6029: The collection still uses the global authority")
+  }
+}
+
+export class AlreadyMigratedPair extends Error {
+  static readonly code = 6030
+  readonly code = 6030
+  readonly name = "AlreadyMigratedPair"
+  readonly msg = "The pair holds nothing for the global authority"
+
+  constructor(readonly logs?: string[]) {
+    super("6030: The pair holds nothing for the global authority")
+  }
+}
+
+export class BrokenCustody extends Error {
+  static readonly code = 6031
+  readonly code = 6031
+  readonly name = "BrokenCustody"
+  readonly msg = "The entangler must hold exactly one token of the pair"
+
+  constructor(readonly logs?: string[]) {
+    super("6031: The entangler must hold exactly one token of the pair")
+  }
+}
+
+export class InvalidSupply extends Error {
+  static readonly code = 6032
+  readonly code = 6032
+  readonly name = "InvalidSupply"
+  readonly msg = "A token of the pair doesn't have a supply of one"
+
+  constructor(readonly logs?: string[]) {
+    super("6032: A token of the pair doesn't have a supply of one")
+  }
+}
+
+export class BackingToken extends Error {
+  static readonly code = 6033
+  readonly code = 6033
+  readonly name = "BackingToken"
+  readonly msg = "The token is the original or entangled token of a pair"
+
+  constructor(readonly logs?: string[]) {
+    super("6033: The token is the original or entangled token of a pair")
+  }
+}
+
+export class UninitializedPair extends Error {
+  static readonly code = 6034
+  readonly code = 6034
+  readonly name = "UninitializedPair"
+  readonly msg = "The original has no initialized pair"
+
+  constructor(readonly logs?: string[]) {
+    super("6034: The original has no initialized pair")
+  }
+}
+
+export class UnresizableAccount extends Error {
+  static readonly code = 6035
+  readonly code = 6035
+  readonly name = "UnresizableAccount"
+  readonly msg = "Only the state, collections and pairs can be resized"
+
+  constructor(readonly logs?: string[]) {
+    super("6035: Only the state, collections and pairs can be resized")
+  }
+}
+
+export class ForeignOriginal extends Error {
+  static readonly code = 6036
+  readonly code = 6036
+  readonly name = "ForeignOriginal"
+  readonly msg = "The global authority doesn't escrow the original for this pair"
+
+  constructor(readonly logs?: string[]) {
+    super("6036: The global authority doesn't escrow the original for this pair")
+  }
+}
+
+export class CompressedOriginal extends Error {
+  static readonly code = 6037
+  readonly code = 6037
+  readonly name = "CompressedOriginal"
+  readonly msg = "Compressed originals are held in their tree, which verify_pair can't read"
+
+  constructor(readonly logs?: string[]) {
+    super("6037: Compressed originals are held in their tree, which verify_pair can't read")
+  }
+}
+
+export class MissingOriginalEscrow extends Error {
+  static readonly code = 6038
+  readonly code = 6038
+  readonly name = "MissingOriginalEscrow"
+  readonly msg = "Custodial collections need the escrow of the original"
+
+  constructor(readonly logs?: string[]) {
+    super("6038: Custodial collections need the escrow of the original")
+  }
+}
+
+export class InvalidBatchSigner extends Error {
+  static readonly code = 6039
+  readonly code = 6039
+  readonly name = "InvalidBatchSigner"
+  readonly msg = "A group of the batch has another signer than the batch"
+
+  constructor(readonly logs?: string[]) {
+    super("6039: A group of the batch has another signer than the batch")
+  }
+}
+
+export class MalformedBatch extends Error {
+  static readonly code = 6040
+  readonly code = 6040
+  readonly name = "MalformedBatch"
+  readonly msg = "The batch accounts don't match the transfer hook account counts"
+
+  constructor(readonly logs?: string[]) {
+    super("6040: The batch accounts don't match the transfer hook account counts")
+  }
+}
+
+export class InvalidAdmin extends Error {
+  static readonly code = 6041
+  readonly code = 6041
+  readonly name = "InvalidAdmin"
+  readonly msg = "The signer isn't the entangler's admin"
+
+  constructor(readonly logs?: string[]) {
+    super("6041: The signer isn't the entangler's admin")
+  }
+}
+
+export class RecordsNotBackfilled extends Error {
+  static readonly code = 6042
+  readonly code = 6042
+  readonly name = "RecordsNotBackfilled"
+  readonly msg = "Originals initialized before original records existed must all be recorded first"
+
+  constructor(readonly logs?: string[]) {
+    super("6042: Originals initialized before original records existed must all be recorded first")
+  }
+}
+
+export class MissingCollectionCreator extends Error {
+  static readonly code = 6043
+  readonly code = 6043
+  readonly name = "MissingCollectionCreator"
+  readonly msg = "The entangled collection's metadata lists no creator receiving royalties"
+
+  constructor(readonly logs?: string[]) {
+    super("6043: The entangled collection's metadata lists no creator receiving royalties")
+  }
+}
+
+export class InvalidTreeCreator extends Error {
+  static readonly code = 6044
+  readonly code = 6044
+  readonly name = "InvalidTreeCreator"
+  readonly msg = "Only the admin or the collection's creator can create its tree"
+
+  constructor(readonly logs?: string[]) {
+    super("6044: Only the admin or the collection's creator can create its tree")
+  }
+}
+
+export class UndersizedTree extends Error {
+  static readonly code = 6045
+  readonly code = 6045
+  readonly name = "UndersizedTree"
+  readonly msg = "The tree can't hold an entangled token for every original of the collection"
+
+  constructor(readonly logs?: string[]) {
+    super("6045: The tree can't hold an entangled token for every original of the collection")
+  }
+}
+
+export function fromCode(code: number, logs?: string[]): CustomError | null {
+  switch (code) {
+    case 6000:
+      return new InvalidCollection(logs)
+    case 6001:
+      return new UnverifiedCollection(logs)
+    case 6002:
+      return new UnverifiedCreator(logs)
+    case 6003:
+      return new MissingProgrammableAccounts(logs)
+    case 6004:
+      return new RuleSetRequiresProgrammable(logs)
+    case 6005:
+      return new InvalidRuleSet(logs)
+    case 6006:
+      return new NonTransferableOriginal(logs)
+    case 6007:
+      return new TransferFeeOriginal(logs)
+    case 6008:
+      return new TransferHookRequiresToken2022(logs)
+    case 6009:
+      return new InvalidEntangledTokenProgram(logs)
+    case 6010:
+      return new InvalidMerkleTree(logs)
+    case 6011:
+      return new InvalidCompressedMetadata(logs)
+    case 6012:
+      return new CompressedCollection(logs)
+    case 6013:
+      return new InvalidEntangledTree(logs)
+    case 6014:
+      return new InvalidEntangledAsset(logs)
+    case 6015:
+      return new MissingEditionAccounts(logs)
+    case 6016:
+      return new InvalidPrintEdition(logs)
+    case 6017:
+      return new MissingEntangledEdition(logs)
+    case 6018:
+      return new NonCustodialCompressed(logs)
+    case 6019:
+      return new NonCustodialOriginal(logs)
+    case 6020:
+      return new MissingOriginalEdition(logs)
+    case 6021:
+      return new InvalidFrozenAccount(logs)
+    case 6022:
+      return new NonCustodialCollection(logs)
+    case 6023:
+      return new EmptyBatch(logs)
+    case 6024:
+      return new InvalidMetadata(logs)
+    case 6025:
+      return new InvalidEdition(logs)
+    case 6026:
+      return new InvalidAuthority(logs)
+    case 6027:
+      return new AlreadyMigrated(logs)
+    case 6028:
+      return new UnmigratableCollection(logs)
+    case 6029:
+      return new UnmigratedCollection(logs)
+    case 6030:
+      return new AlreadyMigratedPair(logs)
+    case 6031:
+      return new BrokenCustody(logs)
+    case 6032:
+      return new InvalidSupply(logs)
+    case 6033:
+      return new BackingToken(logs)
+    case 6034:
+      return new UninitializedPair(logs)
+    case 6035:
+      return new UnresizableAccount(logs)
+    case 6036:
+      return new ForeignOriginal(logs)
+    case 6037:
+      return new CompressedOriginal(logs)
+    case 6038:
+      return new MissingOriginalEscrow(logs)
+    case 6039:
+      return new InvalidBatchSigner(logs)
+    case 6040:
+      return new MalformedBatch(logs)
+    case 6041:
+      return new InvalidAdmin(logs)
+    case 6042:
+      return new RecordsNotBackfilled(logs)
+    case 6043:
+      return new MissingCollectionCreator(logs)
+    case 6044:
+      return new InvalidTreeCreator(logs)
+    case 6045:
+      return new UndersizedTree(logs)
+  }
+
+  return null
+}
@@ -1,11 +1,14 @@
 import { PROGRAM_ID } from "../programId"
 import * as anchor from "./anchor"
+import * as custom from "./custom"
 
 export function fromCode(
   code: number,
   logs?: string[]
-): anchor.AnchorError | null {
-  return anchor.fromCode(code, logs)
+): custom.CustomError | anchor.AnchorError | null {
+  return code >= 6000
+    ? custom.fromCode(code, logs)
+    : anchor.fromCode(code, logs)
 }
 
 function hasOwnProperty<X extends object, Y extends PropertyKey>(
@@ -17,7 +20,9 @@ function hasOwnProperty<X extends object, Y extends PropertyKey>(
 
 const errorRe = /Program (\w+) failed: custom program error: (\w+)/
 
-export function fromTxError(err: unknown): anchor.AnchorError | null {
+export function fromTxError(
+  err: unknown
+): custom.CustomError | anchor.AnchorError | null {
   if (
     typeof err !== "object" ||
     err === null ||
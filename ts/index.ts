@@ -4,8 +4,10 @@ import {
   getAssociatedTokenAddressSync,
 } from "@solana/spl-token";
 import {
+  AccountMeta,
   Connection,
   PublicKey,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
   SYSVAR_RENT_PUBKEY,
   SystemProgram,
 } from "@solana/web3.js";
@@ -14,13 +16,38 @@ import {
   burnOriginal,
   createCollection,
   createCollectionEntry,
+  createEntangledEdition,
+  createEntangledTree,
   disentangle,
+  disentangleBatch,
+  disentangleCompressed,
+  disentangleTreePair,
   entangle,
+  entangleBatch,
+  entangleCompressed,
+  entangleTreePair,
+  initializeAndEntangle,
+  initializeCompressedPair,
   initializePair,
+  initializeTreePair,
+  migrateCollection,
+  migrateCompressedOriginal,
+  migratePair,
+  recordOriginal,
+  rescueTokens,
+  resizeAccount,
+  setCollectionVerification,
   setEntanglerState,
+  setRecordsBackfilled,
+  sweepAuthorityLamports,
+  verifyPair,
 } from "./instructions";
 import {
+  getAssetId,
+  getBubblegumSigner,
+  getCollectionAuthority,
   getCollectionEntry,
+  getEditionMarker,
   getEntangledCollection,
   getEntangledCollectionMint,
   getEntangledMint,
@@ -29,16 +56,52 @@ import {
   getEntanglerState,
   getMasterEdition,
   getMetadata,
+  getOriginalRecord,
+  getTokenRecord,
+  getTreeConfig,
 } from "./pda";
 
+import {
+  AUTHORIZATION_RULES_PROGRAM_ID,
+  BUBBLEGUM_PROGRAM_ID,
+  SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+  SPL_NOOP_PROGRAM_ID,
+} from "./constants";
 import BN from "bn.js";
+import { EntangledStandard, EntangledStandardKind } from "./types";
 import { PROGRAM_ID as METADATA_PROGRAM_ID } from "@metaplex-foundation/mpl-token-metadata";
 
 export * from "./accounts";
 export * from "./instructions";
 export * from "./errors";
+export * from "./types";
 export * from "./programId";
 
+/** A compressed NFT with the proof of its leaf */
+export interface CompressedAsset {
+  merkleTree: PublicKey;
+  root: Array<number>;
+  /** The Borsh-serialized Bubblegum metadata of the leaf */
+  metadata: Uint8Array;
+  nonce: BN;
+  index: number;
+  leafOwner: PublicKey;
+  leafDelegate: PublicKey;
+  /** The merkle proof, minus the tree's canopy */
+  proof: Array<PublicKey>;
+}
+
+/** The master edition a print edition original was printed from */
+export interface PrintEdition {
+  masterMint: PublicKey;
+  /** A token account holding the master edition */
+  masterToken: PublicKey;
+  edition: BN;
+}
+
+const proofAccounts = (proof: Array<PublicKey>): Array<AccountMeta> =>
+  proof.map((node) => ({ pubkey: node, isSigner: false, isWritable: false }));
+
 export class EntanglerWrapper {
   signer: PublicKey;
   id: PublicKey;
@@ -53,20 +116,24 @@ export class EntanglerWrapper {
   originalCollectionMint: PublicKey;
   originalCollectionMetadata: PublicKey;
   originalMintEscrow: PublicKey;
+  entangledTokenProgram: PublicKey;
+  nonCustodial = false;
 
   constructor(
     originalCollectionMint: PublicKey,
     signer: PublicKey,
     id: PublicKey,
     creator: PublicKey,
-    royalties: number
+    royalties: number,
+    entangledTokenProgram = TOKEN_PROGRAM_ID
   ) {
     this.signer = signer;
     this.id = id;
     this.creator = creator;
     this.royalties = royalties;
+    this.entangledTokenProgram = entangledTokenProgram;
 
-    this.entanglerAuthority = getEntanglerAuthority();
+    this.entanglerAuthority = getCollectionAuthority(id);
     this.entangledCollection = getEntangledCollection(id);
     this.entangledCollectionMint = getEntangledCollectionMint(id);
     this.entangledCollectionMasterEdition = getMasterEdition(
@@ -99,6 +166,28 @@ export class EntanglerWrapper {
     )[0];
   }
 
+  /** Uses the global authority, for collections that weren't migrated to their own */
+  withGlobalAuthority() {
+    this.entanglerAuthority = getEntanglerAuthority();
+    this.entangledCollectionMintAccount = getAssociatedTokenAddressSync(
+      this.entangledCollectionMint,
+      this.entanglerAuthority,
+      true
+    );
+    this.originalMintEscrow = getAssociatedTokenAddressSync(
+      this.originalCollectionMint,
+      this.entanglerAuthority,
+      true
+    );
+    return this;
+  }
+
+  /** Leaves originals frozen in their owner's account, for non-custodial collections */
+  withNonCustodial() {
+    this.nonCustodial = true;
+    return this;
+  }
+
   static fetcher = {
     entanglerState: async (connection: Connection) => {
       return await EntanglerState.fetch(connection, getEntanglerState());
@@ -131,14 +220,49 @@ export class EntanglerWrapper {
         }
       );
     },
-    createCollection: (oneWay: boolean) => {
+    setRecordsBackfilled: (recordsBackfilled: boolean) => {
+      return setRecordsBackfilled(
+        { recordsBackfilled },
+        {
+          signer: this.signer,
+          state: getEntanglerState(),
+          systemProgram: SystemProgram.programId,
+        }
+      );
+    },
+    resizeAccount: (account: PublicKey) => {
+      return resizeAccount({
+        signer: this.signer,
+        account,
+        systemProgram: SystemProgram.programId,
+      });
+    },
+    createCollection: (
+      oneWay: boolean,
+      allowUnverified = false,
+      originalCreator: PublicKey | null = null,
+      standard: EntangledStandardKind = new EntangledStandard.NonFungible(),
+      ruleSet: PublicKey | null = null,
+      transferHookProgram: PublicKey | null = null,
+      nonCustodial = false
+    ) => {
       const entangledCollectionMintAccount = getAssociatedTokenAddressSync(
         this.entangledCollectionMint,
         this.entanglerAuthority,
         true
       );
       return createCollection(
-        { id: this.id, royalties: this.royalties, oneWay },
+        {
+          id: this.id,
+          royalties: this.royalties,
+          oneWay,
+          allowUnverified,
+          originalCreator,
+          entangledStandard: standard,
+          ruleSet,
+          transferHookProgram,
+          nonCustodial,
+        },
         {
           signer: this.signer,
           creator: this.creator,
@@ -160,6 +284,19 @@ export class EntanglerWrapper {
         }
       );
     },
+    setCollectionVerification: (
+      allowUnverified: boolean,
+      originalCreator: PublicKey | null
+    ) => {
+      return setCollectionVerification(
+        { allowUnverified, originalCreator },
+        {
+          signer: this.signer,
+          state: getEntanglerState(),
+          entangledCollection: this.entangledCollection,
+        }
+      );
+    },
     createCollectionEntry: (
       key: string,
       feeMint: PublicKey,
@@ -194,19 +331,24 @@ export class EntanglerWrapper {
         }
       );
     },
-    initializePair: (originalMint: PublicKey) => {
+    initializePair: (
+      originalMint: PublicKey,
+      originalTokenProgram = TOKEN_PROGRAM_ID
+    ) => {
       const originalMetadata = getMetadata(originalMint);
       const originalMintEscrow = getAssociatedTokenAddressSync(
         originalMint,
         this.entanglerAuthority,
-        true
+        true,
+        originalTokenProgram
       );
       const entangledMint = getEntangledMint(this.id, originalMint);
       const entangledMetadata = getMetadata(entangledMint);
       const entangledMintEscrow = getAssociatedTokenAddressSync(
         entangledMint,
         this.entanglerAuthority,
-        true
+        true,
+        this.entangledTokenProgram
       );
 
       return initializePair({
@@ -219,38 +361,74 @@ export class EntanglerWrapper {
         entangledCollectionMetadata: this.entangledCollectionMetadata,
         originalMint,
         originalMetadata,
-        originalMintEscrow,
+        originalEdition: getMasterEdition(originalMint),
+        originalMintEscrow: this.nonCustodial ? null : originalMintEscrow,
+        originalRecord: getOriginalRecord(originalMint),
         entangledMint,
         entangledMetadata,
+        entangledMasterEdition: getMasterEdition(entangledMint),
         entangledMintEscrow,
+        entangledMintEscrowRecord: getTokenRecord(
+          entangledMint,
+          entangledMintEscrow
+        ),
         metadataProgram: METADATA_PROGRAM_ID,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        originalTokenProgram,
+        tokenProgram: this.entangledTokenProgram,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
         systemProgram: SystemProgram.programId,
       });
     },
-    entangle: (originalMint: PublicKey) => {
+    createEntangledEdition: (originalMint: PublicKey) => {
+      const entangledMint = getEntangledMint(this.id, originalMint);
+
+      return createEntangledEdition({
+        signer: this.signer,
+        entanglerAuthority: this.entanglerAuthority,
+        entangledCollection: this.entangledCollection,
+        originalMint,
+        entangledMint,
+        entangledMetadata: getMetadata(entangledMint),
+        entangledMasterEdition: getMasterEdition(entangledMint),
+        metadataProgram: METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+    },
+    entangle: (
+      originalMint: PublicKey,
+      authorizationRules: PublicKey | null = null,
+      entangledAuthorizationRules: PublicKey | null = null,
+      originalTokenProgram = TOKEN_PROGRAM_ID
+    ) => {
       const originalMetadata = getMetadata(originalMint);
       const originalMintEscrow = getAssociatedTokenAddressSync(
         originalMint,
         this.entanglerAuthority,
-        true
+        true,
+        originalTokenProgram
       );
       const entangledMint = getEntangledMint(this.id, originalMint);
       const entangledMetadata = getMetadata(entangledMint);
       const entangledMintEscrow = getAssociatedTokenAddressSync(
         entangledMint,
         this.entanglerAuthority,
-        true
+        true,
+        this.entangledTokenProgram
       );
       const originalMintAccount = getAssociatedTokenAddressSync(
         originalMint,
-        this.signer
+        this.signer,
+        false,
+        originalTokenProgram
       );
       const entangledMintAccount = getAssociatedTokenAddressSync(
         entangledMint,
-        this.signer
+        this.signer,
+        false,
+        this.entangledTokenProgram
       );
 
       return entangle({
@@ -262,40 +440,185 @@ export class EntanglerWrapper {
         originalMint,
         originalMetadata,
         originalMintAccount,
-        originalMintEscrow,
+        originalMintEscrow: this.nonCustodial ? null : originalMintEscrow,
+        originalMasterEdition: getMasterEdition(originalMint),
+        originalMintAccountRecord: getTokenRecord(
+          originalMint,
+          originalMintAccount
+        ),
+        originalMintEscrowRecord: getTokenRecord(
+          originalMint,
+          originalMintEscrow
+        ),
+        authorizationRules,
         entangledPair: getEntangledPair(entangledMint),
         entangledMint,
         entangledMetadata,
+        entangledMasterEdition: getMasterEdition(entangledMint),
+        entangledAuthorizationRules,
         entangledMintAccount,
+        entangledMintAccountRecord: getTokenRecord(
+          entangledMint,
+          entangledMintAccount
+        ),
         entangledMintEscrow,
+        entangledMintEscrowRecord: getTokenRecord(
+          entangledMint,
+          entangledMintEscrow
+        ),
         metadataProgram: METADATA_PROGRAM_ID,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        authorizationRulesProgram: AUTHORIZATION_RULES_PROGRAM_ID,
+        sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        originalTokenProgram,
+        tokenProgram: this.entangledTokenProgram,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
         systemProgram: SystemProgram.programId,
       });
     },
-    disentangle: (originalMint: PublicKey) => {
+    entangleBatch: (
+      originalMints: PublicKey[],
+      originalTokenProgram = TOKEN_PROGRAM_ID,
+      hookAccounts: AccountMeta[][] = []
+    ) => {
+      // Each group is followed by the transfer hook accounts of its original
+      const groupHooks = originalMints.map((_, i) => hookAccounts[i] ?? []);
+      const ix = entangleBatch(
+        {
+          hookAccounts: Uint8Array.from(
+            groupHooks.map((hooks) => hooks.length)
+          ),
+        },
+        { signer: this.signer }
+      );
+      for (const [i, originalMint] of originalMints.entries()) {
+        ix.keys.push(
+          ...this.instruction.entangle(
+            originalMint,
+            null,
+            null,
+            originalTokenProgram
+          ).keys,
+          ...groupHooks[i]
+        );
+      }
+      return ix;
+    },
+    initializeAndEntangle: (
+      originalMint: PublicKey,
+      authorizationRules: PublicKey | null = null,
+      entangledAuthorizationRules: PublicKey | null = null,
+      originalTokenProgram = TOKEN_PROGRAM_ID
+    ) => {
       const originalMetadata = getMetadata(originalMint);
       const originalMintEscrow = getAssociatedTokenAddressSync(
         originalMint,
         this.entanglerAuthority,
-        true
+        true,
+        originalTokenProgram
       );
       const entangledMint = getEntangledMint(this.id, originalMint);
       const entangledMetadata = getMetadata(entangledMint);
       const entangledMintEscrow = getAssociatedTokenAddressSync(
         entangledMint,
         this.entanglerAuthority,
-        true
+        true,
+        this.entangledTokenProgram
       );
       const originalMintAccount = getAssociatedTokenAddressSync(
         originalMint,
-        this.signer
+        this.signer,
+        false,
+        originalTokenProgram
       );
       const entangledMintAccount = getAssociatedTokenAddressSync(
         entangledMint,
-        this.signer
+        this.signer,
+        false,
+        this.entangledTokenProgram
+      );
+
+      return initializeAndEntangle({
+        signer: this.signer,
+        creator: this.creator,
+        entanglerAuthority: this.entanglerAuthority,
+        masterEdition: this.entangledCollectionMasterEdition,
+        entangledCollection: this.entangledCollection,
+        entangledCollectionMint: this.entangledCollectionMint,
+        entangledCollectionMetadata: this.entangledCollectionMetadata,
+        originalMint,
+        originalMetadata,
+        originalMintAccount,
+        originalMintEscrow: this.nonCustodial ? null : originalMintEscrow,
+        originalEdition: getMasterEdition(originalMint),
+        originalMintAccountRecord: getTokenRecord(
+          originalMint,
+          originalMintAccount
+        ),
+        originalMintEscrowRecord: getTokenRecord(
+          originalMint,
+          originalMintEscrow
+        ),
+        authorizationRules,
+        originalRecord: getOriginalRecord(originalMint),
+        entangledPair: getEntangledPair(entangledMint),
+        entangledMint,
+        entangledMetadata,
+        entangledMasterEdition: getMasterEdition(entangledMint),
+        entangledAuthorizationRules,
+        entangledMintAccount,
+        entangledMintAccountRecord: getTokenRecord(
+          entangledMint,
+          entangledMintAccount
+        ),
+        entangledMintEscrow,
+        entangledMintEscrowRecord: getTokenRecord(
+          entangledMint,
+          entangledMintEscrow
+        ),
+        metadataProgram: METADATA_PROGRAM_ID,
+        authorizationRulesProgram: AUTHORIZATION_RULES_PROGRAM_ID,
+        sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        originalTokenProgram,
+        tokenProgram: this.entangledTokenProgram,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      });
+    },
+    disentangle: (
+      originalMint: PublicKey,
+      authorizationRules: PublicKey | null = null,
+      entangledAuthorizationRules: PublicKey | null = null,
+      originalTokenProgram = TOKEN_PROGRAM_ID,
+      frozenAccount: PublicKey | null = null
+    ) => {
+      const originalMetadata = getMetadata(originalMint);
+      const originalMintEscrow = getAssociatedTokenAddressSync(
+        originalMint,
+        this.entanglerAuthority,
+        true,
+        originalTokenProgram
+      );
+      const entangledMint = getEntangledMint(this.id, originalMint);
+      const entangledMetadata = getMetadata(entangledMint);
+      const entangledMintEscrow = getAssociatedTokenAddressSync(
+        entangledMint,
+        this.entanglerAuthority,
+        true,
+        this.entangledTokenProgram
+      );
+      const originalMintAccount = getAssociatedTokenAddressSync(
+        originalMint,
+        this.signer,
+        false,
+        originalTokenProgram
+      );
+      const entangledMintAccount = getAssociatedTokenAddressSync(
+        entangledMint,
+        this.signer,
+        false,
+        this.entangledTokenProgram
       );
 
       return disentangle({
@@ -307,30 +630,90 @@ export class EntanglerWrapper {
         originalMint,
         originalMetadata,
         originalMintAccount,
-        originalMintEscrow,
+        originalMintEscrow: frozenAccount ? null : originalMintEscrow,
+        originalMasterEdition: getMasterEdition(originalMint),
+        originalMintAccountRecord: getTokenRecord(
+          originalMint,
+          originalMintAccount
+        ),
+        originalMintEscrowRecord: getTokenRecord(
+          originalMint,
+          originalMintEscrow
+        ),
+        frozenAccount,
+        authorizationRules,
+        entangledPair: getEntangledPair(entangledMint),
         entangledMint,
         entangledMetadata,
+        entangledMasterEdition: getMasterEdition(entangledMint),
+        entangledAuthorizationRules,
         entangledMintAccount,
+        entangledMintAccountRecord: getTokenRecord(
+          entangledMint,
+          entangledMintAccount
+        ),
         entangledMintEscrow,
+        entangledMintEscrowRecord: getTokenRecord(
+          entangledMint,
+          entangledMintEscrow
+        ),
         metadataProgram: METADATA_PROGRAM_ID,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        authorizationRulesProgram: AUTHORIZATION_RULES_PROGRAM_ID,
+        sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        originalTokenProgram,
+        tokenProgram: this.entangledTokenProgram,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
         systemProgram: SystemProgram.programId,
       });
     },
-    burnOriginal: (originalMint: PublicKey) => {
+    disentangleBatch: (
+      originalMints: PublicKey[],
+      originalTokenProgram = TOKEN_PROGRAM_ID,
+      hookAccounts: AccountMeta[][] = []
+    ) => {
+      // Each group is followed by the transfer hook accounts of its original
+      const groupHooks = originalMints.map((_, i) => hookAccounts[i] ?? []);
+      const ix = disentangleBatch(
+        {
+          hookAccounts: Uint8Array.from(
+            groupHooks.map((hooks) => hooks.length)
+          ),
+        },
+        { signer: this.signer }
+      );
+      for (const [i, originalMint] of originalMints.entries()) {
+        ix.keys.push(
+          ...this.instruction.disentangle(
+            originalMint,
+            null,
+            null,
+            originalTokenProgram
+          ).keys,
+          ...groupHooks[i]
+        );
+      }
+      return ix;
+    },
+    burnOriginal: (
+      originalMint: PublicKey,
+      originalTokenProgram = TOKEN_PROGRAM_ID,
+      printEdition: PrintEdition | null = null
+    ) => {
       const originalMetadata = getMetadata(originalMint);
       const originalMintEscrow = getAssociatedTokenAddressSync(
         originalMint,
         this.entanglerAuthority,
-        true
+        true,
+        originalTokenProgram
       );
       const entangledMint = getEntangledMint(this.id, originalMint);
       const entangledPair = getEntangledPair(entangledMint);
       const entangledMintAccount = getAssociatedTokenAddressSync(
         entangledMint,
-        this.signer
+        this.signer,
+        false,
+        this.entangledTokenProgram
       );
       const masterEdition = getMasterEdition(originalMint);
 
@@ -343,17 +726,645 @@ export class EntanglerWrapper {
         originalCollectionMetadata: this.originalCollectionMetadata,
         entangledCollectionMint: this.entangledCollectionMint,
         masterEdition,
+        parentMint: printEdition && printEdition.masterMint,
+        parentEdition:
+          printEdition && getMasterEdition(printEdition.masterMint),
+        parentToken: printEdition && printEdition.masterToken,
+        editionMarker:
+          printEdition &&
+          getEditionMarker(printEdition.masterMint, printEdition.edition),
         originalMint,
         originalMetadata,
         originalMintEscrow,
+        originalMintEscrowRecord: getTokenRecord(
+          originalMint,
+          originalMintEscrow
+        ),
         entangledMint,
         entangledMintAccount,
         metadataProgram: METADATA_PROGRAM_ID,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        originalTokenProgram,
+        tokenProgram: this.entangledTokenProgram,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
         systemProgram: SystemProgram.programId,
       });
     },
+    initializeCompressedPair: (asset: CompressedAsset) => {
+      const entangledMint = getEntangledMint(
+        this.id,
+        getAssetId(asset.merkleTree, asset.nonce)
+      );
+      const entangledMintEscrow = getAssociatedTokenAddressSync(
+        entangledMint,
+        this.entanglerAuthority,
+        true,
+        this.entangledTokenProgram
+      );
+
+      const ix = initializeCompressedPair(
+        {
+          root: asset.root,
+          metadata: asset.metadata,
+          nonce: asset.nonce,
+          index: asset.index,
+        },
+        {
+          signer: this.signer,
+          creator: this.creator,
+          entanglerAuthority: this.entanglerAuthority,
+          masterEdition: this.entangledCollectionMasterEdition,
+          entangledCollection: this.entangledCollection,
+          entangledCollectionMint: this.entangledCollectionMint,
+          entangledCollectionMetadata: this.entangledCollectionMetadata,
+          merkleTree: asset.merkleTree,
+          leafOwner: asset.leafOwner,
+          leafDelegate: asset.leafDelegate,
+          entangledMint,
+          entangledMetadata: getMetadata(entangledMint),
+          entangledMasterEdition: getMasterEdition(entangledMint),
+          entangledMintEscrow,
+          entangledMintEscrowRecord: getTokenRecord(
+            entangledMint,
+            entangledMintEscrow
+          ),
+          metadataProgram: METADATA_PROGRAM_ID,
+          compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+          sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+          tokenProgram: this.entangledTokenProgram,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        }
+      );
+      ix.keys.push(...proofAccounts(asset.proof));
+      return ix;
+    },
+    entangleCompressed: (
+      asset: CompressedAsset,
+      entangledAuthorizationRules: PublicKey | null = null
+    ) => {
+      const entangledMint = getEntangledMint(
+        this.id,
+        getAssetId(asset.merkleTree, asset.nonce)
+      );
+      const entangledMintEscrow = getAssociatedTokenAddressSync(
+        entangledMint,
+        this.entanglerAuthority,
+        true,
+        this.entangledTokenProgram
+      );
+      const entangledMintAccount = getAssociatedTokenAddressSync(
+        entangledMint,
+        this.signer,
+        false,
+        this.entangledTokenProgram
+      );
+
+      const ix = entangleCompressed(
+        {
+          root: asset.root,
+          metadata: asset.metadata,
+          nonce: asset.nonce,
+          index: asset.index,
+          proofLen: asset.proof.length,
+        },
+        {
+          signer: this.signer,
+          entanglerAuthority: this.entanglerAuthority,
+          entangledCollection: this.entangledCollection,
+          entangledPair: getEntangledPair(entangledMint),
+          entangledCollectionMint: this.entangledCollectionMint,
+          entangledCollectionMetadata: this.entangledCollectionMetadata,
+          treeConfig: getTreeConfig(asset.merkleTree),
+          merkleTree: asset.merkleTree,
+          leafDelegate: asset.leafDelegate,
+          entangledMint,
+          entangledMasterEdition: getMasterEdition(entangledMint),
+          entangledAuthorizationRules,
+          entangledMintAccount,
+          entangledMintAccountRecord: getTokenRecord(
+            entangledMint,
+            entangledMintAccount
+          ),
+          entangledMetadata: getMetadata(entangledMint),
+          entangledMintEscrow,
+          entangledMintEscrowRecord: getTokenRecord(
+            entangledMint,
+            entangledMintEscrow
+          ),
+          metadataProgram: METADATA_PROGRAM_ID,
+          authorizationRulesProgram: AUTHORIZATION_RULES_PROGRAM_ID,
+          bubblegumProgram: BUBBLEGUM_PROGRAM_ID,
+          compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+          logWrapper: SPL_NOOP_PROGRAM_ID,
+          sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+          tokenProgram: this.entangledTokenProgram,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        }
+      );
+      ix.keys.push(...proofAccounts(asset.proof));
+      return ix;
+    },
+    disentangleCompressed: (
+      asset: CompressedAsset,
+      entangledAuthorizationRules: PublicKey | null = null
+    ) => {
+      const entangledMint = getEntangledMint(
+        this.id,
+        getAssetId(asset.merkleTree, asset.nonce)
+      );
+      const entangledMintEscrow = getAssociatedTokenAddressSync(
+        entangledMint,
+        this.entanglerAuthority,
+        true,
+        this.entangledTokenProgram
+      );
+      const entangledMintAccount = getAssociatedTokenAddressSync(
+        entangledMint,
+        this.signer,
+        false,
+        this.entangledTokenProgram
+      );
+
+      const ix = disentangleCompressed(
+        {
+          root: asset.root,
+          metadata: asset.metadata,
+          nonce: asset.nonce,
+          index: asset.index,
+          proofLen: asset.proof.length,
+        },
+        {
+          signer: this.signer,
+          entanglerAuthority: this.entanglerAuthority,
+          entangledCollection: this.entangledCollection,
+          entangledCollectionMint: this.entangledCollectionMint,
+          entangledCollectionMetadata: this.entangledCollectionMetadata,
+          treeConfig: getTreeConfig(asset.merkleTree),
+          merkleTree: asset.merkleTree,
+          entangledMint,
+          entangledMasterEdition: getMasterEdition(entangledMint),
+          entangledAuthorizationRules,
+          entangledMintAccount,
+          entangledMintAccountRecord: getTokenRecord(
+            entangledMint,
+            entangledMintAccount
+          ),
+          entangledMetadata: getMetadata(entangledMint),
+          entangledMintEscrow,
+          entangledMintEscrowRecord: getTokenRecord(
+            entangledMint,
+            entangledMintEscrow
+          ),
+          metadataProgram: METADATA_PROGRAM_ID,
+          authorizationRulesProgram: AUTHORIZATION_RULES_PROGRAM_ID,
+          bubblegumProgram: BUBBLEGUM_PROGRAM_ID,
+          compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+          logWrapper: SPL_NOOP_PROGRAM_ID,
+          sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+          tokenProgram: this.entangledTokenProgram,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        }
+      );
+      ix.keys.push(...proofAccounts(asset.proof));
+      return ix;
+    },
+    createEntangledTree: (
+      merkleTree: PublicKey,
+      maxDepth: number,
+      maxBufferSize: number
+    ) => {
+      return createEntangledTree(
+        { maxDepth, maxBufferSize },
+        {
+          signer: this.signer,
+          state: getEntanglerState(),
+          entanglerAuthority: this.entanglerAuthority,
+          entangledCollection: this.entangledCollection,
+          entangledCollectionMetadata: this.entangledCollectionMetadata,
+          originalCollectionMetadata: this.originalCollectionMetadata,
+          treeConfig: getTreeConfig(merkleTree),
+          merkleTree,
+          bubblegumProgram: BUBBLEGUM_PROGRAM_ID,
+          compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+          logWrapper: SPL_NOOP_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        }
+      );
+    },
+    initializeTreePair: (
+      originalMint: PublicKey,
+      merkleTree: PublicKey,
+      originalTokenProgram = TOKEN_PROGRAM_ID
+    ) => {
+      const entangledMint = getEntangledMint(this.id, originalMint);
+
+      return initializeTreePair({
+        signer: this.signer,
+        creator: this.creator,
+        entanglerAuthority: this.entanglerAuthority,
+        masterEdition: this.entangledCollectionMasterEdition,
+        entangledCollection: this.entangledCollection,
+        entangledPair: getEntangledPair(entangledMint),
+        entangledCollectionMint: this.entangledCollectionMint,
+        entangledCollectionMetadata: this.entangledCollectionMetadata,
+        originalMint,
+        originalMetadata: getMetadata(originalMint),
+        originalEdition: getMasterEdition(originalMint),
+        originalMintEscrow: getAssociatedTokenAddressSync(
+          originalMint,
+          this.entanglerAuthority,
+          true,
+          originalTokenProgram
+        ),
+        originalRecord: getOriginalRecord(originalMint),
+        entangledMint,
+        treeConfig: getTreeConfig(merkleTree),
+        merkleTree,
+        bubblegumSigner: getBubblegumSigner(),
+        metadataProgram: METADATA_PROGRAM_ID,
+        bubblegumProgram: BUBBLEGUM_PROGRAM_ID,
+        compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        logWrapper: SPL_NOOP_PROGRAM_ID,
+        originalTokenProgram,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+    },
+    entangleTreePair: (
+      originalMint: PublicKey,
+      asset: CompressedAsset,
+      authorizationRules: PublicKey | null = null,
+      originalTokenProgram = TOKEN_PROGRAM_ID
+    ) => {
+      const originalMintEscrow = getAssociatedTokenAddressSync(
+        originalMint,
+        this.entanglerAuthority,
+        true,
+        originalTokenProgram
+      );
+      const originalMintAccount = getAssociatedTokenAddressSync(
+        originalMint,
+        this.signer,
+        false,
+        originalTokenProgram
+      );
+      const entangledMint = getEntangledMint(this.id, originalMint);
+
+      const ix = entangleTreePair(
+        {
+          root: asset.root,
+          metadata: asset.metadata,
+          nonce: asset.nonce,
+          index: asset.index,
+          proofLen: asset.proof.length,
+        },
+        {
+          signer: this.signer,
+          entanglerAuthority: this.entanglerAuthority,
+          entangledCollection: this.entangledCollection,
+          entangledPair: getEntangledPair(entangledMint),
+          originalMint,
+          originalMetadata: getMetadata(originalMint),
+          originalMasterEdition: getMasterEdition(originalMint),
+          authorizationRules,
+          originalMintAccount,
+          originalMintAccountRecord: getTokenRecord(
+            originalMint,
+            originalMintAccount
+          ),
+          originalMintEscrow,
+          originalMintEscrowRecord: getTokenRecord(
+            originalMint,
+            originalMintEscrow
+          ),
+          entangledMint,
+          treeConfig: getTreeConfig(asset.merkleTree),
+          merkleTree: asset.merkleTree,
+          metadataProgram: METADATA_PROGRAM_ID,
+          authorizationRulesProgram: AUTHORIZATION_RULES_PROGRAM_ID,
+          bubblegumProgram: BUBBLEGUM_PROGRAM_ID,
+          compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+          logWrapper: SPL_NOOP_PROGRAM_ID,
+          sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+          originalTokenProgram,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        }
+      );
+      ix.keys.push(...proofAccounts(asset.proof));
+      return ix;
+    },
+    disentangleTreePair: (
+      originalMint: PublicKey,
+      asset: CompressedAsset,
+      authorizationRules: PublicKey | null = null,
+      originalTokenProgram = TOKEN_PROGRAM_ID
+    ) => {
+      const originalMintEscrow = getAssociatedTokenAddressSync(
+        originalMint,
+        this.entanglerAuthority,
+        true,
+        originalTokenProgram
+      );
+      const originalMintAccount = getAssociatedTokenAddressSync(
+        originalMint,
+        this.signer,
+        false,
+        originalTokenProgram
+      );
+      const entangledMint = getEntangledMint(this.id, originalMint);
+
+      const ix = disentangleTreePair(
+        {
+          root: asset.root,
+          metadata: asset.metadata,
+          nonce: asset.nonce,
+          index: asset.index,
+          proofLen: asset.proof.length,
+        },
+        {
+          signer: this.signer,
+          entanglerAuthority: this.entanglerAuthority,
+          entangledCollection: this.entangledCollection,
+          entangledPair: getEntangledPair(entangledMint),
+          originalMint,
+          originalMetadata: getMetadata(originalMint),
+          originalMasterEdition: getMasterEdition(originalMint),
+          authorizationRules,
+          originalMintAccount,
+          originalMintAccountRecord: getTokenRecord(
+            originalMint,
+            originalMintAccount
+          ),
+          originalMintEscrow,
+          originalMintEscrowRecord: getTokenRecord(
+            originalMint,
+            originalMintEscrow
+          ),
+          entangledMint,
+          treeConfig: getTreeConfig(asset.merkleTree),
+          merkleTree: asset.merkleTree,
+          leafDelegate: asset.leafDelegate,
+          metadataProgram: METADATA_PROGRAM_ID,
+          authorizationRulesProgram: AUTHORIZATION_RULES_PROGRAM_ID,
+          bubblegumProgram: BUBBLEGUM_PROGRAM_ID,
+          compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+          logWrapper: SPL_NOOP_PROGRAM_ID,
+          sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+          originalTokenProgram,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        }
+      );
+      ix.keys.push(...proofAccounts(asset.proof));
+      return ix;
+    },
+    migrateCollection: () => {
+      const globalAuthority = getEntanglerAuthority();
+      const entanglerAuthority = getCollectionAuthority(this.id);
+
+      return migrateCollection({
+        signer: this.signer,
+        state: getEntanglerState(),
+        globalAuthority,
+        entanglerAuthority,
+        entangledCollection: this.entangledCollection,
+        entangledCollectionMint: this.entangledCollectionMint,
+        entangledCollectionMetadata: this.entangledCollectionMetadata,
+        masterEdition: this.entangledCollectionMasterEdition,
+        legacyCollectionMintAccount: getAssociatedTokenAddressSync(
+          this.entangledCollectionMint,
+          globalAuthority,
+          true
+        ),
+        entangledCollectionMintAccount: getAssociatedTokenAddressSync(
+          this.entangledCollectionMint,
+          entanglerAuthority,
+          true
+        ),
+        metadataProgram: METADATA_PROGRAM_ID,
+        sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+    },
+    migratePair: (
+      originalMint: PublicKey,
+      originalEscrowed: boolean,
+      authorizationRules: PublicKey | null = null,
+      entangledAuthorizationRules: PublicKey | null = null,
+      originalTokenProgram = TOKEN_PROGRAM_ID
+    ) => {
+      const globalAuthority = getEntanglerAuthority();
+      const entanglerAuthority = getCollectionAuthority(this.id);
+      const entangledMint = getEntangledMint(this.id, originalMint);
+      const originalMintEscrow = getAssociatedTokenAddressSync(
+        originalMint,
+        globalAuthority,
+        true,
+        originalTokenProgram
+      );
+      const originalMintDestination = getAssociatedTokenAddressSync(
+        originalMint,
+        entanglerAuthority,
+        true,
+        originalTokenProgram
+      );
+      const entangledMintEscrow = getAssociatedTokenAddressSync(
+        entangledMint,
+        globalAuthority,
+        true,
+        this.entangledTokenProgram
+      );
+      const entangledMintDestination = getAssociatedTokenAddressSync(
+        entangledMint,
+        entanglerAuthority,
+        true,
+        this.entangledTokenProgram
+      );
+
+      return migratePair({
+        signer: this.signer,
+        globalAuthority,
+        entanglerAuthority,
+        entangledCollection: this.entangledCollection,
+        originalMint,
+        originalMetadata: getMetadata(originalMint),
+        originalMasterEdition: getMasterEdition(originalMint),
+        authorizationRules,
+        originalMintEscrow: originalEscrowed ? originalMintEscrow : null,
+        originalMintEscrowRecord: getTokenRecord(
+          originalMint,
+          originalMintEscrow
+        ),
+        originalMintDestination,
+        originalMintDestinationRecord: getTokenRecord(
+          originalMint,
+          originalMintDestination
+        ),
+        entangledMint,
+        entangledPair: getEntangledPair(entangledMint),
+        entangledMetadata: getMetadata(entangledMint),
+        entangledMasterEdition: getMasterEdition(entangledMint),
+        entangledAuthorizationRules,
+        entangledMintEscrow,
+        entangledMintEscrowRecord: getTokenRecord(
+          entangledMint,
+          entangledMintEscrow
+        ),
+        entangledMintDestination,
+        entangledMintDestinationRecord: getTokenRecord(
+          entangledMint,
+          entangledMintDestination
+        ),
+        metadataProgram: METADATA_PROGRAM_ID,
+        authorizationRulesProgram: AUTHORIZATION_RULES_PROGRAM_ID,
+        sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        originalTokenProgram,
+        tokenProgram: this.entangledTokenProgram,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+    },
+    migrateCompressedOriginal: (asset: CompressedAsset) => {
+      const globalAuthority = getEntanglerAuthority();
+      const entanglerAuthority = getCollectionAuthority(this.id);
+      const entangledMint = getEntangledMint(
+        this.id,
+        getAssetId(asset.merkleTree, asset.nonce)
+      );
+
+      const ix = migrateCompressedOriginal(
+        {
+          root: asset.root,
+          metadata: asset.metadata,
+          nonce: asset.nonce,
+          index: asset.index,
+        },
+        {
+          signer: this.signer,
+          globalAuthority,
+          entanglerAuthority,
+          entangledCollection: this.entangledCollection,
+          treeConfig: getTreeConfig(asset.merkleTree),
+          merkleTree: asset.merkleTree,
+          entangledMint,
+          entangledPair: getEntangledPair(entangledMint),
+          entangledMintEscrow: getAssociatedTokenAddressSync(
+            entangledMint,
+            globalAuthority,
+            true,
+            this.entangledTokenProgram
+          ),
+          entangledMintDestination: getAssociatedTokenAddressSync(
+            entangledMint,
+            entanglerAuthority,
+            true,
+            this.entangledTokenProgram
+          ),
+          bubblegumProgram: BUBBLEGUM_PROGRAM_ID,
+          compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+          logWrapper: SPL_NOOP_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        }
+      );
+      ix.keys.push(...proofAccounts(asset.proof));
+      return ix;
+    },
+    verifyPair: (
+      originalMint: PublicKey,
+      originalTokenProgram = TOKEN_PROGRAM_ID,
+      frozenAccount: PublicKey | null = null
+    ) => {
+      const entangledMint = getEntangledMint(this.id, originalMint);
+
+      return verifyPair({
+        entanglerAuthority: this.entanglerAuthority,
+        entangledCollection: this.entangledCollection,
+        entangledPair: getEntangledPair(entangledMint),
+        originalMint,
+        originalMintEscrow: getAssociatedTokenAddressSync(
+          originalMint,
+          this.entanglerAuthority,
+          true,
+          originalTokenProgram
+        ),
+        frozenAccount,
+        entangledMint,
+        entangledMintEscrow: getAssociatedTokenAddressSync(
+          entangledMint,
+          this.entanglerAuthority,
+          true,
+          this.entangledTokenProgram
+        ),
+        originalTokenProgram,
+        tokenProgram: this.entangledTokenProgram,
+      });
+    },
+    recordOriginal: (originalMint: PublicKey) => {
+      const entangledMint = getEntangledMint(this.id, originalMint);
+
+      return recordOriginal({
+        signer: this.signer,
+        entangledCollection: this.entangledCollection,
+        originalMint,
+        entangledMint,
+        entangledPair: getEntangledPair(entangledMint),
+        originalRecord: getOriginalRecord(originalMint),
+        systemProgram: SystemProgram.programId,
+      });
+    },
+    rescueTokens: (
+      mint: PublicKey,
+      destination: PublicKey,
+      amount: BN,
+      tokenProgram = TOKEN_PROGRAM_ID,
+      source = getAssociatedTokenAddressSync(
+        mint,
+        this.entanglerAuthority,
+        true,
+        tokenProgram
+      )
+    ) => {
+      const entangledMint = getEntangledMint(this.id, mint);
+
+      return rescueTokens(
+        { amount },
+        {
+          signer: this.signer,
+          state: getEntanglerState(),
+          entangledCollection: this.entangledCollection,
+          entanglerAuthority: this.entanglerAuthority,
+          mint,
+          metadata: getMetadata(mint),
+          originalRecord: getOriginalRecord(mint),
+          entangledMint,
+          entangledPair: getEntangledPair(entangledMint),
+          source,
+          destination,
+          tokenProgram,
+        }
+      );
+    },
+    sweepAuthorityLamports: (earner: PublicKey) => {
+      const global = this.entanglerAuthority.equals(getEntanglerAuthority());
+
+      return sweepAuthorityLamports({
+        signer: this.signer,
+        state: getEntanglerState(),
+        entangledCollection: global ? null : this.entangledCollection,
+        entanglerAuthority: this.entanglerAuthority,
+        earner,
+        systemProgram: SystemProgram.programId,
+      });
+    },
   };
 }
@@ -1,6 +1,6 @@
 import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
 import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
-import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
 import { PROGRAM_ID } from "../programId"
 
 export interface BurnOriginalAccounts {
@@ -12,14 +12,26 @@ export interface BurnOriginalAccounts {
   originalCollectionMetadata: PublicKey
   originalMint: PublicKey
   originalMetadata: PublicKey
-  /** The master edition of the token */
+  /** The master or print edition of the token */
   masterEdition: PublicKey
+  /** The mint of the master edition, for print edition originals */
+  parentMint: PublicKey | null
+  /** The master edition, for print edition originals */
+  parentEdition: PublicKey | null
+  /** A token account holding the master edition, for print edition originals */
+  parentToken: PublicKey | null
+  /** The edition marker of the print, for print edition originals */
+  editionMarker: PublicKey | null
   originalMintEscrow: PublicKey
+  /** The token record of the escrow, for programmable originals */
+  originalMintEscrowRecord: PublicKey | null
   entangledMint: PublicKey
   entangledCollectionMint: PublicKey
   entangledMintAccount: PublicKey
   /** Common Solana programs */
   metadataProgram: PublicKey
+  sysvarInstructions: PublicKey | null
+  originalTokenProgram: PublicKey
   tokenProgram: PublicKey
   associatedTokenProgram: PublicKey
   systemProgram: PublicKey
@@ -50,7 +62,32 @@ export function burnOriginal(accounts: BurnOriginalAccounts) {
     { pubkey: accounts.originalMint, isSigner: false, isWritable: true },
     { pubkey: accounts.originalMetadata, isSigner: false, isWritable: true },
     { pubkey: accounts.masterEdition, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.parentMint ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.parentEdition ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.parentToken ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.editionMarker ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
     { pubkey: accounts.originalMintEscrow, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.originalMintEscrowRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
     { pubkey: accounts.entangledMint, isSigner: false, isWritable: false },
     {
       pubkey: accounts.entangledCollectionMint,
@@ -63,6 +100,16 @@ export function burnOriginal(accounts: BurnOriginalAccounts) {
       isWritable: true,
     },
     { pubkey: accounts.metadataProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.sysvarInstructions ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.originalTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
     { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
     {
       pubkey: accounts.associatedTokenProgram,
@@ -1,19 +1,26 @@
 import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
 import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
-import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
 import { PROGRAM_ID } from "../programId"
 
 export interface CreateCollectionArgs {
   id: PublicKey
   royalties: number
   oneWay: boolean
+  allowUnverified: boolean
+  originalCreator: PublicKey | null
+  entangledStandard: types.EntangledStandardKind
+  ruleSet: PublicKey | null
+  transferHookProgram: PublicKey | null
+  nonCustodial: boolean
 }
 
 export interface CreateCollectionAccounts {
   signer: PublicKey
   /** The creator receiving royalties */
   creator: PublicKey
-  /** The PDA that has authority over entangled minted */
+  /** The PDA of the collection that has authority over entangled minted */
   entanglerAuthority: PublicKey
   /** The account storing the collection's data */
   entangledCollection: PublicKey
@@ -41,6 +48,12 @@ export const layout = borsh.struct([
   borsh.publicKey("id"),
   borsh.u16("royalties"),
   borsh.bool("oneWay"),
+  borsh.bool("allowUnverified"),
+  borsh.option(borsh.publicKey(), "originalCreator"),
+  types.EntangledStandard.layout("entangledStandard"),
+  borsh.option(borsh.publicKey(), "ruleSet"),
+  borsh.option(borsh.publicKey(), "transferHookProgram"),
+  borsh.bool("nonCustodial"),
 ])
 
 /**
@@ -99,6 +112,12 @@ export function createCollection(
       id: args.id,
       royalties: args.royalties,
       oneWay: args.oneWay,
+      allowUnverified: args.allowUnverified,
+      originalCreator: args.originalCreator,
+      entangledStandard: args.entangledStandard.toEncodable(),
+      ruleSet: args.ruleSet,
+      transferHookProgram: args.transferHookProgram,
+      nonCustodial: args.nonCustodial,
     },
     buffer
   )
@@ -0,0 +1,48 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface CreateEntangledEditionAccounts {
+  signer: PublicKey
+  entanglerAuthority: PublicKey
+  /** The collection of the entangled mint, resized if it predates the current layout */
+  entangledCollection: PublicKey
+  /** The original mint, or the asset id of a compressed original */
+  originalMint: PublicKey
+  /** The entangled mint of the original in the collection, still controlled by the authority */
+  entangledMint: PublicKey
+  entangledMetadata: PublicKey
+  entangledMasterEdition: PublicKey
+  /** Common Solana programs */
+  metadataProgram: PublicKey
+  tokenProgram: PublicKey
+  systemProgram: PublicKey
+}
+
+/**
+ * Creates the missing master edition of an entangled token, for pairs
+ * initialized before entangled tokens got one
+ */
+export function createEntangledEdition(accounts: CreateEntangledEditionAccounts) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: true },
+    { pubkey: accounts.entanglerAuthority, isSigner: false, isWritable: false },
+    { pubkey: accounts.entangledCollection, isSigner: false, isWritable: true },
+    { pubkey: accounts.originalMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.entangledMint, isSigner: false, isWritable: true },
+    { pubkey: accounts.entangledMetadata, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledMasterEdition,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.metadataProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([239, 164, 54, 236, 175, 25, 229, 41])
+  const data = identifier
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -0,0 +1,81 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface CreateEntangledTreeArgs {
+  maxDepth: number
+  maxBufferSize: number
+}
+
+export interface CreateEntangledTreeAccounts {
+  /** The admin or the collection's creator, since the tree can only be set once */
+  signer: PublicKey
+  /** The entangler's state */
+  state: PublicKey
+  entanglerAuthority: PublicKey
+  /** The account storing the collection's data */
+  entangledCollection: PublicKey
+  /** The metadata of the entangled collection, listing its creator */
+  entangledCollectionMetadata: PublicKey
+  /** The metadata of the original collection, holding its size */
+  originalCollectionMetadata: PublicKey
+  treeConfig: PublicKey
+  /** The merkle tree, allocated beforehand for the compression program */
+  merkleTree: PublicKey
+  /** Common Solana programs */
+  bubblegumProgram: PublicKey
+  compressionProgram: PublicKey
+  logWrapper: PublicKey
+  systemProgram: PublicKey
+}
+
+export const layout = borsh.struct([
+  borsh.u32("maxDepth"),
+  borsh.u32("maxBufferSize"),
+])
+
+/**
+ * Creates the tree holding the entangled tokens of a compressed collection, once. The merkle
+ * tree must be allocated beforehand for the compression program, and hold a leaf for every
+ * original of a sized collection. Admin or collection creator only
+ */
+export function createEntangledTree(
+  args: CreateEntangledTreeArgs,
+  accounts: CreateEntangledTreeAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: true },
+    { pubkey: accounts.state, isSigner: false, isWritable: false },
+    { pubkey: accounts.entanglerAuthority, isSigner: false, isWritable: false },
+    { pubkey: accounts.entangledCollection, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledCollectionMetadata,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.originalCollectionMetadata,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.treeConfig, isSigner: false, isWritable: true },
+    { pubkey: accounts.merkleTree, isSigner: false, isWritable: true },
+    { pubkey: accounts.bubblegumProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.compressionProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.logWrapper, isSigner: false, isWritable: false },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([48, 237, 209, 78, 119, 116, 100, 103])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      maxDepth: args.maxDepth,
+      maxBufferSize: args.maxBufferSize,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
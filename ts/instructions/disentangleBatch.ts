@@ -0,0 +1,43 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface DisentangleBatchArgs {
+  hookAccounts: Uint8Array
+}
+
+export interface DisentangleBatchAccounts {
+  /** Must sign every group of the batch */
+  signer: PublicKey
+}
+
+export const layout = borsh.struct([borsh.vecU8("hookAccounts")])
+
+/**
+ * Swaps many entangled tokens back at once. The remaining accounts hold one group of
+ * `disentangle` accounts per token, each followed by its `hook_accounts` transfer hook accounts
+ */
+export function disentangleBatch(
+  args: DisentangleBatchArgs,
+  accounts: DisentangleBatchAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: false },
+  ]
+  const identifier = Buffer.from([97, 236, 152, 140, 78, 4, 96, 67])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      hookAccounts: Buffer.from(
+        args.hookAccounts.buffer,
+        args.hookAccounts.byteOffset,
+        args.hookAccounts.length
+      ),
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -0,0 +1,153 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface DisentangleCompressedArgs {
+  root: Array<number>
+  metadata: Uint8Array
+  nonce: BN
+  index: number
+  proofLen: number
+}
+
+export interface DisentangleCompressedAccounts {
+  signer: PublicKey
+  entanglerAuthority: PublicKey
+  entangledCollection: PublicKey
+  entangledCollectionMint: PublicKey
+  entangledCollectionMetadata: PublicKey
+  treeConfig: PublicKey
+  /** The Bubblegum tree holding the original */
+  merkleTree: PublicKey
+  entangledMint: PublicKey
+  /** The master edition of the entangled token, for programmable collections */
+  entangledMasterEdition: PublicKey | null
+  /** The rule set of the entangled token, for programmable collections */
+  entangledAuthorizationRules: PublicKey | null
+  entangledMintAccount: PublicKey
+  entangledMintAccountRecord: PublicKey | null
+  entangledMetadata: PublicKey
+  entangledMintEscrow: PublicKey
+  entangledMintEscrowRecord: PublicKey | null
+  /** Common Solana programs */
+  metadataProgram: PublicKey
+  authorizationRulesProgram: PublicKey | null
+  bubblegumProgram: PublicKey
+  compressionProgram: PublicKey
+  logWrapper: PublicKey
+  sysvarInstructions: PublicKey | null
+  tokenProgram: PublicKey
+  associatedTokenProgram: PublicKey
+  systemProgram: PublicKey
+  rent: PublicKey
+}
+
+export const layout = borsh.struct([
+  borsh.array(borsh.u8(), 32, "root"),
+  borsh.vecU8("metadata"),
+  borsh.u64("nonce"),
+  borsh.u32("index"),
+  borsh.u8("proofLen"),
+])
+
+/**
+ * Swap from the entangled token to the compressed original one.
+ * The remaining accounts hold the first `proof_len` nodes of the merkle proof,
+ * followed by the transfer hook accounts of the entangled mint
+ */
+export function disentangleCompressed(
+  args: DisentangleCompressedArgs,
+  accounts: DisentangleCompressedAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: true },
+    { pubkey: accounts.entanglerAuthority, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.entangledCollection,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.entangledCollectionMint,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.entangledCollectionMetadata,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.treeConfig, isSigner: false, isWritable: false },
+    { pubkey: accounts.merkleTree, isSigner: false, isWritable: true },
+    { pubkey: accounts.entangledMint, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.entangledMasterEdition ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.entangledAuthorizationRules ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.entangledMintAccount,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.entangledMintAccountRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.entangledMetadata, isSigner: false, isWritable: true },
+    { pubkey: accounts.entangledMintEscrow, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledMintEscrowRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.metadataProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.authorizationRulesProgram ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.bubblegumProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.compressionProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.logWrapper, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.sysvarInstructions ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.associatedTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.rent, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([163, 23, 4, 27, 70, 72, 30, 146])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      root: args.root,
+      metadata: Buffer.from(
+        args.metadata.buffer,
+        args.metadata.byteOffset,
+        args.metadata.length
+      ),
+      nonce: args.nonce,
+      index: args.index,
+      proofLen: args.proofLen,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -1,6 +1,6 @@
 import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
 import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
-import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
 import { PROGRAM_ID } from "../programId"
 
 export interface EntangleAccounts {
@@ -12,14 +12,30 @@ export interface EntangleAccounts {
   entangledCollectionMetadata: PublicKey
   originalMint: PublicKey
   originalMetadata: PublicKey
+  /** The edition of the original, for programmable originals and non-custodial collections */
+  originalMasterEdition: PublicKey | null
+  /** The rule set of the original, for programmable originals */
+  authorizationRules: PublicKey | null
   originalMintAccount: PublicKey
-  originalMintEscrow: PublicKey
+  originalMintAccountRecord: PublicKey | null
+  /** The escrow of the original, for custodial collections */
+  originalMintEscrow: PublicKey | null
+  originalMintEscrowRecord: PublicKey | null
   entangledMint: PublicKey
+  /** The master edition of the entangled token, for programmable collections */
+  entangledMasterEdition: PublicKey | null
+  /** The rule set of the entangled token, for programmable collections */
+  entangledAuthorizationRules: PublicKey | null
   entangledMintAccount: PublicKey
+  entangledMintAccountRecord: PublicKey | null
   entangledMetadata: PublicKey
   entangledMintEscrow: PublicKey
+  entangledMintEscrowRecord: PublicKey | null
   /** Common Solana programs */
   metadataProgram: PublicKey
+  authorizationRulesProgram: PublicKey | null
+  sysvarInstructions: PublicKey | null
+  originalTokenProgram: PublicKey
   tokenProgram: PublicKey
   associatedTokenProgram: PublicKey
   systemProgram: PublicKey
@@ -48,18 +64,77 @@ export function entangle(accounts: EntangleAccounts) {
       isWritable: false,
     },
     { pubkey: accounts.originalMint, isSigner: false, isWritable: true },
-    { pubkey: accounts.originalMetadata, isSigner: false, isWritable: false },
+    { pubkey: accounts.originalMetadata, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.originalMasterEdition ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.authorizationRules ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
     { pubkey: accounts.originalMintAccount, isSigner: false, isWritable: true },
-    { pubkey: accounts.originalMintEscrow, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.originalMintAccountRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.originalMintEscrow ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.originalMintEscrowRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
     { pubkey: accounts.entangledMint, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.entangledMasterEdition ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.entangledAuthorizationRules ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
     {
       pubkey: accounts.entangledMintAccount,
       isSigner: false,
       isWritable: true,
     },
+    {
+      pubkey: accounts.entangledMintAccountRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
     { pubkey: accounts.entangledMetadata, isSigner: false, isWritable: true },
     { pubkey: accounts.entangledMintEscrow, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledMintEscrowRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
     { pubkey: accounts.metadataProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.authorizationRulesProgram ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.sysvarInstructions ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.originalTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
     { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
     {
       pubkey: accounts.associatedTokenProgram,
@@ -0,0 +1,43 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface EntangleBatchArgs {
+  hookAccounts: Uint8Array
+}
+
+export interface EntangleBatchAccounts {
+  /** Must sign every group of the batch */
+  signer: PublicKey
+}
+
+export const layout = borsh.struct([borsh.vecU8("hookAccounts")])
+
+/**
+ * Swaps many original tokens at once. The remaining accounts hold one group of
+ * `entangle` accounts per token, each followed by its `hook_accounts` transfer hook accounts
+ */
+export function entangleBatch(
+  args: EntangleBatchArgs,
+  accounts: EntangleBatchAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: false },
+  ]
+  const identifier = Buffer.from([1, 192, 14, 212, 222, 204, 237, 240])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      hookAccounts: Buffer.from(
+        args.hookAccounts.buffer,
+        args.hookAccounts.byteOffset,
+        args.hookAccounts.length
+      ),
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -0,0 +1,144 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface EntangleTreePairArgs {
+  root: Array<number>
+  metadata: Uint8Array
+  nonce: BN
+  index: number
+  proofLen: number
+}
+
+export interface EntangleTreePairAccounts {
+  signer: PublicKey
+  entanglerAuthority: PublicKey
+  entangledCollection: PublicKey
+  entangledPair: PublicKey
+  originalMint: PublicKey
+  originalMetadata: PublicKey
+  /** The master edition of the original, for programmable originals */
+  originalMasterEdition: PublicKey | null
+  /** The rule set of the original, for programmable originals */
+  authorizationRules: PublicKey | null
+  originalMintAccount: PublicKey
+  originalMintAccountRecord: PublicKey | null
+  originalMintEscrow: PublicKey
+  originalMintEscrowRecord: PublicKey | null
+  /** The address an SPL entangled mint would have, keying the pair */
+  entangledMint: PublicKey
+  treeConfig: PublicKey
+  /** The collection's entangled tree */
+  merkleTree: PublicKey
+  /** Common Solana programs */
+  metadataProgram: PublicKey
+  authorizationRulesProgram: PublicKey | null
+  bubblegumProgram: PublicKey
+  compressionProgram: PublicKey
+  logWrapper: PublicKey
+  sysvarInstructions: PublicKey | null
+  originalTokenProgram: PublicKey
+  associatedTokenProgram: PublicKey
+  systemProgram: PublicKey
+}
+
+export const layout = borsh.struct([
+  borsh.array(borsh.u8(), 32, "root"),
+  borsh.vecU8("metadata"),
+  borsh.u64("nonce"),
+  borsh.u32("index"),
+  borsh.u8("proofLen"),
+])
+
+/**
+ * Swap from the original token to the compressed entangled one.
+ * The remaining accounts hold the first `proof_len` nodes of the merkle proof,
+ * followed by the transfer hook accounts of the original mint
+ */
+export function entangleTreePair(
+  args: EntangleTreePairArgs,
+  accounts: EntangleTreePairAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: true },
+    { pubkey: accounts.entanglerAuthority, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.entangledCollection,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.entangledPair, isSigner: false, isWritable: false },
+    { pubkey: accounts.originalMint, isSigner: false, isWritable: true },
+    { pubkey: accounts.originalMetadata, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.originalMasterEdition ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.authorizationRules ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.originalMintAccount, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.originalMintAccountRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.originalMintEscrow, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.originalMintEscrowRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.entangledMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.treeConfig, isSigner: false, isWritable: false },
+    { pubkey: accounts.merkleTree, isSigner: false, isWritable: true },
+    { pubkey: accounts.metadataProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.authorizationRulesProgram ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.bubblegumProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.compressionProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.logWrapper, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.sysvarInstructions ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.originalTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.associatedTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([70, 29, 230, 76, 84, 222, 181, 234])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      root: args.root,
+      metadata: Buffer.from(
+        args.metadata.buffer,
+        args.metadata.byteOffset,
+        args.metadata.length
+      ),
+      nonce: args.nonce,
+      index: args.index,
+      proofLen: args.proofLen,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
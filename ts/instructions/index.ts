@@ -8,6 +8,11 @@ export type {
   CreateCollectionArgs,
   CreateCollectionAccounts,
 } from "./createCollection"
+export { setCollectionVerification } from "./setCollectionVerification"
+export type {
+  SetCollectionVerificationArgs,
+  SetCollectionVerificationAccounts,
+} from "./setCollectionVerification"
 export { createCollectionEntry } from "./createCollectionEntry"
 export type {
   CreateCollectionEntryArgs,
@@ -15,9 +20,73 @@ export type {
 } from "./createCollectionEntry"
 export { initializePair } from "./initializePair"
 export type { InitializePairAccounts } from "./initializePair"
+export { createEntangledEdition } from "./createEntangledEdition"
+export type { CreateEntangledEditionAccounts } from "./createEntangledEdition"
 export { entangle } from "./entangle"
 export type { EntangleAccounts } from "./entangle"
+export { entangleBatch } from "./entangleBatch"
+export type { EntangleBatchAccounts } from "./entangleBatch"
+export { initializeAndEntangle } from "./initializeAndEntangle"
+export type { InitializeAndEntangleAccounts } from "./initializeAndEntangle"
 export { disentangle } from "./disentangle"
 export type { DisentangleAccounts } from "./disentangle"
+export { disentangleBatch } from "./disentangleBatch"
+export type { DisentangleBatchAccounts } from "./disentangleBatch"
+export { initializeCompressedPair } from "./initializeCompressedPair"
+export type {
+  InitializeCompressedPairArgs,
+  InitializeCompressedPairAccounts,
+} from "./initializeCompressedPair"
+export { entangleCompressed } from "./entangleCompressed"
+export type {
+  EntangleCompressedArgs,
+  EntangleCompressedAccounts,
+} from "./entangleCompressed"
+export { disentangleCompressed } from "./disentangleCompressed"
+export type {
+  DisentangleCompressedArgs,
+  DisentangleCompressedAccounts,
+} from "./disentangleCompressed"
+export { createEntangledTree } from "./createEntangledTree"
+export type {
+  CreateEntangledTreeArgs,
+  CreateEntangledTreeAccounts,
+} from "./createEntangledTree"
+export { initializeTreePair } from "./initializeTreePair"
+export type { InitializeTreePairAccounts } from "./initializeTreePair"
+export { entangleTreePair } from "./entangleTreePair"
+export type {
+  EntangleTreePairArgs,
+  EntangleTreePairAccounts,
+} from "./entangleTreePair"
+export { disentangleTreePair } from "./disentangleTreePair"
+export type {
+  DisentangleTreePairArgs,
+  DisentangleTreePairAccounts,
+} from "./disentangleTreePair"
 export { burnOriginal } from "./burnOriginal"
 export type { BurnOriginalAccounts } from "./burnOriginal"
+export { migrateCollection } from "./migrateCollection"
+export type { MigrateCollectionAccounts } from "./migrateCollection"
+export { migratePair } from "./migratePair"
+export type { MigratePairAccounts } from "./migratePair"
+export { migrateCompressedOriginal } from "./migrateCompressedOriginal"
+export type {
+  MigrateCompressedOriginalArgs,
+  MigrateCompressedOriginalAccounts,
+} from "./migrateCompressedOriginal"
+export { verifyPair } from "./verifyPair"
+export type { VerifyPairAccounts } from "./verifyPair"
+export { recordOriginal } from "./recordOriginal"
+export type { RecordOriginalAccounts } from "./recordOriginal"
+export { setRecordsBackfilled } from "./setRecordsBackfilled"
+export type {
+  SetRecordsBackfilledArgs,
+  SetRecordsBackfilledAccounts,
+} from "./setRecordsBackfilled"
+export { rescueTokens } from "./rescueTokens"
+export type { RescueTokensArgs, RescueTokensAccounts } from "./rescueTokens"
+export { sweepAuthorityLamports } from "./sweepAuthorityLamports"
+export type { SweepAuthorityLamportsAccounts } from "./sweepAuthorityLamports"
+export { resizeAccount } from "./resizeAccount"
+export type { ResizeAccountAccounts } from "./resizeAccount"
@@ -0,0 +1,159 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface InitializeAndEntangleAccounts {
+  signer: PublicKey
+  /** The creator receiving the collection's royalties */
+  creator: PublicKey
+  entanglerAuthority: PublicKey
+  masterEdition: PublicKey
+  entangledCollection: PublicKey
+  entangledPair: PublicKey
+  entangledCollectionMint: PublicKey
+  entangledCollectionMetadata: PublicKey
+  originalMint: PublicKey
+  originalMetadata: PublicKey
+  /** The master or print edition of the original */
+  originalEdition: PublicKey | null
+  /** The rule set of the original, for programmable originals */
+  authorizationRules: PublicKey | null
+  originalMintAccount: PublicKey
+  originalMintAccountRecord: PublicKey | null
+  /** The escrow of the original, for custodial collections */
+  originalMintEscrow: PublicKey | null
+  originalMintEscrowRecord: PublicKey | null
+  /** Marks the original as backing a pair, created by the first of its pairs */
+  originalRecord: PublicKey
+  entangledMint: PublicKey
+  entangledMetadata: PublicKey
+  /** The master edition of the entangled token, except for Token-2022 collections */
+  entangledMasterEdition: PublicKey | null
+  /** The rule set of the entangled token, for programmable collections */
+  entangledAuthorizationRules: PublicKey | null
+  entangledMintAccount: PublicKey
+  entangledMintAccountRecord: PublicKey | null
+  entangledMintEscrow: PublicKey
+  entangledMintEscrowRecord: PublicKey | null
+  /** Common Solana programs */
+  metadataProgram: PublicKey
+  authorizationRulesProgram: PublicKey | null
+  sysvarInstructions: PublicKey | null
+  originalTokenProgram: PublicKey
+  tokenProgram: PublicKey
+  associatedTokenProgram: PublicKey
+  systemProgram: PublicKey
+  rent: PublicKey
+}
+
+/** Creates the entanglement pair on first use and swaps in the same instruction */
+export function initializeAndEntangle(accounts: InitializeAndEntangleAccounts) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: true },
+    { pubkey: accounts.creator, isSigner: false, isWritable: false },
+    { pubkey: accounts.entanglerAuthority, isSigner: false, isWritable: true },
+    { pubkey: accounts.masterEdition, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledCollection,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.entangledPair, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledCollectionMint,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.entangledCollectionMetadata,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.originalMint, isSigner: false, isWritable: true },
+    { pubkey: accounts.originalMetadata, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.originalEdition ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.authorizationRules ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.originalMintAccount, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.originalMintAccountRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.originalMintEscrow ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.originalMintEscrowRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.originalRecord, isSigner: false, isWritable: true },
+    { pubkey: accounts.entangledMint, isSigner: false, isWritable: true },
+    { pubkey: accounts.entangledMetadata, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledMasterEdition ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.entangledAuthorizationRules ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.entangledMintAccount,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.entangledMintAccountRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.entangledMintEscrow, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledMintEscrowRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.metadataProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.authorizationRulesProgram ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.sysvarInstructions ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.originalTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.associatedTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.rent, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([219, 76, 5, 7, 251, 223, 40, 172])
+  const data = identifier
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
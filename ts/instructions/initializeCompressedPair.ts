@@ -0,0 +1,129 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface InitializeCompressedPairArgs {
+  root: Array<number>
+  metadata: Uint8Array
+  nonce: BN
+  index: number
+}
+
+export interface InitializeCompressedPairAccounts {
+  signer: PublicKey
+  /** The creator receiving the collection's royalties */
+  creator: PublicKey
+  entanglerAuthority: PublicKey
+  masterEdition: PublicKey
+  entangledCollection: PublicKey
+  entangledCollectionMint: PublicKey
+  entangledCollectionMetadata: PublicKey
+  /** The Bubblegum tree holding the original */
+  merkleTree: PublicKey
+  /** The owner of the original, part of the proven leaf */
+  leafOwner: PublicKey
+  /** The delegate of the original, part of the proven leaf */
+  leafDelegate: PublicKey
+  entangledMint: PublicKey
+  entangledMetadata: PublicKey
+  /** The master edition of the entangled token, except for Token-2022 collections */
+  entangledMasterEdition: PublicKey | null
+  entangledMintEscrow: PublicKey
+  entangledMintEscrowRecord: PublicKey | null
+  /** Common Solana programs */
+  metadataProgram: PublicKey
+  compressionProgram: PublicKey
+  sysvarInstructions: PublicKey | null
+  tokenProgram: PublicKey
+  associatedTokenProgram: PublicKey
+  systemProgram: PublicKey
+  rent: PublicKey
+}
+
+export const layout = borsh.struct([
+  borsh.array(borsh.u8(), 32, "root"),
+  borsh.vecU8("metadata"),
+  borsh.u64("nonce"),
+  borsh.u32("index"),
+])
+
+/**
+ * Creates an entanglement pair for a compressed token of the collection.
+ * The remaining accounts hold the merkle proof of the leaf
+ */
+export function initializeCompressedPair(
+  args: InitializeCompressedPairArgs,
+  accounts: InitializeCompressedPairAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: true },
+    { pubkey: accounts.creator, isSigner: false, isWritable: false },
+    { pubkey: accounts.entanglerAuthority, isSigner: false, isWritable: true },
+    { pubkey: accounts.masterEdition, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledCollection,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.entangledCollectionMint,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.entangledCollectionMetadata,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.merkleTree, isSigner: false, isWritable: false },
+    { pubkey: accounts.leafOwner, isSigner: false, isWritable: false },
+    { pubkey: accounts.leafDelegate, isSigner: false, isWritable: false },
+    { pubkey: accounts.entangledMint, isSigner: false, isWritable: true },
+    { pubkey: accounts.entangledMetadata, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledMasterEdition ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.entangledMintEscrow, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledMintEscrowRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.metadataProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.compressionProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.sysvarInstructions ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.associatedTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.rent, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([216, 236, 110, 102, 171, 87, 7, 56])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      root: args.root,
+      metadata: Buffer.from(
+        args.metadata.buffer,
+        args.metadata.byteOffset,
+        args.metadata.length
+      ),
+      nonce: args.nonce,
+      index: args.index,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
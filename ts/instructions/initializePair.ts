@@ -1,11 +1,11 @@
 import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
 import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
-import * as borsh from "@project-serum/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
 import { PROGRAM_ID } from "../programId"
 
 export interface InitializePairAccounts {
   signer: PublicKey
-  /** The update authority of thee collection */
+  /** The creator receiving the collection's royalties */
   creator: PublicKey
   entanglerAuthority: PublicKey
   masterEdition: PublicKey
@@ -14,12 +14,22 @@ export interface InitializePairAccounts {
   entangledCollectionMetadata: PublicKey
   originalMint: PublicKey
   originalMetadata: PublicKey
-  originalMintEscrow: PublicKey
+  /** The master or print edition of the original */
+  originalEdition: PublicKey | null
+  /** The escrow of the original, for custodial collections */
+  originalMintEscrow: PublicKey | null
+  /** Marks the original as backing a pair, created by the first of its pairs */
+  originalRecord: PublicKey
   entangledMint: PublicKey
   entangledMetadata: PublicKey
+  /** The master edition of the entangled token, except for Token-2022 collections */
+  entangledMasterEdition: PublicKey | null
   entangledMintEscrow: PublicKey
+  entangledMintEscrowRecord: PublicKey | null
   /** Common Solana programs */
   metadataProgram: PublicKey
+  sysvarInstructions: PublicKey | null
+  originalTokenProgram: PublicKey
   tokenProgram: PublicKey
   associatedTokenProgram: PublicKey
   systemProgram: PublicKey
@@ -50,11 +60,41 @@ export function initializePair(accounts: InitializePairAccounts) {
     },
     { pubkey: accounts.originalMint, isSigner: false, isWritable: true },
     { pubkey: accounts.originalMetadata, isSigner: false, isWritable: false },
-    { pubkey: accounts.originalMintEscrow, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.originalEdition ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.originalMintEscrow ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.originalRecord, isSigner: false, isWritable: true },
     { pubkey: accounts.entangledMint, isSigner: false, isWritable: true },
     { pubkey: accounts.entangledMetadata, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledMasterEdition ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
     { pubkey: accounts.entangledMintEscrow, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledMintEscrowRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
     { pubkey: accounts.metadataProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.sysvarInstructions ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.originalTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
     { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
     {
       pubkey: accounts.associatedTokenProgram,
@@ -0,0 +1,95 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface InitializeTreePairAccounts {
+  signer: PublicKey
+  /** The creator receiving the collection's royalties */
+  creator: PublicKey
+  entanglerAuthority: PublicKey
+  masterEdition: PublicKey
+  entangledCollection: PublicKey
+  entangledPair: PublicKey
+  entangledCollectionMint: PublicKey
+  entangledCollectionMetadata: PublicKey
+  originalMint: PublicKey
+  originalMetadata: PublicKey
+  /** The master or print edition of the original */
+  originalEdition: PublicKey | null
+  originalMintEscrow: PublicKey
+  /** Marks the original as backing a pair, created by the first of its pairs */
+  originalRecord: PublicKey
+  /** The address an SPL entangled mint would have, keying the pair */
+  entangledMint: PublicKey
+  treeConfig: PublicKey
+  /** The collection's entangled tree */
+  merkleTree: PublicKey
+  bubblegumSigner: PublicKey
+  /** Common Solana programs */
+  metadataProgram: PublicKey
+  bubblegumProgram: PublicKey
+  compressionProgram: PublicKey
+  logWrapper: PublicKey
+  originalTokenProgram: PublicKey
+  associatedTokenProgram: PublicKey
+  systemProgram: PublicKey
+}
+
+/** Creates an entanglement pair whose entangled token is a leaf of the collection's tree */
+export function initializeTreePair(accounts: InitializeTreePairAccounts) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: true },
+    { pubkey: accounts.creator, isSigner: false, isWritable: false },
+    { pubkey: accounts.entanglerAuthority, isSigner: false, isWritable: false },
+    { pubkey: accounts.masterEdition, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.entangledCollection,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.entangledPair, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledCollectionMint,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.entangledCollectionMetadata,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.originalMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.originalMetadata, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.originalEdition ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.originalMintEscrow, isSigner: false, isWritable: true },
+    { pubkey: accounts.originalRecord, isSigner: false, isWritable: true },
+    { pubkey: accounts.entangledMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.treeConfig, isSigner: false, isWritable: true },
+    { pubkey: accounts.merkleTree, isSigner: false, isWritable: true },
+    { pubkey: accounts.bubblegumSigner, isSigner: false, isWritable: false },
+    { pubkey: accounts.metadataProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.bubblegumProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.compressionProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.logWrapper, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.originalTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.associatedTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([145, 34, 95, 11, 20, 216, 32, 176])
+  const data = identifier
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
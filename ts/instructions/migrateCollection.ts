@@ -0,0 +1,78 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface MigrateCollectionAccounts {
+  signer: PublicKey
+  /** The entangler's state */
+  state: PublicKey
+  /** The authority shared by collections created before per-collection authorities */
+  globalAuthority: PublicKey
+  /** The collection's own authority */
+  entanglerAuthority: PublicKey
+  /** The account storing the collection's data */
+  entangledCollection: PublicKey
+  entangledCollectionMint: PublicKey
+  entangledCollectionMetadata: PublicKey
+  /** The master edition of the collection */
+  masterEdition: PublicKey
+  /** The ATA of the global authority storing the collection NFT */
+  legacyCollectionMintAccount: PublicKey
+  /** The ATA of the collection's authority receiving the collection NFT */
+  entangledCollectionMintAccount: PublicKey
+  /** Common Solana programs */
+  metadataProgram: PublicKey
+  sysvarInstructions: PublicKey
+  tokenProgram: PublicKey
+  associatedTokenProgram: PublicKey
+  systemProgram: PublicKey
+}
+
+/**
+ * Moves a collection from the global authority to its own one. Pairs then need
+ * `migrate_pair` before they can be swapped again
+ */
+export function migrateCollection(accounts: MigrateCollectionAccounts) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: true },
+    { pubkey: accounts.state, isSigner: false, isWritable: false },
+    { pubkey: accounts.globalAuthority, isSigner: false, isWritable: false },
+    { pubkey: accounts.entanglerAuthority, isSigner: false, isWritable: false },
+    { pubkey: accounts.entangledCollection, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledCollectionMint,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.entangledCollectionMetadata,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.masterEdition, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.legacyCollectionMintAccount,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.entangledCollectionMintAccount,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.metadataProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.sysvarInstructions, isSigner: false, isWritable: false },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.associatedTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([23, 210, 133, 21, 57, 239, 124, 148])
+  const data = identifier
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -0,0 +1,99 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface MigrateCompressedOriginalArgs {
+  root: Array<number>
+  metadata: Uint8Array
+  nonce: BN
+  index: number
+}
+
+export interface MigrateCompressedOriginalAccounts {
+  signer: PublicKey
+  /** The authority shared by collections created before per-collection authorities */
+  globalAuthority: PublicKey
+  /** The collection's own authority */
+  entanglerAuthority: PublicKey
+  entangledCollection: PublicKey
+  treeConfig: PublicKey
+  /** The Bubblegum tree holding the original */
+  merkleTree: PublicKey
+  /** The entangled mint of the original, proving it belongs to the collection */
+  entangledMint: PublicKey
+  /** The pair of the entangled mint, resized if it predates the current layout */
+  entangledPair: PublicKey
+  /** The escrow of the global authority for the entangled token */
+  entangledMintEscrow: PublicKey
+  /** The escrow of the collection's authority for the entangled token */
+  entangledMintDestination: PublicKey
+  /** Common Solana programs */
+  bubblegumProgram: PublicKey
+  compressionProgram: PublicKey
+  logWrapper: PublicKey
+  systemProgram: PublicKey
+}
+
+export const layout = borsh.struct([
+  borsh.array(borsh.u8(), 32, "root"),
+  borsh.vecU8("metadata"),
+  borsh.u64("nonce"),
+  borsh.u32("index"),
+])
+
+/**
+ * Moves an escrowed compressed original to its collection's authority.
+ * The remaining accounts hold the merkle proof of the leaf
+ */
+export function migrateCompressedOriginal(
+  args: MigrateCompressedOriginalArgs,
+  accounts: MigrateCompressedOriginalAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: true },
+    { pubkey: accounts.globalAuthority, isSigner: false, isWritable: false },
+    { pubkey: accounts.entanglerAuthority, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.entangledCollection,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.treeConfig, isSigner: false, isWritable: false },
+    { pubkey: accounts.merkleTree, isSigner: false, isWritable: true },
+    { pubkey: accounts.entangledMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.entangledPair, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledMintEscrow,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.entangledMintDestination,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.bubblegumProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.compressionProgram, isSigner: false, isWritable: false },
+    { pubkey: accounts.logWrapper, isSigner: false, isWritable: false },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([129, 48, 102, 119, 185, 103, 93, 110])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      root: args.root,
+      metadata: Buffer.from(
+        args.metadata.buffer,
+        args.metadata.byteOffset,
+        args.metadata.length
+      ),
+      nonce: args.nonce,
+      index: args.index,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -0,0 +1,150 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface MigratePairAccounts {
+  signer: PublicKey
+  /** The authority shared by collections created before per-collection authorities */
+  globalAuthority: PublicKey
+  /** The collection's own authority */
+  entanglerAuthority: PublicKey
+  entangledCollection: PublicKey
+  /** The original mint, or the asset id of a compressed original */
+  originalMint: PublicKey
+  originalMetadata: PublicKey
+  /** The edition of the original, for programmable originals */
+  originalMasterEdition: PublicKey | null
+  /** The rule set of the original, for programmable originals */
+  authorizationRules: PublicKey | null
+  /** The escrow of the global authority, once the original got entangled */
+  originalMintEscrow: PublicKey | null
+  originalMintEscrowRecord: PublicKey | null
+  originalMintDestination: PublicKey
+  originalMintDestinationRecord: PublicKey | null
+  entangledMint: PublicKey
+  /** The pair of the entangled mint, once entangled, resized if it predates the current layout */
+  entangledPair: PublicKey
+  entangledMetadata: PublicKey
+  /** The master edition of the entangled token, for Metaplex collections */
+  entangledMasterEdition: PublicKey | null
+  /** The rule set of the entangled token, for programmable collections */
+  entangledAuthorizationRules: PublicKey | null
+  entangledMintEscrow: PublicKey
+  entangledMintEscrowRecord: PublicKey | null
+  entangledMintDestination: PublicKey
+  entangledMintDestinationRecord: PublicKey | null
+  /** Common Solana programs */
+  metadataProgram: PublicKey
+  authorizationRulesProgram: PublicKey | null
+  sysvarInstructions: PublicKey | null
+  originalTokenProgram: PublicKey
+  tokenProgram: PublicKey
+  associatedTokenProgram: PublicKey
+  systemProgram: PublicKey
+}
+
+/**
+ * Moves the escrowed token and the authorities of a pair to its collection's authority.
+ * The remaining accounts hold the transfer hook accounts of the escrowed token
+ */
+export function migratePair(accounts: MigratePairAccounts) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: true },
+    { pubkey: accounts.globalAuthority, isSigner: false, isWritable: false },
+    { pubkey: accounts.entanglerAuthority, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.entangledCollection,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.originalMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.originalMetadata, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.originalMasterEdition ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.authorizationRules ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.originalMintEscrow ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.originalMintEscrowRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.originalMintDestination,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.originalMintDestinationRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.entangledMint, isSigner: false, isWritable: true },
+    { pubkey: accounts.entangledPair, isSigner: false, isWritable: true },
+    { pubkey: accounts.entangledMetadata, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledMasterEdition ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.entangledAuthorizationRules ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.entangledMintEscrow, isSigner: false, isWritable: true },
+    {
+      pubkey: accounts.entangledMintEscrowRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.entangledMintDestination,
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: accounts.entangledMintDestinationRecord ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: accounts.metadataProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.authorizationRulesProgram ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.sysvarInstructions ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.originalTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.associatedTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([222, 28, 58, 57, 227, 213, 148, 232])
+  const data = identifier
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -0,0 +1,36 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface RecordOriginalAccounts {
+  signer: PublicKey
+  /** The collection of the pair, resized if it predates the current layout */
+  entangledCollection: PublicKey
+  /** The original mint, or the asset id of a compressed original */
+  originalMint: PublicKey
+  entangledMint: PublicKey
+  entangledPair: PublicKey
+  originalRecord: PublicKey
+  systemProgram: PublicKey
+}
+
+/**
+ * Records an original initialized before originals got a record, so that it can't be
+ * rescued. Permissionless
+ */
+export function recordOriginal(accounts: RecordOriginalAccounts) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: true },
+    { pubkey: accounts.entangledCollection, isSigner: false, isWritable: true },
+    { pubkey: accounts.originalMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.entangledMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.entangledPair, isSigner: false, isWritable: false },
+    { pubkey: accounts.originalRecord, isSigner: false, isWritable: true },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([70, 148, 4, 58, 71, 161, 11, 198])
+  const data = identifier
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
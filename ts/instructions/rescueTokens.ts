@@ -0,0 +1,69 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface RescueTokensArgs {
+  amount: BN
+}
+
+export interface RescueTokensAccounts {
+  signer: PublicKey
+  /** The entangler's state */
+  state: PublicKey
+  /** The collection the mint is proven to back no pair of */
+  entangledCollection: PublicKey
+  /** The collection's authority or the global one, owning the source account */
+  entanglerAuthority: PublicKey
+  mint: PublicKey
+  metadata: PublicKey
+  /** Exists for the originals of pairs initialized since records, whatever their collection */
+  originalRecord: PublicKey
+  /**
+   * The entangled mint of the mint as an original of the collection, which exists for its
+   * pairs, including those initialized before records
+   */
+  entangledMint: PublicKey
+  /** The pair of that entangled mint, the only account tree pairs have */
+  entangledPair: PublicKey
+  source: PublicKey
+  destination: PublicKey
+  tokenProgram: PublicKey
+}
+
+export const layout = borsh.struct([borsh.u64("amount")])
+
+/**
+ * Moves tokens sent by mistake out of an account of an entangler authority, as long as
+ * they back no pair. The remaining accounts hold the transfer hook accounts of the mint
+ */
+export function rescueTokens(
+  args: RescueTokensArgs,
+  accounts: RescueTokensAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: false },
+    { pubkey: accounts.state, isSigner: false, isWritable: false },
+    { pubkey: accounts.entangledCollection, isSigner: false, isWritable: false },
+    { pubkey: accounts.entanglerAuthority, isSigner: false, isWritable: false },
+    { pubkey: accounts.mint, isSigner: false, isWritable: false },
+    { pubkey: accounts.metadata, isSigner: false, isWritable: false },
+    { pubkey: accounts.originalRecord, isSigner: false, isWritable: false },
+    { pubkey: accounts.entangledMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.entangledPair, isSigner: false, isWritable: false },
+    { pubkey: accounts.source, isSigner: false, isWritable: true },
+    { pubkey: accounts.destination, isSigner: false, isWritable: true },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([222, 81, 199, 209, 182, 62, 62, 186])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      amount: args.amount,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -0,0 +1,29 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface ResizeAccountAccounts {
+  /** Pays for the rent of the new bytes */
+  signer: PublicKey
+  /** The state, a collection or a pair created with an older, shorter layout */
+  account: PublicKey
+  systemProgram: PublicKey
+}
+
+/**
+ * Grows the state, a collection or a pair created with an older layout to the current one,
+ * so that it can be deserialized again. Fields added since take their default, so resized
+ * collections only accept verified originals until the admin opts them out. Permissionless
+ */
+export function resizeAccount(accounts: ResizeAccountAccounts) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: true },
+    { pubkey: accounts.account, isSigner: false, isWritable: true },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([89, 215, 202, 211, 3, 206, 175, 157])
+  const data = identifier
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
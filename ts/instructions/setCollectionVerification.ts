@@ -0,0 +1,46 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface SetCollectionVerificationArgs {
+  allowUnverified: boolean
+  originalCreator: PublicKey | null
+}
+
+export interface SetCollectionVerificationAccounts {
+  signer: PublicKey
+  /** The entangler's state */
+  state: PublicKey
+  /** The account storing the collection's data */
+  entangledCollection: PublicKey
+}
+
+export const layout = borsh.struct([
+  borsh.bool("allowUnverified"),
+  borsh.option(borsh.publicKey(), "originalCreator"),
+])
+
+/** Sets how strictly originals are checked against the collection */
+export function setCollectionVerification(
+  args: SetCollectionVerificationArgs,
+  accounts: SetCollectionVerificationAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: false },
+    { pubkey: accounts.state, isSigner: false, isWritable: false },
+    { pubkey: accounts.entangledCollection, isSigner: false, isWritable: true },
+  ]
+  const identifier = Buffer.from([201, 214, 24, 14, 214, 122, 96, 62])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      allowUnverified: args.allowUnverified,
+      originalCreator: args.originalCreator,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -0,0 +1,44 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface SetRecordsBackfilledArgs {
+  recordsBackfilled: boolean
+}
+
+export interface SetRecordsBackfilledAccounts {
+  /** The admin, paying for the state's resize */
+  signer: PublicKey
+  /** The entangler's state, resized if it predates the current layout */
+  state: PublicKey
+  systemProgram: PublicKey
+}
+
+export const layout = borsh.struct([borsh.bool("recordsBackfilled")])
+
+/**
+ * Declares whether every original initialized before original records existed got one
+ * from `record_original`, which rescues from the global authority require. Admin only
+ */
+export function setRecordsBackfilled(
+  args: SetRecordsBackfilledArgs,
+  accounts: SetRecordsBackfilledAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: true },
+    { pubkey: accounts.state, isSigner: false, isWritable: true },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([168, 49, 172, 204, 117, 169, 111, 108])
+  const buffer = Buffer.alloc(1000)
+  const len = layout.encode(
+    {
+      recordsBackfilled: args.recordsBackfilled,
+    },
+    buffer
+  )
+  const data = Buffer.concat([identifier, buffer]).slice(0, 8 + len)
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
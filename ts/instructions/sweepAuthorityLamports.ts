@@ -0,0 +1,37 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface SweepAuthorityLamportsAccounts {
+  signer: PublicKey
+  /** The entangler's state */
+  state: PublicKey
+  /** The collection whose authority gets swept, none for the global authority */
+  entangledCollection: PublicKey | null
+  entanglerAuthority: PublicKey
+  earner: PublicKey
+  systemProgram: PublicKey
+}
+
+/** Moves the lamports an entangler authority holds beyond its rent exemption to the earner */
+export function sweepAuthorityLamports(
+  accounts: SweepAuthorityLamportsAccounts
+) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.signer, isSigner: true, isWritable: false },
+    { pubkey: accounts.state, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.entangledCollection ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.entanglerAuthority, isSigner: false, isWritable: true },
+    { pubkey: accounts.earner, isSigner: false, isWritable: true },
+    { pubkey: accounts.systemProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([73, 255, 253, 148, 220, 84, 100, 181])
+  const data = identifier
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
@@ -0,0 +1,61 @@
+import { TransactionInstruction, PublicKey, AccountMeta } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh" // eslint-disable-line @typescript-eslint/no-unused-vars
+import { PROGRAM_ID } from "../programId"
+
+export interface VerifyPairAccounts {
+  entanglerAuthority: PublicKey
+  entangledCollection: PublicKey
+  /** The pair, which doesn't exist until the original gets entangled */
+  entangledPair: PublicKey
+  /** The original mint, or the asset id of a compressed original, which gets rejected */
+  originalMint: PublicKey
+  /** The escrow of the original, which doesn't exist until the original gets entangled */
+  originalMintEscrow: PublicKey
+  /** The token account the original is frozen in, for non-custodial collections */
+  frozenAccount: PublicKey | null
+  entangledMint: PublicKey
+  entangledMintEscrow: PublicKey
+  /** Common Solana programs */
+  originalTokenProgram: PublicKey
+  tokenProgram: PublicKey
+}
+
+/**
+ * Checks that the entangler holds exactly one token of a pair, and returns its custody.
+ * Permissionless, for auditors and other programs
+ */
+export function verifyPair(accounts: VerifyPairAccounts) {
+  const keys: Array<AccountMeta> = [
+    { pubkey: accounts.entanglerAuthority, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.entangledCollection,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.entangledPair, isSigner: false, isWritable: false },
+    { pubkey: accounts.originalMint, isSigner: false, isWritable: false },
+    { pubkey: accounts.originalMintEscrow, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.frozenAccount ?? PROGRAM_ID,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.entangledMint, isSigner: false, isWritable: false },
+    {
+      pubkey: accounts.entangledMintEscrow,
+      isSigner: false,
+      isWritable: false,
+    },
+    {
+      pubkey: accounts.originalTokenProgram,
+      isSigner: false,
+      isWritable: false,
+    },
+    { pubkey: accounts.tokenProgram, isSigner: false, isWritable: false },
+  ]
+  const identifier = Buffer.from([39, 183, 14, 202, 5, 248, 156, 35])
+  const data = identifier
+  const ix = new TransactionInstruction({ keys, programId: PROGRAM_ID, data })
+  return ix
+}
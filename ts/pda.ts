@@ -1,16 +1,20 @@
 import {
   AUTHORITY_SEED,
+  BUBBLEGUM_PROGRAM_ID,
+  COLLECTION_AUTHORITY_SEED,
   COLLECTION_ENTRY_SEED,
   COLLECTION_MINT_SEED,
   COLLECTION_SEED,
   ENTANGLEMENT_MINT_SEED,
   ENTANGLEMENT_PAIR_SEED,
+  ORIGINAL_RECORD_SEED,
   STATE_SEED,
 } from "./constants";
 
 import { PROGRAM_ID as ENTANGLER_PROGRAM_ID } from "./programId";
 import { PROGRAM_ID as METADATA_PROGRAM_ID } from "@metaplex-foundation/mpl-token-metadata";
 import { PublicKey } from "@solana/web3.js";
+import BN from "bn.js";
 
 export const getEntanglerAuthority = () => {
   return PublicKey.findProgramAddressSync(
@@ -19,6 +23,13 @@ export const getEntanglerAuthority = () => {
   )[0];
 };
 
+export const getCollectionAuthority = (id: PublicKey) => {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from(COLLECTION_AUTHORITY_SEED), id.toBuffer()],
+    ENTANGLER_PROGRAM_ID
+  )[0];
+};
+
 export const getCollectionEntry = (key: string) => {
   return PublicKey.findProgramAddressSync(
     [Buffer.from(COLLECTION_ENTRY_SEED), Buffer.from(key)],
@@ -54,6 +65,13 @@ export const getEntangledPair = (entangledMint: PublicKey) => {
   )[0];
 };
 
+export const getOriginalRecord = (originalMint: PublicKey) => {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from(ORIGINAL_RECORD_SEED), originalMint.toBuffer()],
+    ENTANGLER_PROGRAM_ID
+  )[0];
+};
+
 export const getEntangledMint = (id: PublicKey, originalMint: PublicKey) => {
   return PublicKey.findProgramAddressSync(
     [
@@ -83,3 +101,54 @@ export const getMasterEdition = (mint: PublicKey) => {
     METADATA_PROGRAM_ID
   )[0];
 };
+
+export const getEditionMarker = (masterMint: PublicKey, edition: BN) => {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("metadata"),
+      METADATA_PROGRAM_ID.toBuffer(),
+      masterMint.toBuffer(),
+      Buffer.from("edition"),
+      Buffer.from(edition.divn(248).toString()),
+    ],
+    METADATA_PROGRAM_ID
+  )[0];
+};
+
+export const getTokenRecord = (mint: PublicKey, token: PublicKey) => {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("metadata"),
+      METADATA_PROGRAM_ID.toBuffer(),
+      mint.toBuffer(),
+      Buffer.from("token_record"),
+      token.toBuffer(),
+    ],
+    METADATA_PROGRAM_ID
+  )[0];
+};
+
+export const getAssetId = (merkleTree: PublicKey, nonce: BN) => {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("asset"),
+      merkleTree.toBuffer(),
+      nonce.toArrayLike(Buffer, "le", 8),
+    ],
+    BUBBLEGUM_PROGRAM_ID
+  )[0];
+};
+
+export const getTreeConfig = (merkleTree: PublicKey) => {
+  return PublicKey.findProgramAddressSync(
+    [merkleTree.toBuffer()],
+    BUBBLEGUM_PROGRAM_ID
+  )[0];
+};
+
+export const getBubblegumSigner = () => {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("collection_cpi")],
+    BUBBLEGUM_PROGRAM_ID
+  )[0];
+};
@@ -0,0 +1,150 @@
+import { PublicKey } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh"
+
+export interface NonFungibleJSON {
+  kind: "NonFungible"
+}
+
+export class NonFungible {
+  static readonly discriminator = 0
+  static readonly kind = "NonFungible"
+  readonly discriminator = 0
+  readonly kind = "NonFungible"
+
+  toJSON(): NonFungibleJSON {
+    return {
+      kind: "NonFungible",
+    }
+  }
+
+  toEncodable() {
+    return {
+      NonFungible: {},
+    }
+  }
+}
+
+export interface ProgrammableNonFungibleJSON {
+  kind: "ProgrammableNonFungible"
+}
+
+export class ProgrammableNonFungible {
+  static readonly discriminator = 1
+  static readonly kind = "ProgrammableNonFungible"
+  readonly discriminator = 1
+  readonly kind = "ProgrammableNonFungible"
+
+  toJSON(): ProgrammableNonFungibleJSON {
+    return {
+      kind: "ProgrammableNonFungible",
+    }
+  }
+
+  toEncodable() {
+    return {
+      ProgrammableNonFungible: {},
+    }
+  }
+}
+
+export interface Token2022JSON {
+  kind: "Token2022"
+}
+
+export class Token2022 {
+  static readonly discriminator = 2
+  static readonly kind = "Token2022"
+  readonly discriminator = 2
+  readonly kind = "Token2022"
+
+  toJSON(): Token2022JSON {
+    return {
+      kind: "Token2022",
+    }
+  }
+
+  toEncodable() {
+    return {
+      Token2022: {},
+    }
+  }
+}
+
+export interface CompressedJSON {
+  kind: "Compressed"
+}
+
+export class Compressed {
+  static readonly discriminator = 3
+  static readonly kind = "Compressed"
+  readonly discriminator = 3
+  readonly kind = "Compressed"
+
+  toJSON(): CompressedJSON {
+    return {
+      kind: "Compressed",
+    }
+  }
+
+  toEncodable() {
+    return {
+      Compressed: {},
+    }
+  }
+}
+
+// eslint-disable-next-line @typescript-eslint/no-explicit-any
+export function fromDecoded(obj: any): types.EntangledStandardKind {
+  if (typeof obj !== "object") {
+    throw new Error("Invalid enum object")
+  }
+
+  if ("NonFungible" in obj) {
+    return new NonFungible()
+  }
+  if ("ProgrammableNonFungible" in obj) {
+    return new ProgrammableNonFungible()
+  }
+  if ("Token2022" in obj) {
+    return new Token2022()
+  }
+  if ("Compressed" in obj) {
+    return new Compressed()
+  }
+
+  throw new Error("Invalid enum object")
+}
+
+export function fromJSON(
+  obj: types.EntangledStandardJSON
+): types.EntangledStandardKind {
+  switch (obj.kind) {
+    case "NonFungible": {
+      return new NonFungible()
+    }
+    case "ProgrammableNonFungible": {
+      return new ProgrammableNonFungible()
+    }
+    case "Token2022": {
+      return new Token2022()
+    }
+    case "Compressed": {
+      return new Compressed()
+    }
+  }
+}
+
+export function layout(property?: string) {
+  const ret = borsh.rustEnum([
+    borsh.struct([], "NonFungible"),
+    borsh.struct([], "ProgrammableNonFungible"),
+    borsh.struct([], "Token2022"),
+    borsh.struct([], "Compressed"),
+  ])
+  if (property !== undefined) {
+    return ret.replicate(property)
+  }
+  return ret
+}
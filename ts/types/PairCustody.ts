@@ -0,0 +1,110 @@
+import { PublicKey } from "@solana/web3.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import BN from "bn.js" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as types from "../types" // eslint-disable-line @typescript-eslint/no-unused-vars
+import * as borsh from "@coral-xyz/borsh"
+
+export interface PairCustodyFields {
+  /** The original mint */
+  originalMint: PublicKey
+  /** The mint of the entangled token */
+  entangledMint: PublicKey
+  /** The PDA holding the token */
+  authority: PublicKey
+  /** Whether the entangler holds the original rather than the entangled token */
+  entangled: boolean
+  /** The escrow holding the token, or the account the original is frozen in */
+  custodyAccount: PublicKey
+}
+
+export interface PairCustodyJSON {
+  /** The original mint */
+  originalMint: string
+  /** The mint of the entangled token */
+  entangledMint: string
+  /** The PDA holding the token */
+  authority: string
+  /** Whether the entangler holds the original rather than the entangled token */
+  entangled: boolean
+  /** The escrow holding the token, or the account the original is frozen in */
+  custodyAccount: string
+}
+
+/** The custody of a pair, as returned by `verify_pair` */
+export class PairCustody {
+  /** The original mint */
+  readonly originalMint: PublicKey
+  /** The mint of the entangled token */
+  readonly entangledMint: PublicKey
+  /** The PDA holding the token */
+  readonly authority: PublicKey
+  /** Whether the entangler holds the original rather than the entangled token */
+  readonly entangled: boolean
+  /** The escrow holding the token, or the account the original is frozen in */
+  readonly custodyAccount: PublicKey
+
+  constructor(fields: PairCustodyFields) {
+    this.originalMint = fields.originalMint
+    this.entangledMint = fields.entangledMint
+    this.authority = fields.authority
+    this.entangled = fields.entangled
+    this.custodyAccount = fields.custodyAccount
+  }
+
+  static layout(property?: string) {
+    return borsh.struct(
+      [
+        borsh.publicKey("originalMint"),
+        borsh.publicKey("entangledMint"),
+        borsh.publicKey("authority"),
+        borsh.bool("entangled"),
+        borsh.publicKey("custodyAccount"),
+      ],
+      property
+    )
+  }
+
+  // eslint-disable-next-line @typescript-eslint/no-explicit-any
+  static fromDecoded(obj: any) {
+    return new PairCustody({
+      originalMint: obj.originalMint,
+      entangledMint: obj.entangledMint,
+      authority: obj.authority,
+      entangled: obj.entangled,
+      custodyAccount: obj.custodyAccount,
+    })
+  }
+
+  static toEncodable(fields: PairCustodyFields) {
+    return {
+      originalMint: fields.originalMint,
+      entangledMint: fields.entangledMint,
+      authority: fields.authority,
+      entangled: fields.entangled,
+      custodyAccount: fields.custodyAccount,
+    }
+  }
+
+  toJSON(): PairCustodyJSON {
+    return {
+      originalMint: this.originalMint.toString(),
+      entangledMint: this.entangledMint.toString(),
+      authority: this.authority.toString(),
+      entangled: this.entangled,
+      custodyAccount: this.custodyAccount.toString(),
+    }
+  }
+
+  static fromJSON(obj: PairCustodyJSON): PairCustody {
+    return new PairCustody({
+      originalMint: new PublicKey(obj.originalMint),
+      entangledMint: new PublicKey(obj.entangledMint),
+      authority: new PublicKey(obj.authority),
+      entangled: obj.entangled,
+      custodyAccount: new PublicKey(obj.custodyAccount),
+    })
+  }
+
+  toEncodable() {
+    return PairCustody.toEncodable(this)
+  }
+}
@@ -0,0 +1,16 @@
+import * as EntangledStandard from "./EntangledStandard"
+
+export { PairCustody } from "./PairCustody"
+export type { PairCustodyFields, PairCustodyJSON } from "./PairCustody"
+export { EntangledStandard }
+
+export type EntangledStandardKind =
+  | EntangledStandard.NonFungible
+  | EntangledStandard.ProgrammableNonFungible
+  | EntangledStandard.Token2022
+  | EntangledStandard.Compressed
+export type EntangledStandardJSON =
+  | EntangledStandard.NonFungibleJSON
+  | EntangledStandard.ProgrammableNonFungibleJSON
+  | EntangledStandard.Token2022JSON
+  | EntangledStandard.CompressedJSON